@@ -0,0 +1,684 @@
+use crate::config::{config_acl_users, config_aclfile, config_password};
+use crate::rocks::errors::RError;
+use glob::Pattern;
+use lazy_static::lazy_static;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use subtle::{Choice, ConstantTimeEq};
+
+pub const DEFAULT_USER: &str = "default";
+
+// command categories, same names as redis, `all` matches every command
+const CATEGORIES: &[(&str, &[&str])] = &[
+    (
+        "keyspace",
+        &[
+            "del",
+            "unlink",
+            "dump",
+            "restore",
+            "sort",
+            "exists",
+            "expire",
+            "expireat",
+            "pexpire",
+            "pexpireat",
+            "ttl",
+            "pttl",
+            "type",
+            "object",
+            "scan",
+            "keys",
+        ],
+    ),
+    (
+        "read",
+        &[
+            "get",
+            "mget",
+            "strlen",
+            "bitfield_ro",
+            "exists",
+            "dump",
+            "ttl",
+            "pttl",
+            "type",
+            "object",
+            "scan",
+            "keys",
+            "scard",
+            "sintercard",
+            "sismember",
+            "smismember",
+            "smembers",
+            "srandmember",
+            "lrange",
+            "llen",
+            "lindex",
+            "lpos",
+            "hget",
+            "hmget",
+            "hlen",
+            "hgetall",
+            "hkeys",
+            "hvals",
+            "hexists",
+            "hstrlen",
+            "zcard",
+            "zscore",
+            "zrange",
+            "zrevrange",
+            "zrangebyscore",
+            "zrevrangebyscore",
+            "zcount",
+            "zrank",
+            "pfcount",
+        ],
+    ),
+    (
+        "write",
+        &[
+            "set",
+            "getset",
+            "mset",
+            "del",
+            "unlink",
+            "restore",
+            "sort",
+            "incr",
+            "decr",
+            "incrby",
+            "decrby",
+            "expire",
+            "expireat",
+            "pexpire",
+            "pexpireat",
+            "sadd",
+            "sinterstore",
+            "spop",
+            "srem",
+            "lpush",
+            "rpush",
+            "lpop",
+            "rpop",
+            "lmpop",
+            "brpoplpush",
+            "ltrim",
+            "lset",
+            "lrem",
+            "linsert",
+            "hset",
+            "hmset",
+            "hsetnx",
+            "hdel",
+            "hincrby",
+            "zadd",
+            "zrem",
+            "zremrangebyscore",
+            "zremrangebyrank",
+            "zpopmin",
+            "zpopmax",
+            "zmpop",
+            "zincrby",
+            "pfadd",
+        ],
+    ),
+    (
+        "string",
+        &[
+            "get",
+            "mget",
+            "set",
+            "getset",
+            "mset",
+            "strlen",
+            "incr",
+            "decr",
+            "incrby",
+            "decrby",
+            "bitfield_ro",
+        ],
+    ),
+    (
+        "list",
+        &[
+            "lpush",
+            "rpush",
+            "lpop",
+            "rpop",
+            "lmpop",
+            "brpoplpush",
+            "lrange",
+            "ltrim",
+            "llen",
+            "lindex",
+            "lpos",
+            "lset",
+            "lrem",
+            "linsert",
+        ],
+    ),
+    (
+        "hash",
+        &[
+            "hset", "hmset", "hsetnx", "hget", "hmget", "hlen", "hgetall", "hdel", "hkeys",
+            "hvals", "hincrby", "hexists", "hstrlen",
+        ],
+    ),
+    (
+        "set",
+        &[
+            "sadd",
+            "scard",
+            "sintercard",
+            "sinterstore",
+            "sismember",
+            "smismember",
+            "smembers",
+            "srandmember",
+            "spop",
+            "srem",
+        ],
+    ),
+    (
+        "sortedset",
+        &[
+            "zadd",
+            "zcard",
+            "zscore",
+            "zrem",
+            "zremrangebyscore",
+            "zremrangebyrank",
+            "zrange",
+            "zrevrange",
+            "zrangebyscore",
+            "zrevrangebyscore",
+            "zcount",
+            "zpopmin",
+            "zpopmax",
+            "zmpop",
+            "zrank",
+            "zincrby",
+        ],
+    ),
+    ("hyperloglog", &["pfadd", "pfcount"]),
+    (
+        "pubsub",
+        &[
+            "publish",
+            "subscribe",
+            "unsubscribe",
+            "spublish",
+            "ssubscribe",
+            "sunsubscribe",
+            "pubsub",
+        ],
+    ),
+    (
+        "connection",
+        &[
+            "ping", "auth", "hello", "reset", "client", "wait", "command",
+        ],
+    ),
+    ("scripting", &["eval", "evalsha", "script"]),
+    ("transaction", &["multi", "exec", "discard"]),
+    (
+        "admin",
+        &[
+            "acl", "client", "slowlog", "latency", "config", "cluster", "debug",
+        ],
+    ),
+];
+
+pub fn categories() -> Vec<&'static str> {
+    let mut categories = vec!["all"];
+    categories.extend(CATEGORIES.iter().map(|(name, _)| *name));
+    categories
+}
+
+/// Commands in the category, `None` if the category does not exist.
+pub fn category_commands(category: &str) -> Option<Vec<&'static str>> {
+    if category == "all" {
+        let commands: BTreeSet<&str> = CATEGORIES
+            .iter()
+            .flat_map(|(_, commands)| commands.iter().copied())
+            .collect();
+        return Some(commands.into_iter().collect());
+    }
+    CATEGORIES
+        .iter()
+        .find(|(name, _)| *name == category)
+        .map(|(_, commands)| commands.to_vec())
+}
+
+fn in_category(category: &str, command: &str) -> bool {
+    category == "all"
+        || CATEGORIES
+            .iter()
+            .any(|(name, commands)| *name == category && commands.contains(&command))
+}
+
+fn hash_password(password: &str) -> [u8; 32] {
+    Sha256::digest(password.as_bytes()).into()
+}
+
+fn hex(hash: &[u8; 32]) -> String {
+    hash.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn parse_hex(hash: &str) -> Option<[u8; 32]> {
+    if hash.len() != 64 || !hash.is_ascii() {
+        return None;
+    }
+    let mut out = [0u8; 32];
+    for (i, b) in out.iter_mut().enumerate() {
+        *b = u8::from_str_radix(&hash[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(out)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct User {
+    pub name: String,
+    pub password_hashes: Vec<[u8; 32]>,
+    pub enabled: bool,
+    pub nopass: bool,
+    // command names, or category names prefixed by `@`
+    pub allowed_commands: BTreeSet<String>,
+    pub denied_commands: BTreeSet<String>,
+    pub key_patterns: Vec<String>,
+}
+
+impl User {
+    /// New users are disabled and have no permissions, same as redis.
+    pub fn new(name: &str) -> User {
+        User {
+            name: name.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// Apply a single ACL rule, e.g. `on`, `>password`, `~pattern`, `+get` or
+    /// `-@write`.
+    pub fn apply_rule(&mut self, rule: &str) -> Result<(), RError> {
+        let rule_lower = rule.to_lowercase();
+        match rule_lower.as_str() {
+            "on" => self.enabled = true,
+            "off" => self.enabled = false,
+            "nopass" => {
+                self.nopass = true;
+                self.password_hashes.clear();
+            }
+            "resetpass" => {
+                self.nopass = false;
+                self.password_hashes.clear();
+            }
+            "allkeys" => self.key_patterns = vec!["*".to_owned()],
+            "resetkeys" => self.key_patterns.clear(),
+            "allcommands" => return self.apply_rule("+@all"),
+            "nocommands" => return self.apply_rule("-@all"),
+            "reset" => *self = User::new(&self.name),
+            _ => {
+                let mut chars = rule.chars();
+                let prefix = chars.next();
+                let value = chars.as_str();
+                match prefix {
+                    Some('>') => {
+                        self.nopass = false;
+                        let hash = hash_password(value);
+                        if !self.password_hashes.contains(&hash) {
+                            self.password_hashes.push(hash);
+                        }
+                    }
+                    Some('<') => {
+                        let hash = hash_password(value);
+                        self.password_hashes.retain(|h| h != &hash);
+                    }
+                    Some('#') => {
+                        let hash = parse_hex(value).ok_or_else(|| Self::rule_error(rule))?;
+                        self.nopass = false;
+                        if !self.password_hashes.contains(&hash) {
+                            self.password_hashes.push(hash);
+                        }
+                    }
+                    Some('~') => {
+                        Pattern::new(value).map_err(|_| Self::rule_error(rule))?;
+                        self.key_patterns.push(value.to_owned());
+                    }
+                    Some(c @ ('+' | '-')) => {
+                        self.apply_command_rule(c == '+', &value.to_lowercase())?
+                    }
+                    _ => return Err(Self::rule_error(rule)),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_command_rule(&mut self, allow: bool, value: &str) -> Result<(), RError> {
+        let (add, remove) = if allow {
+            (&mut self.allowed_commands, &mut self.denied_commands)
+        } else {
+            (&mut self.denied_commands, &mut self.allowed_commands)
+        };
+        match value.strip_prefix('@') {
+            Some("all") => {
+                remove.clear();
+                add.clear();
+            }
+            Some(category) => {
+                if category_commands(category).is_none() {
+                    return Err(RError::owned_error(format!(
+                        "ERR Error in ACL SETUSER modifier '{}{value}': Unknown command or category name in ACL",
+                        if allow { '+' } else { '-' }
+                    )));
+                }
+                // later rules override earlier ones on the same commands
+                remove.retain(|c| c.starts_with('@') || !in_category(category, c));
+                remove.remove(&format!("@{category}"));
+            }
+            None => {
+                remove.remove(value);
+            }
+        }
+        add.insert(value.to_owned());
+        Ok(())
+    }
+
+    fn rule_error(rule: &str) -> RError {
+        RError::owned_error(format!(
+            "ERR Error in ACL SETUSER modifier '{rule}': Syntax error"
+        ))
+    }
+
+    /// Every hash is compared in constant time, so the reply time does not
+    /// tell how much of a hash matched, nor which one.
+    pub fn check_password(&self, password: &str) -> bool {
+        let hash = hash_password(password);
+        let matched = self
+            .password_hashes
+            .iter()
+            .fold(Choice::from(0), |matched, h| matched | h.ct_eq(&hash));
+        self.nopass || bool::from(matched)
+    }
+
+    pub fn can_execute(&self, command: &str) -> bool {
+        // publish is named `pub` internally
+        let command = if command == "pub" { "publish" } else { command };
+        if self.denied_commands.contains(command) {
+            return false;
+        }
+        if self.allowed_commands.contains(command) {
+            return true;
+        }
+        let in_categories = |rules: &BTreeSet<String>| {
+            rules
+                .iter()
+                .filter_map(|r| r.strip_prefix('@'))
+                .any(|category| in_category(category, command))
+        };
+        !in_categories(&self.denied_commands) && in_categories(&self.allowed_commands)
+    }
+
+    pub fn can_access_key(&self, key: &str) -> bool {
+        self.key_patterns
+            .iter()
+            .any(|p| Pattern::new(p).map_or(false, |p| p.matches(key)))
+    }
+
+    pub fn flags(&self) -> Vec<&'static str> {
+        let mut flags = vec![if self.enabled { "on" } else { "off" }];
+        if self.nopass {
+            flags.push("nopass");
+        }
+        flags
+    }
+
+    pub fn password_hexes(&self) -> Vec<String> {
+        self.password_hashes.iter().map(hex).collect()
+    }
+
+    /// Command rules, allowed rules are listed before denied ones.
+    pub fn commands_description(&self) -> String {
+        let mut rules: Vec<String> = self
+            .allowed_commands
+            .iter()
+            .map(|c| format!("+{c}"))
+            .collect();
+        rules.extend(self.denied_commands.iter().map(|c| format!("-{c}")));
+        if rules.is_empty() {
+            rules.push("-@all".to_owned());
+        }
+        rules.join(" ")
+    }
+
+    /// Same format as `ACL LIST`, could be used as rules of `ACL SETUSER`.
+    pub fn describe(&self) -> String {
+        let mut parts = vec![format!("user {}", self.name)];
+        parts.extend(self.flags().iter().map(|f| f.to_string()));
+        parts.extend(self.password_hexes().into_iter().map(|h| format!("#{h}")));
+        parts.extend(self.key_patterns.iter().map(|p| format!("~{p}")));
+        parts.push(self.commands_description());
+        parts.join(" ")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AclStore {
+    users: Arc<RwLock<HashMap<String, User>>>,
+}
+
+impl AclStore {
+    /// Load the `default` user from `password` config and other users from
+    /// `acl_users` config, each entry is a user name followed by its rules.
+    /// The users of `aclfile` replace them if the file exists.
+    pub fn from_config() -> AclStore {
+        let mut default = User::new(DEFAULT_USER);
+        let mut rules = vec!["on", "allkeys", "+@all"];
+        let password = config_password().map(|p| format!(">{p}"));
+        rules.push(password.as_deref().unwrap_or("nopass"));
+        for rule in rules {
+            default.apply_rule(rule).unwrap();
+        }
+
+        let store = AclStore {
+            users: Arc::new(RwLock::new(HashMap::new())),
+        };
+        store.insert(default);
+        for line in config_acl_users() {
+            let mut parts = line.split_whitespace();
+            if let Some(name) = parts.next() {
+                let rules: Vec<String> = parts.map(|r| r.to_owned()).collect();
+                store
+                    .set_user(name, &rules)
+                    .unwrap_or_else(|e| panic!("invalid acl user config `{line}`: {e}"));
+            }
+        }
+        if let Some(path) = config_aclfile().filter(|p| Path::new(p).exists()) {
+            store
+                .load(Path::new(&path))
+                .unwrap_or_else(|e| panic!("invalid aclfile {path}: {e}"));
+        }
+        store
+    }
+
+    /// Write every user as a `user <name> <rules>` line, the format of
+    /// `ACL LIST`. The file is replaced at once so a crash never leaves a
+    /// partial one.
+    pub fn save(&self, path: &Path) -> Result<(), RError> {
+        let mut content = String::new();
+        for user in self.users() {
+            content.push_str(&user.describe());
+            content.push('\n');
+        }
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, content)
+            .and_then(|_| fs::rename(&tmp_path, path))
+            .map_err(|e| RError::owned_error(format!("ERR failed to save aclfile, {e}")))
+    }
+
+    /// Replace all users by the ones of a file written by `save`, nothing is
+    /// changed if any line is invalid. The `default` user is kept if the file
+    /// does not define it.
+    pub fn load(&self, path: &Path) -> Result<(), RError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| RError::owned_error(format!("ERR failed to load aclfile, {e}")))?;
+        let mut users = HashMap::new();
+        for (i, line) in content.lines().enumerate() {
+            let mut parts = line.split_whitespace();
+            let name = match (parts.next(), parts.next()) {
+                (None, _) => continue,
+                (Some("user"), Some(name)) => name,
+                _ => {
+                    return Err(RError::owned_error(format!(
+                        "ERR aclfile line {} should start with 'user <name>'",
+                        i + 1
+                    )))
+                }
+            };
+            let mut user = User::new(name);
+            for rule in parts {
+                user.apply_rule(rule)
+                    .map_err(|e| RError::owned_error(format!("ERR aclfile line {}: {e}", i + 1)))?;
+            }
+            users.insert(name.to_owned(), user);
+        }
+
+        let mut current = self.users.write().unwrap();
+        if !users.contains_key(DEFAULT_USER) {
+            if let Some(default) = current.get(DEFAULT_USER) {
+                users.insert(DEFAULT_USER.to_owned(), default.clone());
+            }
+        }
+        *current = users;
+        Ok(())
+    }
+
+    fn insert(&self, user: User) {
+        self.users.write().unwrap().insert(user.name.clone(), user);
+    }
+
+    pub fn get_user(&self, name: &str) -> Option<User> {
+        self.users.read().unwrap().get(name).cloned()
+    }
+
+    /// Create the user if not exists, then apply the rules in order. Nothing
+    /// is changed if any rule is invalid.
+    pub fn set_user(&self, name: &str, rules: &[String]) -> Result<(), RError> {
+        let mut user = self.get_user(name).unwrap_or_else(|| User::new(name));
+        for rule in rules {
+            user.apply_rule(rule)?;
+        }
+        self.insert(user);
+        Ok(())
+    }
+
+    pub fn del_user(&self, name: &str) -> bool {
+        self.users.write().unwrap().remove(name).is_some()
+    }
+
+    pub fn users(&self) -> Vec<User> {
+        let mut users: Vec<User> = self.users.read().unwrap().values().cloned().collect();
+        users.sort_by(|a, b| a.name.cmp(&b.name));
+        users
+    }
+
+    /// Return the user if it is enabled and the password matches.
+    pub fn authenticate(&self, name: &str, password: &str) -> Option<User> {
+        self.get_user(name)
+            .filter(|user| user.enabled && user.check_password(password))
+    }
+}
+
+lazy_static! {
+    pub static ref ACL_STORE: AclStore = AclStore::from_config();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AclStore, User, DEFAULT_USER};
+    use std::collections::HashMap;
+    use std::sync::{Arc, RwLock};
+
+    fn user(rules: &[&str]) -> User {
+        let mut user = User::new("alice");
+        for rule in rules {
+            user.apply_rule(rule).unwrap();
+        }
+        user
+    }
+
+    #[test]
+    fn test_command_rules() {
+        let alice = user(&["on", "~*", "-@all", "+get"]);
+        assert!(alice.can_execute("get"));
+        assert!(!alice.can_execute("set"));
+        assert!(alice.can_access_key("foo"));
+
+        let alice = user(&["+@all", "-@write", "+set"]);
+        assert!(alice.can_execute("get"));
+        assert!(alice.can_execute("set"));
+        assert!(!alice.can_execute("del"));
+
+        let alice = user(&["+@read", "~cache:*"]);
+        assert!(alice.can_execute("hget"));
+        assert!(!alice.can_execute("hset"));
+        assert!(alice.can_access_key("cache:1"));
+        assert!(!alice.can_access_key("other"));
+    }
+
+    #[test]
+    fn test_password_rules() {
+        let alice = user(&["on", ">secret"]);
+        assert!(alice.check_password("secret"));
+        assert!(!alice.check_password("other"));
+
+        let hash = alice.password_hexes().pop().unwrap();
+        let bob = user(&[&format!("#{hash}")]);
+        assert!(bob.check_password("secret"));
+
+        let alice = user(&[">secret", "<secret"]);
+        assert!(!alice.check_password("secret"));
+        assert!(User::new("bob").apply_rule("bad").is_err());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let store = AclStore {
+            users: Arc::new(RwLock::new(HashMap::new())),
+        };
+        store
+            .set_user(DEFAULT_USER, &["on".to_owned(), "nopass".to_owned()])
+            .unwrap();
+        let rules: Vec<String> = ["on", ">secret", "~cache:*", "+get"]
+            .iter()
+            .map(|r| r.to_string())
+            .collect();
+        store.set_user("alice", &rules).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("users.acl");
+        store.save(&path).unwrap();
+
+        store.set_user("alice", &["off".to_owned()]).unwrap();
+        store.set_user("bob", &["on".to_owned()]).unwrap();
+        store.load(&path).unwrap();
+
+        let alice = store.get_user("alice").unwrap();
+        assert!(alice.enabled);
+        assert!(alice.check_password("secret"));
+        assert!(alice.can_execute("get"));
+        assert!(!alice.can_execute("set"));
+        assert!(alice.can_access_key("cache:1"));
+        assert!(store.get_user("bob").is_none());
+        assert!(store.get_user(DEFAULT_USER).is_some());
+
+        // an invalid line leaves the users untouched
+        std::fs::write(&path, "user alice off\nbogus\n").unwrap();
+        assert!(store.load(&path).is_err());
+        assert!(store.get_user("alice").unwrap().enabled);
+    }
+}
@@ -0,0 +1,131 @@
+use crate::config::{audit_log_file_or_default, audit_log_max_size_mb_or_default, LOGGER};
+use lazy_static::lazy_static;
+use serde::Serialize;
+use slog::error;
+use std::io;
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+lazy_static! {
+    static ref AUDIT_LOG: Mutex<Option<AuditLog>> = Mutex::new(None);
+}
+
+/// A line of the audit log, written after every command when `audit_log` is
+/// enabled.
+#[derive(Serialize, Debug)]
+pub struct AuditEntry<'a> {
+    // unix timestamp in milliseconds
+    pub ts: u64,
+    pub client_ip: &'a str,
+    pub client_id: u64,
+    pub cmd: &'a str,
+    pub key: Option<String>,
+    // "ok" or "error"
+    pub result: &'static str,
+}
+
+/// Append only file of json lines, rotated to `<path>.1` once it would grow
+/// over `max_size` bytes.
+pub struct AuditLog {
+    path: String,
+    max_size: u64,
+    file: Option<File>,
+    size: u64,
+}
+
+impl AuditLog {
+    pub fn new(path: String, max_size: u64) -> AuditLog {
+        AuditLog {
+            path,
+            max_size,
+            file: None,
+            size: 0,
+        }
+    }
+
+    pub async fn append(&mut self, entry: &AuditEntry<'_>) -> io::Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        if self.file.is_some() && self.size + line.len() as u64 > self.max_size {
+            self.file = None;
+            fs::rename(&self.path, format!("{}.1", self.path)).await?;
+        }
+        if self.file.is_none() {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .await?;
+            self.size = file.metadata().await?.len();
+            self.file = Some(file);
+        }
+        if let Some(file) = &mut self.file {
+            file.write_all(&line).await?;
+            file.flush().await?;
+            self.size += line.len() as u64;
+        }
+        Ok(())
+    }
+}
+
+/// Append `entry` to the audit log of the server config, failures are logged
+/// and never fail the command.
+pub async fn audit(entry: AuditEntry<'_>) {
+    let path = audit_log_file_or_default();
+    let max_size = audit_log_max_size_mb_or_default() * 1024 * 1024;
+
+    let mut audit_log = AUDIT_LOG.lock().await;
+    // the file and size limit take effect on config reload
+    if !matches!(&*audit_log, Some(log) if log.path == path && log.max_size == max_size) {
+        *audit_log = Some(AuditLog::new(path, max_size));
+    }
+    if let Some(log) = audit_log.as_mut() {
+        if let Err(e) = log.append(&entry).await {
+            error!(LOGGER, "failed to write audit log {}, {}", log.path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuditEntry, AuditLog};
+
+    fn entry(cmd: &str) -> AuditEntry {
+        AuditEntry {
+            ts: 1,
+            client_ip: "127.0.0.1:5000",
+            client_id: 3,
+            cmd,
+            key: Some("foo".to_owned()),
+            result: "ok",
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_append_and_rotate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log").to_str().unwrap().to_owned();
+        let mut log = AuditLog::new(path.clone(), 200);
+
+        log.append(&entry("set")).await.unwrap();
+        log.append(&entry("get")).await.unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            r#"{"ts":1,"client_ip":"127.0.0.1:5000","client_id":3,"cmd":"set","key":"foo","result":"ok"}"#
+        );
+        assert!(lines[1].contains(r#""cmd":"get""#));
+
+        // the third line does not fit, the log is rotated
+        log.append(&entry("del")).await.unwrap();
+        let rotated = std::fs::read_to_string(format!("{path}.1")).unwrap();
+        assert_eq!(rotated, content);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), 1);
+        assert!(content.contains(r#""cmd":"del""#));
+    }
+}
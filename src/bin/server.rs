@@ -4,10 +4,11 @@ use std::thread;
 
 use clap::Parser;
 use local_ip_address::local_ip;
-use slog::info;
+use slog::{error, info};
 use sysinfo::set_open_files_limit;
 use tokio::net::TcpListener;
 use tokio::runtime::Runtime;
+use tokio::signal::unix::SignalKind;
 use tokio::{fs, signal};
 
 use mapuche::config::{
@@ -16,7 +17,7 @@ use mapuche::config::{
     config_prometheus_listen_or_default, config_prometheus_port_or_default,
     config_raft_api_port_or_default, config_raft_internal_port_or_default,
     config_ring_port_or_default, config_ring_v_node_num_or_default, data_store_dir_or_default,
-    set_global_config, Config, LOGGER,
+    reload_config, set_config_file, set_global_config, Config, LOGGER,
 };
 use mapuche::hash_ring::{HashRing, NodeInfo};
 use mapuche::metrics::PrometheusServer;
@@ -32,12 +33,13 @@ pub async fn main() -> mapuche::Result<()> {
     let mut config: Option<Config> = None;
 
     if let Some(config_file_name) = cli.config {
-        let config_content = fs::read_to_string(config_file_name)
+        let config_content = fs::read_to_string(&config_file_name)
             .await
             .expect("Failed to read config file");
+        set_config_file(&config_file_name);
 
         // deserialize toml config
-        config = match toml::from_str(&config_content) {
+        config = match Config::from_toml(&config_content) {
             Ok(d) => Some(d),
             Err(e) => {
                 println!("Unable to load config file {e}");
@@ -90,6 +92,8 @@ pub async fn main() -> mapuche::Result<()> {
         start_raft()?;
     }
 
+    watch_config_reload()?;
+
     // Bind a TCP listener
     let listener = TcpListener::bind(&format!("{}:{}", &listen_addr, port)).await?;
 
@@ -98,6 +102,20 @@ pub async fn main() -> mapuche::Result<()> {
     Ok(())
 }
 
+// reload the config file on SIGHUP
+fn watch_config_reload() -> mapuche::Result<()> {
+    let mut hangup = signal::unix::signal(SignalKind::hangup())?;
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            match reload_config() {
+                Ok(_) => info!(LOGGER, "config reloaded"),
+                Err(e) => error!(LOGGER, "failed to reload config, {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
 fn start_pmt(prom_listen: &str, prom_port: &str, instance_id: u64) -> mapuche::Result<()> {
     let pmt_server =
         PrometheusServer::new(format!("{}:{}", prom_listen, prom_port), instance_id as i64);
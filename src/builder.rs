@@ -0,0 +1,249 @@
+use crate::cmd::{Del, Expire, Get, Hget, Hset, IncrDecr, Push, Sadd, Set, Zadd};
+use crate::rocks::kv::kvpair::KvPair;
+use crate::{Command, MapucheError};
+use bytes::Bytes;
+
+/// Typed constructors of the commands, for embedding mapuche as a library
+/// instead of building request frames by hand.
+///
+/// Arguments which would be rejected by the command parser are rejected by
+/// the builders before a `Command` is created.
+pub struct CommandBuilder;
+
+impl CommandBuilder {
+    pub fn get(key: &str) -> Command {
+        Command::Get(Get::new(key))
+    }
+
+    /// `SET key value [PX expire_ms]`
+    pub fn set(key: &str, value: &[u8], expire_ms: Option<i64>) -> crate::Result<Command> {
+        if matches!(expire_ms, Some(ms) if ms <= 0) {
+            return Err(MapucheError::String("invalid expire time").into());
+        }
+        Ok(Command::Set(Set::new(
+            key,
+            Bytes::copy_from_slice(value),
+            expire_ms,
+        )))
+    }
+
+    pub fn del(keys: &[&str]) -> crate::Result<Command> {
+        if keys.is_empty() {
+            return Err(MapucheError::String("no keys to delete").into());
+        }
+        let mut del = Del::default();
+        for key in keys {
+            del.add_key(key.to_string());
+        }
+        Ok(Command::Del(del))
+    }
+
+    pub fn incr(key: &str) -> Command {
+        Command::Incr(IncrDecr::new(key, 1))
+    }
+
+    pub fn incrby(key: &str, step: i64) -> Command {
+        Command::Incrby(IncrDecr::new(key, step))
+    }
+
+    pub fn expire(key: &str, seconds: i64) -> Command {
+        Command::Expire(Expire::new(key, seconds))
+    }
+
+    pub fn hset(key: &str, fields: &[(&str, &[u8])]) -> crate::Result<Command> {
+        if fields.is_empty() {
+            return Err(MapucheError::String("no fields to set").into());
+        }
+        let mut hset = Hset::default();
+        hset.set_key(key);
+        for (field, value) in fields {
+            hset.add_field_value(KvPair::new(field.to_string(), value.to_vec()));
+        }
+        Ok(Command::Hset(hset))
+    }
+
+    pub fn hget(key: &str, field: &str) -> Command {
+        Command::Hget(Hget::new(key, field))
+    }
+
+    pub fn lpush(key: &str, items: &[&[u8]]) -> crate::Result<Command> {
+        Ok(Command::Lpush(Self::push(key, items)?))
+    }
+
+    pub fn rpush(key: &str, items: &[&[u8]]) -> crate::Result<Command> {
+        Ok(Command::Rpush(Self::push(key, items)?))
+    }
+
+    fn push(key: &str, items: &[&[u8]]) -> crate::Result<Push> {
+        if items.is_empty() {
+            return Err(MapucheError::String("no items to push").into());
+        }
+        let mut push = Push::new(key);
+        for item in items {
+            push.add_item(Bytes::copy_from_slice(item));
+        }
+        Ok(push)
+    }
+
+    pub fn sadd(key: &str, members: &[&str]) -> crate::Result<Command> {
+        if members.is_empty() {
+            return Err(MapucheError::String("no members to add").into());
+        }
+        let mut sadd = Sadd::new(key);
+        for member in members {
+            sadd.add_member(member);
+        }
+        Ok(Command::Sadd(sadd))
+    }
+
+    pub fn zadd(key: &str) -> ZaddBuilder {
+        ZaddBuilder {
+            key: key.to_owned(),
+            members: vec![],
+            nx: false,
+            xx: false,
+            ch: false,
+        }
+    }
+}
+
+/// `ZADD key [NX|XX] [CH] score member [score member ...]`
+pub struct ZaddBuilder {
+    key: String,
+    members: Vec<(f64, String)>,
+    nx: bool,
+    xx: bool,
+    ch: bool,
+}
+
+impl ZaddBuilder {
+    /// Only add new members.
+    pub fn nx(mut self) -> Self {
+        self.nx = true;
+        self
+    }
+
+    /// Only update existing members.
+    pub fn xx(mut self) -> Self {
+        self.xx = true;
+        self
+    }
+
+    /// Reply the number of changed members instead of added ones.
+    pub fn ch(mut self) -> Self {
+        self.ch = true;
+        self
+    }
+
+    pub fn add(mut self, score: f64, member: &str) -> Self {
+        self.members.push((score, member.to_owned()));
+        self
+    }
+
+    pub fn build(self) -> crate::Result<Command> {
+        if self.nx && self.xx {
+            return Err(MapucheError::String("NX and XX options are exclusive").into());
+        }
+        if self.members.is_empty() {
+            return Err(MapucheError::String("no members to add").into());
+        }
+        if self.members.iter().any(|(score, _)| score.is_nan()) {
+            return Err(MapucheError::String("score is not a valid float").into());
+        }
+        let mut zadd = Zadd::new(&self.key);
+        if self.nx || self.xx {
+            zadd.set_exists(self.xx);
+        }
+        zadd.set_changed_only(self.ch);
+        for (score, member) in &self.members {
+            zadd.add_score(*score);
+            zadd.add_member(member);
+        }
+        Ok(Command::Zadd(zadd))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CommandBuilder;
+    use crate::{Command, Frame};
+    use bytes::Bytes;
+
+    // the command parsed from the request frame of `args`
+    fn parsed(args: &[&str]) -> String {
+        let frame = Frame::Array(
+            args.iter()
+                .map(|arg| Frame::Bulk(Bytes::from(arg.to_string())))
+                .collect(),
+        );
+        serde_json::to_string(&Command::from_frame(frame).unwrap()).unwrap()
+    }
+
+    fn built(cmd: Command) -> String {
+        serde_json::to_string(&cmd).unwrap()
+    }
+
+    #[test]
+    fn test_builders_match_parsed_commands() {
+        assert_eq!(built(CommandBuilder::get("k")), parsed(&["get", "k"]));
+        assert_eq!(
+            built(CommandBuilder::set("k", b"v", Some(1500)).unwrap()),
+            parsed(&["set", "k", "v", "px", "1500"])
+        );
+        assert_eq!(
+            built(CommandBuilder::del(&["k1", "k2"]).unwrap()),
+            parsed(&["del", "k1", "k2"])
+        );
+        assert_eq!(built(CommandBuilder::incr("k")), parsed(&["incr", "k"]));
+        assert_eq!(
+            built(CommandBuilder::incrby("k", 5)),
+            parsed(&["incrby", "k", "5"])
+        );
+        assert_eq!(
+            built(CommandBuilder::expire("k", 10)),
+            parsed(&["expire", "k", "10"])
+        );
+        assert_eq!(
+            built(CommandBuilder::hset("k", &[("f", b"v")]).unwrap()),
+            parsed(&["hset", "k", "f", "v"])
+        );
+        assert_eq!(
+            built(CommandBuilder::hget("k", "f")),
+            parsed(&["hget", "k", "f"])
+        );
+        assert_eq!(
+            built(CommandBuilder::rpush("k", &[b"a", b"b"]).unwrap()),
+            parsed(&["rpush", "k", "a", "b"])
+        );
+        assert_eq!(
+            built(CommandBuilder::sadd("k", &["m"]).unwrap()),
+            parsed(&["sadd", "k", "m"])
+        );
+        assert_eq!(
+            built(
+                CommandBuilder::zadd("k")
+                    .xx()
+                    .ch()
+                    .add(1.5, "m1")
+                    .add(2.0, "m2")
+                    .build()
+                    .unwrap()
+            ),
+            parsed(&["zadd", "k", "xx", "ch", "1.5", "m1", "2", "m2"])
+        );
+    }
+
+    #[test]
+    fn test_invalid_builders() {
+        assert!(CommandBuilder::zadd("k").build().is_err());
+        assert!(CommandBuilder::zadd("k")
+            .nx()
+            .xx()
+            .add(1.0, "m")
+            .build()
+            .is_err());
+        assert!(CommandBuilder::set("k", b"v", Some(0)).is_err());
+        assert!(CommandBuilder::del(&[]).is_err());
+        assert!(CommandBuilder::lpush("k", &[]).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+use crate::config::{
+    cache_enabled_or_default, cache_max_entries_or_default, cache_ttl_seconds_or_default,
+};
+use crate::metrics::STRING_CACHE_HIT_RATIO;
+use crate::rocks::encoding::KeyDecoder;
+use crate::rocks::kv::value::Value;
+use crate::utils::ttl_from_timestamp;
+use lazy_static::lazy_static;
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+lazy_static! {
+    pub static ref STRING_CACHE: Mutex<StringCache> = Mutex::new(StringCache::new(
+        cache_max_entries_or_default(),
+        Duration::from_secs(cache_ttl_seconds_or_default()),
+    ));
+}
+
+/// In-memory LRU cache in front of the meta column family for string keys.
+///
+/// Entries hold the raw encoded meta value together with the instant after
+/// which the entry must not be served any more, which is the earlier of the
+/// configured cache ttl and the key's own expiry.
+///
+/// Every invalidation bumps the generation. A reader populating the cache
+/// after a miss passes the generation seen before its read, the value is
+/// dropped if a write invalidated the cache meanwhile, so a stale value read
+/// concurrently with a write is never cached.
+pub struct StringCache {
+    inner: LruCache<Vec<u8>, (Value, Instant)>,
+    ttl: Duration,
+    generation: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl StringCache {
+    pub fn new(max_entries: usize, ttl: Duration) -> Self {
+        StringCache {
+            inner: LruCache::new(NonZeroUsize::new(max_entries.max(1)).unwrap()),
+            ttl,
+            generation: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn get(&mut self, key: &[u8]) -> Option<Value> {
+        let now = Instant::now();
+        let (value, fresh) = match self.inner.get(key) {
+            Some((value, deadline)) => (Some(value.clone()), *deadline > now),
+            None => (None, false),
+        };
+        if value.is_some() && !fresh {
+            self.inner.pop(key);
+        }
+        let value = value.filter(|_| fresh);
+        if value.is_some() {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+        STRING_CACHE_HIT_RATIO.set(self.hit_ratio());
+        value
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Cache the value read at `generation`, ignored if the cache was
+    /// invalidated since.
+    pub fn put(&mut self, key: Vec<u8>, value: Value, generation: u64) {
+        if generation != self.generation {
+            return;
+        }
+        let mut deadline = Instant::now() + self.ttl;
+        let ttl = KeyDecoder::decode_key_ttl(&value);
+        if ttl > 0 {
+            let remaining = Instant::now() + Duration::from_millis(ttl_from_timestamp(ttl) as u64);
+            deadline = deadline.min(remaining);
+        }
+        self.inner.put(key, (value, deadline));
+    }
+
+    pub fn invalidate(&mut self, key: &[u8]) {
+        self.generation += 1;
+        self.inner.pop(key);
+    }
+
+    pub fn clear(&mut self) {
+        self.generation += 1;
+        self.inner.clear();
+    }
+
+    pub fn hit_ratio(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / total as f64
+    }
+}
+
+pub fn cache_get(key: &[u8]) -> Option<Value> {
+    if !cache_enabled_or_default() {
+        return None;
+    }
+    STRING_CACHE.lock().unwrap().get(key)
+}
+
+/// Generation to pass to `cache_put`, taken before reading the value.
+pub fn cache_generation() -> u64 {
+    STRING_CACHE.lock().unwrap().generation()
+}
+
+pub fn cache_put(key: Vec<u8>, value: Value, generation: u64) {
+    if !cache_enabled_or_default() {
+        return;
+    }
+    STRING_CACHE.lock().unwrap().put(key, value, generation);
+}
+
+pub fn cache_invalidate(key: &[u8]) {
+    if !cache_enabled_or_default() {
+        return;
+    }
+    STRING_CACHE.lock().unwrap().invalidate(key);
+}
+
+pub fn cache_clear() {
+    STRING_CACHE.lock().unwrap().clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StringCache;
+    use crate::rocks::KEY_ENCODER;
+    use std::time::Duration;
+
+    #[test]
+    fn test_repeated_get_hits_cache() {
+        let mut cache = StringCache::new(16, Duration::from_secs(60));
+        let key = b"k".to_vec();
+        assert!(cache.get(&key).is_none());
+
+        let value = KEY_ENCODER.encode_string_value(&mut b"v".to_vec(), -1);
+        cache.put(key.clone(), value.clone(), 0);
+        assert_eq!(cache.get(&key), Some(value.clone()));
+        assert_eq!(cache.get(&key), Some(value));
+        assert_eq!(cache.hit_ratio(), 2.0 / 3.0);
+
+        cache.invalidate(&key);
+        assert!(cache.get(&key).is_none());
+    }
+
+    #[test]
+    fn test_entry_expires_with_cache_ttl() {
+        let mut cache = StringCache::new(16, Duration::ZERO);
+        let value = KEY_ENCODER.encode_string_value(&mut b"v".to_vec(), -1);
+        cache.put(b"k".to_vec(), value, 0);
+        assert!(cache.get(b"k").is_none());
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let mut cache = StringCache::new(1, Duration::from_secs(60));
+        let value = KEY_ENCODER.encode_string_value(&mut b"v".to_vec(), -1);
+        cache.put(b"a".to_vec(), value.clone(), 0);
+        cache.put(b"b".to_vec(), value, 0);
+        assert!(cache.get(b"a").is_none());
+        assert!(cache.get(b"b").is_some());
+    }
+
+    #[test]
+    fn test_value_read_before_invalidation_not_cached() {
+        let mut cache = StringCache::new(16, Duration::from_secs(60));
+        let value = KEY_ENCODER.encode_string_value(&mut b"v".to_vec(), -1);
+        // a reader misses, then a writer invalidates before the reader caches
+        let generation = cache.generation();
+        cache.invalidate(b"k");
+        cache.put(b"k".to_vec(), value.clone(), generation);
+        assert!(cache.get(b"k").is_none());
+
+        cache.put(b"k".to_vec(), value.clone(), cache.generation());
+        assert_eq!(cache.get(b"k"), Some(value));
+    }
+}
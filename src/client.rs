@@ -2,8 +2,7 @@ use std::fmt;
 use std::fmt::Formatter;
 use std::os::unix::io::{AsRawFd, RawFd};
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::SystemTime;
-use tokio::net::TcpStream;
+use std::time::{Duration, SystemTime};
 use tokio::sync::mpsc::Sender;
 
 // reserve id 0
@@ -15,8 +14,15 @@ pub struct Client {
     // name here does not constrain to uniqueness
     name: String,
     fd: RawFd,
+    addr: String,
     // last command played
     cmd: String,
+    // trace id of the last command played
+    trace_id: String,
+    // last finished command and how long it took
+    last_cmd: String,
+    last_cmd_duration_us: u64,
+    total_cmds: u64,
 
     create_time: SystemTime,
     last_interaction: SystemTime,
@@ -25,32 +31,52 @@ pub struct Client {
 }
 
 impl Client {
-    pub fn new(socket: &TcpStream, kill_tx: Sender<()>) -> Client {
+    pub fn new(socket: &impl AsRawFd, addr: String, kill_tx: Sender<()>) -> Client {
         let now = SystemTime::now();
         Client {
             id: COUNTER.fetch_add(1, Ordering::Relaxed),
             name: "".to_owned(),
             fd: socket.as_raw_fd(),
+            addr,
             cmd: "".to_owned(),
+            trace_id: "".to_owned(),
+            last_cmd: "".to_owned(),
+            last_cmd_duration_us: 0,
+            total_cmds: 0,
             create_time: now,
             last_interaction: now,
             kill_tx,
         }
     }
 
-    pub fn interact(&mut self, cmd_name: &str) {
+    pub fn interact(&mut self, cmd_name: &str, trace_id: &str) {
         self.cmd = cmd_name.to_string();
+        self.trace_id = trace_id.to_string();
         self.last_interaction = SystemTime::now();
     }
 
+    pub fn finish(&mut self, duration: Duration) {
+        self.last_cmd = self.cmd.clone();
+        self.last_cmd_duration_us = duration.as_micros() as u64;
+        self.total_cmds += 1;
+    }
+
     pub async fn kill(&self) {
         let _ = self.kill_tx.send(()).await;
     }
 
+    pub fn kill_sender(&self) -> Sender<()> {
+        self.kill_tx.clone()
+    }
+
     pub fn id(&self) -> u64 {
         self.id
     }
 
+    pub fn addr(&self) -> &str {
+        self.addr.as_str()
+    }
+
     pub fn name(&self) -> &str {
         self.name.as_str()
     }
@@ -72,15 +98,21 @@ impl fmt::Display for Client {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(
             f,
-            "id={} fd={} name={} age={} idle={} flags=N \
+            "id={} addr={} fd={} name={} age={} idle={} flags=N \
             db=0 sub=0 psub=0 multi=-1 qbuf=0 qbuf-free=0 argv-mem=10 obl=0 oll=0 omem=0 \
-            tot-mem=0 events=r cmd={} user=default redir=-1",
+            tot-mem=0 events=r cmd={} user=default redir=-1 last_cmd={} \
+            last_cmd_duration_us={} total_cmds_processed={} current_trace_id={}",
             self.id,
+            self.addr,
             self.fd,
             self.name,
             self.age(),
             self.idle(),
-            self.cmd
+            self.cmd,
+            self.last_cmd,
+            self.last_cmd_duration_us,
+            self.total_cmds,
+            self.trace_id
         )
     }
 }
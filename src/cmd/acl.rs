@@ -0,0 +1,165 @@
+use crate::acl::{categories, category_commands, ACL_STORE, DEFAULT_USER};
+use crate::cmd::Invalid;
+use crate::config::config_aclfile;
+use crate::parse::{Parse, ParseError};
+use crate::rocks::errors::{
+    REDIS_ACL_DEL_DEFAULT_USER_ERR, REDIS_ACL_NO_ACLFILE_ERR, REDIS_ACL_UNKNOWN_CATEGORY_ERR,
+};
+use crate::utils::{
+    resp_array, resp_bulk, resp_err, resp_int, resp_invalid_arguments, resp_nil, resp_ok, resp_str,
+};
+use crate::Frame;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum AclSubcommand {
+    SetUser(String, Vec<String>),
+    GetUser(String),
+    DelUser(Vec<String>),
+    List,
+    WhoAmI,
+    Cat(Option<String>),
+    Save,
+    Load,
+}
+
+/// `ACL SETUSER|GETUSER|DELUSER|LIST|WHOAMI|CAT|SAVE|LOAD`, users are kept in
+/// memory of the node which receives the command. `SAVE` and `LOAD` write and
+/// read them back from `aclfile`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Acl {
+    subcommand: AclSubcommand,
+    valid: bool,
+}
+
+impl Acl {
+    pub fn new(subcommand: AclSubcommand) -> Acl {
+        Acl {
+            subcommand,
+            valid: true,
+        }
+    }
+
+    fn remaining_strings(parse: &mut Parse) -> crate::Result<Vec<String>> {
+        let mut out = vec![];
+        loop {
+            match parse.next_string() {
+                Ok(s) => out.push(s),
+                Err(ParseError::EndOfStream) => return Ok(out),
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Acl> {
+        let subcommand = match parse.next_string()?.to_lowercase().as_str() {
+            "setuser" => {
+                let name = parse.next_string()?;
+                AclSubcommand::SetUser(name, Self::remaining_strings(parse)?)
+            }
+            "getuser" => AclSubcommand::GetUser(parse.next_string()?),
+            "deluser" => {
+                let names = Self::remaining_strings(parse)?;
+                if names.is_empty() {
+                    return Ok(Acl::new_invalid());
+                }
+                AclSubcommand::DelUser(names)
+            }
+            "list" => AclSubcommand::List,
+            "whoami" => AclSubcommand::WhoAmI,
+            "save" => AclSubcommand::Save,
+            "load" => AclSubcommand::Load,
+            "cat" => match parse.next_string() {
+                Ok(category) => AclSubcommand::Cat(Some(category)),
+                Err(ParseError::EndOfStream) => AclSubcommand::Cat(None),
+                Err(e) => return Err(e.into()),
+            },
+            _ => return Ok(Acl::new_invalid()),
+        };
+        Ok(Acl::new(subcommand))
+    }
+
+    pub(crate) fn execute(&self, cur_user: &str) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        match &self.subcommand {
+            AclSubcommand::SetUser(name, rules) => match ACL_STORE.set_user(name, rules) {
+                Ok(_) => resp_ok(),
+                Err(e) => resp_err(e),
+            },
+            AclSubcommand::GetUser(name) => match ACL_STORE.get_user(name) {
+                Some(user) => {
+                    let strings = |v: Vec<String>| {
+                        resp_array(v.into_iter().map(|s| resp_bulk(s.into_bytes())).collect())
+                    };
+                    let flags = user.flags().iter().map(|f| f.to_string()).collect();
+                    Frame::Map(vec![
+                        (resp_str("flags"), strings(flags)),
+                        (resp_str("passwords"), strings(user.password_hexes())),
+                        (
+                            resp_str("commands"),
+                            resp_bulk(user.commands_description().into_bytes()),
+                        ),
+                        (resp_str("keys"), strings(user.key_patterns.clone())),
+                    ])
+                }
+                None => resp_nil(),
+            },
+            AclSubcommand::DelUser(names) => {
+                if names.iter().any(|name| name == DEFAULT_USER) {
+                    return resp_err(REDIS_ACL_DEL_DEFAULT_USER_ERR);
+                }
+                let deleted = names.iter().filter(|name| ACL_STORE.del_user(name)).count();
+                resp_int(deleted as i64)
+            }
+            AclSubcommand::List => resp_array(
+                ACL_STORE
+                    .users()
+                    .iter()
+                    .map(|user| resp_bulk(user.describe().into_bytes()))
+                    .collect(),
+            ),
+            AclSubcommand::WhoAmI => resp_bulk(cur_user.as_bytes().to_vec()),
+            AclSubcommand::Save | AclSubcommand::Load => {
+                let path = match config_aclfile() {
+                    Some(path) => path,
+                    None => return resp_err(REDIS_ACL_NO_ACLFILE_ERR),
+                };
+                let res = if matches!(self.subcommand, AclSubcommand::Save) {
+                    ACL_STORE.save(Path::new(&path))
+                } else {
+                    ACL_STORE.load(Path::new(&path))
+                };
+                res.map_or_else(resp_err, |_| resp_ok())
+            }
+            AclSubcommand::Cat(None) => resp_array(
+                categories()
+                    .into_iter()
+                    .map(|c| resp_bulk(c.as_bytes().to_vec()))
+                    .collect(),
+            ),
+            AclSubcommand::Cat(Some(category)) => {
+                match category_commands(&category.to_lowercase()) {
+                    Some(commands) => resp_array(
+                        commands
+                            .into_iter()
+                            .map(|c| resp_bulk(c.as_bytes().to_vec()))
+                            .collect(),
+                    ),
+                    None => resp_err(REDIS_ACL_UNKNOWN_CATEGORY_ERR),
+                }
+            }
+        }
+    }
+}
+
+impl Invalid for Acl {
+    fn new_invalid() -> Acl {
+        Acl {
+            subcommand: AclSubcommand::List,
+            valid: false,
+        }
+    }
+}
@@ -1,22 +1,28 @@
-use crate::Parse;
+use crate::parse::{Parse, ParseError};
 use serde::{Deserialize, Serialize};
 
 use crate::cmd::Invalid;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Auth {
+    username: Option<String>,
     passwd: String,
     valid: bool,
 }
 
 impl Auth {
-    pub fn new(passwd: String) -> Auth {
+    pub fn new(username: Option<String>, passwd: String) -> Auth {
         Auth {
+            username,
             passwd,
             valid: true,
         }
     }
 
+    pub fn username(&self) -> Option<&str> {
+        self.username.as_deref()
+    }
+
     pub fn passwd(&self) -> &str {
         &self.passwd
     }
@@ -26,18 +32,20 @@ impl Auth {
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Auth> {
-        let passwd = parse.next_string()?;
-
-        Ok(Auth {
-            passwd,
-            valid: true,
-        })
+        // `AUTH password` or `AUTH username password`
+        let first = parse.next_string()?;
+        match parse.next_string() {
+            Ok(passwd) => Ok(Auth::new(Some(first), passwd)),
+            Err(ParseError::EndOfStream) => Ok(Auth::new(None, first)),
+            Err(e) => Err(e.into()),
+        }
     }
 }
 
 impl Invalid for Auth {
     fn new_invalid() -> Auth {
         Auth {
+            username: None,
             passwd: "".to_owned(),
             valid: false,
         }
@@ -0,0 +1,133 @@
+use crate::cmd::Invalid;
+use crate::config::LOGGER;
+use crate::parse::{Parse, ParseError};
+use crate::{Connection, Frame};
+use serde::{Deserialize, Serialize};
+use slog::debug;
+
+use crate::rocks::errors::{REDIS_BITFIELD_RO_ERR, REDIS_BITFIELD_TYPE_ERR, REDIS_BIT_OFFSET_ERR};
+use crate::rocks::string::StringCommand;
+use crate::rocks::{get_client, Result as RocksResult};
+use crate::utils::{resp_err, resp_invalid_arguments};
+
+// same as redis, strings are at most 512MB
+const BITFIELD_MAX_OFFSET: u64 = 512 * 1024 * 1024 * 8;
+
+/// `BITFIELD_RO key [GET encoding offset ...]`, reads integers of arbitrary
+/// width at arbitrary bit offsets of a string.
+///
+/// Encodings are `i1` to `i64` and `u1` to `u63`, an offset prefixed by `#`
+/// is multiplied by the width of the encoding.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Bitfield {
+    key: String,
+    // (encoding, offset) of every GET, validated on execution
+    gets: Vec<(String, String)>,
+    // a SET, INCRBY or OVERFLOW subcommand was given
+    write: bool,
+    valid: bool,
+}
+
+impl Bitfield {
+    pub fn new(key: &str) -> Bitfield {
+        Bitfield {
+            key: key.to_owned(),
+            gets: vec![],
+            write: false,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Bitfield> {
+        let mut bitfield = Bitfield::new(&parse.next_string()?);
+        loop {
+            let subcommand = match parse.next_string() {
+                Ok(subcommand) => subcommand,
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            };
+            if subcommand.to_uppercase() == "GET" {
+                bitfield
+                    .gets
+                    .push((parse.next_string()?, parse.next_string()?));
+            } else {
+                // the remaining arguments are not parsed, the command is
+                // rejected anyway
+                bitfield.write = true;
+                while parse.next_bytes().is_ok() {}
+                break;
+            }
+        }
+        Ok(bitfield)
+    }
+
+    pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.bitfield_ro().await?;
+        debug!(LOGGER, "res, {:?}", response);
+        dst.write_frame(&response).await?;
+        Ok(())
+    }
+
+    pub async fn bitfield_ro(&self) -> RocksResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if self.write {
+            return Ok(resp_err(REDIS_BITFIELD_RO_ERR));
+        }
+        let mut gets = Vec::with_capacity(self.gets.len());
+        for (encoding, offset) in &self.gets {
+            let (signed, bits) = match parse_encoding(encoding) {
+                Some(encoding) => encoding,
+                None => return Ok(resp_err(REDIS_BITFIELD_TYPE_ERR)),
+            };
+            let offset = match parse_offset(offset, bits) {
+                Some(offset) => offset,
+                None => return Ok(resp_err(REDIS_BIT_OFFSET_ERR)),
+            };
+            gets.push((signed, bits, offset));
+        }
+        StringCommand::new(&get_client())
+            .bitfield_get(&self.key, &gets)
+            .await
+    }
+
+    pub fn hash_ring_key(&self) -> crate::Result<String> {
+        Ok(self.key.to_string())
+    }
+}
+
+// `i<bits>` or `u<bits>`, unsigned integers are at most 63 bits so they fit
+// the integer reply
+fn parse_encoding(encoding: &str) -> Option<(bool, u32)> {
+    let (signed, max_bits) = match encoding.get(..1)? {
+        "i" | "I" => (true, 64),
+        "u" | "U" => (false, 63),
+        _ => return None,
+    };
+    let bits: u32 = encoding[1..].parse().ok()?;
+    (1..=max_bits).contains(&bits).then_some((signed, bits))
+}
+
+fn parse_offset(offset: &str, bits: u32) -> Option<u64> {
+    let offset = match offset.strip_prefix('#') {
+        Some(index) => index.parse::<u64>().ok()?.checked_mul(bits as u64)?,
+        None => offset.parse().ok()?,
+    };
+    (offset.checked_add(bits as u64)? <= BITFIELD_MAX_OFFSET).then_some(offset)
+}
+
+impl Invalid for Bitfield {
+    fn new_invalid() -> Bitfield {
+        Bitfield {
+            key: "".to_owned(),
+            gets: vec![],
+            write: false,
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{Command, Db, Frame, MapucheError, Parse};
+
+use crate::cmd::{execute_replicated, Invalid};
+use crate::rocks::errors::{RError, REDIS_TIMEOUT_NEGATIVE_ERR};
+use crate::rocks::list::ListCommand;
+use crate::rocks::{get_client, Result as RocksResult};
+use crate::utils::{resp_err, resp_invalid_arguments, resp_nil};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::{self, Instant};
+
+/// `BRPOPLPUSH source destination timeout`, moves the tail element of
+/// `source` to the head of `destination`, waiting up to `timeout` seconds
+/// for `source` to get elements. A timeout of 0 blocks forever.
+///
+/// The client waits on the node which received it, every attempt to move an
+/// element is replicated like `RPOPLPUSH`, which never blocks.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Brpoplpush {
+    source: String,
+    destination: String,
+    timeout: f64,
+    valid: bool,
+}
+
+impl Brpoplpush {
+    pub fn new(source: &str, destination: &str, timeout: f64) -> Brpoplpush {
+        Brpoplpush {
+            source: source.to_owned(),
+            destination: destination.to_owned(),
+            timeout,
+            valid: true,
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Brpoplpush> {
+        let source = parse.next_string()?;
+        let destination = parse.next_string()?;
+        let timeout = parse.next_string()?.parse::<f64>()?;
+        if !timeout.is_finite() {
+            return Ok(Brpoplpush::new_invalid());
+        }
+        Ok(Brpoplpush::new(&source, &destination, timeout))
+    }
+
+    /// Move an element without blocking, nil if `source` is empty.
+    pub async fn rpoplpush(&self) -> RocksResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        ListCommand::new(&get_client())
+            .rpoplpush(&self.source, &self.destination)
+            .await
+    }
+
+    pub(crate) async fn execute(&self, db: &Db) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        if self.timeout < 0.0 {
+            return resp_err(REDIS_TIMEOUT_NEGATIVE_ERR);
+        }
+        let deadline =
+            (self.timeout > 0.0).then(|| Instant::now() + Duration::from_secs_f64(self.timeout));
+        let waiter = db.list_waiter(&self.source);
+        let frame = loop {
+            let pushed = waiter.notified();
+            let frame = match execute_replicated(Command::Brpoplpush(self.clone())).await {
+                Ok(frame) => frame,
+                Err(e) => resp_err(RError::owned_error(format!("ERR {e}"))),
+            };
+            if !matches!(frame, Frame::Null) {
+                break frame;
+            }
+            match deadline {
+                Some(deadline) => {
+                    if time::timeout_at(deadline, pushed).await.is_err() {
+                        break resp_nil();
+                    }
+                }
+                None => pushed.await,
+            }
+        };
+        db.release_list_waiter(&self.source, waiter);
+        frame
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        vec![self.source.clone(), self.destination.clone()]
+    }
+
+    pub fn hash_ring_key(&self) -> crate::Result<String> {
+        if self.source != self.destination {
+            return Err(MapucheError::String("Cmd don't support cluster").into());
+        }
+        Ok(self.source.to_string())
+    }
+}
+
+impl Invalid for Brpoplpush {
+    fn new_invalid() -> Brpoplpush {
+        Brpoplpush {
+            source: "".to_owned(),
+            destination: "".to_owned(),
+            timeout: 0.0,
+            valid: false,
+        }
+    }
+}
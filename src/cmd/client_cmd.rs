@@ -0,0 +1,201 @@
+use crate::client::Client;
+use crate::cmd::Invalid;
+use crate::parse::ParseError;
+use crate::rocks::errors::{REDIS_INVALID_CLIENT_NAME_ERR, REDIS_NO_SUCH_CLIENT_ERR};
+use crate::utils::{resp_bulk, resp_err, resp_int, resp_invalid_arguments, resp_nil, resp_ok};
+use crate::{Db, Frame, Parse};
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ClientSubcommand {
+    Id,
+    SetName(String),
+    GetName,
+    Info,
+    List,
+    KillId(u64),
+    // old style `CLIENT KILL ip:port` replies OK or error instead of count
+    KillAddr { addr: String, legacy: bool },
+    Pause { timeout_ms: u64, write_only: bool },
+    Unpause,
+}
+
+/// Client connection management,
+/// `CLIENT ID|SETNAME|GETNAME|INFO|LIST|KILL|PAUSE|UNPAUSE`.
+///
+/// These only touch the connection state, they are executed by the
+/// connection handler and never forwarded to other nodes.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClientCmd {
+    subcommand: ClientSubcommand,
+    valid: bool,
+}
+
+impl ClientCmd {
+    pub fn new(subcommand: ClientSubcommand) -> ClientCmd {
+        ClientCmd {
+            subcommand,
+            valid: true,
+        }
+    }
+
+    pub fn subcommand(&self) -> &ClientSubcommand {
+        &self.subcommand
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<ClientCmd> {
+        let subcommand = match parse.next_string()?.to_lowercase().as_str() {
+            "id" => ClientSubcommand::Id,
+            "setname" => ClientSubcommand::SetName(parse.next_string()?),
+            "getname" => ClientSubcommand::GetName,
+            "info" => ClientSubcommand::Info,
+            "list" => ClientSubcommand::List,
+            "kill" => {
+                let arg = parse.next_string()?;
+                match arg.to_lowercase().as_str() {
+                    "id" => ClientSubcommand::KillId(parse.next_int()? as u64),
+                    "addr" => ClientSubcommand::KillAddr {
+                        addr: parse.next_string()?,
+                        legacy: false,
+                    },
+                    _ => ClientSubcommand::KillAddr {
+                        addr: arg,
+                        legacy: true,
+                    },
+                }
+            }
+            "pause" => {
+                let timeout_ms = parse.next_int()?;
+                let write_only = match parse.next_string() {
+                    Ok(mode) => match mode.to_lowercase().as_str() {
+                        "write" => true,
+                        "all" => false,
+                        _ => return Ok(ClientCmd::new_invalid()),
+                    },
+                    Err(ParseError::EndOfStream) => false,
+                    Err(e) => return Err(e.into()),
+                };
+                if timeout_ms < 0 {
+                    return Ok(ClientCmd::new_invalid());
+                }
+                ClientSubcommand::Pause {
+                    timeout_ms: timeout_ms as u64,
+                    write_only,
+                }
+            }
+            "unpause" => ClientSubcommand::Unpause,
+            _ => return Ok(ClientCmd::new_invalid()),
+        };
+        Ok(ClientCmd::new(subcommand))
+    }
+
+    pub(crate) async fn execute(
+        &self,
+        cur_client: &Arc<Mutex<Client>>,
+        clients: &Arc<Mutex<HashMap<u64, Arc<Mutex<Client>>>>>,
+        db: &Db,
+    ) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        match &self.subcommand {
+            ClientSubcommand::Id => resp_int(cur_client.lock().await.id() as i64),
+            ClientSubcommand::SetName(name) => {
+                if !valid_client_name(name) {
+                    return resp_err(REDIS_INVALID_CLIENT_NAME_ERR);
+                }
+                cur_client.lock().await.set_name(name);
+                resp_ok()
+            }
+            ClientSubcommand::GetName => {
+                let client = cur_client.lock().await;
+                if client.name().is_empty() {
+                    resp_nil()
+                } else {
+                    resp_bulk(client.name().as_bytes().to_vec())
+                }
+            }
+            ClientSubcommand::Info => {
+                let mut info = cur_client.lock().await.to_string();
+                info.push('\n');
+                Frame::Bulk(Bytes::from(info))
+            }
+            ClientSubcommand::List => {
+                let clients: Vec<Arc<Mutex<Client>>> =
+                    clients.lock().await.values().cloned().collect();
+                let mut lines = Vec::with_capacity(clients.len());
+                for client in clients {
+                    let client = client.lock().await;
+                    lines.push((client.id(), client.to_string()));
+                }
+                lines.sort_by_key(|(id, _)| *id);
+                let lines: Vec<String> = lines.into_iter().map(|(_, line)| line).collect();
+                let mut list = lines.join("\n");
+                list.push('\n');
+                Frame::Bulk(Bytes::from(list))
+            }
+            ClientSubcommand::KillId(id) => {
+                let killed = Self::kill(clients, |client| client.id() == *id).await;
+                resp_int(killed)
+            }
+            ClientSubcommand::KillAddr { addr, legacy } => {
+                let killed = Self::kill(clients, |client| client.addr() == addr.as_str()).await;
+                match (legacy, killed) {
+                    (false, n) => resp_int(n),
+                    (true, 0) => resp_err(REDIS_NO_SUCH_CLIENT_ERR),
+                    (true, _) => resp_ok(),
+                }
+            }
+            ClientSubcommand::Pause {
+                timeout_ms,
+                write_only,
+            } => {
+                db.pause_clients(Duration::from_millis(*timeout_ms), *write_only);
+                resp_ok()
+            }
+            ClientSubcommand::Unpause => {
+                db.unpause_clients();
+                resp_ok()
+            }
+        }
+    }
+
+    // signal the matched connections to close, the connection of the caller
+    // is closed after the reply is written
+    async fn kill<F>(clients: &Arc<Mutex<HashMap<u64, Arc<Mutex<Client>>>>>, matches: F) -> i64
+    where
+        F: Fn(&Client) -> bool,
+    {
+        let clients: Vec<Arc<Mutex<Client>>> = clients.lock().await.values().cloned().collect();
+        let mut killed = 0;
+        for client in clients {
+            let client = client.lock().await;
+            if matches(&client) {
+                // a full channel means the kill signal is already pending
+                let _ = client.kill_sender().try_send(());
+                killed += 1;
+            }
+        }
+        killed
+    }
+}
+
+/// Same as redis, spaces, newlines and other special chars are not allowed in
+/// client names, an empty name clears the current one.
+pub(crate) fn valid_client_name(name: &str) -> bool {
+    !name.chars().any(|c| c <= ' ' || c > '~')
+}
+
+impl Invalid for ClientCmd {
+    fn new_invalid() -> ClientCmd {
+        ClientCmd {
+            subcommand: ClientSubcommand::Id,
+            valid: false,
+        }
+    }
+}
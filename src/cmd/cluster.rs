@@ -0,0 +1,169 @@
+use crate::cache::cache_clear;
+use crate::cmd::Invalid;
+use crate::config::LOGGER;
+use crate::parse::{Parse, ParseError};
+use crate::raft::get_raft_app;
+use crate::{Connection, Frame, RING_NODES};
+use serde::{Deserialize, Serialize};
+use slog::{debug, info};
+
+use crate::rocks::errors::{
+    REDIS_CLUSTER_RESET_VOTER_ERR, REDIS_INVALID_NUMBER_OF_KEYS_ERR, REDIS_INVALID_SLOT_ERR,
+};
+use crate::rocks::string::StringCommand;
+use crate::rocks::{get_client, get_instance_id, set_instance_id, Result as RocksResult};
+use crate::utils::{
+    resp_array, resp_bulk, resp_err, resp_int, resp_invalid_arguments, resp_ok, CLUSTER_SLOTS,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ClusterSubcmd {
+    GetKeysInSlot { slot: i64, count: i64 },
+    CountKeysInSlot { slot: i64 },
+    Reset { hard: bool },
+    Myid,
+}
+
+/// `CLUSTER GETKEYSINSLOT slot count|COUNTKEYSINSLOT slot|RESET [HARD|SOFT]|MYID`,
+/// the slot of a key is computed like redis cluster so clients can reshard
+/// the keys.
+///
+/// Both slot subcommands scan the whole keyspace of the node, they are slow
+/// admin commands.
+///
+/// `RESET` is executed on the node which received it. `SOFT`, the default,
+/// drops the hash ring so the node serves every key locally, `HARD`
+/// additionally deletes all keys, the application data of the raft state
+/// machine and resets the instance id. Both are refused while the node is a
+/// raft voter.
+///
+/// `MYID` replies the instance id of the node which received it, as a 40
+/// characters hex string like the node ids of redis.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Cluster {
+    subcommand: ClusterSubcmd,
+    valid: bool,
+}
+
+impl Cluster {
+    pub fn new(subcommand: ClusterSubcmd) -> Cluster {
+        Cluster {
+            subcommand,
+            valid: true,
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Cluster> {
+        let subcommand = match parse.next_string()?.to_lowercase().as_str() {
+            "getkeysinslot" => ClusterSubcmd::GetKeysInSlot {
+                slot: parse.next_int()?,
+                count: parse.next_int()?,
+            },
+            "countkeysinslot" => ClusterSubcmd::CountKeysInSlot {
+                slot: parse.next_int()?,
+            },
+            "reset" => match parse.next_string() {
+                Ok(mode) if mode.eq_ignore_ascii_case("hard") => {
+                    ClusterSubcmd::Reset { hard: true }
+                }
+                Ok(mode) if mode.eq_ignore_ascii_case("soft") => {
+                    ClusterSubcmd::Reset { hard: false }
+                }
+                Err(ParseError::EndOfStream) => ClusterSubcmd::Reset { hard: false },
+                _ => return Ok(Cluster::new_invalid()),
+            },
+            "myid" => ClusterSubcmd::Myid,
+            _ => return Ok(Cluster::new_invalid()),
+        };
+        Ok(Cluster::new(subcommand))
+    }
+
+    pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.cluster().await?;
+        debug!(LOGGER, "res, {:?}", response);
+        dst.write_frame(&response).await?;
+        Ok(())
+    }
+
+    // subcommands executed on the node which received them
+    pub(crate) fn is_node_local(&self) -> bool {
+        matches!(
+            self.subcommand,
+            ClusterSubcmd::Reset { .. } | ClusterSubcmd::Myid
+        )
+    }
+
+    pub async fn cluster(&self) -> RocksResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let (slot, count) = match self.subcommand {
+            ClusterSubcmd::GetKeysInSlot { slot, count } => (slot, Some(count)),
+            ClusterSubcmd::CountKeysInSlot { slot } => (slot, None),
+            ClusterSubcmd::Reset { hard } => return reset(hard).await,
+            ClusterSubcmd::Myid => return Ok(resp_bulk(node_id(get_instance_id()).into_bytes())),
+        };
+        if !(0..CLUSTER_SLOTS as i64).contains(&slot) {
+            return Ok(resp_err(REDIS_INVALID_SLOT_ERR));
+        }
+        let client = get_client();
+        match count {
+            Some(count) if count < 0 => Ok(resp_err(REDIS_INVALID_NUMBER_OF_KEYS_ERR)),
+            Some(count) => {
+                let keys = StringCommand::new(&client)
+                    .keys_in_slot(slot as u16, count as usize)
+                    .await?;
+                Ok(resp_array(keys.into_iter().map(resp_bulk).collect()))
+            }
+            None => {
+                let keys = StringCommand::new(&client)
+                    .keys_in_slot(slot as u16, usize::MAX)
+                    .await?;
+                Ok(resp_int(keys.len() as i64))
+            }
+        }
+    }
+}
+
+// the hex instance id zero padded to 16 characters, then right padded to the
+// 40 characters of a redis node id
+fn node_id(instance_id: u64) -> String {
+    format!("{:0<40}", format!("{instance_id:016x}"))
+}
+
+async fn reset(hard: bool) -> RocksResult<Frame> {
+    if let Some(app) = get_raft_app() {
+        let metrics = app.raft.metrics();
+        let is_voter = metrics
+            .borrow()
+            .membership_config
+            .membership()
+            .voter_ids()
+            .any(|id| id == app.id);
+        if is_voter {
+            return Ok(resp_err(REDIS_CLUSTER_RESET_VOTER_ERR));
+        }
+    }
+    unsafe {
+        RING_NODES = None;
+    }
+    if hard {
+        get_client().flush_all()?;
+        cache_clear();
+        if let Some(app) = get_raft_app() {
+            app.store.clear_state_machine_data().await?;
+        }
+        set_instance_id(0);
+    }
+    info!(LOGGER, "cluster reset, hard: {}", hard);
+    Ok(resp_ok())
+}
+
+impl Invalid for Cluster {
+    fn new_invalid() -> Cluster {
+        Cluster {
+            subcommand: ClusterSubcmd::CountKeysInSlot { slot: 0 },
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,1127 @@
+use crate::utils::{resp_array, resp_bulk, resp_str};
+use crate::Frame;
+
+/// Argument of a command in `COMMAND DOCS`, `oneof` and `block` arguments
+/// hold their own arguments.
+pub struct ArgDoc {
+    name: &'static str,
+    arg_type: &'static str,
+    optional: bool,
+    multiple: bool,
+    arguments: &'static [ArgDoc],
+}
+
+impl ArgDoc {
+    const fn new(name: &'static str, arg_type: &'static str) -> ArgDoc {
+        ArgDoc {
+            name,
+            arg_type,
+            optional: false,
+            multiple: false,
+            arguments: &[],
+        }
+    }
+
+    const fn optional(self) -> ArgDoc {
+        ArgDoc {
+            optional: true,
+            ..self
+        }
+    }
+
+    const fn multiple(self) -> ArgDoc {
+        ArgDoc {
+            multiple: true,
+            ..self
+        }
+    }
+
+    fn to_frame(&self) -> Frame {
+        let mut flags = vec![];
+        if self.optional {
+            flags.push(resp_str("optional"));
+        }
+        if self.multiple {
+            flags.push(resp_str("multiple"));
+        }
+        let mut pairs = vec![
+            (resp_str("name"), resp_bulk(self.name.as_bytes().to_vec())),
+            (
+                resp_str("type"),
+                resp_bulk(self.arg_type.as_bytes().to_vec()),
+            ),
+            (resp_str("flags"), resp_array(flags)),
+        ];
+        if !self.arguments.is_empty() {
+            pairs.push((
+                resp_str("arguments"),
+                resp_array(self.arguments.iter().map(ArgDoc::to_frame).collect()),
+            ));
+        }
+        Frame::Map(pairs)
+    }
+}
+
+const fn key(name: &'static str) -> ArgDoc {
+    ArgDoc::new(name, "key")
+}
+
+const fn string(name: &'static str) -> ArgDoc {
+    ArgDoc::new(name, "string")
+}
+
+const fn integer(name: &'static str) -> ArgDoc {
+    ArgDoc::new(name, "integer")
+}
+
+const fn double(name: &'static str) -> ArgDoc {
+    ArgDoc::new(name, "double")
+}
+
+const fn pattern(name: &'static str) -> ArgDoc {
+    ArgDoc::new(name, "pattern")
+}
+
+const fn unix_time(name: &'static str) -> ArgDoc {
+    ArgDoc::new(name, "unix-time")
+}
+
+const fn token(name: &'static str) -> ArgDoc {
+    ArgDoc::new(name, "pure-token")
+}
+
+const fn oneof(name: &'static str, arguments: &'static [ArgDoc]) -> ArgDoc {
+    ArgDoc {
+        arguments,
+        ..ArgDoc::new(name, "oneof")
+    }
+}
+
+const fn block(name: &'static str, arguments: &'static [ArgDoc]) -> ArgDoc {
+    ArgDoc {
+        arguments,
+        ..ArgDoc::new(name, "block")
+    }
+}
+
+/// Documentation of a command replied by `COMMAND DOCS`, with the summary,
+/// version and complexity of the redis command it implements.
+pub struct CommandDoc {
+    name: &'static str,
+    summary: &'static str,
+    since: &'static str,
+    group: &'static str,
+    complexity: &'static str,
+    arguments: &'static [ArgDoc],
+}
+
+impl CommandDoc {
+    pub fn to_frame(&self) -> Frame {
+        let field = |s: &str| resp_bulk(s.as_bytes().to_vec());
+        Frame::Map(vec![
+            (resp_str("summary"), field(self.summary)),
+            (resp_str("since"), field(self.since)),
+            (resp_str("group"), field(self.group)),
+            (resp_str("complexity"), field(self.complexity)),
+            (
+                resp_str("arguments"),
+                resp_array(self.arguments.iter().map(ArgDoc::to_frame).collect()),
+            ),
+        ])
+    }
+}
+
+/// Documentation of the command `name`, in lower case.
+pub fn command_doc(name: &str) -> Option<&'static CommandDoc> {
+    COMMAND_DOCS.iter().find(|doc| doc.name == name)
+}
+
+/// Documentation of every supported command, as `(name, doc)`.
+pub fn command_docs() -> impl Iterator<Item = (&'static str, &'static CommandDoc)> {
+    COMMAND_DOCS.iter().map(|doc| (doc.name, doc))
+}
+
+const COMMAND_DOCS: &[CommandDoc] = &[
+    // keys
+    CommandDoc {
+        name: "del",
+        summary: "Deletes one or more keys.",
+        since: "1.0.0",
+        group: "generic",
+        complexity: "O(N) where N is the number of keys that will be removed.",
+        arguments: &[key("key").multiple()],
+    },
+    CommandDoc {
+        name: "unlink",
+        summary: "Asynchronously deletes one or more keys.",
+        since: "4.0.0",
+        group: "generic",
+        complexity: "O(1) for each key removed regardless of its size.",
+        arguments: &[key("key").multiple()],
+    },
+    CommandDoc {
+        name: "exists",
+        summary: "Determines whether one or more keys exist.",
+        since: "1.0.0",
+        group: "generic",
+        complexity: "O(N) where N is the number of keys to check.",
+        arguments: &[key("key").multiple()],
+    },
+    CommandDoc {
+        name: "expire",
+        summary: "Sets the expiration time of a key in seconds.",
+        since: "1.0.0",
+        group: "generic",
+        complexity: "O(1)",
+        arguments: &[key("key"), integer("seconds")],
+    },
+    CommandDoc {
+        name: "expireat",
+        summary: "Sets the expiration time of a key to a Unix timestamp.",
+        since: "1.2.0",
+        group: "generic",
+        complexity: "O(1)",
+        arguments: &[key("key"), unix_time("unix-time-seconds")],
+    },
+    CommandDoc {
+        name: "pexpire",
+        summary: "Sets the expiration time of a key in milliseconds.",
+        since: "2.6.0",
+        group: "generic",
+        complexity: "O(1)",
+        arguments: &[key("key"), integer("milliseconds")],
+    },
+    CommandDoc {
+        name: "pexpireat",
+        summary: "Sets the expiration time of a key to a Unix milliseconds timestamp.",
+        since: "2.6.0",
+        group: "generic",
+        complexity: "O(1)",
+        arguments: &[key("key"), unix_time("unix-time-milliseconds")],
+    },
+    CommandDoc {
+        name: "ttl",
+        summary: "Returns the expiration time in seconds of a key.",
+        since: "1.0.0",
+        group: "generic",
+        complexity: "O(1)",
+        arguments: &[key("key")],
+    },
+    CommandDoc {
+        name: "pttl",
+        summary: "Returns the expiration time in milliseconds of a key.",
+        since: "2.6.0",
+        group: "generic",
+        complexity: "O(1)",
+        arguments: &[key("key")],
+    },
+    CommandDoc {
+        name: "type",
+        summary: "Determines the type of value stored at a key.",
+        since: "1.0.0",
+        group: "generic",
+        complexity: "O(1)",
+        arguments: &[key("key")],
+    },
+    CommandDoc {
+        name: "object",
+        summary: "A container for object introspection commands.",
+        since: "2.2.3",
+        group: "generic",
+        complexity: "Depends on subcommand.",
+        arguments: &[],
+    },
+    CommandDoc {
+        name: "dump",
+        summary: "Returns a serialized representation of the value stored at a key.",
+        since: "2.6.0",
+        group: "generic",
+        complexity: "O(1) to access the key and additional O(N*M) to serialize it, where N is the number of Redis objects composing the value and M their average size.",
+        arguments: &[key("key")],
+    },
+    CommandDoc {
+        name: "restore",
+        summary: "Creates a key from the serialized representation of a value.",
+        since: "2.6.0",
+        group: "generic",
+        complexity: "O(1) to create the new key and additional O(N*M) to reconstruct the serialized value, where N is the number of Redis objects composing the value and M their average size.",
+        arguments: &[
+            key("key"),
+            integer("ttl"),
+            string("serialized-value"),
+            token("replace").optional(),
+        ],
+    },
+    CommandDoc {
+        name: "sort",
+        summary: "Sorts the elements in a list, a set, or a sorted set, optionally storing the result.",
+        since: "1.0.0",
+        group: "generic",
+        complexity: "O(N+M*log(M)) where N is the number of elements in the list or set to sort, and M the number of returned elements.",
+        arguments: &[
+            key("key"),
+            pattern("by-pattern").optional(),
+            block("limit", &[integer("offset"), integer("count")]).optional(),
+            pattern("get-pattern").optional().multiple(),
+            oneof("order", &[token("asc"), token("desc")]).optional(),
+            token("sorting").optional(),
+            key("destination").optional(),
+        ],
+    },
+    CommandDoc {
+        name: "keys",
+        summary: "Returns all key names that match a pattern.",
+        since: "1.0.0",
+        group: "generic",
+        complexity: "O(N) with N being the number of keys in the database.",
+        arguments: &[pattern("pattern")],
+    },
+    CommandDoc {
+        name: "scan",
+        summary: "Iterates over the key names in the database.",
+        since: "2.8.0",
+        group: "generic",
+        complexity: "O(1) for every call. O(N) for a complete iteration.",
+        arguments: &[
+            integer("cursor"),
+            pattern("pattern").optional(),
+            integer("count").optional(),
+        ],
+    },
+    CommandDoc {
+        name: "wait",
+        summary: "Blocks until the asynchronous replication of all preceding write commands sent by the connection is completed.",
+        since: "3.0.0",
+        group: "generic",
+        complexity: "O(1)",
+        arguments: &[integer("numreplicas"), integer("timeout")],
+    },
+    CommandDoc {
+        name: "cluster",
+        summary: "A container for Redis Cluster commands.",
+        since: "3.0.0",
+        group: "cluster",
+        complexity: "Depends on subcommand.",
+        arguments: &[],
+    },
+    // string
+    CommandDoc {
+        name: "get",
+        summary: "Returns the string value of a key.",
+        since: "1.0.0",
+        group: "string",
+        complexity: "O(1)",
+        arguments: &[key("key")],
+    },
+    CommandDoc {
+        name: "set",
+        summary: "Sets the string value of a key, ignoring its type. The key is created if it doesn't exist.",
+        since: "1.0.0",
+        group: "string",
+        complexity: "O(1)",
+        arguments: &[
+            key("key"),
+            string("value"),
+            oneof("expiration", &[integer("seconds"), integer("milliseconds")]).optional(),
+            oneof("condition", &[token("nx"), token("xx")]).optional(),
+        ],
+    },
+    CommandDoc {
+        name: "getset",
+        summary: "Returns the previous string value of a key after setting it to a new value.",
+        since: "1.0.0",
+        group: "string",
+        complexity: "O(1)",
+        arguments: &[key("key"), string("value")],
+    },
+    CommandDoc {
+        name: "mget",
+        summary: "Atomically returns the string values of one or more keys.",
+        since: "1.0.0",
+        group: "string",
+        complexity: "O(N) where N is the number of keys to retrieve.",
+        arguments: &[key("key").multiple()],
+    },
+    CommandDoc {
+        name: "mset",
+        summary: "Atomically creates or modifies the string values of one or more keys.",
+        since: "1.0.1",
+        group: "string",
+        complexity: "O(N) where N is the number of keys to set.",
+        arguments: &[block("data", &[key("key"), string("value")]).multiple()],
+    },
+    CommandDoc {
+        name: "incr",
+        summary: "Increments the integer value of a key by one. Uses 0 as initial value if the key doesn't exist.",
+        since: "1.0.0",
+        group: "string",
+        complexity: "O(1)",
+        arguments: &[key("key")],
+    },
+    CommandDoc {
+        name: "incrby",
+        summary: "Increments the integer value of a key by a number. Uses 0 as initial value if the key doesn't exist.",
+        since: "1.0.0",
+        group: "string",
+        complexity: "O(1)",
+        arguments: &[key("key"), integer("increment")],
+    },
+    CommandDoc {
+        name: "decr",
+        summary: "Decrements the integer value of a key by one. Uses 0 as initial value if the key doesn't exist.",
+        since: "1.0.0",
+        group: "string",
+        complexity: "O(1)",
+        arguments: &[key("key")],
+    },
+    CommandDoc {
+        name: "decrby",
+        summary: "Decrements a number from the integer value of a key. Uses 0 as initial value if the key doesn't exist.",
+        since: "1.0.0",
+        group: "string",
+        complexity: "O(1)",
+        arguments: &[key("key"), integer("decrement")],
+    },
+    CommandDoc {
+        name: "strlen",
+        summary: "Returns the length of a string value.",
+        since: "2.2.0",
+        group: "string",
+        complexity: "O(1)",
+        arguments: &[key("key")],
+    },
+    CommandDoc {
+        name: "bitfield_ro",
+        summary: "Performs arbitrary read-only bitfield integer operations on strings.",
+        since: "6.0.0",
+        group: "bitmap",
+        complexity: "O(1) for each subcommand specified",
+        arguments: &[
+            key("key"),
+            block("get-block", &[string("encoding"), integer("offset")])
+                .optional()
+                .multiple(),
+        ],
+    },
+    // hash
+    CommandDoc {
+        name: "hget",
+        summary: "Returns the value of a field in a hash.",
+        since: "2.0.0",
+        group: "hash",
+        complexity: "O(1)",
+        arguments: &[key("key"), string("field")],
+    },
+    CommandDoc {
+        name: "hstrlen",
+        summary: "Returns the length of the value of a field.",
+        since: "3.2.0",
+        group: "hash",
+        complexity: "O(1)",
+        arguments: &[key("key"), string("field")],
+    },
+    CommandDoc {
+        name: "hexists",
+        summary: "Determines whether a field exists in a hash.",
+        since: "2.0.0",
+        group: "hash",
+        complexity: "O(1)",
+        arguments: &[key("key"), string("field")],
+    },
+    CommandDoc {
+        name: "hlen",
+        summary: "Returns the number of fields in a hash.",
+        since: "2.0.0",
+        group: "hash",
+        complexity: "O(1)",
+        arguments: &[key("key")],
+    },
+    CommandDoc {
+        name: "hmget",
+        summary: "Returns the values of all fields in a hash.",
+        since: "2.0.0",
+        group: "hash",
+        complexity: "O(N) where N is the number of fields being requested.",
+        arguments: &[key("key"), string("field").multiple()],
+    },
+    CommandDoc {
+        name: "hdel",
+        summary: "Deletes one or more fields and their values from a hash. Deletes the hash if no fields remain.",
+        since: "2.0.0",
+        group: "hash",
+        complexity: "O(N) where N is the number of fields to be removed.",
+        arguments: &[key("key"), string("field").multiple()],
+    },
+    CommandDoc {
+        name: "hset",
+        summary: "Creates or modifies the value of a field in a hash.",
+        since: "2.0.0",
+        group: "hash",
+        complexity: "O(1) for each field/value pair added, so O(N) to add N field/value pairs when the command is called with multiple field/value pairs.",
+        arguments: &[
+            key("key"),
+            block("data", &[string("field"), string("value")]).multiple(),
+        ],
+    },
+    CommandDoc {
+        name: "hsetnx",
+        summary: "Sets the value of a field in a hash only when the field doesn't exist.",
+        since: "2.0.0",
+        group: "hash",
+        complexity: "O(1)",
+        arguments: &[key("key"), string("field"), string("value")],
+    },
+    CommandDoc {
+        name: "hmset",
+        summary: "Sets the values of multiple fields.",
+        since: "2.0.0",
+        group: "hash",
+        complexity: "O(N) where N is the number of fields being set.",
+        arguments: &[
+            key("key"),
+            block("data", &[string("field"), string("value")]).multiple(),
+        ],
+    },
+    CommandDoc {
+        name: "hkeys",
+        summary: "Returns all fields in a hash.",
+        since: "2.0.0",
+        group: "hash",
+        complexity: "O(N) where N is the size of the hash.",
+        arguments: &[key("key")],
+    },
+    CommandDoc {
+        name: "hvals",
+        summary: "Returns all values in a hash.",
+        since: "2.0.0",
+        group: "hash",
+        complexity: "O(N) where N is the size of the hash.",
+        arguments: &[key("key")],
+    },
+    CommandDoc {
+        name: "hgetall",
+        summary: "Returns all fields and values in a hash.",
+        since: "2.0.0",
+        group: "hash",
+        complexity: "O(N) where N is the size of the hash.",
+        arguments: &[key("key")],
+    },
+    CommandDoc {
+        name: "hincrby",
+        summary: "Increments the integer value of a field in a hash by a number. Uses 0 as initial value if the field doesn't exist.",
+        since: "2.0.0",
+        group: "hash",
+        complexity: "O(1)",
+        arguments: &[key("key"), string("field"), integer("increment")],
+    },
+    // list
+    CommandDoc {
+        name: "lpush",
+        summary: "Prepends one or more elements to a list. Creates the key if it doesn't exist.",
+        since: "1.0.0",
+        group: "list",
+        complexity: "O(1) for each element added, so O(N) to add N elements when the command is called with multiple arguments.",
+        arguments: &[key("key"), string("element").multiple()],
+    },
+    CommandDoc {
+        name: "rpush",
+        summary: "Appends one or more elements to a list. Creates the key if it doesn't exist.",
+        since: "1.0.0",
+        group: "list",
+        complexity: "O(1) for each element added, so O(N) to add N elements when the command is called with multiple arguments.",
+        arguments: &[key("key"), string("element").multiple()],
+    },
+    CommandDoc {
+        name: "lpop",
+        summary: "Returns the first elements in a list after removing it. Deletes the list if the last element was popped.",
+        since: "1.0.0",
+        group: "list",
+        complexity: "O(N) where N is the number of elements returned",
+        arguments: &[key("key"), integer("count").optional()],
+    },
+    CommandDoc {
+        name: "rpop",
+        summary: "Returns and removes the last elements of the list. Deletes the list if the last element was popped.",
+        since: "1.0.0",
+        group: "list",
+        complexity: "O(N) where N is the number of elements returned",
+        arguments: &[key("key"), integer("count").optional()],
+    },
+    CommandDoc {
+        name: "lrange",
+        summary: "Returns a range of elements from a list.",
+        since: "1.0.0",
+        group: "list",
+        complexity: "O(S+N) where S is the distance of start offset from HEAD for small lists, from nearest end (HEAD or TAIL) for large lists; and N is the number of elements in the specified range.",
+        arguments: &[key("key"), integer("start"), integer("stop")],
+    },
+    CommandDoc {
+        name: "ltrim",
+        summary: "Removes elements from both ends a list. Deletes the list if all elements were trimmed.",
+        since: "1.0.0",
+        group: "list",
+        complexity: "O(N) where N is the number of elements to be removed by the operation.",
+        arguments: &[key("key"), integer("start"), integer("stop")],
+    },
+    CommandDoc {
+        name: "llen",
+        summary: "Returns the length of a list.",
+        since: "1.0.0",
+        group: "list",
+        complexity: "O(1)",
+        arguments: &[key("key")],
+    },
+    CommandDoc {
+        name: "lindex",
+        summary: "Returns an element from a list by its index.",
+        since: "1.0.0",
+        group: "list",
+        complexity: "O(N) where N is the number of elements to traverse to get to the element at index. This makes asking for the first or the last element of the list O(1).",
+        arguments: &[key("key"), integer("index")],
+    },
+    CommandDoc {
+        name: "lpos",
+        summary: "Returns the index of matching elements in a list.",
+        since: "6.0.6",
+        group: "list",
+        complexity: "O(N) where N is the number of elements in the list, for the average case. When searching for elements near the head or the tail of the list, or when the MAXLEN option is provided, the command may run in constant time.",
+        arguments: &[
+            key("key"),
+            string("element"),
+            integer("rank").optional(),
+            integer("num-matches").optional(),
+            integer("len").optional(),
+        ],
+    },
+    CommandDoc {
+        name: "lset",
+        summary: "Sets the value of an element in a list by its index.",
+        since: "1.0.0",
+        group: "list",
+        complexity: "O(N) where N is the length of the list. Setting either the first or the last element of the list is O(1).",
+        arguments: &[key("key"), integer("index"), string("element")],
+    },
+    CommandDoc {
+        name: "lrem",
+        summary: "Removes elements from a list. Deletes the list if the last element was removed.",
+        since: "1.0.0",
+        group: "list",
+        complexity: "O(N+M) where N is the length of the list and M is the number of elements removed.",
+        arguments: &[key("key"), integer("count"), string("element")],
+    },
+    CommandDoc {
+        name: "linsert",
+        summary: "Inserts an element before or after another element in a list.",
+        since: "2.2.0",
+        group: "list",
+        complexity: "O(N) where N is the number of elements to traverse before seeing the value pivot. This means that inserting somewhere on the left end on the list (head) can be considered O(1) and inserting somewhere on the right end (tail) is O(N).",
+        arguments: &[
+            key("key"),
+            oneof("where", &[token("before"), token("after")]),
+            string("pivot"),
+            string("element"),
+        ],
+    },
+    CommandDoc {
+        name: "lmpop",
+        summary: "Returns multiple elements from a list after removing them. Deletes the list if the last element was popped.",
+        since: "7.0.0",
+        group: "list",
+        complexity: "O(N+M) where N is the number of provided keys and M is the number of elements returned.",
+        arguments: &[
+            integer("numkeys"),
+            key("key").multiple(),
+            oneof("where", &[token("left"), token("right")]),
+            integer("count").optional(),
+        ],
+    },
+    CommandDoc {
+        name: "brpoplpush",
+        summary: "Pops an element from a list, pushes it to another list and returns it. Block until an element is available otherwise. Deletes the list if the last element was popped.",
+        since: "2.2.0",
+        group: "list",
+        complexity: "O(1)",
+        arguments: &[key("source"), key("destination"), double("timeout")],
+    },
+    // set
+    CommandDoc {
+        name: "sadd",
+        summary: "Adds one or more members to a set. Creates the key if it doesn't exist.",
+        since: "1.0.0",
+        group: "set",
+        complexity: "O(1) for each element added, so O(N) to add N elements when the command is called with multiple arguments.",
+        arguments: &[key("key"), string("member").multiple()],
+    },
+    CommandDoc {
+        name: "scard",
+        summary: "Returns the number of members in a set.",
+        since: "1.0.0",
+        group: "set",
+        complexity: "O(1)",
+        arguments: &[key("key")],
+    },
+    CommandDoc {
+        name: "sismember",
+        summary: "Determines whether a member belongs to a set.",
+        since: "1.0.0",
+        group: "set",
+        complexity: "O(1)",
+        arguments: &[key("key"), string("member")],
+    },
+    CommandDoc {
+        name: "smismember",
+        summary: "Determines whether multiple members belong to a set.",
+        since: "6.2.0",
+        group: "set",
+        complexity: "O(N) where N is the number of elements being checked for membership",
+        arguments: &[key("key"), string("member").multiple()],
+    },
+    CommandDoc {
+        name: "smembers",
+        summary: "Returns all members of a set.",
+        since: "1.0.0",
+        group: "set",
+        complexity: "O(N) where N is the set cardinality.",
+        arguments: &[key("key")],
+    },
+    CommandDoc {
+        name: "srandmember",
+        summary: "Get one or multiple random members from a set",
+        since: "1.0.0",
+        group: "set",
+        complexity: "Without the count argument O(1), otherwise O(N) where N is the absolute value of the passed count.",
+        arguments: &[key("key"), integer("count").optional()],
+    },
+    CommandDoc {
+        name: "spop",
+        summary: "Returns one or more random members from a set after removing them. Deletes the set if the last member was popped.",
+        since: "1.0.0",
+        group: "set",
+        complexity: "Without the count argument O(1), otherwise O(N) where N is the value of the passed count.",
+        arguments: &[key("key"), integer("count").optional()],
+    },
+    CommandDoc {
+        name: "srem",
+        summary: "Removes one or more members from a set. Deletes the set if the last member was removed.",
+        since: "1.0.0",
+        group: "set",
+        complexity: "O(N) where N is the number of members to be removed.",
+        arguments: &[key("key"), string("member").multiple()],
+    },
+    CommandDoc {
+        name: "sintercard",
+        summary: "Returns the number of members of the intersect of multiple sets.",
+        since: "7.0.0",
+        group: "set",
+        complexity: "O(N*M) worst case where N is the cardinality of the smallest set and M is the number of sets.",
+        arguments: &[
+            integer("numkeys"),
+            key("key").multiple(),
+            integer("limit").optional(),
+        ],
+    },
+    CommandDoc {
+        name: "sinterstore",
+        summary: "Stores the intersect of multiple sets in a key.",
+        since: "1.0.0",
+        group: "set",
+        complexity: "O(N*M) worst case where N is the cardinality of the smallest set and M is the number of sets.",
+        arguments: &[key("destination"), key("key").multiple()],
+    },
+    // sorted set
+    CommandDoc {
+        name: "zadd",
+        summary: "Adds one or more members to a sorted set, or updates their scores. Creates the key if it doesn't exist.",
+        since: "1.2.0",
+        group: "sorted_set",
+        complexity: "O(log(N)) for each item added, where N is the number of elements in the sorted set.",
+        arguments: &[
+            key("key"),
+            oneof("condition", &[token("nx"), token("xx")]).optional(),
+            oneof("comparison", &[token("gt"), token("lt")]).optional(),
+            token("change").optional(),
+            token("increment").optional(),
+            block("data", &[double("score"), string("member")]).multiple(),
+        ],
+    },
+    CommandDoc {
+        name: "zcard",
+        summary: "Returns the number of members in a sorted set.",
+        since: "1.2.0",
+        group: "sorted_set",
+        complexity: "O(1)",
+        arguments: &[key("key")],
+    },
+    CommandDoc {
+        name: "zscore",
+        summary: "Returns the score of a member in a sorted set.",
+        since: "1.2.0",
+        group: "sorted_set",
+        complexity: "O(1)",
+        arguments: &[key("key"), string("member")],
+    },
+    CommandDoc {
+        name: "zrem",
+        summary: "Removes one or more members from a sorted set. Deletes the sorted set if all members were removed.",
+        since: "1.2.0",
+        group: "sorted_set",
+        complexity: "O(M*log(N)) with N being the number of elements in the sorted set and M the number of elements to be removed.",
+        arguments: &[key("key"), string("member").multiple()],
+    },
+    CommandDoc {
+        name: "zremrangebyscore",
+        summary: "Removes members in a sorted set within a range of scores. Deletes the sorted set if all members were removed.",
+        since: "1.2.0",
+        group: "sorted_set",
+        complexity: "O(log(N)+M) with N being the number of elements in the sorted set and M the number of elements removed by the operation.",
+        arguments: &[key("key"), double("min"), double("max")],
+    },
+    CommandDoc {
+        name: "zremrangebyrank",
+        summary: "Removes members in a sorted set within a range of indexes. Deletes the sorted set if all members were removed.",
+        since: "2.0.0",
+        group: "sorted_set",
+        complexity: "O(log(N)+M) with N being the number of elements in the sorted set and M the number of elements removed by the operation.",
+        arguments: &[key("key"), integer("start"), integer("stop")],
+    },
+    CommandDoc {
+        name: "zrange",
+        summary: "Returns members in a sorted set within a range of indexes.",
+        since: "1.2.0",
+        group: "sorted_set",
+        complexity: "O(log(N)+M) with N being the number of elements in the sorted set and M the number of elements returned.",
+        arguments: &[
+            key("key"),
+            integer("start"),
+            integer("stop"),
+            token("rev").optional(),
+            token("withscores").optional(),
+        ],
+    },
+    CommandDoc {
+        name: "zrevrange",
+        summary: "Returns members in a sorted set within a range of indexes in reverse order.",
+        since: "1.2.0",
+        group: "sorted_set",
+        complexity: "O(log(N)+M) with N being the number of elements in the sorted set and M the number of elements returned.",
+        arguments: &[
+            key("key"),
+            integer("start"),
+            integer("stop"),
+            token("withscores").optional(),
+        ],
+    },
+    CommandDoc {
+        name: "zrangebyscore",
+        summary: "Returns members in a sorted set within a range of scores.",
+        since: "1.0.5",
+        group: "sorted_set",
+        complexity: "O(log(N)+M) with N being the number of elements in the sorted set and M the number of elements being returned.",
+        arguments: &[
+            key("key"),
+            double("min"),
+            double("max"),
+            token("withscores").optional(),
+            block("limit", &[integer("offset"), integer("count")]).optional(),
+        ],
+    },
+    CommandDoc {
+        name: "zrevrangebyscore",
+        summary: "Returns members in a sorted set within a range of scores in reverse order.",
+        since: "2.2.0",
+        group: "sorted_set",
+        complexity: "O(log(N)+M) with N being the number of elements in the sorted set and M the number of elements being returned.",
+        arguments: &[
+            key("key"),
+            double("max"),
+            double("min"),
+            token("withscores").optional(),
+            block("limit", &[integer("offset"), integer("count")]).optional(),
+        ],
+    },
+    CommandDoc {
+        name: "zcount",
+        summary: "Returns the count of members in a sorted set that have scores within a range.",
+        since: "2.0.0",
+        group: "sorted_set",
+        complexity: "O(log(N)) with N being the number of elements in the sorted set.",
+        arguments: &[key("key"), double("min"), double("max")],
+    },
+    CommandDoc {
+        name: "zpopmin",
+        summary: "Returns the lowest-scoring members from a sorted set after removing them. Deletes the sorted set if the last member was popped.",
+        since: "5.0.0",
+        group: "sorted_set",
+        complexity: "O(log(N)*M) with N being the number of elements in the sorted set, and M being the number of elements popped.",
+        arguments: &[key("key"), integer("count").optional()],
+    },
+    CommandDoc {
+        name: "zpopmax",
+        summary: "Returns the highest-scoring members from a sorted set after removing them. Deletes the sorted set if the last member was popped.",
+        since: "5.0.0",
+        group: "sorted_set",
+        complexity: "O(log(N)*M) with N being the number of elements in the sorted set, and M being the number of elements popped.",
+        arguments: &[key("key"), integer("count").optional()],
+    },
+    CommandDoc {
+        name: "zmpop",
+        summary: "Returns the highest- or lowest-scoring members from one or more sorted sets after removing them. Deletes the sorted set if the last member was popped.",
+        since: "7.0.0",
+        group: "sorted_set",
+        complexity: "O(K) + O(M*log(N)) where K is the number of provided keys, N being the number of elements in the sorted set, and M being the number of elements popped.",
+        arguments: &[
+            integer("numkeys"),
+            key("key").multiple(),
+            oneof("where", &[token("min"), token("max")]),
+            integer("count").optional(),
+        ],
+    },
+    CommandDoc {
+        name: "zrank",
+        summary: "Returns the index of a member in a sorted set ordered by ascending scores.",
+        since: "2.0.0",
+        group: "sorted_set",
+        complexity: "O(log(N))",
+        arguments: &[key("key"), string("member")],
+    },
+    CommandDoc {
+        name: "zincrby",
+        summary: "Increments the score of a member in a sorted set.",
+        since: "1.2.0",
+        group: "sorted_set",
+        complexity: "O(log(N)) where N is the number of elements in the sorted set.",
+        arguments: &[key("key"), double("increment"), string("member")],
+    },
+    // hyperloglog
+    CommandDoc {
+        name: "pfadd",
+        summary: "Adds elements to a HyperLogLog key. Creates the key if it doesn't exist.",
+        since: "2.8.9",
+        group: "hyperloglog",
+        complexity: "O(1) to add every element.",
+        arguments: &[key("key"), string("element").optional().multiple()],
+    },
+    CommandDoc {
+        name: "pfcount",
+        summary: "Returns the approximated cardinality of the set(s) observed by the HyperLogLog key(s).",
+        since: "2.8.9",
+        group: "hyperloglog",
+        complexity: "O(1) with a very small average constant time when called with a single key. O(N) with N being the number of keys, and much bigger constant times, when called with multiple keys.",
+        arguments: &[key("key").multiple()],
+    },
+    // pubsub
+    CommandDoc {
+        name: "publish",
+        summary: "Posts a message to a channel.",
+        since: "2.0.0",
+        group: "pubsub",
+        complexity: "O(N+M) where N is the number of clients subscribed to the receiving channel and M is the total number of subscribed patterns (by any client).",
+        arguments: &[string("channel"), string("message")],
+    },
+    CommandDoc {
+        name: "subscribe",
+        summary: "Listens for messages published to channels.",
+        since: "2.0.0",
+        group: "pubsub",
+        complexity: "O(N) where N is the number of channels to subscribe to.",
+        arguments: &[string("channel").multiple()],
+    },
+    CommandDoc {
+        name: "unsubscribe",
+        summary: "Stops listening to messages posted to channels.",
+        since: "2.0.0",
+        group: "pubsub",
+        complexity: "O(N) where N is the number of channels to unsubscribe.",
+        arguments: &[string("channel").optional().multiple()],
+    },
+    CommandDoc {
+        name: "spublish",
+        summary: "Post a message to a shard channel",
+        since: "7.0.0",
+        group: "pubsub",
+        complexity: "O(N) where N is the number of clients subscribed to the receiving shard channel.",
+        arguments: &[string("shardchannel"), string("message")],
+    },
+    CommandDoc {
+        name: "ssubscribe",
+        summary: "Listens for messages published to shard channels.",
+        since: "7.0.0",
+        group: "pubsub",
+        complexity: "O(N) where N is the number of shard channels to subscribe to.",
+        arguments: &[string("shardchannel").multiple()],
+    },
+    CommandDoc {
+        name: "sunsubscribe",
+        summary: "Stops listening to messages posted to shard channels.",
+        since: "7.0.0",
+        group: "pubsub",
+        complexity: "O(N) where N is the number of shard channels to unsubscribe.",
+        arguments: &[string("shardchannel").optional().multiple()],
+    },
+    CommandDoc {
+        name: "pubsub",
+        summary: "A container for Pub/Sub commands.",
+        since: "2.8.0",
+        group: "pubsub",
+        complexity: "Depends on subcommand.",
+        arguments: &[],
+    },
+    // scripting
+    CommandDoc {
+        name: "eval",
+        summary: "Executes a server-side Lua script.",
+        since: "2.6.0",
+        group: "scripting",
+        complexity: "Depends on the script that is executed.",
+        arguments: &[
+            string("script"),
+            integer("numkeys"),
+            key("key").optional().multiple(),
+            string("arg").optional().multiple(),
+        ],
+    },
+    CommandDoc {
+        name: "evalsha",
+        summary: "Executes a server-side Lua script by SHA1 digest.",
+        since: "2.6.0",
+        group: "scripting",
+        complexity: "Depends on the script that is executed.",
+        arguments: &[
+            string("sha1"),
+            integer("numkeys"),
+            key("key").optional().multiple(),
+            string("arg").optional().multiple(),
+        ],
+    },
+    CommandDoc {
+        name: "script",
+        summary: "A container for Lua scripts management commands.",
+        since: "2.6.0",
+        group: "scripting",
+        complexity: "Depends on subcommand.",
+        arguments: &[],
+    },
+    // transactions
+    CommandDoc {
+        name: "multi",
+        summary: "Starts a transaction.",
+        since: "1.2.0",
+        group: "transactions",
+        complexity: "O(1)",
+        arguments: &[],
+    },
+    CommandDoc {
+        name: "exec",
+        summary: "Executes all commands in a transaction.",
+        since: "1.2.0",
+        group: "transactions",
+        complexity: "Depends on commands in the transaction",
+        arguments: &[],
+    },
+    CommandDoc {
+        name: "discard",
+        summary: "Discards a transaction.",
+        since: "2.0.0",
+        group: "transactions",
+        complexity: "O(N), when N is the number of queued commands",
+        arguments: &[],
+    },
+    // connection
+    CommandDoc {
+        name: "ping",
+        summary: "Returns the server's liveliness response.",
+        since: "1.0.0",
+        group: "connection",
+        complexity: "O(1)",
+        arguments: &[string("message").optional()],
+    },
+    CommandDoc {
+        name: "auth",
+        summary: "Authenticates the connection.",
+        since: "1.0.0",
+        group: "connection",
+        complexity: "O(N) where N is the number of passwords defined for the user",
+        arguments: &[string("username").optional(), string("password")],
+    },
+    CommandDoc {
+        name: "hello",
+        summary: "Handshakes with the Redis server.",
+        since: "6.0.0",
+        group: "connection",
+        complexity: "O(1)",
+        arguments: &[block(
+            "arguments",
+            &[
+                integer("protover"),
+                block("auth", &[string("username"), string("password")]).optional(),
+                string("clientname").optional(),
+            ],
+        )
+        .optional()],
+    },
+    CommandDoc {
+        name: "reset",
+        summary: "Resets the connection.",
+        since: "6.2.0",
+        group: "connection",
+        complexity: "O(1)",
+        arguments: &[],
+    },
+    CommandDoc {
+        name: "client",
+        summary: "A container for client connection commands.",
+        since: "2.4.0",
+        group: "connection",
+        complexity: "Depends on subcommand.",
+        arguments: &[],
+    },
+    // server
+    CommandDoc {
+        name: "acl",
+        summary: "A container for Access List Control commands.",
+        since: "6.0.0",
+        group: "server",
+        complexity: "Depends on subcommand.",
+        arguments: &[],
+    },
+    CommandDoc {
+        name: "slowlog",
+        summary: "A container for slow log commands.",
+        since: "2.2.12",
+        group: "server",
+        complexity: "Depends on subcommand.",
+        arguments: &[],
+    },
+    CommandDoc {
+        name: "latency",
+        summary: "A container for latency diagnostics commands.",
+        since: "2.8.13",
+        group: "server",
+        complexity: "Depends on subcommand.",
+        arguments: &[],
+    },
+    CommandDoc {
+        name: "config",
+        summary: "A container for server configuration commands.",
+        since: "2.0.0",
+        group: "server",
+        complexity: "Depends on subcommand.",
+        arguments: &[],
+    },
+    CommandDoc {
+        name: "debug",
+        summary: "A container for debugging commands.",
+        since: "1.0.0",
+        group: "server",
+        complexity: "Depends on subcommand.",
+        arguments: &[],
+    },
+    CommandDoc {
+        name: "command",
+        summary: "Returns detailed information about all commands.",
+        since: "2.8.13",
+        group: "server",
+        complexity: "Depends on subcommand.",
+        arguments: &[],
+    },
+];
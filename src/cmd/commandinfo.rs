@@ -0,0 +1,165 @@
+use crate::cmd::commanddocs::{command_doc, command_docs};
+use crate::cmd::Invalid;
+use crate::parse::{Parse, ParseError};
+use crate::rocks::errors::{
+    RError, REDIS_GETKEYS_ARGS_ERR, REDIS_GETKEYS_INVALID_CMD_ERR, REDIS_GETKEYS_NO_KEYS_ERR,
+};
+use crate::utils::{resp_array, resp_bulk, resp_err, resp_invalid_arguments};
+use crate::Frame;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum CommandSubcmd {
+    GetKeys { name: String, args: Vec<String> },
+    Docs(Vec<String>),
+}
+
+/// `COMMAND GETKEYS command [arg ...]`, the keys the command would operate
+/// on, for proxies routing commands to the nodes owning the keys.
+///
+/// `COMMAND DOCS [command ...]`, the documentation of the commands, of all the
+/// supported ones without arguments.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommandInfo {
+    subcommand: CommandSubcmd,
+    valid: bool,
+}
+
+impl CommandInfo {
+    pub fn new(subcommand: CommandSubcmd) -> CommandInfo {
+        CommandInfo {
+            subcommand,
+            valid: true,
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<CommandInfo> {
+        let subcommand = match parse.next_string()?.to_lowercase().as_str() {
+            "getkeys" => {
+                let name = parse.next_string()?.to_lowercase();
+                let mut args = vec![];
+                loop {
+                    match parse.next_string() {
+                        Ok(arg) => args.push(arg),
+                        Err(ParseError::EndOfStream) => break,
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+                CommandSubcmd::GetKeys { name, args }
+            }
+            "docs" => {
+                let mut names = vec![];
+                loop {
+                    match parse.next_string() {
+                        Ok(name) => names.push(name.to_lowercase()),
+                        Err(ParseError::EndOfStream) => break,
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+                CommandSubcmd::Docs(names)
+            }
+            _ => return Ok(CommandInfo::new_invalid()),
+        };
+        Ok(CommandInfo::new(subcommand))
+    }
+
+    pub(crate) fn execute(&self) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        match &self.subcommand {
+            CommandSubcmd::GetKeys { name, args } => match get_keys(name, args) {
+                Ok(keys) => resp_array(
+                    keys.into_iter()
+                        .map(|key| resp_bulk(key.as_bytes().to_vec()))
+                        .collect(),
+                ),
+                Err(e) => resp_err(e),
+            },
+            CommandSubcmd::Docs(names) if names.is_empty() => Frame::Map(
+                command_docs()
+                    .map(|(name, doc)| (resp_bulk(name.as_bytes().to_vec()), doc.to_frame()))
+                    .collect(),
+            ),
+            // unknown commands get an empty map
+            CommandSubcmd::Docs(names) => Frame::Map(
+                names
+                    .iter()
+                    .map(|name| {
+                        let doc = command_doc(name).map_or(Frame::Map(vec![]), |d| d.to_frame());
+                        (resp_bulk(name.as_bytes().to_vec()), doc)
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Keys among the arguments of the command `name`, the arguments exclude the
+/// command name.
+pub fn get_keys<'a>(name: &str, args: &'a [String]) -> Result<Vec<&'a String>, RError> {
+    let keys = match name {
+        "get" | "set" | "getset" | "strlen" | "type" | "incr" | "decr" | "incrby" | "decrby"
+        | "expire" | "expireat" | "pexpire" | "pexpireat" | "ttl" | "pttl" | "dump" | "restore"
+        | "bitfield_ro" | "sadd" | "scard" | "sismember" | "smismember" | "smembers"
+        | "srandmember" | "spop" | "srem" | "lpush" | "rpush" | "lpop" | "rpop" | "lrange"
+        | "ltrim" | "llen" | "lindex" | "lpos" | "lset" | "lrem" | "linsert" | "hset" | "hmset"
+        | "hsetnx" | "hget" | "hmget" | "hlen" | "hgetall" | "hdel" | "hkeys" | "hvals"
+        | "hincrby" | "hexists" | "hstrlen" | "zadd" | "zcard" | "zscore" | "zrem"
+        | "zremrangebyscore" | "zremrangebyrank" | "zrange" | "zrevrange" | "zrangebyscore"
+        | "zrevrangebyscore" | "zcount" | "zpopmin" | "zpopmax" | "zrank" | "zincrby" | "pfadd" => {
+            args.iter().take(1).collect()
+        }
+        // the subcommand comes first
+        "object" => args.iter().skip(1).take(1).collect(),
+        "mget" | "del" | "unlink" | "exists" | "pfcount" | "sinterstore" => args.iter().collect(),
+        "mset" => args.iter().step_by(2).collect(),
+        "sort" => {
+            let mut keys: Vec<&String> = args.iter().take(1).collect();
+            let store = args
+                .windows(2)
+                .skip(1)
+                .find(|w| w[0].eq_ignore_ascii_case("store"));
+            if let Some(w) = store {
+                keys.push(&w[1]);
+            }
+            keys
+        }
+        "brpoplpush" => args.iter().take(2).collect(),
+        "sintercard" | "lmpop" | "zmpop" => numkeys_keys(args, 0)?,
+        "eval" | "evalsha" => numkeys_keys(args, 1)?,
+        "publish" | "subscribe" | "unsubscribe" | "spublish" | "ssubscribe" | "sunsubscribe"
+        | "pubsub" | "ping" | "scan" | "keys" | "cluster" | "auth" | "client" | "hello"
+        | "reset" | "acl" | "slowlog" | "latency" | "wait" | "config" | "debug" | "script"
+        | "command" | "multi" | "exec" | "discard" => return Err(REDIS_GETKEYS_NO_KEYS_ERR),
+        _ => return Err(REDIS_GETKEYS_INVALID_CMD_ERR),
+    };
+    if keys.is_empty() {
+        return Err(REDIS_GETKEYS_ARGS_ERR);
+    }
+    Ok(keys)
+}
+
+// keys following the numkeys argument at `idx`
+fn numkeys_keys(args: &[String], idx: usize) -> Result<Vec<&String>, RError> {
+    let numkeys = args
+        .get(idx)
+        .and_then(|n| n.parse::<usize>().ok())
+        .ok_or(REDIS_GETKEYS_ARGS_ERR)?;
+    match args.get(idx + 1..idx + 1 + numkeys) {
+        Some(keys) => Ok(keys.iter().collect()),
+        None => Err(REDIS_GETKEYS_ARGS_ERR),
+    }
+}
+
+impl Invalid for CommandInfo {
+    fn new_invalid() -> CommandInfo {
+        CommandInfo {
+            subcommand: CommandSubcmd::GetKeys {
+                name: "".to_owned(),
+                args: vec![],
+            },
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::cmd::Invalid;
+use crate::config::reload_config;
+use crate::metrics::reset_counters;
+use crate::rocks::errors::RError;
+use crate::utils::{resp_err, resp_invalid_arguments, resp_ok};
+use crate::{Db, Frame, Parse};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ConfigSubcommand {
+    Rewrite,
+    ResetStat,
+}
+
+/// `CONFIG REWRITE|RESETSTAT`, executed on the node which received it.
+///
+/// The config is only changed by editing the config file, so `REWRITE` syncs
+/// the running config with the file by reloading it, like `SIGHUP` does.
+/// `RESETSTAT` clears the slow log and resets the prometheus counters.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigCmd {
+    subcommand: ConfigSubcommand,
+    valid: bool,
+}
+
+impl ConfigCmd {
+    pub fn new(subcommand: ConfigSubcommand) -> ConfigCmd {
+        ConfigCmd {
+            subcommand,
+            valid: true,
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<ConfigCmd> {
+        let subcommand = match parse.next_string()?.to_lowercase().as_str() {
+            "rewrite" => ConfigSubcommand::Rewrite,
+            "resetstat" => ConfigSubcommand::ResetStat,
+            _ => return Ok(ConfigCmd::new_invalid()),
+        };
+        Ok(ConfigCmd::new(subcommand))
+    }
+
+    pub(crate) fn execute(&self, db: &Db) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        match self.subcommand {
+            ConfigSubcommand::Rewrite => match reload_config() {
+                Ok(_) => resp_ok(),
+                Err(e) => resp_err(RError::owned_error(format!("ERR {e}"))),
+            },
+            ConfigSubcommand::ResetStat => {
+                db.slow_log().lock().unwrap().reset();
+                reset_counters();
+                resp_ok()
+            }
+        }
+    }
+}
+
+impl Invalid for ConfigCmd {
+    fn new_invalid() -> ConfigCmd {
+        ConfigCmd {
+            subcommand: ConfigSubcommand::ResetStat,
+            valid: false,
+        }
+    }
+}
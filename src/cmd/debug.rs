@@ -0,0 +1,140 @@
+use crate::cache::cache_clear;
+use crate::cmd::{Dump, Invalid};
+use crate::config::{enable_debug_commands_or_default, set_quicklist_packed_threshold};
+use crate::eviction::idle_time;
+use crate::gc::trigger_gc;
+use crate::parse::Parse;
+use crate::rocks::errors::{REDIS_DEBUG_NOT_ALLOWED_ERR, REDIS_NO_SUCH_KEY_ERR};
+use crate::rocks::string::StringCommand;
+use crate::rocks::{get_client, Result as RocksResult};
+use crate::utils::{resp_err, resp_invalid_arguments, resp_ok, set_active_expire};
+use crate::Frame;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum DebugSubcmd {
+    Sleep(f64),
+    Reload,
+    Object(String),
+    QuicklistPackedThreshold(u64),
+    FlushWal,
+    SetActiveExpire(bool),
+    Gc,
+}
+
+/// `DEBUG SLEEP seconds|RELOAD|OBJECT key|QUICKLIST-PACKED-THRESHOLD size|
+/// FLUSHWAL|SET-ACTIVE-EXPIRE 0|1|GC`, executed on the node which received it and only allowed
+/// if `enable_debug_commands` is set.
+///
+/// `QUICKLIST-PACKED-THRESHOLD` overrides `list_max_ziplist_value` until
+/// restart, lists written afterwards are only compact encoded if none of their
+/// elements is longer than `size` bytes. `FLUSHWAL` writes and syncs the
+/// rocksdb write-ahead log. `SET-ACTIVE-EXPIRE 0` stops expiring keys on the
+/// node, neither on access nor by compaction, until `SET-ACTIVE-EXPIRE 1`.
+/// `GC` starts a scan of the gc version keys without waiting for
+/// `async_gc_interval`, the reply does not wait for the scan.
+///
+/// The column families can not be reopened while the server is running, so
+/// `RELOAD` reloads the state kept in memory from rocksdb instead: the string
+/// cache is dropped and the keyspace key counts are recounted.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DebugCmd {
+    subcommand: DebugSubcmd,
+    valid: bool,
+}
+
+impl DebugCmd {
+    pub fn new(subcommand: DebugSubcmd) -> DebugCmd {
+        DebugCmd {
+            subcommand,
+            valid: true,
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<DebugCmd> {
+        let subcommand = match parse.next_string()?.to_lowercase().as_str() {
+            "sleep" => match parse.next_string()?.parse::<f64>() {
+                Ok(secs) if secs.is_finite() && secs >= 0.0 => DebugSubcmd::Sleep(secs),
+                _ => return Ok(DebugCmd::new_invalid()),
+            },
+            "reload" => DebugSubcmd::Reload,
+            "flushwal" => DebugSubcmd::FlushWal,
+            "gc" => DebugSubcmd::Gc,
+            "object" => DebugSubcmd::Object(parse.next_string()?),
+            "quicklist-packed-threshold" => match parse.next_int()? {
+                size if size >= 0 => DebugSubcmd::QuicklistPackedThreshold(size as u64),
+                _ => return Ok(DebugCmd::new_invalid()),
+            },
+            "set-active-expire" => match parse.next_int()? {
+                0 => DebugSubcmd::SetActiveExpire(false),
+                1 => DebugSubcmd::SetActiveExpire(true),
+                _ => return Ok(DebugCmd::new_invalid()),
+            },
+            _ => return Ok(DebugCmd::new_invalid()),
+        };
+        Ok(DebugCmd::new(subcommand))
+    }
+
+    pub(crate) async fn execute(&self) -> Frame {
+        if !enable_debug_commands_or_default() {
+            return resp_err(REDIS_DEBUG_NOT_ALLOWED_ERR);
+        }
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        let res = match &self.subcommand {
+            DebugSubcmd::Sleep(secs) => {
+                tokio::time::sleep(Duration::from_secs_f64(*secs)).await;
+                Ok(resp_ok())
+            }
+            DebugSubcmd::Reload => {
+                cache_clear();
+                get_client().reconcile_key_count().map(|_| resp_ok())
+            }
+            DebugSubcmd::Object(key) => debug_object(key).await,
+            DebugSubcmd::QuicklistPackedThreshold(size) => {
+                set_quicklist_packed_threshold(*size as usize);
+                Ok(resp_ok())
+            }
+            DebugSubcmd::FlushWal => get_client().flush_wal().map(|_| resp_ok()),
+            DebugSubcmd::SetActiveExpire(enabled) => {
+                set_active_expire(*enabled);
+                Ok(resp_ok())
+            }
+            DebugSubcmd::Gc => {
+                trigger_gc();
+                Ok(resp_ok())
+            }
+        };
+        res.unwrap_or_else(resp_err)
+    }
+}
+
+// same fields as redis, the address and the lru clock have no meaning here
+async fn debug_object(key: &str) -> RocksResult<Frame> {
+    let client = get_client();
+    let encoding = match StringCommand::new(&client).object_encoding(key).await? {
+        Frame::Simple(encoding) => encoding,
+        _ => return Ok(resp_err(REDIS_NO_SUCH_KEY_ERR)),
+    };
+    let serialized_len = match Dump::new(key).dump().await? {
+        Frame::Bulk(payload) => payload.len(),
+        _ => return Ok(resp_err(REDIS_NO_SUCH_KEY_ERR)),
+    };
+    let idle = idle_time(key)?.unwrap_or(0);
+    let data_type = StringCommand::new(&client).key_type(key)?;
+    Ok(Frame::Simple(format!(
+        "Value at:0 refcount:1 encoding:{encoding} serializedlength:{serialized_len} \
+        lru_seconds_idle:{idle} type:{data_type}"
+    )))
+}
+
+impl Invalid for DebugCmd {
+    fn new_invalid() -> DebugCmd {
+        DebugCmd {
+            subcommand: DebugSubcmd::Reload,
+            valid: false,
+        }
+    }
+}
@@ -51,8 +51,12 @@ impl Del {
         })
     }
 
-    pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = self.del().await?;
+    pub(crate) async fn apply(&self, dst: &mut Connection, unlink: bool) -> crate::Result<()> {
+        let response = if unlink {
+            self.unlink().await?
+        } else {
+            self.del().await?
+        };
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
@@ -66,6 +70,13 @@ impl Del {
         StringCommand::new(&get_client()).del(&self.keys).await
     }
 
+    pub async fn unlink(&self) -> RocksResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        StringCommand::new(&get_client()).unlink(&self.keys).await
+    }
+
     pub fn hash_ring_key(&self) -> crate::Result<String> {
         if self.keys.len() != 1 {
             return Err(MapucheError::String("Cmd don't support cluster").into());
@@ -0,0 +1,213 @@
+use crate::{Connection, Frame, Parse};
+
+use crate::cmd::Invalid;
+use crate::config::LOGGER;
+use crc::{Crc, CRC_64_XZ};
+use serde::{Deserialize, Serialize};
+use slog::debug;
+use std::collections::HashSet;
+
+use crate::rocks::encoding::DataType;
+use crate::rocks::hash::HashCommand;
+use crate::rocks::list::ListCommand;
+use crate::rocks::set::SetCommand;
+use crate::rocks::string::StringCommand;
+use crate::rocks::zset::ZsetCommand;
+use crate::rocks::{get_client, Result as RocksResult};
+use crate::utils::{resp_bulk, resp_invalid_arguments, resp_nil};
+
+const CRC64: Crc<u64> = Crc::<u64>::new(&CRC_64_XZ);
+
+// bumped whenever the payload layout changes, RESTORE rejects other versions
+const DUMP_VERSION: u16 = 1;
+
+// type byte of the payload, same values as the redis rdb object types
+const DUMP_TYPE_STRING: u8 = 0;
+const DUMP_TYPE_LIST: u8 = 1;
+const DUMP_TYPE_SET: u8 = 2;
+const DUMP_TYPE_ZSET: u8 = 3;
+const DUMP_TYPE_HASH: u8 = 4;
+// not a rdb type, hyperloglogs are plain strings in redis
+const DUMP_TYPE_HLL: u8 = 0x80;
+
+/// Serialize a value as `type(1) | payload | version(2) | crc64(8)`.
+///
+/// The payload of a string is the raw value, other types are the element
+/// count followed by length prefixed elements. Hash fields and values, and
+/// zset members and scores are written in turn.
+pub(crate) fn encode_dump(data_type: &DataType, elements: &[Vec<u8>]) -> Vec<u8> {
+    let mut buf = vec![];
+    let type_byte = match data_type {
+        DataType::String => DUMP_TYPE_STRING,
+        DataType::List => DUMP_TYPE_LIST,
+        DataType::Set => DUMP_TYPE_SET,
+        DataType::Zset => DUMP_TYPE_ZSET,
+        DataType::Hash => DUMP_TYPE_HASH,
+        DataType::Hll => DUMP_TYPE_HLL,
+        DataType::Null => unreachable!(),
+    };
+    buf.push(type_byte);
+    if let DataType::String | DataType::Hll = data_type {
+        buf.extend_from_slice(&elements[0]);
+    } else {
+        buf.extend_from_slice(&(elements.len() as u32).to_be_bytes());
+        for ele in elements {
+            buf.extend_from_slice(&(ele.len() as u32).to_be_bytes());
+            buf.extend_from_slice(ele);
+        }
+    }
+    buf.extend_from_slice(&DUMP_VERSION.to_le_bytes());
+    let checksum = CRC64.checksum(&buf);
+    buf.extend_from_slice(&checksum.to_le_bytes());
+    buf
+}
+
+/// Parse a payload serialized by `encode_dump`, `None` if the version or the
+/// checksum is wrong or the payload is truncated.
+pub(crate) fn decode_dump(buf: &[u8]) -> Option<(DataType, Vec<Vec<u8>>)> {
+    if buf.len() < 11 {
+        return None;
+    }
+    let (body, checksum) = buf.split_at(buf.len() - 8);
+    if CRC64.checksum(body) != u64::from_le_bytes(checksum.try_into().unwrap()) {
+        return None;
+    }
+    let (body, version) = body.split_at(body.len() - 2);
+    if u16::from_le_bytes(version.try_into().unwrap()) != DUMP_VERSION {
+        return None;
+    }
+
+    let data_type = match body[0] {
+        DUMP_TYPE_STRING => return Some((DataType::String, vec![body[1..].to_vec()])),
+        DUMP_TYPE_HLL => return Some((DataType::Hll, vec![body[1..].to_vec()])),
+        DUMP_TYPE_LIST => DataType::List,
+        DUMP_TYPE_SET => DataType::Set,
+        DUMP_TYPE_ZSET => DataType::Zset,
+        DUMP_TYPE_HASH => DataType::Hash,
+        _ => return None,
+    };
+    let mut rest = &body[1..];
+    let count = read_len(&mut rest)?;
+    let mut elements = Vec::with_capacity(count.min(rest.len()));
+    for _ in 0..count {
+        let len = read_len(&mut rest)?;
+        if rest.len() < len {
+            return None;
+        }
+        let (ele, tail) = rest.split_at(len);
+        elements.push(ele.to_vec());
+        rest = tail;
+    }
+    let paired = matches!(data_type, DataType::Hash | DataType::Zset);
+    if !rest.is_empty() || elements.is_empty() || (paired && elements.len() % 2 != 0) {
+        return None;
+    }
+    // members and fields are written as is by `RESTORE`, they must be unique
+    let step = if paired { 2 } else { 1 };
+    let mut seen = HashSet::new();
+    if !elements.iter().step_by(step).all(|e| seen.insert(e)) {
+        return None;
+    }
+    if let DataType::Zset = data_type {
+        // scores are written as strings
+        let valid_scores = elements
+            .chunks(2)
+            .all(|ms| String::from_utf8_lossy(&ms[1]).parse::<f64>().is_ok());
+        if !valid_scores {
+            return None;
+        }
+    }
+    Some((data_type, elements))
+}
+
+fn read_len(rest: &mut &[u8]) -> Option<usize> {
+    if rest.len() < 4 {
+        return None;
+    }
+    let (n, tail) = rest.split_at(4);
+    *rest = tail;
+    Some(u32::from_be_bytes(n.try_into().unwrap()) as usize)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Dump {
+    key: String,
+    valid: bool,
+}
+
+impl Dump {
+    pub fn new(key: impl ToString) -> Dump {
+        Dump {
+            key: key.to_string(),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Dump> {
+        let key = parse.next_string()?;
+        Ok(Dump::new(key))
+    }
+
+    pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.dump().await?;
+        debug!(LOGGER, "res, {:?}", response);
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn dump(&self) -> RocksResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let client = get_client();
+        let data_type = StringCommand::new(&client).key_type(&self.key)?;
+        let frame = match data_type {
+            DataType::String | DataType::Hll => StringCommand::new(&client).get(&self.key).await?,
+            DataType::List => ListCommand::new(&client).lrange(&self.key, 0, -1).await?,
+            DataType::Set => SetCommand::new(&client).smembers(&self.key).await?,
+            DataType::Zset => {
+                ZsetCommand::new(&client)
+                    .zrange(&self.key, 0, -1, true, false)
+                    .await?
+            }
+            DataType::Hash => {
+                HashCommand::new(&client)
+                    .hgetall(&self.key, true, true)
+                    .await?
+            }
+            DataType::Null => return Ok(resp_nil()),
+        };
+        let elements = match frame {
+            Frame::Bulk(val) => vec![val.to_vec()],
+            Frame::Array(items) => items
+                .into_iter()
+                .filter_map(|item| match item {
+                    Frame::Bulk(val) => Some(val.to_vec()),
+                    _ => None,
+                })
+                .collect(),
+            // the key expired after its type was read
+            Frame::Null => return Ok(resp_nil()),
+            other => return Ok(other),
+        };
+        Ok(resp_bulk(encode_dump(&data_type, &elements)))
+    }
+
+    pub fn hash_ring_key(&self) -> crate::Result<String> {
+        Ok(self.key.to_string())
+    }
+}
+
+impl Invalid for Dump {
+    fn new_invalid() -> Dump {
+        Dump {
+            key: "".to_owned(),
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,400 @@
+use crate::acl::ACL_STORE;
+use crate::cmd::{execute_replicated, Invalid};
+use crate::config::lua_time_limit_or_default;
+use crate::parse::{Parse, ParseError};
+use crate::rocks::errors::{
+    RError, REDIS_NOPERM_KEY_ERR, REDIS_NOSCRIPT_ERR, REDIS_NUMKEYS_GREATER_ERR,
+    REDIS_NUMKEYS_NEGATIVE_ERR, REDIS_SCRIPT_NOT_ALLOWED_CMD_ERR, REDIS_SCRIPT_UNKNOWN_CMD_ERR,
+};
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Command, Frame};
+use bytes::Bytes;
+use lazy_static::lazy_static;
+use lru::LruCache;
+use mlua::{Error as LuaError, HookTriggers, Lua, LuaOptions, StdLib, Value as LuaValue, Variadic};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::num::NonZeroUsize;
+use std::sync::Mutex as StdMutex;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::time::timeout_at;
+
+// scripts cached for `EVALSHA`, the least recently used are dropped first
+const SCRIPTS_MAX_LEN: usize = 1000;
+
+// instructions run between two checks of the script time limit
+const TIME_LIMIT_CHECK_INTERVAL: u32 = 10000;
+
+lazy_static! {
+    // scripts of `SCRIPT LOAD` and `EVAL` by their sha1 hex digest
+    static ref SCRIPTS: StdMutex<LruCache<String, String>> =
+        StdMutex::new(LruCache::new(NonZeroUsize::new(SCRIPTS_MAX_LEN).unwrap()));
+    // scripts and the transactions of `EXEC` of the node run one at a time
+    pub(crate) static ref SCRIPT_LOCK: Mutex<()> = Mutex::new(());
+}
+
+pub(crate) fn script_sha(script: &str) -> String {
+    Sha1::digest(script.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Cache the script for `EVALSHA`, returns its sha1 hex digest.
+pub(crate) fn cache_script(script: &str) -> String {
+    let sha = script_sha(script);
+    SCRIPTS.lock().unwrap().put(sha.clone(), script.to_owned());
+    sha
+}
+
+pub(crate) fn script_exists(sha: &str) -> bool {
+    SCRIPTS.lock().unwrap().contains(&sha.to_lowercase())
+}
+
+pub(crate) fn flush_scripts() {
+    SCRIPTS.lock().unwrap().clear();
+}
+
+/// `EVAL script numkeys [key ...] [arg ...]` and `EVALSHA sha1 numkeys
+/// [key ...] [arg ...]`, `script` holds the digest for `EVALSHA`.
+///
+/// Scripts run on the node which received the command, in a new lua state
+/// with `KEYS`, `ARGV`, `redis.call` and `redis.pcall`. Scripts of the node
+/// are serialized, but commands of other clients can run between the calls of
+/// a script. Write commands called by a script are replicated by raft like
+/// the ones sent by clients, and checked against the acl of the user.
+///
+/// A script, including the commands it calls, is aborted after
+/// `lua_time_limit`, so the next script of the node waits no longer than that.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Eval {
+    script: String,
+    numkeys: i64,
+    keys: Vec<String>,
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Eval {
+    pub fn new(script: &str, keys: Vec<String>, args: Vec<String>) -> Eval {
+        Eval {
+            script: script.to_owned(),
+            numkeys: keys.len() as i64,
+            keys,
+            args,
+            valid: true,
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Eval> {
+        let script = parse.next_string()?;
+        let numkeys = parse.next_int()?;
+        let mut rest = vec![];
+        loop {
+            match parse.next_string() {
+                Ok(s) => rest.push(s),
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        let mut eval = Eval::new(&script, vec![], vec![]);
+        eval.numkeys = numkeys;
+        // a wrong numkeys is replied when the command is executed
+        if numkeys >= 0 && numkeys as usize <= rest.len() {
+            eval.args = rest.split_off(numkeys as usize);
+            eval.keys = rest;
+        }
+        Ok(eval)
+    }
+
+    pub(crate) fn keys(&self) -> &Vec<String> {
+        &self.keys
+    }
+
+    pub(crate) fn hash_ring_key(&self) -> crate::Result<String> {
+        match self.keys.first() {
+            Some(key) => Ok(key.clone()),
+            None => Err("no key for eval".into()),
+        }
+    }
+
+    /// Run the script, looked up by its digest if `by_sha`, as `user`.
+    pub(crate) async fn eval(&self, by_sha: bool, user: &str) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        if self.numkeys < 0 {
+            return resp_err(REDIS_NUMKEYS_NEGATIVE_ERR);
+        }
+        if self.numkeys as usize != self.keys.len() {
+            return resp_err(REDIS_NUMKEYS_GREATER_ERR);
+        }
+        let script = if by_sha {
+            match SCRIPTS.lock().unwrap().get(&self.script.to_lowercase()) {
+                Some(script) => script.clone(),
+                None => return resp_err(REDIS_NOSCRIPT_ERR),
+            }
+        } else {
+            cache_script(&self.script);
+            self.script.clone()
+        };
+
+        let _guard = SCRIPT_LOCK.lock().await;
+        let time_limit = TimeLimit::new(lua_time_limit_or_default());
+        let res = match sandboxed_lua(time_limit) {
+            Ok(lua) => run_script(&lua, &script, &self.keys, &self.args, user, time_limit).await,
+            Err(e) => Err(e),
+        };
+        match res {
+            Ok(frame) => frame,
+            Err(e) => resp_err(RError::owned_error(format!(
+                "ERR Error running script, {e}"
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct TimeLimit {
+    limit: Duration,
+    deadline: Instant,
+}
+
+impl TimeLimit {
+    fn new(limit: Duration) -> TimeLimit {
+        TimeLimit {
+            limit,
+            deadline: Instant::now() + limit,
+        }
+    }
+
+    fn exceeded(&self) -> bool {
+        Instant::now() > self.deadline
+    }
+
+    fn error(&self) -> LuaError {
+        LuaError::RuntimeError(format!(
+            "script killed after running for more than {} milliseconds",
+            self.limit.as_millis()
+        ))
+    }
+}
+
+// only the table, string and math libraries like redis, scripts can not reach
+// the files, processes or modules of the host. Scripts running past the time
+// limit are aborted.
+fn sandboxed_lua(time_limit: TimeLimit) -> mlua::Result<Lua> {
+    let lua = Lua::new_with(
+        StdLib::TABLE | StdLib::STRING | StdLib::MATH,
+        LuaOptions::default(),
+    )?;
+    for name in ["loadfile", "dofile", "require"] {
+        lua.globals().set(name, LuaValue::Nil)?;
+    }
+
+    let triggers = HookTriggers {
+        every_nth_instruction: Some(TIME_LIMIT_CHECK_INTERVAL),
+        ..Default::default()
+    };
+    lua.set_hook(triggers, move |lua, _| {
+        if time_limit.exceeded() {
+            // raised at every instruction from now on, a script catching it
+            // with pcall is stopped by the next one out of the pcall
+            let triggers = HookTriggers {
+                every_nth_instruction: Some(1),
+                ..Default::default()
+            };
+            lua.set_hook(triggers, move |_, _| Err(time_limit.error()))?;
+            return Err(time_limit.error());
+        }
+        Ok(())
+    })?;
+    Ok(lua)
+}
+
+async fn run_script(
+    lua: &Lua,
+    script: &str,
+    keys: &[String],
+    args: &[String],
+    user: &str,
+    time_limit: TimeLimit,
+) -> mlua::Result<Frame> {
+    let globals = lua.globals();
+    globals.set("KEYS", keys.to_vec())?;
+    globals.set("ARGV", args.to_vec())?;
+
+    let redis = lua.create_table()?;
+    for (name, raise) in [("call", true), ("pcall", false)] {
+        let user = user.to_owned();
+        let func = lua.create_async_function(move |lua, args: Variadic<LuaValue>| {
+            let user = user.clone();
+            async move { redis_call(lua, args, raise, &user, time_limit).await }
+        })?;
+        redis.set(name, func)?;
+    }
+    globals.set("redis", redis)?;
+
+    let value: LuaValue = lua.load(script).call_async(()).await?;
+    Ok(lua_to_frame(value))
+}
+
+// `redis.call` raises the error replies, `redis.pcall` returns them as a
+// table with an `err` field. Both raise when the time limit is exceeded, a
+// write still waiting for raft then is no longer awaited but may be applied.
+async fn redis_call<'lua>(
+    lua: &'lua Lua,
+    args: Variadic<LuaValue<'lua>>,
+    raise: bool,
+    user: &str,
+    time_limit: TimeLimit,
+) -> mlua::Result<LuaValue<'lua>> {
+    if time_limit.exceeded() {
+        return Err(time_limit.error());
+    }
+    let mut frames = Vec::with_capacity(args.len());
+    for arg in args.iter() {
+        let bytes = match arg {
+            LuaValue::String(s) => Bytes::copy_from_slice(s.as_bytes()),
+            LuaValue::Integer(i) => Bytes::from(i.to_string()),
+            LuaValue::Number(n) => Bytes::from(n.to_string()),
+            _ => {
+                return Err(LuaError::RuntimeError(
+                    "Lua redis() command arguments must be strings or integers".to_owned(),
+                ))
+            }
+        };
+        frames.push(Frame::Bulk(bytes));
+    }
+    let frame = match Command::from_frame(Frame::Array(frames)) {
+        Ok(cmd) => match check_permission(&cmd, user) {
+            Some(denied) => denied,
+            None => {
+                let deadline = time_limit.deadline.into();
+                match timeout_at(deadline, execute_replicated(cmd)).await {
+                    Ok(res) => res.unwrap_or_else(|e| Frame::Error(format!("ERR {e}"))),
+                    Err(_) => return Err(time_limit.error()),
+                }
+            }
+        },
+        Err(e) => Frame::Error(format!("ERR {e}")),
+    };
+    match frame {
+        Frame::Error(e) if raise => Err(LuaError::RuntimeError(e)),
+        frame => frame_to_lua(lua, frame),
+    }
+}
+
+fn check_permission(cmd: &Command, user: &str) -> Option<Frame> {
+    match cmd {
+        Command::Unknown(_) => return Some(resp_err(REDIS_SCRIPT_UNKNOWN_CMD_ERR)),
+        // scripting, transactions and commands of the connection are handled
+        // by the server for a client, not by `execute_replicated`
+        Command::Eval(_)
+        | Command::Evalsha(_)
+        | Command::Script(_)
+        | Command::Multi(_)
+        | Command::Exec(_)
+        | Command::Discard(_)
+        | Command::Subscribe(_)
+        | Command::Unsubscribe(_)
+        | Command::Ssubscribe(_)
+        | Command::Sunsubscribe(_)
+        | Command::Auth(_)
+        | Command::Hello(_)
+        | Command::Reset(_)
+        | Command::ClientCmd(_)
+        | Command::Wait(_) => return Some(resp_err(REDIS_SCRIPT_NOT_ALLOWED_CMD_ERR)),
+        _ => {}
+    }
+    match ACL_STORE.get_user(user).filter(|u| u.enabled) {
+        Some(u) if u.can_execute(cmd.get_name()) => {
+            if cmd.keys().iter().all(|key| u.can_access_key(key)) {
+                None
+            } else {
+                Some(resp_err(REDIS_NOPERM_KEY_ERR))
+            }
+        }
+        _ => Some(resp_err(RError::owned_error(format!(
+            "NOPERM User {} has no permissions to run the '{}' command",
+            user,
+            cmd.get_name()
+        )))),
+    }
+}
+
+fn frame_to_lua(lua: &Lua, frame: Frame) -> mlua::Result<LuaValue> {
+    let value = match frame {
+        Frame::Simple(s) => {
+            let table = lua.create_table()?;
+            table.set("ok", s)?;
+            LuaValue::Table(table)
+        }
+        Frame::Error(s) | Frame::TxnFailed(s) => {
+            let table = lua.create_table()?;
+            table.set("err", s)?;
+            LuaValue::Table(table)
+        }
+        Frame::Integer(i) => LuaValue::Integer(i),
+        Frame::Bulk(b) => LuaValue::String(lua.create_string(&b)?),
+        Frame::Null => LuaValue::Boolean(false),
+        Frame::Double(d) => LuaValue::String(lua.create_string(&d.to_string())?),
+        Frame::Array(frames) => {
+            let table = lua.create_table()?;
+            for (i, frame) in frames.into_iter().enumerate() {
+                table.set(i + 1, frame_to_lua(lua, frame)?)?;
+            }
+            LuaValue::Table(table)
+        }
+        // flattened like a RESP2 reply
+        Frame::Map(pairs) => {
+            let table = lua.create_table()?;
+            for (i, (k, v)) in pairs.into_iter().enumerate() {
+                table.set(i * 2 + 1, frame_to_lua(lua, k)?)?;
+                table.set(i * 2 + 2, frame_to_lua(lua, v)?)?;
+            }
+            LuaValue::Table(table)
+        }
+    };
+    Ok(value)
+}
+
+// same conversions as redis, arrays stop at the first nil
+fn lua_to_frame(value: LuaValue) -> Frame {
+    match value {
+        LuaValue::Boolean(true) => Frame::Integer(1),
+        LuaValue::Integer(i) => Frame::Integer(i),
+        LuaValue::Number(n) => Frame::Integer(n as i64),
+        LuaValue::String(s) => Frame::Bulk(Bytes::copy_from_slice(s.as_bytes())),
+        LuaValue::Table(table) => {
+            if let Ok(LuaValue::String(err)) = table.get::<_, LuaValue>("err") {
+                return Frame::Error(err.to_string_lossy().to_string());
+            }
+            if let Ok(LuaValue::String(ok)) = table.get::<_, LuaValue>("ok") {
+                return Frame::Simple(ok.to_string_lossy().to_string());
+            }
+            let mut frames = vec![];
+            for i in 1.. {
+                match table.raw_get::<_, LuaValue>(i) {
+                    Ok(LuaValue::Nil) | Err(_) => break,
+                    Ok(value) => frames.push(lua_to_frame(value)),
+                }
+            }
+            Frame::Array(frames)
+        }
+        _ => Frame::Null,
+    }
+}
+
+impl Invalid for Eval {
+    fn new_invalid() -> Eval {
+        Eval {
+            script: "".to_owned(),
+            numkeys: 0,
+            keys: vec![],
+            args: vec![],
+            valid: false,
+        }
+    }
+}
@@ -65,7 +65,13 @@ impl Expire {
         is_millis: bool,
         expire_at: bool,
     ) -> crate::Result<()> {
-        let response = retry_call(|| {
+        let cmd = match (is_millis, expire_at) {
+            (false, false) => "expire",
+            (false, true) => "expireat",
+            (true, false) => "pexpire",
+            (true, true) => "pexpireat",
+        };
+        let response = retry_call(cmd, || {
             async move { self.expire(is_millis, expire_at).await.map_err(Into::into) }.boxed()
         })
         .await?;
@@ -0,0 +1,68 @@
+use crate::{Connection, Frame, Parse};
+
+use crate::cmd::{retry_call, Invalid};
+use crate::config::LOGGER;
+use bytes::Bytes;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use slog::debug;
+
+use crate::rocks::string::StringCommand;
+use crate::rocks::{get_client, Result as RocksResult};
+use crate::utils::resp_invalid_arguments;
+
+/// `GETSET key value`, sets the key and replies its previous value, nil if
+/// it did not exist.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Getset {
+    key: String,
+    value: Bytes,
+    valid: bool,
+}
+
+impl Getset {
+    pub fn new(key: impl ToString, value: Bytes) -> Getset {
+        Getset {
+            key: key.to_string(),
+            value,
+            valid: true,
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Getset> {
+        let key = parse.next_string()?;
+        let value = parse.next_bytes()?;
+        Ok(Getset::new(key, value))
+    }
+
+    pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
+        let response = retry_call("getset", || async move { self.getset().await }.boxed()).await?;
+        debug!(LOGGER, "res, {:?}", response);
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn getset(&self) -> RocksResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        StringCommand::new(&get_client())
+            .getset(&self.key, &self.value)
+            .await
+    }
+
+    pub fn hash_ring_key(&self) -> crate::Result<String> {
+        Ok(self.key.to_string())
+    }
+}
+
+impl Invalid for Getset {
+    fn new_invalid() -> Getset {
+        Getset {
+            key: "".to_owned(),
+            value: Bytes::new(),
+            valid: false,
+        }
+    }
+}
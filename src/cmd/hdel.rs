@@ -57,7 +57,7 @@ impl Hdel {
     }
 
     pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = retry_call(|| async move { self.hdel().await }.boxed()).await?;
+        let response = retry_call("hdel", || async move { self.hdel().await }.boxed()).await?;
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
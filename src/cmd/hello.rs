@@ -0,0 +1,88 @@
+use crate::cmd::Invalid;
+use crate::parse::{Parse, ParseError};
+use serde::{Deserialize, Serialize};
+
+/// `HELLO [protover [AUTH username password] [SETNAME clientname]]`, switch
+/// the connection protocol and optionally authenticate and name the client.
+///
+/// Like `AUTH`, it changes connection state and is handled by the connection
+/// handler directly.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Hello {
+    version: Option<u8>,
+    auth: Option<(String, String)>,
+    setname: Option<String>,
+    valid: bool,
+}
+
+impl Hello {
+    pub fn new(
+        version: Option<u8>,
+        auth: Option<(String, String)>,
+        setname: Option<String>,
+    ) -> Hello {
+        Hello {
+            version,
+            auth,
+            setname,
+            valid: true,
+        }
+    }
+
+    pub fn version(&self) -> Option<u8> {
+        self.version
+    }
+
+    pub fn auth(&self) -> Option<&(String, String)> {
+        self.auth.as_ref()
+    }
+
+    pub fn setname(&self) -> Option<&str> {
+        self.setname.as_deref()
+    }
+
+    pub fn valid(&self) -> bool {
+        self.valid
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Hello> {
+        let version = match parse.next_int() {
+            Ok(version) => version,
+            Err(ParseError::EndOfStream) => return Ok(Hello::new(None, None, None)),
+            Err(e) => return Err(e.into()),
+        };
+        // out of range versions are rejected with NOPROTO by the handler
+        let version = u8::try_from(version).unwrap_or(0);
+
+        let mut auth = None;
+        let mut setname = None;
+        loop {
+            let option = match parse.next_string() {
+                Ok(option) => option,
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            };
+            match option.to_lowercase().as_str() {
+                "auth" => {
+                    let username = parse.next_string()?;
+                    let password = parse.next_string()?;
+                    auth = Some((username, password));
+                }
+                "setname" => setname = Some(parse.next_string()?),
+                _ => return Ok(Hello::new_invalid()),
+            }
+        }
+        Ok(Hello::new(Some(version), auth, setname))
+    }
+}
+
+impl Invalid for Hello {
+    fn new_invalid() -> Hello {
+        Hello {
+            version: None,
+            auth: None,
+            setname: None,
+            valid: false,
+        }
+    }
+}
@@ -48,8 +48,11 @@ impl Hgetall {
     }
 
     pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = self.hgetall().await?;
+        let mut response = self.hgetall().await?;
         debug!(LOGGER, "res, {:?}", response);
+        if dst.protocol() >= 3 {
+            response = response.into_map();
+        }
         dst.write_frame(&response).await?;
 
         Ok(())
@@ -73,7 +73,8 @@ impl Hincrby {
     }
 
     pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = retry_call(|| async move { self.hincrby().await }.boxed()).await?;
+        let response =
+            retry_call("hincrby", || async move { self.hincrby().await }.boxed()).await?;
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
@@ -79,8 +79,17 @@ impl Hset {
         is_hmset: bool,
         is_nx: bool,
     ) -> crate::Result<()> {
-        let response =
-            retry_call(|| async move { self.hset(is_hmset, is_nx).await }.boxed()).await?;
+        let cmd = if is_hmset {
+            "hmset"
+        } else if is_nx {
+            "hsetnx"
+        } else {
+            "hset"
+        };
+        let response = retry_call(cmd, || {
+            async move { self.hset(is_hmset, is_nx).await }.boxed()
+        })
+        .await?;
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
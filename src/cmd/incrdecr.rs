@@ -60,8 +60,13 @@ impl IncrDecr {
         }
     }
 
-    pub(crate) async fn apply(&self, dst: &mut Connection, inc: bool) -> crate::Result<()> {
-        let response = retry_call(|| {
+    pub(crate) async fn apply(
+        &self,
+        dst: &mut Connection,
+        cmd: &str,
+        inc: bool,
+    ) -> crate::Result<()> {
+        let response = retry_call(cmd, || {
             async move {
                 let mut the_clone = self.clone();
                 the_clone.incr_by(inc).await.map_err(Into::into)
@@ -0,0 +1,90 @@
+use crate::cmd::Invalid;
+use crate::latency::LATENCY_MONITOR;
+use crate::parse::{Parse, ParseError};
+use crate::utils::{resp_array, resp_bulk, resp_int, resp_invalid_arguments};
+use crate::Frame;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum LatencySubcmd {
+    History(String),
+    Latest,
+    Reset(Vec<String>),
+}
+
+/// `LATENCY HISTORY event|LATEST|RESET [event ...]`, the samples are kept in
+/// memory of the node which recorded them.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Latency {
+    subcommand: LatencySubcmd,
+    valid: bool,
+}
+
+impl Latency {
+    pub fn new(subcommand: LatencySubcmd) -> Latency {
+        Latency {
+            subcommand,
+            valid: true,
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Latency> {
+        let subcommand = match parse.next_string()?.to_lowercase().as_str() {
+            "history" => LatencySubcmd::History(parse.next_string()?),
+            "latest" => LatencySubcmd::Latest,
+            "reset" => {
+                let mut events = vec![];
+                loop {
+                    match parse.next_string() {
+                        Ok(event) => events.push(event),
+                        Err(ParseError::EndOfStream) => break,
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+                LatencySubcmd::Reset(events)
+            }
+            _ => return Ok(Latency::new_invalid()),
+        };
+        Ok(Latency::new(subcommand))
+    }
+
+    pub(crate) fn execute(&self) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        let mut monitor = LATENCY_MONITOR.lock().unwrap();
+        match &self.subcommand {
+            LatencySubcmd::History(event) => resp_array(
+                monitor
+                    .history(event)
+                    .into_iter()
+                    .map(|(ts, ms)| resp_array(vec![resp_int(ts as i64), resp_int(ms as i64)]))
+                    .collect(),
+            ),
+            LatencySubcmd::Latest => resp_array(
+                monitor
+                    .latest()
+                    .into_iter()
+                    .map(|(event, ts, ms, max_ms)| {
+                        resp_array(vec![
+                            resp_bulk(event.into_bytes()),
+                            resp_int(ts as i64),
+                            resp_int(ms as i64),
+                            resp_int(max_ms as i64),
+                        ])
+                    })
+                    .collect(),
+            ),
+            LatencySubcmd::Reset(events) => resp_int(monitor.reset(events) as i64),
+        }
+    }
+}
+
+impl Invalid for Latency {
+    fn new_invalid() -> Latency {
+        Latency {
+            subcommand: LatencySubcmd::Latest,
+            valid: false,
+        }
+    }
+}
@@ -73,7 +73,8 @@ impl Linsert {
     }
 
     pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = retry_call(|| async move { self.linsert().await }.boxed()).await?;
+        let response =
+            retry_call("linsert", || async move { self.linsert().await }.boxed()).await?;
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
@@ -0,0 +1,104 @@
+use crate::{Connection, Frame, MapucheError, Parse};
+
+use crate::cmd::{retry_call, Invalid};
+use crate::config::LOGGER;
+use crate::parse::ParseError;
+use crate::rocks::list::ListCommand;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use slog::debug;
+
+use crate::rocks::{get_client, Result as RocksResult};
+use crate::utils::resp_invalid_arguments;
+
+/// `LMPOP numkeys key [key ...] LEFT|RIGHT [COUNT count]`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Lmpop {
+    keys: Vec<String>,
+    op_left: bool,
+    count: i64,
+    valid: bool,
+}
+
+impl Lmpop {
+    pub fn new(keys: Vec<String>, op_left: bool, count: i64) -> Lmpop {
+        Lmpop {
+            keys,
+            op_left,
+            count,
+            valid: true,
+        }
+    }
+
+    pub fn keys(&self) -> &Vec<String> {
+        &self.keys
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Lmpop> {
+        let numkeys = parse.next_int()?;
+        if numkeys <= 0 {
+            return Ok(Lmpop::new_invalid());
+        }
+        let mut keys = Vec::with_capacity(numkeys as usize);
+        for _ in 0..numkeys {
+            keys.push(parse.next_string()?);
+        }
+        let op_left = match parse.next_string()?.to_uppercase().as_str() {
+            "LEFT" => true,
+            "RIGHT" => false,
+            _ => return Ok(Lmpop::new_invalid()),
+        };
+
+        let mut count = 1;
+        match parse.next_string() {
+            Ok(option) if option.to_uppercase() == "COUNT" => count = parse.next_int()?,
+            Ok(_) => return Ok(Lmpop::new_invalid()),
+            Err(ParseError::EndOfStream) => {}
+            Err(e) => return Err(e.into()),
+        }
+        if count <= 0 {
+            return Ok(Lmpop::new_invalid());
+        }
+
+        Ok(Lmpop::new(keys, op_left, count))
+    }
+
+    pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
+        let response = retry_call("lmpop", || async move { self.lmpop().await }.boxed()).await?;
+        debug!(LOGGER, "res, {:?}", response);
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn lmpop(&self) -> RocksResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        ListCommand::new(&get_client())
+            .lmpop(&self.keys, self.op_left, self.count)
+            .await
+    }
+
+    pub fn keys(&self) -> &Vec<String> {
+        &self.keys
+    }
+
+    pub fn hash_ring_key(&self) -> crate::Result<String> {
+        if self.keys.len() != 1 {
+            return Err(MapucheError::String("Cmd don't support cluster").into());
+        }
+        Ok(self.keys[0].to_string())
+    }
+}
+
+impl Invalid for Lmpop {
+    fn new_invalid() -> Lmpop {
+        Lmpop {
+            keys: vec![],
+            op_left: true,
+            count: 0,
+            valid: false,
+        }
+    }
+}
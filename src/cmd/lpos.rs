@@ -0,0 +1,117 @@
+use crate::{Connection, Frame, Parse};
+
+use crate::cmd::Invalid;
+use crate::config::LOGGER;
+use crate::parse::ParseError;
+use crate::rocks::errors::{
+    REDIS_LPOS_COUNT_NEGATIVE_ERR, REDIS_LPOS_MAXLEN_NEGATIVE_ERR, REDIS_LPOS_RANK_ZERO_ERR,
+};
+use crate::rocks::list::ListCommand;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use slog::debug;
+
+use crate::rocks::{get_client, Result as RocksResult};
+use crate::utils::{resp_err, resp_invalid_arguments};
+
+/// `LPOS key element [RANK rank] [COUNT num-matches] [MAXLEN len]`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Lpos {
+    key: String,
+    element: Bytes,
+    rank: i64,
+    count: Option<i64>,
+    maxlen: i64,
+    valid: bool,
+}
+
+impl Lpos {
+    pub fn new(key: &str, element: Bytes, rank: i64, count: Option<i64>, maxlen: i64) -> Lpos {
+        Lpos {
+            key: key.to_owned(),
+            element,
+            rank,
+            count,
+            maxlen,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Lpos> {
+        let key = parse.next_string()?;
+        let element = parse.next_bytes()?;
+
+        let mut rank = 1;
+        let mut count = None;
+        let mut maxlen = 0;
+        // options are accepted in any order
+        loop {
+            let option = match parse.next_string() {
+                Ok(option) => option,
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            };
+            match option.to_uppercase().as_str() {
+                "RANK" => rank = parse.next_int()?,
+                "COUNT" => count = Some(parse.next_int()?),
+                "MAXLEN" => maxlen = parse.next_int()?,
+                _ => return Ok(Lpos::new_invalid()),
+            }
+        }
+
+        Ok(Lpos::new(&key, element, rank, count, maxlen))
+    }
+
+    pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.lpos().await?;
+        debug!(LOGGER, "res, {:?}", response);
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn lpos(&self) -> RocksResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if self.rank == 0 {
+            return Ok(resp_err(REDIS_LPOS_RANK_ZERO_ERR));
+        }
+        if self.count.map_or(false, |count| count < 0) {
+            return Ok(resp_err(REDIS_LPOS_COUNT_NEGATIVE_ERR));
+        }
+        if self.maxlen < 0 {
+            return Ok(resp_err(REDIS_LPOS_MAXLEN_NEGATIVE_ERR));
+        }
+        ListCommand::new(&get_client())
+            .lpos(
+                &self.key,
+                &self.element,
+                self.rank,
+                self.count.map(|count| count as usize),
+                self.maxlen as usize,
+            )
+            .await
+    }
+
+    pub fn hash_ring_key(&self) -> crate::Result<String> {
+        Ok(self.key.to_string())
+    }
+}
+
+impl Invalid for Lpos {
+    fn new_invalid() -> Lpos {
+        Lpos {
+            key: "".to_owned(),
+            element: Bytes::new(),
+            rank: 1,
+            count: None,
+            maxlen: 0,
+            valid: false,
+        }
+    }
+}
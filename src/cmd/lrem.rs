@@ -55,7 +55,7 @@ impl Lrem {
     }
 
     pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = retry_call(|| async move { self.lrem().await }.boxed()).await?;
+        let response = retry_call("lrem", || async move { self.lrem().await }.boxed()).await?;
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
@@ -61,7 +61,7 @@ impl Lset {
     }
 
     pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = retry_call(|| async move { self.lset().await }.boxed()).await?;
+        let response = retry_call("lset", || async move { self.lset().await }.boxed()).await?;
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
@@ -65,7 +65,7 @@ impl Ltrim {
     }
 
     pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = retry_call(|| async move { self.ltrim().await }.boxed()).await?;
+        let response = retry_call("ltrim", || async move { self.ltrim().await }.boxed()).await?;
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
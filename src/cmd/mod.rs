@@ -13,6 +13,9 @@ pub use set::Set;
 mod subscribe;
 pub use subscribe::{Subscribe, Unsubscribe};
 
+mod pubsub;
+pub use pubsub::{Pubsub, PubsubSubcmd};
+
 mod ping;
 pub use ping::Ping;
 
@@ -28,9 +31,18 @@ pub use mset::Mset;
 mod strlen;
 pub use strlen::Strlen;
 
+mod getset;
+pub use getset::Getset;
+
+mod bitfield;
+pub use bitfield::Bitfield;
+
 mod cmdtype;
 pub use cmdtype::Type;
 
+mod object;
+pub use object::{Object, ObjectSubcmd};
+
 mod exists;
 pub use exists::Exists;
 
@@ -46,8 +58,17 @@ pub use ttl::TTL;
 mod del;
 pub use del::Del;
 
+mod dump;
+pub use dump::Dump;
+
+mod restore;
+pub use restore::Restore;
+
+mod sort;
+pub use sort::Sort;
+
 mod scan;
-pub use scan::Scan;
+pub use scan::{scan_keys, Scan};
 
 mod sadd;
 pub use sadd::Sadd;
@@ -55,6 +76,12 @@ pub use sadd::Sadd;
 mod scard;
 pub use scard::Scard;
 
+mod sintercard;
+pub use sintercard::Sintercard;
+
+mod sinterstore;
+pub use sinterstore::Sinterstore;
+
 mod sismember;
 pub use sismember::Sismember;
 
@@ -91,6 +118,15 @@ pub use llen::Llen;
 mod lindex;
 pub use lindex::Lindex;
 
+mod lpos;
+pub use lpos::Lpos;
+
+mod lmpop;
+pub use lmpop::Lmpop;
+
+mod brpoplpush;
+pub use brpoplpush::Brpoplpush;
+
 mod lset;
 pub use lset::Lset;
 
@@ -157,12 +193,21 @@ pub use zrangebyscore::Zrangebyscore;
 mod zpop;
 pub use zpop::Zpop;
 
+mod zmpop;
+pub use zmpop::Zmpop;
+
 mod zrank;
 pub use zrank::Zrank;
 
 mod zincrby;
 pub use zincrby::Zincrby;
 
+mod pfadd;
+pub use pfadd::Pfadd;
+
+mod pfcount;
+pub use pfcount::Pfcount;
+
 mod zrem;
 pub use zrem::Zrem;
 
@@ -175,16 +220,66 @@ pub use zremrangebyscore::Zremrangebyscore;
 mod keys;
 pub use keys::Keys;
 
+mod cluster;
+pub use cluster::{Cluster, ClusterSubcmd};
+
 mod auth;
 pub use auth::Auth;
 
-use crate::config::txn_retry_count;
-use crate::metrics::TXN_RETRY_COUNTER;
+mod client_cmd;
+pub(crate) use client_cmd::valid_client_name;
+pub use client_cmd::{ClientCmd, ClientSubcommand};
+
+mod hello;
+pub use hello::Hello;
+
+mod reset;
+pub use reset::Reset;
+
+mod multi;
+pub use multi::{Discard, Exec, Multi};
+
+mod slowlog;
+pub use slowlog::{Slowlog, SlowlogSubcommand};
+
+mod latency;
+pub use latency::{Latency, LatencySubcmd};
+
+mod commanddocs;
+mod commandinfo;
+pub use commandinfo::{CommandInfo, CommandSubcmd};
+
+mod wait;
+pub use wait::Wait;
+
+mod config_cmd;
+pub use config_cmd::{ConfigCmd, ConfigSubcommand};
+
+mod debug;
+pub use debug::{DebugCmd, DebugSubcmd};
+
+mod acl;
+pub use acl::{Acl, AclSubcommand};
+
+mod eval;
+pub use eval::Eval;
+pub(crate) use eval::SCRIPT_LOCK;
+
+mod script;
+pub use script::{Script, ScriptSubcmd};
+
+use crate::config::{config_infra_or_default, retry_backoff_or_default, txn_retry_count_for_cmd};
+use crate::metrics::{TXN_RETRY_CMD_COUNTER, TXN_RETRY_COUNTER};
+use crate::raft::store::RaftResponse;
+use crate::raft::RaftRequest;
+use crate::rocks::encoding::DataType;
 use crate::rocks::errors::REDIS_NOT_SUPPORTED_ERR;
-use crate::{Connection, Db, Frame, Parse, ParseError, Shutdown};
+use crate::{Connection, Db, Frame, Parse, ParseError, Shutdown, RAFT_CLIENT};
 
 use crate::rocks::Result as RocksResult;
-use crate::utils::resp_err;
+use crate::utils::{resp_err, resp_nil};
+use tokio::time::sleep;
+use uuid::Uuid;
 
 /// Enumeration of supported Redis commands.
 ///
@@ -198,13 +293,25 @@ pub enum Command {
     Set(Set),
     Subscribe(Subscribe),
     Unsubscribe(Unsubscribe),
+    Spublish(Publish),
+    Ssubscribe(Subscribe),
+    Sunsubscribe(Unsubscribe),
+    Pubsub(Pubsub),
     Del(Del),
+    Unlink(Del),
+    Dump(Dump),
+    Restore(Restore),
+    Sort(Sort),
     Ping(Ping),
     Strlen(Strlen),
+    Getset(Getset),
+    Object(Object),
     Type(Type),
     Exists(Exists),
     Incr(IncrDecr),
     Decr(IncrDecr),
+    Incrby(IncrDecr),
+    Decrby(IncrDecr),
     Expire(Expire),
     ExpireAt(Expire),
     Pexpire(Expire),
@@ -213,10 +320,13 @@ pub enum Command {
     PTTL(TTL),
     Scan(Scan),
     Keys(Keys),
+    Cluster(Cluster),
 
     // set
     Sadd(Sadd),
     Scard(Scard),
+    Sintercard(Sintercard),
+    Sinterstore(Sinterstore),
     Sismember(Sismember),
     Smismember(Smismember),
     Smembers(Smembers),
@@ -229,10 +339,13 @@ pub enum Command {
     Rpush(Push),
     Lpop(Pop),
     Rpop(Pop),
+    Lmpop(Lmpop),
+    Brpoplpush(Brpoplpush),
     Lrange(Lrange),
     Ltrim(Ltrim),
     Llen(Llen),
     Lindex(Lindex),
+    Lpos(Lpos),
     Lset(Lset),
     Lrem(Lrem),
     Linsert(Linsert),
@@ -251,6 +364,7 @@ pub enum Command {
     Hincrby(Hincrby),
     Hexists(Hexists),
     Hstrlen(Hstrlen),
+    BitfieldRo(Bitfield),
 
     // sorted set
     Zadd(Zadd),
@@ -266,10 +380,29 @@ pub enum Command {
     Zcount(Zcount),
     Zpopmin(Zpop),
     Zpopmax(Zpop),
+    Zmpop(Zmpop),
     Zrank(Zrank),
     Zincrby(Zincrby),
+    Pfadd(Pfadd),
+    Pfcount(Pfcount),
 
     Auth(Auth),
+    ClientCmd(ClientCmd),
+    Hello(Hello),
+    Reset(Reset),
+    Multi(Multi),
+    Exec(Exec),
+    Discard(Discard),
+    Acl(Acl),
+    Slowlog(Slowlog),
+    Latency(Latency),
+    CommandInfo(CommandInfo),
+    Wait(Wait),
+    ConfigCmd(ConfigCmd),
+    DebugCmd(DebugCmd),
+    Eval(Eval),
+    Evalsha(Eval),
+    Script(Script),
 
     Unknown(Unknown),
 }
@@ -312,12 +445,34 @@ impl Command {
             "set" => Command::Set(Set::parse_frames(&mut parse)?),
             "subscribe" => Command::Subscribe(Subscribe::parse_frames(&mut parse)?),
             "unsubscribe" => Command::Unsubscribe(Unsubscribe::parse_frames(&mut parse)?),
+            "spublish" => Command::Spublish(Publish::parse_frames(&mut parse)?),
+            "ssubscribe" => Command::Ssubscribe(Subscribe::parse_frames(&mut parse)?),
+            "sunsubscribe" => Command::Sunsubscribe(Unsubscribe::parse_frames(&mut parse)?),
+            "pubsub" => Command::Pubsub(transform_parse(
+                Pubsub::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "del" => Command::Del(transform_parse(Del::parse_frames(&mut parse), &mut parse)),
+            "unlink" => Command::Unlink(transform_parse(Del::parse_frames(&mut parse), &mut parse)),
+            "dump" => Command::Dump(transform_parse(Dump::parse_frames(&mut parse), &mut parse)),
+            "restore" => Command::Restore(transform_parse(
+                Restore::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "sort" => Command::Sort(transform_parse(Sort::parse_frames(&mut parse), &mut parse)),
             "ping" => Command::Ping(Ping::parse_frames(&mut parse)?),
             "strlen" => Command::Strlen(transform_parse(
                 Strlen::parse_frames(&mut parse),
                 &mut parse,
             )),
+            "getset" => Command::Getset(transform_parse(
+                Getset::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "object" => Command::Object(transform_parse(
+                Object::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "type" => Command::Type(transform_parse(Type::parse_frames(&mut parse), &mut parse)),
             "exists" => Command::Exists(transform_parse(
                 Exists::parse_frames(&mut parse),
@@ -331,6 +486,14 @@ impl Command {
                 IncrDecr::parse_frames(&mut parse, true),
                 &mut parse,
             )),
+            "incrby" => Command::Incrby(transform_parse(
+                IncrDecr::parse_frames(&mut parse, false),
+                &mut parse,
+            )),
+            "decrby" => Command::Decrby(transform_parse(
+                IncrDecr::parse_frames(&mut parse, false),
+                &mut parse,
+            )),
             "expire" => Command::Expire(transform_parse(
                 Expire::parse_frames(&mut parse),
                 &mut parse,
@@ -351,8 +514,20 @@ impl Command {
             "pttl" => Command::PTTL(transform_parse(TTL::parse_frames(&mut parse), &mut parse)),
             "scan" => Command::Scan(transform_parse(Scan::parse_frames(&mut parse), &mut parse)),
             "keys" => Command::Keys(transform_parse(Keys::parse_frames(&mut parse), &mut parse)),
+            "cluster" => Command::Cluster(transform_parse(
+                Cluster::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "sadd" => Command::Sadd(transform_parse(Sadd::parse_frames(&mut parse), &mut parse)),
             "scard" => Command::Scard(transform_parse(Scard::parse_frames(&mut parse), &mut parse)),
+            "sintercard" => Command::Sintercard(transform_parse(
+                Sintercard::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "sinterstore" => Command::Sinterstore(transform_parse(
+                Sinterstore::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "sismember" => Command::Sismember(transform_parse(
                 Sismember::parse_frames(&mut parse),
                 &mut parse,
@@ -375,6 +550,11 @@ impl Command {
             "rpush" => Command::Rpush(transform_parse(Push::parse_frames(&mut parse), &mut parse)),
             "lpop" => Command::Lpop(transform_parse(Pop::parse_frames(&mut parse), &mut parse)),
             "rpop" => Command::Rpop(transform_parse(Pop::parse_frames(&mut parse), &mut parse)),
+            "lmpop" => Command::Lmpop(transform_parse(Lmpop::parse_frames(&mut parse), &mut parse)),
+            "brpoplpush" => Command::Brpoplpush(transform_parse(
+                Brpoplpush::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "lrange" => Command::Lrange(transform_parse(
                 Lrange::parse_frames(&mut parse),
                 &mut parse,
@@ -385,6 +565,7 @@ impl Command {
                 Lindex::parse_frames(&mut parse),
                 &mut parse,
             )),
+            "lpos" => Command::Lpos(transform_parse(Lpos::parse_frames(&mut parse), &mut parse)),
             "lset" => Command::Lset(transform_parse(Lset::parse_frames(&mut parse), &mut parse)),
             "lrem" => Command::Lrem(transform_parse(Lrem::parse_frames(&mut parse), &mut parse)),
             "linsert" => Command::Linsert(transform_parse(
@@ -414,6 +595,10 @@ impl Command {
                 Hexists::parse_frames(&mut parse),
                 &mut parse,
             )),
+            "bitfield_ro" => Command::BitfieldRo(transform_parse(
+                Bitfield::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "hstrlen" => Command::Hstrlen(transform_parse(
                 Hstrlen::parse_frames(&mut parse),
                 &mut parse,
@@ -459,12 +644,57 @@ impl Command {
             "zpopmax" => {
                 Command::Zpopmax(transform_parse(Zpop::parse_frames(&mut parse), &mut parse))
             }
+            "zmpop" => Command::Zmpop(transform_parse(Zmpop::parse_frames(&mut parse), &mut parse)),
             "zrank" => Command::Zrank(transform_parse(Zrank::parse_frames(&mut parse), &mut parse)),
             "zincrby" => Command::Zincrby(transform_parse(
                 Zincrby::parse_frames(&mut parse),
                 &mut parse,
             )),
+            "pfadd" => Command::Pfadd(transform_parse(Pfadd::parse_frames(&mut parse), &mut parse)),
+            "pfcount" => Command::Pfcount(transform_parse(
+                Pfcount::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "auth" => Command::Auth(transform_parse(Auth::parse_frames(&mut parse), &mut parse)),
+            "hello" => Command::Hello(transform_parse(Hello::parse_frames(&mut parse), &mut parse)),
+            "reset" => Command::Reset(Reset::parse_frames(&mut parse)?),
+            "multi" => Command::Multi(Multi::parse_frames(&mut parse)?),
+            "exec" => Command::Exec(Exec::parse_frames(&mut parse)?),
+            "discard" => Command::Discard(Discard::parse_frames(&mut parse)?),
+            "acl" => Command::Acl(transform_parse(Acl::parse_frames(&mut parse), &mut parse)),
+            "client" => Command::ClientCmd(transform_parse(
+                ClientCmd::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "slowlog" => Command::Slowlog(transform_parse(
+                Slowlog::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "latency" => Command::Latency(transform_parse(
+                Latency::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "command" => Command::CommandInfo(transform_parse(
+                CommandInfo::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "wait" => Command::Wait(transform_parse(Wait::parse_frames(&mut parse), &mut parse)),
+            "config" => Command::ConfigCmd(transform_parse(
+                ConfigCmd::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "debug" => Command::DebugCmd(transform_parse(
+                DebugCmd::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "eval" => Command::Eval(transform_parse(Eval::parse_frames(&mut parse), &mut parse)),
+            "evalsha" => {
+                Command::Evalsha(transform_parse(Eval::parse_frames(&mut parse), &mut parse))
+            }
+            "script" => Command::Script(transform_parse(
+                Script::parse_frames(&mut parse),
+                &mut parse,
+            )),
 
             _ => {
                 // The command is not recognized and an Unknown command is
@@ -490,14 +720,16 @@ impl Command {
         use Command::*;
 
         match self {
-            Ping(_) | Type(_) | Auth(_) | Unknown(_) => CommandType::MANAGE,
-            Mset(_) | Set(_) | Del(_) | Incr(_) | Decr(_) | Expire(_) | ExpireAt(_)
-            | Pexpire(_) | PexpireAt(_) | Sadd(_) | Spop(_) | Srem(_) | Lpush(_) | Rpush(_)
-            | Lpop(_) | Rpop(_) | Ltrim(_) | Lset(_) | Lrem(_) | Linsert(_) | Hset(_)
-            | Hmset(_) | Hsetnx(_) | Hdel(_) | Hincrby(_) | Zadd(_) | Zrem(_)
-            | Zremrangebyscore(_) | Zremrangebyrank(_) | Zpopmin(_) | Zpopmax(_) | Zincrby(_) => {
-                CommandType::WRITE
-            }
+            Ping(_) | Type(_) | Auth(_) | ClientCmd(_) | Hello(_) | Reset(_) | Multi(_)
+            | Exec(_) | Discard(_) | Acl(_) | Slowlog(_) | Latency(_) | CommandInfo(_)
+            | Wait(_) | ConfigCmd(_) | DebugCmd(_) | Script(_) | Unknown(_) => CommandType::MANAGE,
+            Mset(_) | Set(_) | Getset(_) | Del(_) | Unlink(_) | Restore(_) | Sort(_) | Incr(_)
+            | Decr(_) | Incrby(_) | Decrby(_) | Expire(_) | ExpireAt(_) | Pexpire(_)
+            | PexpireAt(_) | Sadd(_) | Sinterstore(_) | Spop(_) | Srem(_) | Lpush(_) | Rpush(_)
+            | Lpop(_) | Rpop(_) | Lmpop(_) | Brpoplpush(_) | Ltrim(_) | Lset(_) | Lrem(_)
+            | Linsert(_) | Hset(_) | Hmset(_) | Hsetnx(_) | Hdel(_) | Hincrby(_) | Zadd(_)
+            | Zrem(_) | Zremrangebyscore(_) | Zremrangebyrank(_) | Zpopmin(_) | Zpopmax(_)
+            | Zmpop(_) | Zincrby(_) | Pfadd(_) | Eval(_) | Evalsha(_) => CommandType::WRITE,
             _ => CommandType::READ,
         }
     }
@@ -518,16 +750,27 @@ impl Command {
             Get(cmd) => cmd.apply(dst).await,
             Mget(cmd) => cmd.apply(dst).await,
             Mset(cmd) => cmd.apply(dst).await,
-            Publish(cmd) => cmd.apply(db, dst).await,
+            Publish(cmd) => cmd.apply(db, dst, false).await,
+            Spublish(cmd) => cmd.apply(db, dst, true).await,
+            Pubsub(cmd) => cmd.apply(db, dst).await,
             Set(cmd) => cmd.apply(dst).await,
-            Subscribe(cmd) => cmd.apply(db, dst, shutdown).await,
-            Del(cmd) => cmd.apply(dst).await,
+            Subscribe(cmd) => cmd.apply(db, dst, shutdown, false).await,
+            Ssubscribe(cmd) => cmd.apply(db, dst, shutdown, true).await,
+            Del(cmd) => cmd.apply(dst, false).await,
+            Unlink(cmd) => cmd.apply(dst, true).await,
+            Dump(cmd) => cmd.apply(dst).await,
+            Restore(cmd) => cmd.apply(dst).await,
+            Sort(cmd) => cmd.apply(dst).await,
             Ping(cmd) => cmd.apply(dst).await,
             Strlen(cmd) => cmd.apply(dst).await,
+            Getset(cmd) => cmd.apply(dst).await,
+            Object(cmd) => cmd.apply(dst).await,
             Type(cmd) => cmd.apply(dst).await,
             Exists(cmd) => cmd.apply(dst).await,
-            Incr(cmd) => cmd.apply(dst, true).await,
-            Decr(cmd) => cmd.apply(dst, false).await,
+            Incr(cmd) => cmd.apply(dst, "incr", true).await,
+            Decr(cmd) => cmd.apply(dst, "decr", false).await,
+            Incrby(cmd) => cmd.apply(dst, "incrby", true).await,
+            Decrby(cmd) => cmd.apply(dst, "decrby", false).await,
             Expire(cmd) => cmd.apply(dst, false, false).await,
             ExpireAt(cmd) => cmd.apply(dst, false, true).await,
             Pexpire(cmd) => cmd.apply(dst, true, false).await,
@@ -536,8 +779,11 @@ impl Command {
             PTTL(cmd) => cmd.apply(dst, true).await,
             Scan(cmd) => cmd.apply(dst).await,
             Keys(cmd) => cmd.apply(dst).await,
+            Cluster(cmd) => cmd.apply(dst).await,
             Sadd(cmd) => cmd.apply(dst).await,
             Scard(cmd) => cmd.apply(dst).await,
+            Sintercard(cmd) => cmd.apply(dst).await,
+            Sinterstore(cmd) => cmd.apply(dst).await,
             Sismember(cmd) => cmd.apply(dst).await,
             Smismember(cmd) => cmd.apply(dst).await,
             Smembers(cmd) => cmd.apply(dst).await,
@@ -548,10 +794,12 @@ impl Command {
             Rpush(cmd) => cmd.apply(dst, false).await,
             Lpop(cmd) => cmd.apply(dst, true).await,
             Rpop(cmd) => cmd.apply(dst, false).await,
+            Lmpop(cmd) => cmd.apply(dst).await,
             Lrange(cmd) => cmd.apply(dst).await,
             Ltrim(cmd) => cmd.apply(dst).await,
             Llen(cmd) => cmd.apply(dst).await,
             Lindex(cmd) => cmd.apply(dst).await,
+            Lpos(cmd) => cmd.apply(dst).await,
             Lset(cmd) => cmd.apply(dst).await,
             Lrem(cmd) => cmd.apply(dst).await,
             Linsert(cmd) => cmd.apply(dst).await,
@@ -568,6 +816,7 @@ impl Command {
             Hincrby(cmd) => cmd.apply(dst).await,
             Hexists(cmd) => cmd.apply(dst).await,
             Hstrlen(cmd) => cmd.apply(dst).await,
+            BitfieldRo(cmd) => cmd.apply(dst).await,
             Zadd(cmd) => cmd.apply(dst).await,
             Zcard(cmd) => cmd.apply(dst).await,
             Zscore(cmd) => cmd.apply(dst).await,
@@ -581,13 +830,17 @@ impl Command {
             Zcount(cmd) => cmd.apply(dst).await,
             Zpopmin(cmd) => cmd.apply(dst, true).await,
             Zpopmax(cmd) => cmd.apply(dst, false).await,
+            Zmpop(cmd) => cmd.apply(dst).await,
             Zrank(cmd) => cmd.apply(dst).await,
             Zincrby(cmd) => cmd.apply(dst).await,
+            Pfadd(cmd) => cmd.apply(dst).await,
+            Pfcount(cmd) => cmd.apply(dst).await,
 
             Unknown(cmd) => cmd.apply(dst).await,
-            // `Unsubscribe` cannot be applied. It may only be received from the
-            // context of a `Subscribe` command.
-            Unsubscribe(_) => Err("`Unsubscribe` is unsupported in this context".into()),
+            // Within the context of a `Subscribe` command, `Unsubscribe` is
+            // handled by the subscription loop instead.
+            Unsubscribe(cmd) => cmd.apply(dst, false).await,
+            Sunsubscribe(cmd) => cmd.apply(dst, true).await,
 
             _ => Ok(()),
         }
@@ -603,10 +856,18 @@ impl Command {
             Mset(cmd) => cmd.hash_ring_key(),
             Set(cmd) => cmd.hash_ring_key(),
             Del(cmd) => cmd.hash_ring_key(),
+            Unlink(cmd) => cmd.hash_ring_key(),
+            Dump(cmd) => cmd.hash_ring_key(),
+            Restore(cmd) => cmd.hash_ring_key(),
+            Sort(cmd) => cmd.hash_ring_key(),
             Strlen(cmd) => cmd.hash_ring_key(),
+            Getset(cmd) => cmd.hash_ring_key(),
+            Object(cmd) => cmd.hash_ring_key(),
             Exists(cmd) => cmd.hash_ring_key(),
             Incr(cmd) => cmd.hash_ring_key(),
             Decr(cmd) => cmd.hash_ring_key(),
+            Incrby(cmd) => cmd.hash_ring_key(),
+            Decrby(cmd) => cmd.hash_ring_key(),
             Expire(cmd) => cmd.hash_ring_key(),
             ExpireAt(cmd) => cmd.hash_ring_key(),
             Pexpire(cmd) => cmd.hash_ring_key(),
@@ -615,6 +876,8 @@ impl Command {
             PTTL(cmd) => cmd.hash_ring_key(),
             Sadd(cmd) => cmd.hash_ring_key(),
             Scard(cmd) => cmd.hash_ring_key(),
+            Sintercard(cmd) => cmd.hash_ring_key(),
+            Sinterstore(cmd) => cmd.hash_ring_key(),
             Sismember(cmd) => cmd.hash_ring_key(),
             Smismember(cmd) => cmd.hash_ring_key(),
             Smembers(cmd) => cmd.hash_ring_key(),
@@ -625,10 +888,13 @@ impl Command {
             Rpush(cmd) => cmd.hash_ring_key(),
             Lpop(cmd) => cmd.hash_ring_key(),
             Rpop(cmd) => cmd.hash_ring_key(),
+            Lmpop(cmd) => cmd.hash_ring_key(),
+            Brpoplpush(cmd) => cmd.hash_ring_key(),
             Lrange(cmd) => cmd.hash_ring_key(),
             Ltrim(cmd) => cmd.hash_ring_key(),
             Llen(cmd) => cmd.hash_ring_key(),
             Lindex(cmd) => cmd.hash_ring_key(),
+            Lpos(cmd) => cmd.hash_ring_key(),
             Lset(cmd) => cmd.hash_ring_key(),
             Lrem(cmd) => cmd.hash_ring_key(),
             Linsert(cmd) => cmd.hash_ring_key(),
@@ -645,6 +911,7 @@ impl Command {
             Hincrby(cmd) => cmd.hash_ring_key(),
             Hexists(cmd) => cmd.hash_ring_key(),
             Hstrlen(cmd) => cmd.hash_ring_key(),
+            BitfieldRo(cmd) => cmd.hash_ring_key(),
             Zadd(cmd) => cmd.hash_ring_key(),
             Zcard(cmd) => cmd.hash_ring_key(),
             Zscore(cmd) => cmd.hash_ring_key(),
@@ -658,13 +925,41 @@ impl Command {
             Zcount(cmd) => cmd.hash_ring_key(),
             Zpopmin(cmd) => cmd.hash_ring_key(),
             Zpopmax(cmd) => cmd.hash_ring_key(),
+            Zmpop(cmd) => cmd.hash_ring_key(),
             Zrank(cmd) => cmd.hash_ring_key(),
             Zincrby(cmd) => cmd.hash_ring_key(),
+            Pfadd(cmd) => cmd.hash_ring_key(),
+            Pfcount(cmd) => cmd.hash_ring_key(),
+            Eval(cmd) => cmd.hash_ring_key(),
+            Evalsha(cmd) => cmd.hash_ring_key(),
 
             _ => Err("`Unsubscribe` is unsupported in this context".into()),
         }
     }
 
+    /// Every key the command reads or writes, checked against the key
+    /// patterns of the acl user. Unlike `hash_ring_key`, multi-key commands
+    /// return all their keys.
+    pub(crate) fn keys(&self) -> Vec<String> {
+        use Command::*;
+
+        match self {
+            Mget(cmd) => cmd.keys().clone(),
+            Mset(cmd) => cmd.keys().clone(),
+            Del(cmd) | Unlink(cmd) => cmd.keys().clone(),
+            Exists(cmd) => cmd.keys().clone(),
+            Pfcount(cmd) => cmd.keys().clone(),
+            Sintercard(cmd) => cmd.keys().clone(),
+            Lmpop(cmd) => cmd.keys().clone(),
+            Zmpop(cmd) => cmd.keys().clone(),
+            Eval(cmd) | Evalsha(cmd) => cmd.keys().clone(),
+            Sinterstore(cmd) => cmd.keys(),
+            Brpoplpush(cmd) => cmd.keys(),
+            Sort(cmd) => cmd.keys(),
+            _ => self.hash_ring_key().map_or(vec![], |key| vec![key]),
+        }
+    }
+
     /// Execute the command for remote node requests, only used in cluster.
     pub(crate) async fn execute_for_remote(mut self) -> crate::Result<Frame> {
         use Command::*;
@@ -675,10 +970,18 @@ impl Command {
             Mset(cmd) => cmd.batch_put().await,
             Set(cmd) => cmd.set().await,
             Del(cmd) => cmd.del().await,
+            Unlink(cmd) => cmd.unlink().await,
+            Dump(cmd) => cmd.dump().await,
+            Restore(cmd) => cmd.restore().await,
+            Sort(cmd) => cmd.sort().await,
             Strlen(cmd) => cmd.strlen().await,
+            Getset(cmd) => cmd.getset().await,
+            Object(cmd) => cmd.object().await,
             Exists(cmd) => cmd.exists().await,
             Incr(cmd) => cmd.incr_by(true).await,
             Decr(cmd) => cmd.incr_by(false).await,
+            Incrby(cmd) => cmd.incr_by(true).await,
+            Decrby(cmd) => cmd.incr_by(false).await,
             Expire(cmd) => cmd.expire(false, false).await,
             ExpireAt(cmd) => cmd.expire(false, true).await,
             Pexpire(cmd) => cmd.expire(true, false).await,
@@ -687,6 +990,8 @@ impl Command {
             PTTL(cmd) => cmd.ttl(true).await,
             Sadd(cmd) => cmd.sadd().await,
             Scard(cmd) => cmd.scard().await,
+            Sintercard(cmd) => cmd.sintercard().await,
+            Sinterstore(cmd) => cmd.sinterstore().await,
             Sismember(cmd) => cmd.sismember().await,
             Smismember(cmd) => cmd.smismember().await,
             Smembers(cmd) => cmd.smembers().await,
@@ -697,10 +1002,13 @@ impl Command {
             Rpush(cmd) => cmd.push(false).await,
             Lpop(cmd) => cmd.pop(true).await,
             Rpop(cmd) => cmd.pop(false).await,
+            Lmpop(cmd) => cmd.lmpop().await,
+            Brpoplpush(cmd) => cmd.rpoplpush().await,
             Lrange(cmd) => cmd.lrange().await,
             Ltrim(cmd) => cmd.ltrim().await,
             Llen(cmd) => cmd.llen().await,
             Lindex(cmd) => cmd.lindex().await,
+            Lpos(cmd) => cmd.lpos().await,
             Lset(cmd) => cmd.lset().await,
             Lrem(cmd) => cmd.lrem().await,
             Linsert(cmd) => cmd.linsert().await,
@@ -717,6 +1025,7 @@ impl Command {
             Hincrby(cmd) => cmd.hincrby().await,
             Hexists(cmd) => cmd.hexists().await,
             Hstrlen(cmd) => cmd.hstrlen().await,
+            BitfieldRo(cmd) => cmd.bitfield_ro().await,
             Zadd(cmd) => cmd.zadd().await,
             Zcard(cmd) => cmd.zcard().await,
             Zscore(cmd) => cmd.zscore().await,
@@ -730,14 +1039,84 @@ impl Command {
             Zcount(cmd) => cmd.zcount().await,
             Zpopmin(cmd) => cmd.zpop(true).await,
             Zpopmax(cmd) => cmd.zpop(false).await,
+            Zmpop(cmd) => cmd.zmpop().await,
             Zrank(cmd) => cmd.zrank().await,
             Zincrby(cmd) => cmd.zincrby().await,
+            Pfadd(cmd) => cmd.pfadd().await,
+            Pfcount(cmd) => cmd.pfcount().await,
+            Type(cmd) => cmd.cmd_type().await,
+            Keys(cmd) => cmd.keys().await,
+            Scan(cmd) => cmd.scan().await,
+            Ping(cmd) => Ok(cmd.pong()),
 
             _ => Ok(resp_err(REDIS_NOT_SUPPORTED_ERR)),
         }?;
         Ok(frame)
     }
 
+    /// Whether the command can be queued by `MULTI`. Commands changing the
+    /// connection state, blocking it or handled by the node receiving them are
+    /// not.
+    pub(crate) fn queueable(&self) -> bool {
+        use Command::*;
+
+        !matches!(
+            self,
+            Publish(_)
+                | Spublish(_)
+                | Subscribe(_)
+                | Unsubscribe(_)
+                | Ssubscribe(_)
+                | Sunsubscribe(_)
+                | Pubsub(_)
+                | Cluster(_)
+                | Auth(_)
+                | ClientCmd(_)
+                | Hello(_)
+                | Reset(_)
+                | Multi(_)
+                | Exec(_)
+                | Discard(_)
+                | Acl(_)
+                | Slowlog(_)
+                | Latency(_)
+                | CommandInfo(_)
+                | Wait(_)
+                | ConfigCmd(_)
+                | DebugCmd(_)
+                | Eval(_)
+                | Evalsha(_)
+                | Script(_)
+                | Unknown(_)
+        )
+    }
+
+    /// Returns the data type the command operates on, `None` for commands
+    /// not bound to a single type. Deleted key types are observed by `del`.
+    pub(crate) fn data_type(&self) -> Option<DataType> {
+        use Command::*;
+
+        match self {
+            Get(_) | Mget(_) | Mset(_) | Set(_) | Getset(_) | Strlen(_) | Incr(_) | Decr(_)
+            | Incrby(_) | Decrby(_) | BitfieldRo(_) => Some(DataType::String),
+            Sadd(_) | Scard(_) | Sintercard(_) | Sinterstore(_) | Sismember(_) | Smismember(_)
+            | Smembers(_) | Srandmember(_) | Spop(_) | Srem(_) => Some(DataType::Set),
+            Lpush(_) | Rpush(_) | Lpop(_) | Rpop(_) | Lmpop(_) | Brpoplpush(_) | Lrange(_)
+            | Ltrim(_) | Llen(_) | Lindex(_) | Lpos(_) | Lset(_) | Lrem(_) | Linsert(_) => {
+                Some(DataType::List)
+            }
+            Hset(_) | Hmset(_) | Hsetnx(_) | Hget(_) | Hmget(_) | Hlen(_) | Hgetall(_)
+            | Hdel(_) | Hkeys(_) | Hvals(_) | Hincrby(_) | Hexists(_) | Hstrlen(_) => {
+                Some(DataType::Hash)
+            }
+            Zadd(_) | Zcard(_) | Zscore(_) | Zrem(_) | Zremrangebyscore(_) | Zremrangebyrank(_)
+            | Zrange(_) | Zrevrange(_) | Zrangebyscore(_) | Zrevrangebyscore(_) | Zcount(_)
+            | Zpopmin(_) | Zpopmax(_) | Zmpop(_) | Zrank(_) | Zincrby(_) => Some(DataType::Zset),
+            Pfadd(_) | Pfcount(_) => Some(DataType::Hll),
+            _ => None,
+        }
+    }
+
     /// Returns the command name
     pub(crate) fn get_name(&self) -> &str {
         match self {
@@ -748,13 +1127,25 @@ impl Command {
             Command::Set(_) => "set",
             Command::Subscribe(_) => "subscribe",
             Command::Unsubscribe(_) => "unsubscribe",
+            Command::Spublish(_) => "spublish",
+            Command::Ssubscribe(_) => "ssubscribe",
+            Command::Sunsubscribe(_) => "sunsubscribe",
+            Command::Pubsub(_) => "pubsub",
             Command::Del(_) => "del",
+            Command::Unlink(_) => "unlink",
+            Command::Dump(_) => "dump",
+            Command::Restore(_) => "restore",
+            Command::Sort(_) => "sort",
             Command::Ping(_) => "ping",
             Command::Strlen(_) => "strlen",
+            Command::Getset(_) => "getset",
+            Command::Object(_) => "object",
             Command::Type(_) => "type",
             Command::Exists(_) => "exists",
             Command::Incr(_) => "incr",
             Command::Decr(_) => "decr",
+            Command::Incrby(_) => "incrby",
+            Command::Decrby(_) => "decrby",
             Command::Expire(_) => "expire",
             Command::ExpireAt(_) => "expireat",
             Command::Pexpire(_) => "pexpire",
@@ -763,8 +1154,11 @@ impl Command {
             Command::PTTL(_) => "pttl",
             Command::Scan(_) => "scan",
             Command::Keys(_) => "keys",
+            Command::Cluster(_) => "cluster",
             Command::Sadd(_) => "sadd",
             Command::Scard(_) => "scard",
+            Command::Sintercard(_) => "sintercard",
+            Command::Sinterstore(_) => "sinterstore",
             Command::Sismember(_) => "sismember",
             Command::Smismember(_) => "smismember",
             Command::Smembers(_) => "smembers",
@@ -775,10 +1169,13 @@ impl Command {
             Command::Rpush(_) => "rpush",
             Command::Lpop(_) => "lpop",
             Command::Rpop(_) => "rpop",
+            Command::Lmpop(_) => "lmpop",
+            Command::Brpoplpush(_) => "brpoplpush",
             Command::Lrange(_) => "lrange",
             Command::Ltrim(_) => "ltrim",
             Command::Llen(_) => "llen",
             Command::Lindex(_) => "lindex",
+            Command::Lpos(_) => "lpos",
             Command::Lset(_) => "lset",
             Command::Lrem(_) => "lrem",
             Command::Linsert(_) => "linsert",
@@ -795,6 +1192,7 @@ impl Command {
             Command::Hincrby(_) => "hincrby",
             Command::Hexists(_) => "hexists",
             Command::Hstrlen(_) => "hstrlen",
+            Command::BitfieldRo(_) => "bitfield_ro",
             Command::Zadd(_) => "zadd",
             Command::Zcard(_) => "zcard",
             Command::Zscore(_) => "zscore",
@@ -808,9 +1206,28 @@ impl Command {
             Command::Zcount(_) => "zcount",
             Command::Zpopmin(_) => "zpopmin",
             Command::Zpopmax(_) => "zpopmax",
+            Command::Zmpop(_) => "zmpop",
             Command::Zrank(_) => "zrank",
             Command::Zincrby(_) => "zincrby",
+            Command::Pfadd(_) => "pfadd",
+            Command::Pfcount(_) => "pfcount",
             Command::Auth(_) => "auth",
+            Command::ClientCmd(_) => "client",
+            Command::Hello(_) => "hello",
+            Command::Reset(_) => "reset",
+            Command::Multi(_) => "multi",
+            Command::Exec(_) => "exec",
+            Command::Discard(_) => "discard",
+            Command::Acl(_) => "acl",
+            Command::Slowlog(_) => "slowlog",
+            Command::Latency(_) => "latency",
+            Command::CommandInfo(_) => "command",
+            Command::Wait(_) => "wait",
+            Command::ConfigCmd(_) => "config",
+            Command::DebugCmd(_) => "debug",
+            Command::Eval(_) => "eval",
+            Command::Evalsha(_) => "evalsha",
+            Command::Script(_) => "script",
 
             Command::Unknown(cmd) => cmd.get_name(),
         }
@@ -847,20 +1264,110 @@ fn transform_parse<T: Invalid>(parse_res: crate::Result<T>, parse: &mut Parse) -
     }
 }
 
-async fn retry_call<'a, F>(mut f: F) -> RocksResult<Frame>
+/// Execute the command on this node, write commands go through raft like the
+/// ones executed for clients.
+pub(crate) async fn execute_replicated(cmd: Command) -> crate::Result<Frame> {
+    if config_infra_or_default().need_raft() && matches!(cmd.cmd_type(), CommandType::WRITE) {
+        if let Some(client) = unsafe { &RAFT_CLIENT } {
+            let response = client
+                .write(&RaftRequest::CmdLog {
+                    id: Uuid::new_v4().to_string(),
+                    cmd,
+                    trace_id: None,
+                })
+                .await?;
+            return match response.data {
+                RaftResponse::Frame(frame) => Ok(frame),
+                _ => Ok(resp_nil()),
+            };
+        }
+    }
+    cmd.execute_for_remote().await
+}
+
+async fn retry_call<'a, F>(cmd: &str, mut f: F) -> RocksResult<Frame>
 where
     F: FnMut() -> BoxFuture<'a, RocksResult<Frame>> + Copy,
 {
-    let mut retry = txn_retry_count();
+    let backoff = retry_backoff_or_default();
+    let mut retry = txn_retry_count_for_cmd(cmd);
+    let mut attempt = 0;
     let mut res = Frame::Null;
     while retry > 0 {
         res = f().await?;
         if let Frame::TxnFailed(_) = res {
             retry -= 1;
             TXN_RETRY_COUNTER.inc();
+            TXN_RETRY_CMD_COUNTER.with_label_values(&[cmd]).inc();
+            if retry > 0 {
+                sleep(backoff.delay(attempt)).await;
+                attempt += 1;
+            }
             continue;
         }
         return Ok(res);
     }
     Ok(res)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::retry_call;
+    use super::wait::acked_replicas;
+    use crate::config::retry_backoff_or_default;
+    use crate::Frame;
+    use futures::FutureExt;
+    use openraft::{CommittedLeaderId, LogId};
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_backoff_delay() {
+        let backoff = retry_backoff_or_default();
+        assert_eq!(backoff.delay(0), Duration::from_micros(100));
+        assert_eq!(backoff.delay(1), Duration::from_micros(200));
+        assert_eq!(backoff.delay(3), Duration::from_micros(800));
+        assert_eq!(backoff.delay(20), Duration::from_micros(10_000));
+    }
+
+    #[tokio::test]
+    async fn test_retry_call_backoff_on_conflict() {
+        let attempts = AtomicU32::new(0);
+        let attempts = &attempts;
+        let start = Instant::now();
+        // conflicts three times, then succeeds
+        let res = retry_call("test", || {
+            async move {
+                if attempts.fetch_add(1, Ordering::SeqCst) < 3 {
+                    Ok(Frame::TxnFailed("conflict".to_owned()))
+                } else {
+                    Ok(Frame::Simple("OK".to_owned()))
+                }
+            }
+            .boxed()
+        })
+        .await
+        .unwrap();
+
+        assert!(matches!(res, Frame::Simple(_)));
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+        // 100us + 200us + 400us
+        assert!(start.elapsed() >= Duration::from_micros(700));
+    }
+
+    #[test]
+    fn test_wait_acked_replicas() {
+        let log_id = |index| Some(LogId::new(CommittedLeaderId::new(1, 1), index));
+        let mut replication = BTreeMap::new();
+        replication.insert(1, log_id(10));
+        replication.insert(2, log_id(10));
+        // a learner which is not synced yet
+        replication.insert(3, None);
+        replication.insert(4, log_id(5));
+
+        assert_eq!(acked_replicas(Some(&replication), 1, 10), 1);
+        assert_eq!(acked_replicas(Some(&replication), 1, 5), 2);
+        assert_eq!(acked_replicas(None, 1, 0), 0);
+    }
+}
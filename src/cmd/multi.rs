@@ -0,0 +1,48 @@
+use crate::parse::Parse;
+use serde::{Deserialize, Serialize};
+
+/// `MULTI`, start queuing the commands of the connection until `EXEC` or
+/// `DISCARD`.
+///
+/// Like `RESET`, transactions are connection state and are handled by the
+/// connection handler directly.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Multi {}
+
+impl Multi {
+    pub fn new() -> Multi {
+        Multi {}
+    }
+
+    pub(crate) fn parse_frames(_parse: &mut Parse) -> crate::Result<Multi> {
+        Ok(Multi::new())
+    }
+}
+
+/// `EXEC`, run the commands queued since `MULTI` and reply their replies.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Exec {}
+
+impl Exec {
+    pub fn new() -> Exec {
+        Exec {}
+    }
+
+    pub(crate) fn parse_frames(_parse: &mut Parse) -> crate::Result<Exec> {
+        Ok(Exec::new())
+    }
+}
+
+/// `DISCARD`, drop the commands queued since `MULTI`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Discard {}
+
+impl Discard {
+    pub fn new() -> Discard {
+        Discard {}
+    }
+
+    pub(crate) fn parse_frames(_parse: &mut Parse) -> crate::Result<Discard> {
+        Ok(Discard::new())
+    }
+}
@@ -0,0 +1,151 @@
+use crate::cmd::Invalid;
+use crate::config::{maxmemory_policy_or_default, LOGGER};
+use crate::eviction::{access_frequency, idle_time};
+use crate::parse::Parse;
+use crate::{Connection, Frame};
+use serde::{Deserialize, Serialize};
+use slog::debug;
+
+use crate::rocks::errors::{RError, REDIS_LFU_NOT_SELECTED_ERR, REDIS_LFU_SELECTED_ERR};
+use crate::rocks::string::StringCommand;
+use crate::rocks::{get_client, Result as RocksResult};
+use crate::utils::{resp_array, resp_err, resp_int, resp_invalid_arguments, resp_nil, resp_str};
+
+const OBJECT_HELP: [&str; 13] = [
+    "OBJECT <subcommand> [<arg> [value] [opt] ...]. Subcommands are:",
+    "ENCODING <key>",
+    "    Return the kind of internal representation used in order to store the value associated with a <key>.",
+    "FREQ <key>",
+    "    Return the access frequency index of the <key>. The returned integer is proportional to the logarithm of the recent access frequency of the key.",
+    "IDLETIME <key>",
+    "    Return the idle time of the <key>, that is the approximated number of seconds elapsed since the last access to the key.",
+    "REFCOUNT <key>",
+    "    Return the number of references of the value associated with the specified <key>.",
+    "VERSION <key>",
+    "    Return the version of the <key>, which changes when the key is deleted and created again.",
+    "HELP",
+    "    Print this help.",
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ObjectSubcmd {
+    Encoding,
+    Refcount,
+    Idletime,
+    Freq,
+    Version,
+    Help,
+    Unknown(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Object {
+    subcommand: ObjectSubcmd,
+    key: String,
+    valid: bool,
+}
+
+impl Object {
+    pub fn new(subcommand: ObjectSubcmd, key: impl ToString) -> Object {
+        Object {
+            subcommand,
+            key: key.to_string(),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Object> {
+        let name = parse.next_string()?;
+        let subcommand = match name.to_lowercase().as_str() {
+            "encoding" => ObjectSubcmd::Encoding,
+            "refcount" => ObjectSubcmd::Refcount,
+            "idletime" => ObjectSubcmd::Idletime,
+            "freq" => ObjectSubcmd::Freq,
+            "version" => ObjectSubcmd::Version,
+            "help" => return Ok(Object::new(ObjectSubcmd::Help, "")),
+            _ => {
+                // the arguments of an unknown subcommand are ignored
+                while parse.next_bytes().is_ok() {}
+                return Ok(Object::new(ObjectSubcmd::Unknown(name), ""));
+            }
+        };
+        let key = parse.next_string()?;
+
+        Ok(Object::new(subcommand, key))
+    }
+
+    pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.object().await?;
+
+        debug!(LOGGER, "res, {:?}", response);
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn object(&self) -> RocksResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        match &self.subcommand {
+            ObjectSubcmd::Encoding => {
+                StringCommand::new(&get_client())
+                    .object_encoding(&self.key)
+                    .await
+            }
+            ObjectSubcmd::Refcount => {
+                StringCommand::new(&get_client())
+                    .object_refcount(&self.key)
+                    .await
+            }
+            ObjectSubcmd::Idletime => {
+                if maxmemory_policy_or_default().is_lfu() {
+                    return Ok(resp_err(REDIS_LFU_SELECTED_ERR));
+                }
+                Ok(match idle_time(&self.key)? {
+                    Some(idle) => resp_int(idle as i64),
+                    None => resp_nil(),
+                })
+            }
+            ObjectSubcmd::Freq => {
+                if !maxmemory_policy_or_default().is_lfu() {
+                    return Ok(resp_err(REDIS_LFU_NOT_SELECTED_ERR));
+                }
+                Ok(match access_frequency(&self.key)? {
+                    Some(counter) => resp_int(counter as i64),
+                    None => resp_nil(),
+                })
+            }
+            ObjectSubcmd::Version => {
+                StringCommand::new(&get_client())
+                    .object_version(&self.key)
+                    .await
+            }
+            ObjectSubcmd::Help => Ok(resp_array(
+                OBJECT_HELP.iter().map(|line| resp_str(line)).collect(),
+            )),
+            ObjectSubcmd::Unknown(name) => Ok(resp_err(RError::owned_error(format!(
+                "ERR unknown subcommand '{name}'. Try OBJECT HELP."
+            )))),
+        }
+    }
+
+    pub fn hash_ring_key(&self) -> crate::Result<String> {
+        Ok(self.key.to_string())
+    }
+}
+
+impl Invalid for Object {
+    fn new_invalid() -> Object {
+        Object {
+            subcommand: ObjectSubcmd::Encoding,
+            key: "".to_owned(),
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{Connection, Frame, Parse};
+
+use crate::cmd::{retry_call, Invalid};
+use crate::config::LOGGER;
+use crate::parse::ParseError;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use slog::debug;
+
+use crate::rocks::hll::HllCommand;
+use crate::rocks::{get_client, Result as RocksResult};
+use crate::utils::resp_invalid_arguments;
+
+/// `PFADD key [element ...]`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Pfadd {
+    key: String,
+    elements: Vec<String>,
+    valid: bool,
+}
+
+impl Pfadd {
+    pub fn new(key: &str, elements: Vec<String>) -> Pfadd {
+        Pfadd {
+            key: key.to_owned(),
+            elements,
+            valid: true,
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Pfadd> {
+        let key = parse.next_string()?;
+        let mut elements = vec![];
+        loop {
+            match parse.next_string() {
+                Ok(ele) => elements.push(ele),
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(Pfadd::new(&key, elements))
+    }
+
+    pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
+        let response = retry_call("pfadd", || async move { self.pfadd().await }.boxed()).await?;
+        debug!(LOGGER, "res, {:?}", response);
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn pfadd(&self) -> RocksResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        HllCommand::new(&get_client())
+            .pfadd(&self.key, &self.elements)
+            .await
+    }
+
+    pub fn hash_ring_key(&self) -> crate::Result<String> {
+        Ok(self.key.to_string())
+    }
+}
+
+impl Invalid for Pfadd {
+    fn new_invalid() -> Pfadd {
+        Pfadd {
+            key: "".to_owned(),
+            elements: vec![],
+            valid: false,
+        }
+    }
+}
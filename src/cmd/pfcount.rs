@@ -0,0 +1,71 @@
+use crate::{Connection, Frame, MapucheError, Parse};
+
+use crate::cmd::Invalid;
+use crate::config::LOGGER;
+use crate::parse::ParseError;
+use serde::{Deserialize, Serialize};
+use slog::debug;
+
+use crate::rocks::hll::HllCommand;
+use crate::rocks::{get_client, Result as RocksResult};
+use crate::utils::resp_invalid_arguments;
+
+/// `PFCOUNT key [key ...]`, counts the union of the hyperloglogs.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Pfcount {
+    keys: Vec<String>,
+    valid: bool,
+}
+
+impl Pfcount {
+    pub fn new(keys: Vec<String>) -> Pfcount {
+        Pfcount { keys, valid: true }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Pfcount> {
+        let mut keys = vec![parse.next_string()?];
+        loop {
+            match parse.next_string() {
+                Ok(key) => keys.push(key),
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(Pfcount::new(keys))
+    }
+
+    pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.pfcount().await?;
+        debug!(LOGGER, "res, {:?}", response);
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn pfcount(&self) -> RocksResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        HllCommand::new(&get_client()).pfcount(&self.keys).await
+    }
+
+    pub fn keys(&self) -> &Vec<String> {
+        &self.keys
+    }
+
+    pub fn hash_ring_key(&self) -> crate::Result<String> {
+        if self.keys.len() != 1 {
+            return Err(MapucheError::String("Cmd don't support cluster").into());
+        }
+        Ok(self.keys[0].to_string())
+    }
+}
+
+impl Invalid for Pfcount {
+    fn new_invalid() -> Pfcount {
+        Pfcount {
+            keys: vec![],
+            valid: false,
+        }
+    }
+}
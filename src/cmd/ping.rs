@@ -52,14 +52,19 @@ impl Ping {
     /// The response is written to `dst`. This is called by the server in order
     /// to execute a received command.
     pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = match self.msg.clone() {
-            None => Frame::Simple("PONG".to_string()),
-            Some(msg) => Frame::Bulk(Bytes::from(msg)),
-        };
+        let response = self.pong();
 
         // Write the response back to the client
         dst.write_frame(&response).await?;
 
         Ok(())
     }
+
+    /// The reply of the command, `PONG` or the message.
+    pub(crate) fn pong(&self) -> Frame {
+        match self.msg.clone() {
+            None => Frame::Simple("PONG".to_string()),
+            Some(msg) => Frame::Bulk(Bytes::from(msg)),
+        }
+    }
 }
@@ -62,11 +62,17 @@ impl Publish {
         Ok(Publish { channel, message })
     }
 
-    /// Apply the `Publish` command to the specified `Db` instance.
+    /// Apply the `Publish` command to the specified `Db` instance, `SPUBLISH`
+    /// publishes to the sharded channel when `sharded` is set.
     ///
     /// The response is written to `dst`. This is called by the server in order
     /// to execute a received command.
-    pub(crate) async fn apply(&self, db: &Db, dst: &mut Connection) -> crate::Result<()> {
+    pub(crate) async fn apply(
+        &self,
+        db: &Db,
+        dst: &mut Connection,
+        sharded: bool,
+    ) -> crate::Result<()> {
         // The shared state contains the `tokio::sync::broadcast::Sender` for
         // all active channels. Calling `db.publish` dispatches the message into
         // the appropriate channel.
@@ -76,7 +82,7 @@ impl Publish {
         // receive the message. Subscribers may drop before receiving the
         // message. Given this, `num_subscribers` should only be used as a
         // "hint".
-        let num_subscribers = db.publish(&self.channel, self.message.clone());
+        let num_subscribers = db.publish(&self.channel, self.message.clone(), sharded);
 
         // The number of subscribers is returned as the response to the publish
         // request.
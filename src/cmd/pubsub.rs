@@ -0,0 +1,117 @@
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::utils::{glob_pattern, resp_array, resp_bulk, resp_int, resp_invalid_arguments};
+use crate::{Connection, Db, Frame};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum PubsubSubcmd {
+    Channels(Option<String>),
+    Numsub(Vec<String>),
+    Numpat,
+    ShardChannels(Option<String>),
+    ShardNumsub(Vec<String>),
+}
+
+/// `PUBSUB CHANNELS [pattern]|NUMSUB [channel ...]|NUMPAT|SHARDCHANNELS
+/// [pattern]|SHARDNUMSUB [channel ...]`, introspects the channels subscribed
+/// on this node.
+///
+/// Pattern subscriptions are not supported, so `NUMPAT` is always 0.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Pubsub {
+    subcommand: PubsubSubcmd,
+    valid: bool,
+}
+
+impl Pubsub {
+    pub fn new(subcommand: PubsubSubcmd) -> Pubsub {
+        Pubsub {
+            subcommand,
+            valid: true,
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Pubsub> {
+        let subcommand = match parse.next_string()?.to_lowercase().as_str() {
+            "channels" => PubsubSubcmd::Channels(next_pattern(parse)?),
+            "numsub" => PubsubSubcmd::Numsub(next_channels(parse)?),
+            "numpat" => PubsubSubcmd::Numpat,
+            "shardchannels" => PubsubSubcmd::ShardChannels(next_pattern(parse)?),
+            "shardnumsub" => PubsubSubcmd::ShardNumsub(next_channels(parse)?),
+            _ => return Ok(Pubsub::new_invalid()),
+        };
+        Ok(Pubsub::new(subcommand))
+    }
+
+    pub(crate) async fn apply(&self, db: &Db, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.execute(db);
+        dst.write_frame(&response).await?;
+        Ok(())
+    }
+
+    fn execute(&self, db: &Db) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        match &self.subcommand {
+            PubsubSubcmd::Channels(pattern) => channels(db, false, pattern.as_deref()),
+            PubsubSubcmd::ShardChannels(pattern) => channels(db, true, pattern.as_deref()),
+            PubsubSubcmd::Numsub(names) => numsub(db, false, names),
+            PubsubSubcmd::ShardNumsub(names) => numsub(db, true, names),
+            PubsubSubcmd::Numpat => resp_int(0),
+        }
+    }
+}
+
+fn next_pattern(parse: &mut Parse) -> crate::Result<Option<String>> {
+    match parse.next_string() {
+        Ok(pattern) => Ok(Some(pattern)),
+        Err(ParseError::EndOfStream) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn next_channels(parse: &mut Parse) -> crate::Result<Vec<String>> {
+    let mut channels = vec![];
+    while let Some(channel) = next_pattern(parse)? {
+        channels.push(channel);
+    }
+    Ok(channels)
+}
+
+fn channels(db: &Db, sharded: bool, pattern: Option<&str>) -> Frame {
+    let pattern = pattern.map(glob_pattern);
+    let mut names: Vec<String> = db
+        .channels(sharded)
+        .into_keys()
+        .filter(|name| pattern.as_ref().map_or(true, |p| p.matches(name)))
+        .collect();
+    names.sort();
+    resp_array(
+        names
+            .into_iter()
+            .map(|name| resp_bulk(name.into_bytes()))
+            .collect(),
+    )
+}
+
+// channel names followed by their number of subscribers
+fn numsub(db: &Db, sharded: bool, names: &[String]) -> Frame {
+    let channels = db.channels(sharded);
+    let mut resp = vec![];
+    for name in names {
+        let count = channels.get(name).copied().unwrap_or(0);
+        resp.push(resp_bulk(name.clone().into_bytes()));
+        resp.push(resp_int(count as i64));
+    }
+    resp_array(resp)
+}
+
+impl Invalid for Pubsub {
+    fn new_invalid() -> Pubsub {
+        Pubsub {
+            subcommand: PubsubSubcmd::Numpat,
+            valid: false,
+        }
+    }
+}
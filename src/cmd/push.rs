@@ -65,7 +65,8 @@ impl Push {
     }
 
     pub(crate) async fn apply(&self, dst: &mut Connection, op_left: bool) -> crate::Result<()> {
-        let response = retry_call(|| async move { self.push(op_left).await }.boxed()).await?;
+        let cmd = if op_left { "lpush" } else { "rpush" };
+        let response = retry_call(cmd, || async move { self.push(op_left).await }.boxed()).await?;
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
@@ -0,0 +1,20 @@
+use crate::parse::Parse;
+use serde::{Deserialize, Serialize};
+
+/// `RESET`, bring the connection back to the state it had right after being
+/// accepted, so connection pools can reuse it without reconnecting.
+///
+/// Like `AUTH`, it changes connection state and is handled by the connection
+/// handler directly.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Reset {}
+
+impl Reset {
+    pub fn new() -> Reset {
+        Reset {}
+    }
+
+    pub(crate) fn parse_frames(_parse: &mut Parse) -> crate::Result<Reset> {
+        Ok(Reset::new())
+    }
+}
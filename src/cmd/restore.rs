@@ -0,0 +1,108 @@
+use crate::{Connection, Frame, Parse};
+
+use crate::cmd::dump::decode_dump;
+use crate::cmd::{retry_call, Invalid};
+use crate::config::LOGGER;
+use crate::parse::ParseError;
+use bytes::Bytes;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use slog::debug;
+
+use crate::rocks::errors::{REDIS_DUMP_PAYLOAD_ERR, REDIS_INVALID_TTL_ERR};
+use crate::rocks::string::StringCommand;
+use crate::rocks::{get_client, Result as RocksResult};
+use crate::utils::{resp_err, resp_invalid_arguments, timestamp_from_ttl};
+
+/// `RESTORE key ttl serialized-value [REPLACE]`, ttl in milliseconds and 0
+/// for no expiry.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Restore {
+    key: String,
+    ttl: i64,
+    payload: Bytes,
+    replace: bool,
+    valid: bool,
+}
+
+impl Restore {
+    pub fn new(key: impl ToString, ttl: i64, payload: Bytes, replace: bool) -> Restore {
+        Restore {
+            key: key.to_string(),
+            ttl,
+            payload,
+            replace,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Restore> {
+        let key = parse.next_string()?;
+        let ttl = parse.next_int()?;
+        let payload = parse.next_bytes()?;
+
+        let mut replace = false;
+        loop {
+            match parse.next_string() {
+                Ok(option) if option.to_uppercase() == "REPLACE" => replace = true,
+                Ok(_) => return Ok(Restore::new_invalid()),
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(Restore::new(key, ttl, payload, replace))
+    }
+
+    pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
+        let response =
+            retry_call("restore", || async move { self.restore().await }.boxed()).await?;
+        debug!(LOGGER, "res, {:?}", response);
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn restore(&self) -> RocksResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if self.ttl < 0 {
+            return Ok(resp_err(REDIS_INVALID_TTL_ERR));
+        }
+        let (data_type, elements) = match decode_dump(&self.payload) {
+            Some(decoded) => decoded,
+            None => return Ok(resp_err(REDIS_DUMP_PAYLOAD_ERR)),
+        };
+
+        // 0 for no expiry, the same as the meta value of a key without ttl
+        let timestamp = if self.ttl > 0 {
+            timestamp_from_ttl(self.ttl)
+        } else {
+            0
+        };
+        StringCommand::new(&get_client())
+            .restore(&self.key, data_type, elements, timestamp, self.replace)
+            .await
+    }
+
+    pub fn hash_ring_key(&self) -> crate::Result<String> {
+        Ok(self.key.to_string())
+    }
+}
+
+impl Invalid for Restore {
+    fn new_invalid() -> Restore {
+        Restore {
+            key: "".to_owned(),
+            ttl: 0,
+            payload: Bytes::new(),
+            replace: false,
+            valid: false,
+        }
+    }
+}
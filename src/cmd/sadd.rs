@@ -63,7 +63,7 @@ impl Sadd {
     }
 
     pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = retry_call(|| async move { self.sadd().await }.boxed()).await?;
+        let response = retry_call("sadd", || async move { self.sadd().await }.boxed()).await?;
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
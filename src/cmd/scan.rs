@@ -1,8 +1,9 @@
-use crate::{Connection, Frame, Parse};
+use crate::{Connection, Frame, MapucheError, Parse};
 
 use crate::cmd::Invalid;
 use crate::config::LOGGER;
 use bytes::Bytes;
+use futures::Stream;
 use serde::{Deserialize, Serialize};
 use slog::debug;
 
@@ -14,16 +15,16 @@ use crate::utils::resp_invalid_arguments;
 pub struct Scan {
     start: String,
     count: i64,
-    regex: String,
+    pattern: String,
     valid: bool,
 }
 
 impl Scan {
-    pub fn new(start: String, count: i64, regex: String) -> Scan {
+    pub fn new(start: String, count: i64, pattern: String) -> Scan {
         Scan {
             start,
             count,
-            regex,
+            pattern,
             valid: true,
         }
     }
@@ -35,21 +36,21 @@ impl Scan {
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Scan> {
         let start = parse.next_string()?;
         let mut count = 10;
-        let mut regex = ".*?".to_owned();
+        let mut pattern = "*".to_owned();
         while let Ok(flag) = parse.next_string() {
             if flag.to_uppercase().as_str() == "COUNT" {
                 if let Ok(c) = parse.next_int() {
                     count = c;
                 };
             } else if flag.to_uppercase().as_str() == "MATCH" {
-                regex = parse.next_string()?;
+                pattern = parse.next_string()?;
             }
         }
 
         Ok(Scan {
             start,
             count,
-            regex,
+            pattern,
             valid: true,
         })
     }
@@ -61,7 +62,7 @@ impl Scan {
         }
 
         let mut count = 10;
-        let mut regex = ".*?".to_owned();
+        let mut pattern = "*".to_owned();
         let start = String::from_utf8_lossy(&argv[0]);
         if argv.len() >= 3 {
             if argv[1].to_ascii_uppercase() == b"COUNT" {
@@ -71,7 +72,7 @@ impl Scan {
                     return Ok(Scan::new_invalid());
                 }
             } else if argv[1].to_ascii_uppercase() == b"MATCH" {
-                regex = String::from_utf8_lossy(&argv[2]).to_string();
+                pattern = String::from_utf8_lossy(&argv[2]).to_string();
             } else {
                 return Ok(Scan::new_invalid());
             }
@@ -83,7 +84,7 @@ impl Scan {
                         return Ok(Scan::new_invalid());
                     }
                 } else if argv[3].to_ascii_uppercase() == b"MATCH" {
-                    regex = String::from_utf8_lossy(&argv[4]).to_string();
+                    pattern = String::from_utf8_lossy(&argv[4]).to_string();
                 } else {
                     return Ok(Scan::new_invalid());
                 }
@@ -93,7 +94,7 @@ impl Scan {
         Ok(Scan {
             start: start.to_string(),
             count,
-            regex,
+            pattern,
             valid: true,
         })
     }
@@ -106,21 +107,74 @@ impl Scan {
     }
 
     pub async fn scan(&self) -> RocksResult<Frame> {
-        if !self.valid {
+        if !self.valid || self.count < 1 {
             return Ok(resp_invalid_arguments());
         }
         StringCommand::new(&get_client())
-            .scan(&self.start, self.count.try_into().unwrap(), &self.regex)
+            .scan(
+                &self.start,
+                self.count.min(u32::MAX as i64) as u32,
+                &self.pattern,
+            )
             .await
     }
 }
 
+/// Every live key matching `pattern`, fetched by `SCAN` calls of `batch_size`
+/// keys which follow the cursor until it wraps around to "0".
+pub fn scan_keys(
+    pattern: Option<&str>,
+    batch_size: u32,
+) -> impl Stream<Item = crate::Result<String>> + Send {
+    let pattern = pattern.unwrap_or("*").to_owned();
+    async_stream::try_stream! {
+        let mut cursor = "0".to_owned();
+        loop {
+            let frame = Scan::new(cursor, batch_size as i64, pattern.clone()).scan().await?;
+            let (next_cursor, keys) = decode_scan_reply(frame)?;
+            for key in keys {
+                yield key;
+            }
+            if next_cursor == "0" {
+                break;
+            }
+            cursor = next_cursor;
+        }
+    }
+}
+
+// the next cursor and the keys of a `SCAN` reply
+fn decode_scan_reply(frame: Frame) -> crate::Result<(String, Vec<String>)> {
+    match frame {
+        Frame::Array(mut parts) if parts.len() == 2 => {
+            let keys = match parts.pop() {
+                Some(Frame::Array(keys)) => keys
+                    .into_iter()
+                    .filter_map(|key| match key {
+                        Frame::Bulk(key) => Some(String::from_utf8_lossy(&key).to_string()),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => vec![],
+            };
+            match parts.pop() {
+                Some(Frame::Bulk(cursor)) => {
+                    Ok((String::from_utf8_lossy(&cursor).to_string(), keys))
+                }
+                _ => Err(MapucheError::String("invalid scan cursor").into()),
+            }
+        }
+        Frame::Error(e) => Err(MapucheError::Owned(e).into()),
+        _ => Err(MapucheError::String("invalid scan reply").into()),
+    }
+}
+
 impl Invalid for Scan {
     fn new_invalid() -> Scan {
         Scan {
             start: "".to_owned(),
             count: 0,
-            regex: "".to_owned(),
+            pattern: "".to_owned(),
             valid: false,
         }
     }
@@ -0,0 +1,84 @@
+use crate::cmd::eval::{cache_script, flush_scripts, script_exists};
+use crate::cmd::Invalid;
+use crate::parse::{Parse, ParseError};
+use crate::utils::{resp_array, resp_bulk, resp_int, resp_invalid_arguments, resp_ok};
+use crate::Frame;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ScriptSubcmd {
+    Load(String),
+    Exists(Vec<String>),
+    Flush,
+}
+
+/// `SCRIPT LOAD script|EXISTS sha1 [sha1 ...]|FLUSH [ASYNC|SYNC]`, manages the
+/// scripts cached for `EVALSHA` on the node which received it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Script {
+    subcommand: ScriptSubcmd,
+    valid: bool,
+}
+
+impl Script {
+    pub fn new(subcommand: ScriptSubcmd) -> Script {
+        Script {
+            subcommand,
+            valid: true,
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Script> {
+        let subcommand = match parse.next_string()?.to_lowercase().as_str() {
+            "load" => ScriptSubcmd::Load(parse.next_string()?),
+            "exists" => {
+                let mut shas = vec![parse.next_string()?];
+                loop {
+                    match parse.next_string() {
+                        Ok(sha) => shas.push(sha),
+                        Err(ParseError::EndOfStream) => break,
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+                ScriptSubcmd::Exists(shas)
+            }
+            "flush" => match parse.next_string() {
+                // scripts are always dropped at once
+                Ok(mode) if ["async", "sync"].contains(&mode.to_lowercase().as_str()) => {
+                    ScriptSubcmd::Flush
+                }
+                Err(ParseError::EndOfStream) => ScriptSubcmd::Flush,
+                _ => return Ok(Script::new_invalid()),
+            },
+            _ => return Ok(Script::new_invalid()),
+        };
+        Ok(Script::new(subcommand))
+    }
+
+    pub(crate) fn execute(&self) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        match &self.subcommand {
+            ScriptSubcmd::Load(script) => resp_bulk(cache_script(script).into_bytes()),
+            ScriptSubcmd::Exists(shas) => resp_array(
+                shas.iter()
+                    .map(|sha| resp_int(script_exists(sha) as i64))
+                    .collect(),
+            ),
+            ScriptSubcmd::Flush => {
+                flush_scripts();
+                resp_ok()
+            }
+        }
+    }
+}
+
+impl Invalid for Script {
+    fn new_invalid() -> Script {
+        Script {
+            subcommand: ScriptSubcmd::Flush,
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{Connection, Frame, MapucheError, Parse};
+
+use crate::cmd::Invalid;
+use crate::config::LOGGER;
+use crate::parse::ParseError;
+use serde::{Deserialize, Serialize};
+use slog::debug;
+
+use crate::rocks::set::SetCommand;
+use crate::rocks::{get_client, Result as RocksResult};
+use crate::utils::resp_invalid_arguments;
+
+/// `SINTERCARD numkeys key [key ...] [LIMIT limit]`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Sintercard {
+    keys: Vec<String>,
+    limit: u64,
+    valid: bool,
+}
+
+impl Sintercard {
+    pub fn new(keys: Vec<String>, limit: u64) -> Sintercard {
+        Sintercard {
+            keys,
+            limit,
+            valid: true,
+        }
+    }
+
+    pub fn keys(&self) -> &Vec<String> {
+        &self.keys
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Sintercard> {
+        let numkeys = parse.next_int()?;
+        if numkeys <= 0 {
+            return Ok(Sintercard::new_invalid());
+        }
+        let mut keys = Vec::with_capacity(numkeys as usize);
+        for _ in 0..numkeys {
+            keys.push(parse.next_string()?);
+        }
+
+        // 0 means no limit
+        let mut limit = 0;
+        match parse.next_string() {
+            Ok(option) if option.to_uppercase() == "LIMIT" => limit = parse.next_int()?,
+            Ok(_) => return Ok(Sintercard::new_invalid()),
+            Err(ParseError::EndOfStream) => {}
+            Err(e) => return Err(e.into()),
+        }
+        if limit < 0 {
+            return Ok(Sintercard::new_invalid());
+        }
+
+        Ok(Sintercard::new(keys, limit as u64))
+    }
+
+    pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.sintercard().await?;
+        debug!(LOGGER, "res, {:?}", response);
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn sintercard(&self) -> RocksResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        SetCommand::new(&get_client())
+            .sintercard(&self.keys, self.limit)
+            .await
+    }
+
+    pub fn keys(&self) -> &Vec<String> {
+        &self.keys
+    }
+
+    pub fn hash_ring_key(&self) -> crate::Result<String> {
+        if self.keys.len() != 1 {
+            return Err(MapucheError::String("Cmd don't support cluster").into());
+        }
+        Ok(self.keys[0].to_string())
+    }
+}
+
+impl Invalid for Sintercard {
+    fn new_invalid() -> Sintercard {
+        Sintercard {
+            keys: vec![],
+            limit: 0,
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{Connection, Frame, MapucheError, Parse};
+
+use crate::cmd::{retry_call, Invalid};
+use crate::config::LOGGER;
+use crate::parse::ParseError;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use slog::debug;
+
+use crate::rocks::set::SetCommand;
+use crate::rocks::{get_client, Result as RocksResult};
+use crate::utils::resp_invalid_arguments;
+
+/// `SINTERSTORE destination key [key ...]`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Sinterstore {
+    destination: String,
+    keys: Vec<String>,
+    valid: bool,
+}
+
+impl Sinterstore {
+    pub fn new(destination: &str, keys: Vec<String>) -> Sinterstore {
+        Sinterstore {
+            destination: destination.to_owned(),
+            keys,
+            valid: true,
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Sinterstore> {
+        let destination = parse.next_string()?;
+        let mut keys = vec![parse.next_string()?];
+        loop {
+            match parse.next_string() {
+                Ok(key) => keys.push(key),
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(Sinterstore::new(&destination, keys))
+    }
+
+    pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
+        let response = retry_call("sinterstore", || {
+            async move { self.sinterstore().await }.boxed()
+        })
+        .await?;
+        debug!(LOGGER, "res, {:?}", response);
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn sinterstore(&self) -> RocksResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        SetCommand::new(&get_client())
+            .sinterstore(&self.destination, &self.keys)
+            .await
+    }
+
+    /// The destination followed by the source keys.
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys = vec![self.destination.clone()];
+        keys.extend(self.keys.iter().cloned());
+        keys
+    }
+
+    pub fn hash_ring_key(&self) -> crate::Result<String> {
+        if self.keys.iter().any(|key| key != &self.destination) {
+            return Err(MapucheError::String("Cmd don't support cluster").into());
+        }
+        Ok(self.destination.to_string())
+    }
+}
+
+impl Invalid for Sinterstore {
+    fn new_invalid() -> Sinterstore {
+        Sinterstore {
+            destination: "".to_owned(),
+            keys: vec![],
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crate::cmd::Invalid;
+use crate::parse::{Parse, ParseError};
+use crate::utils::{resp_int, resp_invalid_arguments, resp_ok};
+use crate::{Db, Frame};
+use serde::{Deserialize, Serialize};
+
+// entries replied by `SLOWLOG GET` without count
+const SLOWLOG_DEFAULT_GET_COUNT: usize = 10;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum SlowlogSubcommand {
+    Get(usize),
+    Len,
+    Reset,
+}
+
+/// `SLOWLOG GET [count]|LEN|RESET`, the slow log is kept in memory of the node
+/// which executed the commands.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Slowlog {
+    subcommand: SlowlogSubcommand,
+    valid: bool,
+}
+
+impl Slowlog {
+    pub fn new(subcommand: SlowlogSubcommand) -> Slowlog {
+        Slowlog {
+            subcommand,
+            valid: true,
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Slowlog> {
+        let subcommand = match parse.next_string()?.to_lowercase().as_str() {
+            "get" => match parse.next_int() {
+                // -1 returns all the entries
+                Ok(-1) => SlowlogSubcommand::Get(usize::MAX),
+                Ok(count) if count >= 0 => SlowlogSubcommand::Get(count as usize),
+                Ok(_) => return Ok(Slowlog::new_invalid()),
+                Err(ParseError::EndOfStream) => SlowlogSubcommand::Get(SLOWLOG_DEFAULT_GET_COUNT),
+                Err(e) => return Err(e.into()),
+            },
+            "len" => SlowlogSubcommand::Len,
+            "reset" => SlowlogSubcommand::Reset,
+            _ => return Ok(Slowlog::new_invalid()),
+        };
+        Ok(Slowlog::new(subcommand))
+    }
+
+    pub(crate) fn execute(&self, db: &Db) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        let mut slow_log = db.slow_log().lock().unwrap();
+        match self.subcommand {
+            SlowlogSubcommand::Get(count) => slow_log.get(count),
+            SlowlogSubcommand::Len => resp_int(slow_log.len() as i64),
+            SlowlogSubcommand::Reset => {
+                slow_log.reset();
+                resp_ok()
+            }
+        }
+    }
+}
+
+impl Invalid for Slowlog {
+    fn new_invalid() -> Slowlog {
+        Slowlog {
+            subcommand: SlowlogSubcommand::Len,
+            valid: false,
+        }
+    }
+}
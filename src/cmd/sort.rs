@@ -0,0 +1,107 @@
+use crate::{Connection, Frame, MapucheError, Parse};
+
+use crate::cmd::{retry_call, Invalid};
+use crate::config::LOGGER;
+use crate::parse::ParseError;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use slog::debug;
+
+use crate::rocks::string::StringCommand;
+use crate::rocks::{get_client, Result as RocksResult};
+use crate::utils::resp_invalid_arguments;
+
+/// `SORT key [BY pattern] [LIMIT offset count] [GET pattern ...] [ASC|DESC]
+/// [ALPHA] [STORE destination]`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Sort {
+    key: String,
+    by: Option<String>,
+    limit: Option<(i64, i64)>,
+    get: Vec<String>,
+    desc: bool,
+    alpha: bool,
+    store: Option<String>,
+    valid: bool,
+}
+
+impl Sort {
+    pub fn new(key: impl ToString) -> Sort {
+        Sort {
+            key: key.to_string(),
+            valid: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Sort> {
+        let mut sort = Sort::new(parse.next_string()?);
+        loop {
+            let option = match parse.next_string() {
+                Ok(option) => option,
+                Err(ParseError::EndOfStream) => break,
+                Err(e) => return Err(e.into()),
+            };
+            match option.to_uppercase().as_str() {
+                "BY" => sort.by = Some(parse.next_string()?),
+                "LIMIT" => sort.limit = Some((parse.next_int()?, parse.next_int()?)),
+                "GET" => sort.get.push(parse.next_string()?),
+                "ASC" => sort.desc = false,
+                "DESC" => sort.desc = true,
+                "ALPHA" => sort.alpha = true,
+                "STORE" => sort.store = Some(parse.next_string()?),
+                _ => return Ok(Sort::new_invalid()),
+            }
+        }
+        Ok(sort)
+    }
+
+    pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
+        let response = retry_call("sort", || async move { self.sort().await }.boxed()).await?;
+        debug!(LOGGER, "res, {:?}", response);
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn sort(&self) -> RocksResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        StringCommand::new(&get_client())
+            .sort(
+                &self.key,
+                self.by.as_deref(),
+                self.limit,
+                &self.get,
+                self.desc,
+                self.alpha,
+                self.store.as_deref(),
+            )
+            .await
+    }
+
+    /// The sorted key and the `STORE` destination.
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys = vec![self.key.clone()];
+        keys.extend(self.store.clone());
+        keys
+    }
+
+    pub fn hash_ring_key(&self) -> crate::Result<String> {
+        if matches!(&self.store, Some(dst) if dst != &self.key) {
+            return Err(MapucheError::String("Cmd don't support cluster").into());
+        }
+        Ok(self.key.to_string())
+    }
+}
+
+impl Invalid for Sort {
+    fn new_invalid() -> Sort {
+        Sort::default()
+    }
+}
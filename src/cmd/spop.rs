@@ -57,7 +57,7 @@ impl Spop {
     }
 
     pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = retry_call(|| async move { self.spop().await }.boxed()).await?;
+        let response = retry_call("spop", || async move { self.spop().await }.boxed()).await?;
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
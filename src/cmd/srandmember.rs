@@ -6,9 +6,14 @@ use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 use slog::debug;
 
+use crate::rocks::errors::REDIS_VALUE_OUT_OF_RANGE_ERR;
 use crate::rocks::set::SetCommand;
 use crate::rocks::{get_client, Result as RocksResult};
-use crate::utils::resp_invalid_arguments;
+use crate::utils::{resp_err, resp_invalid_arguments};
+
+// elements of the reply to a negative count are all built in memory, larger
+// counts are refused
+const MAX_REPEATED_COUNT: i64 = 1_000_000;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Srandmember {
@@ -81,11 +86,14 @@ impl Srandmember {
                 repeatable = false;
             } else {
                 repeatable = true;
+                if count < -MAX_REPEATED_COUNT {
+                    return Ok(resp_err(REDIS_VALUE_OUT_OF_RANGE_ERR));
+                }
                 count = -count;
             }
         }
         SetCommand::new(&get_client())
-            .srandmemeber(&self.key, count, repeatable, array_resp)
+            .srandmember(&self.key, count, repeatable, array_resp)
             .await
     }
 
@@ -62,7 +62,7 @@ impl Srem {
     }
 
     pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = retry_call(|| async move { self.srem().await }.boxed()).await?;
+        let response = retry_call("srem", || async move { self.srem().await }.boxed()).await?;
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
@@ -33,6 +33,10 @@ pub struct Unsubscribe {
 /// a trait object.
 type Messages = Pin<Box<dyn Stream<Item = Bytes> + Send>>;
 
+/// A subscription is keyed by the channel name and whether the channel is a
+/// sharded one, the two key-spaces are separate.
+type Channel = (String, bool);
+
 impl Subscribe {
     #[allow(dead_code)]
     /// Creates a new `Subscribe` command to listen on the specified channels.
@@ -93,7 +97,8 @@ impl Subscribe {
         Ok(Subscribe { channels })
     }
 
-    /// Apply the `Subscribe` command to the specified `Db` instance.
+    /// Apply the `Subscribe` command to the specified `Db` instance, the
+    /// channels are sharded ones for `SSUBSCRIBE` when `sharded` is set.
     ///
     /// This function is the entry point and includes the initial list of
     /// channels to subscribe to. Additional `subscribe` and `unsubscribe`
@@ -106,6 +111,7 @@ impl Subscribe {
         db: &Db,
         dst: &mut Connection,
         shutdown: &mut Shutdown,
+        sharded: bool,
     ) -> crate::Result<()> {
         // Each individual channel subscription is handled using a
         // `sync::broadcast` channel. Messages are then fanned out to all
@@ -118,12 +124,19 @@ impl Subscribe {
         // they are received.
         let mut subscriptions = StreamMap::new();
 
+        // `subscribe_to` is used to track additional channels to subscribe
+        // to. When new `SUBSCRIBE` or `SSUBSCRIBE` commands are received
+        // during the execution of `apply`, the new channels are pushed onto
+        // this vec.
+        let mut subscribe_to: Vec<Channel> = self
+            .channels
+            .drain(..)
+            .map(|channel_name| (channel_name, sharded))
+            .collect();
+
         loop {
-            // `self.channels` is used to track additional channels to subscribe
-            // to. When new `SUBSCRIBE` commands are received during the
-            // execution of `apply`, the new channels are pushed onto this vec.
-            for channel_name in self.channels.drain(..) {
-                subscribe_to_channel(channel_name, &mut subscriptions, db, dst).await?;
+            for channel in subscribe_to.drain(..) {
+                subscribe_to_channel(channel, &mut subscriptions, db, dst).await?;
             }
 
             // Wait for one of the following to happen:
@@ -133,8 +146,8 @@ impl Subscribe {
             // - A server shutdown signal.
             select! {
                 // Receive messages from subscribed channels
-                Some((channel_name, msg)) = subscriptions.next() => {
-                    dst.write_frame(&make_message_frame(channel_name, msg)).await?;
+                Some(((channel_name, sharded), msg)) = subscriptions.next() => {
+                    dst.write_frame(&make_message_frame(channel_name, msg, sharded)).await?;
                 }
                 res = dst.read_frame() => {
                     let frame = match res? {
@@ -145,10 +158,16 @@ impl Subscribe {
 
                     handle_command(
                         frame,
-                        &mut self.channels,
+                        &mut subscribe_to,
                         &mut subscriptions,
                         dst,
                     ).await?;
+
+                    // Like redis, the client leaves the subscribed state once
+                    // it is unsubscribed from every channel.
+                    if subscriptions.is_empty() && subscribe_to.is_empty() {
+                        return Ok(());
+                    }
                 }
                 _ = shutdown.recv() => {
                     return Ok(());
@@ -158,13 +177,30 @@ impl Subscribe {
     }
 }
 
+/// Number of subscribed channels of a key-space, it is the count replied by
+/// the subscribe and unsubscribe commands of that key-space.
+fn subscription_count(subscriptions: &StreamMap<Channel, Messages>, sharded: bool) -> usize {
+    subscriptions.keys().filter(|(_, s)| *s == sharded).count()
+}
+
 async fn subscribe_to_channel(
-    channel_name: String,
-    subscriptions: &mut StreamMap<String, Messages>,
+    channel: Channel,
+    subscriptions: &mut StreamMap<Channel, Messages>,
     db: &Db,
     dst: &mut Connection,
 ) -> crate::Result<()> {
-    let mut rx = db.subscribe(channel_name.clone());
+    let (channel_name, sharded) = channel.clone();
+
+    // Subscribing to a channel twice keeps the current subscription, so no
+    // message is lost by replacing its receiver.
+    if subscriptions.contains_key(&channel) {
+        let count = subscription_count(subscriptions, sharded);
+        let response = make_subscribe_frame(channel_name, count, sharded);
+        dst.write_frame(&response).await?;
+        return Ok(());
+    }
+
+    let mut rx = db.subscribe(channel_name.clone(), sharded);
 
     // Subscribe to the channel.
     let rx = Box::pin(async_stream::stream! {
@@ -179,10 +215,11 @@ async fn subscribe_to_channel(
     });
 
     // Track subscription in this client's subscription set.
-    subscriptions.insert(channel_name.clone(), rx);
+    subscriptions.insert(channel, rx);
 
     // Respond with the successful subscription
-    let response = make_subscribe_frame(channel_name, subscriptions.len());
+    let count = subscription_count(subscriptions, sharded);
+    let response = make_subscribe_frame(channel_name, count, sharded);
     dst.write_frame(&response).await?;
 
     Ok(())
@@ -195,38 +232,28 @@ async fn subscribe_to_channel(
 /// `subscriptions`.
 async fn handle_command(
     frame: Frame,
-    subscribe_to: &mut Vec<String>,
-    subscriptions: &mut StreamMap<String, Messages>,
+    subscribe_to: &mut Vec<Channel>,
+    subscriptions: &mut StreamMap<Channel, Messages>,
     dst: &mut Connection,
 ) -> crate::Result<()> {
     // A command has been received from the client.
     //
-    // Only `SUBSCRIBE` and `UNSUBSCRIBE` commands are permitted
-    // in this context.
+    // Only `SUBSCRIBE`, `SSUBSCRIBE` and their unsubscribe commands are
+    // permitted in this context.
     match Command::from_frame(frame)? {
         Command::Subscribe(subscribe) => {
             // The `apply` method will subscribe to the channels we add to this
             // vector.
-            subscribe_to.extend(subscribe.channels.into_iter());
+            subscribe_to.extend(subscribe.channels.into_iter().map(|c| (c, false)));
         }
-        Command::Unsubscribe(mut unsubscribe) => {
-            // If no channels are specified, this requests unsubscribing from
-            // **all** channels. To implement this, the `unsubscribe.channels`
-            // vec is populated with the list of channels currently subscribed
-            // to.
-            if unsubscribe.channels.is_empty() {
-                unsubscribe.channels = subscriptions
-                    .keys()
-                    .map(|channel_name| channel_name.to_string())
-                    .collect();
-            }
-
-            for channel_name in unsubscribe.channels {
-                subscriptions.remove(&channel_name);
-
-                let response = make_unsubscribe_frame(channel_name, subscriptions.len());
-                dst.write_frame(&response).await?;
-            }
+        Command::Ssubscribe(subscribe) => {
+            subscribe_to.extend(subscribe.channels.into_iter().map(|c| (c, true)));
+        }
+        Command::Unsubscribe(unsubscribe) => {
+            unsubscribe_channels(unsubscribe, false, subscriptions, dst).await?;
+        }
+        Command::Sunsubscribe(unsubscribe) => {
+            unsubscribe_channels(unsubscribe, true, subscriptions, dst).await?;
         }
         command => {
             let cmd = Unknown::new(command.get_name());
@@ -236,24 +263,59 @@ async fn handle_command(
     Ok(())
 }
 
+async fn unsubscribe_channels(
+    mut unsubscribe: Unsubscribe,
+    sharded: bool,
+    subscriptions: &mut StreamMap<Channel, Messages>,
+    dst: &mut Connection,
+) -> crate::Result<()> {
+    // If no channels are specified, this requests unsubscribing from **all**
+    // channels of the key-space. To implement this, the
+    // `unsubscribe.channels` vec is populated with the list of channels
+    // currently subscribed to.
+    if unsubscribe.channels.is_empty() {
+        unsubscribe.channels = subscriptions
+            .keys()
+            .filter(|(_, s)| *s == sharded)
+            .map(|(channel_name, _)| channel_name.to_string())
+            .collect();
+        if unsubscribe.channels.is_empty() {
+            return unsubscribe.apply(dst, sharded).await;
+        }
+    }
+
+    for channel_name in unsubscribe.channels {
+        subscriptions.remove(&(channel_name.clone(), sharded));
+
+        let count = subscription_count(subscriptions, sharded);
+        let response = make_unsubscribe_frame(channel_name, count, sharded);
+        dst.write_frame(&response).await?;
+    }
+    Ok(())
+}
+
 /// Creates the response to a subcribe request.
 ///
 /// All of these functions take the `channel_name` as a `String` instead of
 /// a `&str` since `Bytes::from` can reuse the allocation in the `String`, and
 /// taking a `&str` would require copying the data. This allows the caller to
 /// decide whether to clone the channel name or not.
-fn make_subscribe_frame(channel_name: String, num_subs: usize) -> Frame {
+fn make_subscribe_frame(channel_name: String, num_subs: usize, sharded: bool) -> Frame {
     let mut response = Frame::array();
-    response.push_bulk(Bytes::from_static(b"subscribe"));
+    if sharded {
+        response.push_bulk(Bytes::from_static(b"ssubscribe"));
+    } else {
+        response.push_bulk(Bytes::from_static(b"subscribe"));
+    }
     response.push_bulk(Bytes::from(channel_name));
     response.push_int(num_subs as i64);
     response
 }
 
 /// Creates the response to an unsubcribe request.
-fn make_unsubscribe_frame(channel_name: String, num_subs: usize) -> Frame {
+fn make_unsubscribe_frame(channel_name: String, num_subs: usize, sharded: bool) -> Frame {
     let mut response = Frame::array();
-    response.push_bulk(Bytes::from_static(b"unsubscribe"));
+    response.push_bulk(unsubscribe_kind(sharded));
     response.push_bulk(Bytes::from(channel_name));
     response.push_int(num_subs as i64);
     response
@@ -261,14 +323,26 @@ fn make_unsubscribe_frame(channel_name: String, num_subs: usize) -> Frame {
 
 /// Creates a message informing the client about a new message on a channel that
 /// the client subscribes to.
-fn make_message_frame(channel_name: String, msg: Bytes) -> Frame {
+fn make_message_frame(channel_name: String, msg: Bytes, sharded: bool) -> Frame {
     let mut response = Frame::array();
-    response.push_bulk(Bytes::from_static(b"message"));
+    if sharded {
+        response.push_bulk(Bytes::from_static(b"smessage"));
+    } else {
+        response.push_bulk(Bytes::from_static(b"message"));
+    }
     response.push_bulk(Bytes::from(channel_name));
     response.push_bulk(msg);
     response
 }
 
+fn unsubscribe_kind(sharded: bool) -> Bytes {
+    if sharded {
+        Bytes::from_static(b"sunsubscribe")
+    } else {
+        Bytes::from_static(b"unsubscribe")
+    }
+}
+
 impl Unsubscribe {
     #[allow(dead_code)]
     /// Create a new `Unsubscribe` command with the given `channels`.
@@ -323,4 +397,24 @@ impl Unsubscribe {
 
         Ok(Unsubscribe { channels })
     }
+
+    /// Apply `UNSUBSCRIBE` or `SUNSUBSCRIBE` to a client which is not
+    /// subscribed to any channel of the key-space, every channel is replied
+    /// with a count of 0.
+    pub(crate) async fn apply(&self, dst: &mut Connection, sharded: bool) -> crate::Result<()> {
+        if self.channels.is_empty() {
+            // like redis, the channel is nil if none is given
+            let response = Frame::Array(vec![
+                Frame::Bulk(unsubscribe_kind(sharded)),
+                Frame::Null,
+                Frame::Integer(0),
+            ]);
+            dst.write_frame(&response).await?;
+        }
+        for channel_name in &self.channels {
+            let response = make_unsubscribe_frame(channel_name.clone(), 0, sharded);
+            dst.write_frame(&response).await?;
+        }
+        Ok(())
+    }
 }
@@ -23,11 +23,16 @@ impl Unknown {
         &self.command_name
     }
 
+    /// The error replied for the command.
+    pub(crate) fn response(&self) -> Frame {
+        Frame::Error(format!("ERR unknown command '{}'", self.command_name))
+    }
+
     /// Responds to the client, indicating the command is not recognized.
     ///
     /// This usually means the command is not yet implemented by `mapuche`.
     pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = Frame::Error(format!("ERR unknown command '{}'", self.command_name));
+        let response = self.response();
 
         debug!(LOGGER, "res, {:?}", response);
 
@@ -0,0 +1,95 @@
+use crate::cmd::Invalid;
+use crate::parse::Parse;
+use crate::raft::{get_raft_app, MapucheNodeId};
+use crate::utils::{resp_int, resp_invalid_arguments};
+use crate::Frame;
+use openraft::LogId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+// how often raft metrics are checked while waiting for the replicas
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// `WAIT numreplicas timeout` blocks until `numreplicas` raft followers have
+/// replicated every log applied before the command, or `timeout` milliseconds
+/// elapsed. A timeout of 0 blocks forever.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Wait {
+    numreplicas: u64,
+    timeout_ms: u64,
+    valid: bool,
+}
+
+impl Wait {
+    pub fn new(numreplicas: u64, timeout_ms: u64) -> Wait {
+        Wait {
+            numreplicas,
+            timeout_ms,
+            valid: true,
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Wait> {
+        let numreplicas = parse.next_int()?;
+        let timeout_ms = parse.next_int()?;
+        if numreplicas < 0 || timeout_ms < 0 {
+            return Ok(Wait::new_invalid());
+        }
+        Ok(Wait::new(numreplicas as u64, timeout_ms as u64))
+    }
+
+    pub(crate) async fn execute(&self) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        // nothing is replicated without raft
+        let app = match get_raft_app() {
+            Some(app) => app,
+            None => return resp_int(0),
+        };
+        let metrics = app.raft.metrics();
+        let commit_index = metrics
+            .borrow()
+            .last_applied
+            .map(|log_id| log_id.index)
+            .unwrap_or(0);
+        let deadline = Instant::now() + Duration::from_millis(self.timeout_ms);
+        loop {
+            let acked = acked_replicas(metrics.borrow().replication.as_ref(), app.id, commit_index);
+            if acked >= self.numreplicas || (self.timeout_ms > 0 && Instant::now() >= deadline) {
+                return resp_int(acked as i64);
+            }
+            tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Followers whose matched log index reached `index`, the replication map is
+/// only reported by the leader.
+pub(crate) fn acked_replicas(
+    replication: Option<&BTreeMap<MapucheNodeId, Option<LogId<MapucheNodeId>>>>,
+    leader_id: MapucheNodeId,
+    index: u64,
+) -> u64 {
+    replication
+        .map(|replication| {
+            replication
+                .iter()
+                .filter(|(id, matched)| {
+                    **id != leader_id && matched.map_or(false, |log_id| log_id.index >= index)
+                })
+                .count() as u64
+        })
+        .unwrap_or(0)
+}
+
+impl Invalid for Wait {
+    fn new_invalid() -> Wait {
+        Wait {
+            numreplicas: 0,
+            timeout_ms: 0,
+            valid: false,
+        }
+    }
+}
@@ -7,9 +7,10 @@ use futures::FutureExt;
 use serde::{Deserialize, Serialize};
 use slog::debug;
 
+use crate::rocks::errors::REDIS_ZADD_INCR_PAIR_ERR;
 use crate::rocks::zset::ZsetCommand;
 use crate::rocks::{get_client, Result as RocksResult};
-use crate::utils::resp_invalid_arguments;
+use crate::utils::{resp_err, resp_invalid_arguments};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Zadd {
@@ -18,6 +19,8 @@ pub struct Zadd {
     scores: Vec<f64>,
     exists: Option<bool>,
     changed_only: bool,
+    #[serde(default)]
+    incr: bool,
     valid: bool,
 }
 
@@ -29,6 +32,7 @@ impl Zadd {
             scores: vec![],
             exists: None,
             changed_only: false,
+            incr: false,
             valid: true,
         }
     }
@@ -50,6 +54,10 @@ impl Zadd {
         self.changed_only = changed_only;
     }
 
+    pub fn set_incr(&mut self, incr: bool) {
+        self.incr = incr;
+    }
+
     pub fn add_member(&mut self, member: &str) {
         self.members.push(member.to_string());
     }
@@ -79,9 +87,7 @@ impl Zadd {
                 Ok(s) if s.to_uppercase() == "LT" => {
                     // TODO:
                 }
-                Ok(s) if s.to_uppercase() == "INCR" => {
-                    // TODO:
-                }
+                Ok(s) if s.to_uppercase() == "INCR" => zadd.set_incr(true),
                 Ok(s) => {
                     // check if this is a score args
                     match String::from_utf8_lossy(s.as_bytes()).parse::<f64>() {
@@ -155,9 +161,7 @@ impl Zadd {
                 "LT" => {
                     // TODO:
                 }
-                "INCR" => {
-                    // TODO:
-                }
+                "INCR" => zadd.set_incr(true),
                 _ => {
                     // check if this is a score args
                     match String::from_utf8_lossy(arg.as_bytes()).parse::<f64>() {
@@ -214,7 +218,7 @@ impl Zadd {
     }
 
     pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = retry_call(|| async move { self.zadd().await }.boxed()).await?;
+        let response = retry_call("zadd", || async move { self.zadd().await }.boxed()).await?;
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
@@ -225,6 +229,9 @@ impl Zadd {
         if !self.valid {
             return Ok(resp_invalid_arguments());
         }
+        if self.incr && self.members.len() != 1 {
+            return Ok(resp_err(REDIS_ZADD_INCR_PAIR_ERR));
+        }
         ZsetCommand::new(&get_client())
             .zadd(
                 &self.key,
@@ -232,7 +239,7 @@ impl Zadd {
                 &self.scores,
                 self.exists,
                 self.changed_only,
-                false,
+                self.incr,
             )
             .await
     }
@@ -250,6 +257,7 @@ impl Invalid for Zadd {
             scores: vec![],
             exists: None,
             changed_only: false,
+            incr: false,
             valid: false,
         }
     }
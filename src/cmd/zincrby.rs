@@ -53,7 +53,8 @@ impl Zincrby {
     }
 
     pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = retry_call(|| async move { self.zincrby().await }.boxed()).await?;
+        let response =
+            retry_call("zincrby", || async move { self.zincrby().await }.boxed()).await?;
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
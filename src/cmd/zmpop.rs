@@ -0,0 +1,104 @@
+use crate::{Connection, Frame, MapucheError, Parse};
+
+use crate::cmd::{retry_call, Invalid};
+use crate::config::LOGGER;
+use crate::parse::ParseError;
+use crate::rocks::zset::ZsetCommand;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use slog::debug;
+
+use crate::rocks::{get_client, Result as RocksResult};
+use crate::utils::resp_invalid_arguments;
+
+/// `ZMPOP numkeys key [key ...] MIN|MAX [COUNT count]`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Zmpop {
+    keys: Vec<String>,
+    from_min: bool,
+    count: u64,
+    valid: bool,
+}
+
+impl Zmpop {
+    pub fn new(keys: Vec<String>, from_min: bool, count: u64) -> Zmpop {
+        Zmpop {
+            keys,
+            from_min,
+            count,
+            valid: true,
+        }
+    }
+
+    pub fn keys(&self) -> &Vec<String> {
+        &self.keys
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zmpop> {
+        let numkeys = parse.next_int()?;
+        if numkeys <= 0 {
+            return Ok(Zmpop::new_invalid());
+        }
+        let mut keys = Vec::with_capacity(numkeys as usize);
+        for _ in 0..numkeys {
+            keys.push(parse.next_string()?);
+        }
+        let from_min = match parse.next_string()?.to_uppercase().as_str() {
+            "MIN" => true,
+            "MAX" => false,
+            _ => return Ok(Zmpop::new_invalid()),
+        };
+
+        let mut count = 1;
+        match parse.next_string() {
+            Ok(option) if option.to_uppercase() == "COUNT" => count = parse.next_int()?,
+            Ok(_) => return Ok(Zmpop::new_invalid()),
+            Err(ParseError::EndOfStream) => {}
+            Err(e) => return Err(e.into()),
+        }
+        if count <= 0 {
+            return Ok(Zmpop::new_invalid());
+        }
+
+        Ok(Zmpop::new(keys, from_min, count as u64))
+    }
+
+    pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
+        let response = retry_call("zmpop", || async move { self.zmpop().await }.boxed()).await?;
+        debug!(LOGGER, "res, {:?}", response);
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn zmpop(&self) -> RocksResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        ZsetCommand::new(&get_client())
+            .zmpop(&self.keys, self.from_min, self.count)
+            .await
+    }
+
+    pub fn keys(&self) -> &Vec<String> {
+        &self.keys
+    }
+
+    pub fn hash_ring_key(&self) -> crate::Result<String> {
+        if self.keys.len() != 1 {
+            return Err(MapucheError::String("Cmd don't support cluster").into());
+        }
+        Ok(self.keys[0].to_string())
+    }
+}
+
+impl Invalid for Zmpop {
+    fn new_invalid() -> Zmpop {
+        Zmpop {
+            keys: vec![],
+            from_min: true,
+            count: 0,
+            valid: false,
+        }
+    }
+}
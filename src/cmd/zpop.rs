@@ -57,7 +57,8 @@ impl Zpop {
     }
 
     pub(crate) async fn apply(&self, dst: &mut Connection, from_min: bool) -> crate::Result<()> {
-        let response = retry_call(|| async move { self.zpop(from_min).await }.boxed()).await?;
+        let cmd = if from_min { "zpopmin" } else { "zpopmax" };
+        let response = retry_call(cmd, || async move { self.zpop(from_min).await }.boxed()).await?;
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
@@ -65,7 +65,7 @@ impl Zrem {
     }
 
     pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = retry_call(|| async move { self.zrem().await }.boxed()).await?;
+        let response = retry_call("zrem", || async move { self.zrem().await }.boxed()).await?;
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
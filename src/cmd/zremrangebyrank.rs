@@ -63,7 +63,10 @@ impl Zremrangebyrank {
     }
 
     pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = retry_call(|| async move { self.zremrangebyrank().await }.boxed()).await?;
+        let response = retry_call("zremrangebyrank", || {
+            async move { self.zremrangebyrank().await }.boxed()
+        })
+        .await?;
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
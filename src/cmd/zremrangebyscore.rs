@@ -65,7 +65,10 @@ impl Zremrangebyscore {
     }
 
     pub(crate) async fn apply(&self, dst: &mut Connection) -> crate::Result<()> {
-        let response = retry_call(|| async move { self.zremrangebyscore().await }.boxed()).await?;
+        let response = retry_call("zremrangebyscore", || {
+            async move { self.zremrangebyscore().await }.boxed()
+        })
+        .await?;
         debug!(LOGGER, "res, {:?}", response);
         dst.write_frame(&response).await?;
 
@@ -1,13 +1,19 @@
+use crate::eviction::EvictionPolicy;
 use crate::{
     MapucheInfra, DEFAULT_PORT, DEFAULT_RAFT_API_PORT, DEFAULT_RAFT_INTERNAL_PORT,
     DEFAULT_RING_PORT,
 };
+use arc_swap::ArcSwapOption;
 use lazy_static::lazy_static;
 use serde::Deserialize;
 
 use slog::{self, Drain};
 use slog_term;
+use std::collections::HashMap;
 use std::fs::OpenOptions;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 lazy_static! {
     pub static ref LOGGER: slog::Logger = slog::Logger::root(
@@ -21,7 +27,7 @@ lazy_static! {
         ))
         .use_custom_timestamp(crate::utils::timestamp_local)
         .build()
-        .filter_level(slog::Level::from_usize(log_level()).unwrap())
+        .filter(|record| record.level().as_usize() <= LOG_LEVEL.load(Ordering::Relaxed))
         .fuse(),
         slog::o!()
     );
@@ -31,6 +37,33 @@ lazy_static! {
 pub struct Config {
     server: Server,
     backend: Backend,
+    tls: Option<TlsConfig>,
+    retry_backoff: Option<ExponentialBackoff>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TlsConfig {
+    pub cert_file: String,
+    pub key_file: String,
+    pub ca_file: Option<String>,
+    pub require_client_cert: Option<bool>,
+    // name checked against the peer certificate by cluster node connections
+    pub server_name: Option<String>,
+}
+
+// delay between transaction retries, grows by `multiplier` after every conflict
+#[derive(Debug, Deserialize, Clone)]
+pub struct ExponentialBackoff {
+    pub initial_delay_us: u64,
+    pub max_delay_us: u64,
+    pub multiplier: f64,
+}
+
+impl ExponentialBackoff {
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let delay = self.initial_delay_us as f64 * self.multiplier.powi(attempt as i32);
+        Duration::from_micros((delay as u64).min(self.max_delay_us))
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -38,8 +71,16 @@ struct Server {
     listen: Option<String>,
     port: Option<u16>,
     ring_port: Option<u16>,
+    // connections kept to each remote node for forwarded commands
+    p2p_pool_size_per_node: Option<usize>,
+    // wait for a pooled connection, connect and response timeout
+    p2p_connection_timeout_ms: Option<u64>,
     raft_api_port: Option<u16>,
     raft_internal_port: Option<u16>,
+    // write commands applied between two raft snapshots
+    raft_snapshot_interval_commands: Option<u64>,
+    // raft log entries kept before a snapshot is forced
+    raft_max_log_entries: Option<u64>,
     ring_v_node_num: Option<u16>,
     instance_id: Option<String>,
     prometheus_listen: Option<String>,
@@ -50,6 +91,20 @@ struct Server {
     meta_key_number: Option<u16>,
     infra: Option<String>,
     cluster: Option<String>,
+    unixsocket: Option<String>,
+    notify_keyspace_events: Option<String>,
+    slowlog_threshold_us: Option<u64>,
+    slowlog_max_len: Option<usize>,
+    enable_debug_commands: Option<bool>,
+    audit_log: Option<bool>,
+    audit_log_file: Option<String>,
+    audit_log_max_size_mb: Option<u64>,
+    // one user per entry, name followed by acl rules, e.g. "alice on >pass ~* +@all"
+    acl_users: Option<Vec<String>>,
+    // users saved by `ACL SAVE` and loaded at startup and by `ACL LOAD`
+    aclfile: Option<String>,
+    // scripts running longer are aborted with an error, in milliseconds
+    lua_time_limit: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -58,6 +113,8 @@ struct Backend {
     max_connection: Option<usize>,
 
     txn_retry_count: Option<u32>,
+    // overrides txn_retry_count by command name, e.g. { lpush = 20 }
+    txn_retry_count_per_cmd: Option<HashMap<String, u32>>,
 
     data_store_dir: Option<String>,
 
@@ -69,6 +126,8 @@ struct Backend {
     async_gc_worker_number: Option<usize>,
     async_gc_worker_queue_size: Option<usize>,
     async_gc_interval: Option<u64>,
+    async_gc_max_keys_per_tick: Option<usize>,
+    async_gc_sleep_between_keys_us: Option<u64>,
 
     async_del_list_threshold: Option<u32>,
     async_del_hash_threshold: Option<u32>,
@@ -79,23 +138,145 @@ struct Backend {
     async_expire_hash_threshold: Option<u32>,
     async_expire_set_threshold: Option<u32>,
     async_expire_zset_threshold: Option<u32>,
+
+    hash_max_ziplist_entries: Option<usize>,
+    hash_max_ziplist_value: Option<usize>,
+
+    zset_max_ziplist_entries: Option<usize>,
+    zset_max_ziplist_value: Option<usize>,
+
+    list_max_ziplist_size: Option<usize>,
+    list_max_ziplist_value: Option<usize>,
+
+    cache_enabled: Option<bool>,
+    cache_max_entries: Option<usize>,
+    cache_ttl_seconds: Option<u64>,
+
+    maxmemory: Option<u64>,
+    maxmemory_policy: Option<String>,
+    maxmemory_samples: Option<usize>,
+    eviction_check_interval: Option<u64>,
+    lfu_decay_time: Option<u64>,
+
+    rocks_block_cache_mb: Option<usize>,
+    rocks_write_buffer_size_mb: Option<usize>,
+    rocks_max_write_buffer_number: Option<i32>,
+    rocks_level0_file_num_compaction_trigger: Option<i32>,
+    rocks_compression_type: Option<String>,
+    rocks_wal_bytes_per_sync: Option<u64>,
+    rocks_manual_wal_flush: Option<bool>,
+    rocks_wal_recovery_mode: Option<String>,
+    enable_compaction_filter: Option<bool>,
+    // overrides of the rocks_* options by column family name
+    cf_options: Option<HashMap<String, CfOptions>>,
+}
+
+// rocksdb tuning of one column family, unset options fall back to the rocks_*
+// options of the backend section
+#[derive(Debug, Deserialize, Clone)]
+pub struct CfOptions {
+    // a column family with its own size gets a block cache of its own,
+    // others share the cache of rocks_block_cache_mb
+    pub block_cache_mb: Option<usize>,
+    pub write_buffer_size_mb: Option<usize>,
+    pub max_write_buffer_number: Option<i32>,
+    pub level0_file_num_compaction_trigger: Option<i32>,
+    pub compression_type: Option<String>,
 }
 
-// Config
-pub static mut SERVER_CONFIG: Option<Config> = None;
+const COMPRESSION_TYPES: [&str; 4] = ["none", "snappy", "lz4", "zstd"];
+
+const WAL_RECOVERY_MODES: [&str; 4] = [
+    "tolerate_data_loss",
+    "tolerate_corrupted_tail_records",
+    "absolute_consistency",
+    "point_in_time",
+];
+
+// Config, swapped atomically when the config file is reloaded
+pub static SERVER_CONFIG: ArcSwapOption<Config> = ArcSwapOption::const_empty();
+
+// path of the config file the server is started with, read again on reload
+static CONFIG_FILE: ArcSwapOption<String> = ArcSwapOption::const_empty();
+
+// level of the records written by LOGGER, follows `log_level` on reload
+static LOG_LEVEL: AtomicUsize = AtomicUsize::new(4);
+
+// max element length of a compact encoded list set by
+// `DEBUG QUICKLIST-PACKED-THRESHOLD`, overrides `list_max_ziplist_value` until
+// restart, `usize::MAX` while unset
+static QUICKLIST_PACKED_THRESHOLD: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+pub fn set_quicklist_packed_threshold(threshold: usize) {
+    QUICKLIST_PACKED_THRESHOLD.store(threshold, Ordering::Relaxed);
+}
 
 pub fn set_global_config(config: Config) {
-    unsafe {
-        SERVER_CONFIG.replace(config);
+    SERVER_CONFIG.store(Some(Arc::new(config)));
+    LOG_LEVEL.store(log_level(), Ordering::Relaxed);
+}
+
+pub fn set_config_file(path: &str) {
+    CONFIG_FILE.store(Some(Arc::new(path.to_owned())));
+}
+
+impl Config {
+    pub fn from_toml(content: &str) -> Result<Config, String> {
+        let config: Config = toml::from_str(content).map_err(|e| e.to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(level) = &self.server.log_level {
+            if !LOG_LEVELS.contains(&level.as_str()) {
+                return Err(format!("invalid log_level {level}"));
+            }
+        }
+        let cf_compressions = self
+            .backend
+            .cf_options
+            .iter()
+            .flat_map(|m| m.values())
+            .map(|o| &o.compression_type);
+        for compression in cf_compressions
+            .chain([&self.backend.rocks_compression_type])
+            .flatten()
+        {
+            if !COMPRESSION_TYPES.contains(&compression.as_str()) {
+                return Err(format!("invalid compression type {compression}"));
+            }
+        }
+        if let Some(mode) = &self.backend.rocks_wal_recovery_mode {
+            if !WAL_RECOVERY_MODES.contains(&mode.as_str()) {
+                return Err(format!("invalid wal recovery mode {mode}"));
+            }
+        }
+        if let Some(backoff) = &self.retry_backoff {
+            if backoff.multiplier < 1.0 {
+                return Err("retry_backoff multiplier must be at least 1".to_owned());
+            }
+        }
+        Ok(())
     }
 }
 
+/// Re-read the config file and swap the global config, the current config is
+/// kept if the file is missing or invalid. Listeners and other settings read
+/// at startup still need a restart to change.
+pub fn reload_config() -> Result<(), String> {
+    let path = CONFIG_FILE
+        .load_full()
+        .ok_or("server started without config file")?;
+    let content = std::fs::read_to_string(path.as_str()).map_err(|e| e.to_string())?;
+    set_global_config(Config::from_toml(&content)?);
+    Ok(())
+}
+
 pub fn config_listen_or_default() -> String {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(s) = c.server.listen.clone() {
-                return s;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.listen.clone() {
+            return s;
         }
     }
 
@@ -103,35 +284,149 @@ pub fn config_listen_or_default() -> String {
 }
 
 pub fn config_port_or_default() -> String {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(s) = c.server.port {
-                return s.to_string();
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.port {
+            return s.to_string();
         }
     }
 
     DEFAULT_PORT.to_owned()
 }
 
+pub fn config_unixsocket() -> Option<String> {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        return c.server.unixsocket.clone();
+    }
+    None
+}
+
+pub fn notify_keyspace_events_or_default() -> String {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.notify_keyspace_events.clone() {
+            return s;
+        }
+    }
+    // keyspace events are disabled by default
+    "".to_owned()
+}
+
+pub fn slowlog_threshold_us_or_default() -> u64 {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.slowlog_threshold_us {
+            return s;
+        }
+    }
+    // default to 10ms
+    10000
+}
+
+pub fn slowlog_max_len_or_default() -> usize {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.slowlog_max_len {
+            return s;
+        }
+    }
+    // default to 128
+    128
+}
+
+pub fn lua_time_limit_or_default() -> Duration {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(ms) = c.server.lua_time_limit {
+            return Duration::from_millis(ms);
+        }
+    }
+    // default to 5 seconds, like lua-time-limit of redis
+    Duration::from_millis(5000)
+}
+
+pub fn enable_debug_commands_or_default() -> bool {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.server.enable_debug_commands {
+            return b;
+        }
+    }
+    // DEBUG is disabled by default
+    false
+}
+
+pub fn audit_log_or_default() -> bool {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.server.audit_log {
+            return b;
+        }
+    }
+    // the audit log is disabled by default
+    false
+}
+
+pub fn audit_log_file_or_default() -> String {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(f) = c.server.audit_log_file.clone() {
+            return f;
+        }
+    }
+    "audit.log".to_owned()
+}
+
+pub fn audit_log_max_size_mb_or_default() -> u64 {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.audit_log_max_size_mb {
+            return s;
+        }
+    }
+    // default to 100MB
+    100
+}
+
+pub fn config_acl_users() -> Vec<String> {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(users) = c.server.acl_users.clone() {
+            return users;
+        }
+    }
+    vec![]
+}
+
+pub fn config_aclfile() -> Option<String> {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        return c.server.aclfile.clone();
+    }
+    None
+}
+
 pub fn config_ring_port_or_default() -> String {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(s) = c.server.ring_port {
-                return s.to_string();
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.ring_port {
+            return s.to_string();
         }
     }
 
     DEFAULT_RING_PORT.to_owned()
 }
 
+pub fn p2p_pool_size_per_node_or_default() -> usize {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.p2p_pool_size_per_node {
+            return s;
+        }
+    }
+    8
+}
+
+pub fn p2p_connection_timeout_ms_or_default() -> u64 {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.p2p_connection_timeout_ms {
+            return s;
+        }
+    }
+    3000
+}
+
 pub fn config_raft_api_port_or_default() -> String {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(s) = c.server.raft_api_port {
-                return s.to_string();
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.raft_api_port {
+            return s.to_string();
         }
     }
 
@@ -139,23 +434,38 @@ pub fn config_raft_api_port_or_default() -> String {
 }
 
 pub fn config_raft_internal_port_or_default() -> String {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(s) = c.server.raft_internal_port {
-                return s.to_string();
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.raft_internal_port {
+            return s.to_string();
         }
     }
 
     DEFAULT_RAFT_INTERNAL_PORT.to_owned()
 }
 
+pub fn raft_snapshot_interval_commands_or_default() -> u64 {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.raft_snapshot_interval_commands {
+            return s;
+        }
+    }
+    // default to 5000 commands, the log policy of openraft
+    5000
+}
+
+pub fn raft_max_log_entries_or_default() -> u64 {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.raft_max_log_entries {
+            return s;
+        }
+    }
+    20000
+}
+
 pub fn config_ring_v_node_num_or_default() -> u16 {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(s) = c.server.ring_v_node_num {
-                return s;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.ring_v_node_num {
+            return s;
         }
     }
 
@@ -163,55 +473,45 @@ pub fn config_ring_v_node_num_or_default() -> u16 {
 }
 
 pub fn config_instance_id_or_default() -> String {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(s) = c.server.instance_id.clone() {
-                return s;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.instance_id.clone() {
+            return s;
         }
     }
     "1".to_owned()
 }
 
 pub fn config_prometheus_listen_or_default() -> String {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(s) = c.server.prometheus_listen.clone() {
-                return s;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.prometheus_listen.clone() {
+            return s;
         }
     }
     "0.0.0.0".to_owned()
 }
 
 pub fn config_prometheus_port_or_default() -> String {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(s) = c.server.prometheus_port {
-                return s.to_string();
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.prometheus_port {
+            return s.to_string();
         }
     }
     "18080".to_owned()
 }
 
 pub fn config_infra_or_default() -> MapucheInfra {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(s) = c.server.infra.clone() {
-                return s.as_str().into();
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.infra.clone() {
+            return s.as_str().into();
         }
     }
     MapucheInfra::Single
 }
 
 pub fn config_cluster_or_default() -> Vec<String> {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(s) = c.server.cluster.clone() {
-                return s.split(',').map(|s| s.to_string()).collect::<Vec<String>>();
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.cluster.clone() {
+            return s.split(',').map(|s| s.to_string()).collect::<Vec<String>>();
         }
     }
     Vec::default()
@@ -222,47 +522,37 @@ pub fn config_cluster_or_default() -> Vec<String> {
 }
 
 fn log_level_str() -> String {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(l) = c.server.log_level.clone() {
-                return l;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(l) = c.server.log_level.clone() {
+            return l;
         }
     }
     "info".to_owned()
 }
 
+// indexed by the slog level number, "off" filters every record
+const LOG_LEVELS: [&str; 7] = [
+    "off", "critical", "error", "warning", "info", "debug", "trace",
+];
+
 pub fn log_level() -> usize {
     let level_str = log_level_str();
-    match level_str.as_str() {
-        "off" => 0,
-        "critical" => 1,
-        "error" => 2,
-        "warning" => 3,
-        "info" => 4,
-        "debug" => 5,
-        "trace" => 6,
-        _ => 0,
-    }
+    LOG_LEVELS.iter().position(|l| *l == level_str).unwrap_or(0)
 }
 
 pub fn log_file() -> String {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(l) = c.server.log_file.clone() {
-                return l;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(l) = c.server.log_file.clone() {
+            return l;
         }
     }
     "rocksdb-service.log".to_owned()
 }
 
 pub fn config_meta_key_number_or_default() -> u16 {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(s) = c.server.meta_key_number {
-                return s;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.meta_key_number {
+            return s;
         }
     }
 
@@ -271,11 +561,9 @@ pub fn config_meta_key_number_or_default() -> u16 {
 }
 
 pub fn async_expire_set_threshold_or_default() -> u32 {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.async_expire_set_threshold {
-                return b;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.async_expire_set_threshold {
+            return b;
         }
     }
     if async_deletion_enabled_or_default() {
@@ -286,11 +574,9 @@ pub fn async_expire_set_threshold_or_default() -> u32 {
 }
 
 pub fn async_deletion_enabled_or_default() -> bool {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.async_deletion_enabled {
-                return b;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.async_deletion_enabled {
+            return b;
         }
     }
     // default async deletion enabled
@@ -298,11 +584,9 @@ pub fn async_deletion_enabled_or_default() -> bool {
 }
 
 pub fn async_del_set_threshold_or_default() -> u32 {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.async_del_set_threshold {
-                return b;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.async_del_set_threshold {
+            return b;
         }
     }
     if async_deletion_enabled_or_default() {
@@ -313,11 +597,9 @@ pub fn async_del_set_threshold_or_default() -> u32 {
 }
 
 pub fn async_gc_worker_queue_size_or_default() -> usize {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.async_gc_worker_queue_size {
-                return b;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.async_gc_worker_queue_size {
+            return b;
         }
     }
     // default async gc worker queue size
@@ -325,34 +607,48 @@ pub fn async_gc_worker_queue_size_or_default() -> usize {
 }
 
 pub fn async_gc_interval_or_default() -> u64 {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.async_gc_interval {
-                return b;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.async_gc_interval {
+            return b;
         }
     }
     // default async gc interval in ms
     10000
 }
 
+pub fn async_gc_max_keys_per_tick_or_default() -> usize {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.async_gc_max_keys_per_tick {
+            return b;
+        }
+    }
+    // default max gc version keys dispatched per tick, 0 means unlimited
+    10000
+}
+
+pub fn async_gc_sleep_between_keys_us_or_default() -> u64 {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.async_gc_sleep_between_keys_us {
+            return b;
+        }
+    }
+    // default no sleep between gc version keys
+    0
+}
+
 pub fn data_store_dir_or_default() -> String {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.data_store_dir.clone() {
-                return b;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.data_store_dir.clone() {
+            return b;
         }
     }
     "./mapuche_store".to_owned()
 }
 
 pub fn async_gc_worker_number_or_default() -> usize {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.async_gc_worker_number {
-                return b;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.async_gc_worker_number {
+            return b;
         }
     }
     // default async gc worker number
@@ -360,11 +656,9 @@ pub fn async_gc_worker_number_or_default() -> usize {
 }
 
 pub fn async_del_list_threshold_or_default() -> u32 {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.async_del_list_threshold {
-                return b;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.async_del_list_threshold {
+            return b;
         }
     }
     if async_deletion_enabled_or_default() {
@@ -375,11 +669,9 @@ pub fn async_del_list_threshold_or_default() -> u32 {
 }
 
 pub fn cmd_linsert_length_limit_or_default() -> u32 {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.cmd_linsert_length_limit {
-                return b;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.cmd_linsert_length_limit {
+            return b;
         }
     }
     // default linsert length no limit
@@ -387,11 +679,9 @@ pub fn cmd_linsert_length_limit_or_default() -> u32 {
 }
 
 pub fn cmd_lrem_length_limit_or_default() -> u32 {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.cmd_lrem_length_limit {
-                return b;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.cmd_lrem_length_limit {
+            return b;
         }
     }
     // default lrem length no limit
@@ -399,11 +689,9 @@ pub fn cmd_lrem_length_limit_or_default() -> u32 {
 }
 
 pub fn async_expire_list_threshold_or_default() -> u32 {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.async_expire_list_threshold {
-                return b;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.async_expire_list_threshold {
+            return b;
         }
     }
     if async_deletion_enabled_or_default() {
@@ -414,11 +702,9 @@ pub fn async_expire_list_threshold_or_default() -> u32 {
 }
 
 pub fn async_expire_hash_threshold_or_default() -> u32 {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.async_expire_hash_threshold {
-                return b;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.async_expire_hash_threshold {
+            return b;
         }
     }
     if async_deletion_enabled_or_default() {
@@ -429,11 +715,9 @@ pub fn async_expire_hash_threshold_or_default() -> u32 {
 }
 
 pub fn async_del_hash_threshold_or_default() -> u32 {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.async_del_hash_threshold {
-                return b;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.async_del_hash_threshold {
+            return b;
         }
     }
     if async_deletion_enabled_or_default() {
@@ -444,11 +728,9 @@ pub fn async_del_hash_threshold_or_default() -> u32 {
 }
 
 pub fn async_expire_zset_threshold_or_default() -> u32 {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.async_expire_zset_threshold {
-                return b;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.async_expire_zset_threshold {
+            return b;
         }
     }
     if async_deletion_enabled_or_default() {
@@ -459,11 +741,9 @@ pub fn async_expire_zset_threshold_or_default() -> u32 {
 }
 
 pub fn async_del_zset_threshold_or_default() -> u32 {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(b) = c.backend.async_del_zset_threshold {
-                return b;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.async_del_zset_threshold {
+            return b;
         }
     }
     if async_deletion_enabled_or_default() {
@@ -473,12 +753,104 @@ pub fn async_del_zset_threshold_or_default() -> u32 {
     }
 }
 
+pub fn hash_max_ziplist_entries_or_default() -> usize {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.hash_max_ziplist_entries {
+            return b;
+        }
+    }
+    // default max fields of a compact encoded hash
+    128
+}
+
+pub fn hash_max_ziplist_value_or_default() -> usize {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.hash_max_ziplist_value {
+            return b;
+        }
+    }
+    // default max field or value length of a compact encoded hash
+    64
+}
+
+pub fn zset_max_ziplist_entries_or_default() -> usize {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.zset_max_ziplist_entries {
+            return b;
+        }
+    }
+    // default max members of a compact encoded zset
+    128
+}
+
+pub fn zset_max_ziplist_value_or_default() -> usize {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.zset_max_ziplist_value {
+            return b;
+        }
+    }
+    // default max member length of a compact encoded zset
+    64
+}
+
+pub fn list_max_ziplist_size_or_default() -> usize {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.list_max_ziplist_size {
+            return b;
+        }
+    }
+    // default max elements of a compact encoded list
+    128
+}
+
+pub fn list_max_ziplist_value_or_default() -> usize {
+    let threshold = QUICKLIST_PACKED_THRESHOLD.load(Ordering::Relaxed);
+    if threshold != usize::MAX {
+        return threshold;
+    }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.list_max_ziplist_value {
+            return b;
+        }
+    }
+    // default max element length of a compact encoded list
+    64
+}
+
+pub fn cache_enabled_or_default() -> bool {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.cache_enabled {
+            return b;
+        }
+    }
+    // default string cache disabled
+    false
+}
+
+pub fn cache_max_entries_or_default() -> usize {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.cache_max_entries {
+            return b;
+        }
+    }
+    // default string cache capacity
+    10000
+}
+
+pub fn cache_ttl_seconds_or_default() -> u64 {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.cache_ttl_seconds {
+            return b;
+        }
+    }
+    // default string cache entry ttl in seconds
+    60
+}
+
 pub fn config_local_pool_number() -> usize {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(s) = c.backend.local_pool_number {
-                return s;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.backend.local_pool_number {
+            return s;
         }
     }
     // default use 8 localset pool to handle connections
@@ -486,48 +858,246 @@ pub fn config_local_pool_number() -> usize {
 }
 
 pub fn config_max_connection() -> usize {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(s) = c.backend.max_connection {
-                return s;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.backend.max_connection {
+            return s;
         }
     }
     // default use 8 localset pool to handle connections
     10000
 }
 
+pub fn config_tls() -> Option<TlsConfig> {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        return c.tls.clone();
+    }
+    None
+}
+
 pub fn txn_retry_count() -> u32 {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(s) = c.backend.txn_retry_count {
-                return s;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.backend.txn_retry_count {
+            return s;
         }
     }
     // default to 3
     10
 }
 
-pub fn is_auth_enabled() -> bool {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if c.server.password.clone().is_some() {
-                return true;
+pub fn txn_retry_count_for_cmd(cmd: &str) -> u32 {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(m) = &c.backend.txn_retry_count_per_cmd {
+            if let Some(s) = m.get(cmd) {
+                return *s;
             }
         }
     }
+    txn_retry_count()
+}
+
+pub fn retry_backoff_or_default() -> ExponentialBackoff {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.retry_backoff.clone() {
+            return b;
+        }
+    }
+    // default to 100us, doubled up to 10ms
+    ExponentialBackoff {
+        initial_delay_us: 100,
+        max_delay_us: 10_000,
+        multiplier: 2.0,
+    }
+}
+
+pub fn config_password() -> Option<String> {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        return c.server.password.clone();
+    }
+    None
+}
+
+pub fn is_auth_enabled() -> bool {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if c.server.password.clone().is_some() {
+            return true;
+        }
+    }
     false
 }
 
 // return false only if auth is enabled and password mismatch
 pub fn is_auth_matched(password: &str) -> bool {
-    unsafe {
-        if let Some(c) = &SERVER_CONFIG {
-            if let Some(s) = c.server.password.clone() {
-                return s == password;
-            }
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.server.password.clone() {
+            return s == password;
+        }
+    }
+    true
+}
+
+pub fn maxmemory_or_default() -> u64 {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.maxmemory {
+            return b;
+        }
+    }
+    // 0 means no limit
+    0
+}
+
+pub fn maxmemory_policy_or_default() -> EvictionPolicy {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.backend.maxmemory_policy.clone() {
+            return s.as_str().into();
+        }
+    }
+    EvictionPolicy::NoEviction
+}
+
+pub fn maxmemory_samples_or_default() -> usize {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.maxmemory_samples {
+            return b;
+        }
+    }
+    // default keys sampled to pick one eviction victim
+    16
+}
+
+pub fn eviction_check_interval_or_default() -> u64 {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.eviction_check_interval {
+            return b;
+        }
+    }
+    // default check interval in milliseconds
+    1000
+}
+
+pub fn lfu_decay_time_or_default() -> u64 {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.lfu_decay_time {
+            return b;
+        }
+    }
+    // default minutes to decrement the lfu counter by one, 0 means never decay
+    1
+}
+
+fn cf_options(c: &Config, cf_name: &str) -> Option<CfOptions> {
+    c.backend
+        .cf_options
+        .as_ref()
+        .and_then(|m| m.get(cf_name))
+        .cloned()
+}
+
+// block cache size of a column family which does not share the cache
+pub fn rocks_cf_block_cache_mb(cf_name: &str) -> Option<usize> {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        return cf_options(c, cf_name).and_then(|o| o.block_cache_mb);
+    }
+    None
+}
+
+pub fn rocks_block_cache_mb_or_default() -> usize {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.rocks_block_cache_mb {
+            return b;
+        }
+    }
+    // default to the rocksdb block cache size
+    8
+}
+
+pub fn rocks_write_buffer_size_mb_or_default(cf_name: &str) -> usize {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = cf_options(c, cf_name).and_then(|o| o.write_buffer_size_mb) {
+            return b;
+        }
+        if let Some(b) = c.backend.rocks_write_buffer_size_mb {
+            return b;
+        }
+    }
+    // default to the rocksdb write buffer size
+    64
+}
+
+pub fn rocks_max_write_buffer_number_or_default(cf_name: &str) -> i32 {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = cf_options(c, cf_name).and_then(|o| o.max_write_buffer_number) {
+            return b;
+        }
+        if let Some(b) = c.backend.rocks_max_write_buffer_number {
+            return b;
+        }
+    }
+    // default to the rocksdb max write buffer number
+    2
+}
+
+pub fn rocks_level0_file_num_compaction_trigger_or_default(cf_name: &str) -> i32 {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = cf_options(c, cf_name).and_then(|o| o.level0_file_num_compaction_trigger) {
+            return b;
+        }
+        if let Some(b) = c.backend.rocks_level0_file_num_compaction_trigger {
+            return b;
+        }
+    }
+    // default to the rocksdb level0 compaction trigger
+    4
+}
+
+pub fn rocks_compression_type_or_default(cf_name: &str) -> String {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = cf_options(c, cf_name).and_then(|o| o.compression_type) {
+            return s;
+        }
+        if let Some(s) = c.backend.rocks_compression_type.clone() {
+            return s;
+        }
+    }
+    // default to the rocksdb compression
+    "snappy".to_owned()
+}
+
+pub fn rocks_wal_bytes_per_sync_or_default() -> u64 {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.rocks_wal_bytes_per_sync {
+            return b;
+        }
+    }
+    // default to leaving the wal sync to the os
+    0
+}
+
+pub fn rocks_manual_wal_flush_or_default() -> bool {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.rocks_manual_wal_flush {
+            return b;
+        }
+    }
+    // default to writing the wal buffer on every write
+    false
+}
+
+pub fn rocks_wal_recovery_mode_or_default() -> String {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(s) = c.backend.rocks_wal_recovery_mode.clone() {
+            return s;
+        }
+    }
+    // default to the rocksdb recovery mode
+    "point_in_time".to_owned()
+}
+
+pub fn enable_compaction_filter_or_default() -> bool {
+    if let Some(c) = &*SERVER_CONFIG.load() {
+        if let Some(b) = c.backend.enable_compaction_filter {
+            return b;
         }
     }
+    // default to removing expired strings in compactions
     true
 }
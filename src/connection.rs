@@ -1,9 +1,94 @@
 use crate::frame::{self, Frame};
 
 use bytes::{Buf, BytesMut};
+use futures::future::BoxFuture;
 use std::io::{self, Cursor};
-use tokio::io::{AsyncReadExt, AsyncWriteExt, BufWriter};
-use tokio::net::TcpStream;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufWriter, ReadBuf};
+use tokio::net::{TcpStream, UnixStream};
+use tokio_rustls::TlsStream;
+
+/// Socket backing a `Connection`, plain TCP, TLS encrypted or Unix domain socket.
+#[derive(Debug)]
+pub enum Stream {
+    Tcp(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+    Unix(UnixStream),
+}
+
+impl Stream {
+    /// Address of the remote peer, the socket path for Unix domain sockets.
+    pub fn peer_addr(&self) -> String {
+        let addr = match self {
+            Stream::Tcp(s) => s.peer_addr().map(|a| a.to_string()),
+            Stream::Tls(s) => s.get_ref().0.peer_addr().map(|a| a.to_string()),
+            Stream::Unix(s) => s.local_addr().map(|a| {
+                a.as_pathname()
+                    .map_or("".to_owned(), |p| format!("{}:0", p.display()))
+            }),
+        };
+        addr.unwrap_or_default()
+    }
+}
+
+impl AsRawFd for Stream {
+    fn as_raw_fd(&self) -> RawFd {
+        match self {
+            Stream::Tcp(s) => s.as_raw_fd(),
+            Stream::Tls(s) => match s.as_ref() {
+                TlsStream::Client(s) => s.get_ref().0.as_raw_fd(),
+                TlsStream::Server(s) => s.get_ref().0.as_raw_fd(),
+            },
+            Stream::Unix(s) => s.as_raw_fd(),
+        }
+    }
+}
+
+impl AsyncRead for Stream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Stream::Tcp(s) => Pin::new(s).poll_read(cx, buf),
+            Stream::Tls(s) => Pin::new(s).poll_read(cx, buf),
+            Stream::Unix(s) => Pin::new(s).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for Stream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Stream::Tcp(s) => Pin::new(s).poll_write(cx, buf),
+            Stream::Tls(s) => Pin::new(s).poll_write(cx, buf),
+            Stream::Unix(s) => Pin::new(s).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Stream::Tcp(s) => Pin::new(s).poll_flush(cx),
+            Stream::Tls(s) => Pin::new(s).poll_flush(cx),
+            Stream::Unix(s) => Pin::new(s).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Stream::Tcp(s) => Pin::new(s).poll_shutdown(cx),
+            Stream::Tls(s) => Pin::new(s).poll_shutdown(cx),
+            Stream::Unix(s) => Pin::new(s).poll_shutdown(cx),
+        }
+    }
+}
 
 /// Send and receive `Frame` values from a remote peer.
 ///
@@ -19,19 +104,22 @@ use tokio::net::TcpStream;
 /// The contents of the write buffer are then written to the socket.
 #[derive(Debug)]
 pub struct Connection {
-    // The `TcpStream`. It is decorated with a `BufWriter`, which provides write
+    // The `Stream`. It is decorated with a `BufWriter`, which provides write
     // level buffering. The `BufWriter` implementation provided by Tokio is
     // sufficient for our needs.
-    stream: BufWriter<TcpStream>,
+    stream: BufWriter<Stream>,
 
     // The buffer for reading frames.
     buffer: BytesMut,
+
+    // RESP protocol version negotiated by HELLO, 2 until then.
+    protocol: u8,
 }
 
 impl Connection {
     /// Create a new `Connection`, backed by `socket`. Read and write buffers
     /// are initialized.
-    pub fn new(socket: TcpStream) -> Connection {
+    pub fn new(socket: Stream) -> Connection {
         Connection {
             stream: BufWriter::new(socket),
             // Default to a 4KB read buffer. For the use case of mini redis,
@@ -39,9 +127,18 @@ impl Connection {
             // value to their specific use case. There is a high likelihood that
             // a larger read buffer will work better.
             buffer: BytesMut::with_capacity(4 * 1024),
+            protocol: 2,
         }
     }
 
+    pub fn protocol(&self) -> u8 {
+        self.protocol
+    }
+
+    pub fn set_protocol(&mut self, protocol: u8) {
+        self.protocol = protocol;
+    }
+
     /// Read a single `Frame` value from the underlying stream.
     ///
     /// The function waits until it has retrieved enough data to parse a frame.
@@ -93,6 +190,23 @@ impl Connection {
         // with bytes.
         let mut buf = Cursor::new(&self.buffer[..]);
 
+        // requests which are not RESP arrays are inline commands
+        if self.buffer.first().map_or(false, |b| *b != b'*') {
+            return match Frame::parse_inline(&mut buf) {
+                Ok(frame) => {
+                    let len = buf.position() as usize;
+                    self.buffer.advance(len);
+                    match frame {
+                        // empty lines are ignored
+                        Frame::Array(args) if args.is_empty() => self.parse_frame(),
+                        frame => Ok(Some(frame)),
+                    }
+                }
+                Err(Incomplete) => Ok(None),
+                Err(e) => Err(e.into()),
+            };
+        }
+
         // The first step is to check if enough data has been buffered to parse
         // a single frame. This step is usually much faster than doing a full
         // parse of the frame, and allows us to skip allocating data structures
@@ -154,25 +268,7 @@ impl Connection {
     /// write stream. The data will be written to the buffer. Once the buffer is
     /// full, it is flushed to the underlying socket.
     pub async fn write_frame(&mut self, frame: &Frame) -> io::Result<()> {
-        // Arrays are encoded by encoding each entry. All other frame types are
-        // considered literals. For now, mapuche is not able to encode
-        // recursive frame structures. See below for more details.
-        match frame {
-            Frame::Array(val) => {
-                // Encode the frame type prefix. For an array, it is `*`.
-                self.stream.write_u8(b'*').await?;
-
-                // Encode the length of the array.
-                self.write_decimal(val.len() as i64).await?;
-
-                // Iterate and encode each entry in the array.
-                for entry in &**val {
-                    self.write_value(entry).await?;
-                }
-            }
-            // The frame type is a literal. Encode the value directly.
-            _ => self.write_value(frame).await?,
-        }
+        self.write_value(frame).await?;
 
         // Ensure the encoded frame is written to the socket. The calls above
         // are to the buffered stream and writes. Calling `flush` writes the
@@ -180,47 +276,98 @@ impl Connection {
         self.stream.flush().await
     }
 
-    /// Write a frame literal to the stream
-    async fn write_value(&mut self, frame: &Frame) -> io::Result<()> {
-        match frame {
-            Frame::Simple(val) => {
-                self.stream.write_u8(b'+').await?;
-                self.stream.write_all(val.as_bytes()).await?;
-                self.stream.write_all(b"\r\n").await?;
-            }
-            Frame::Error(val) => {
-                self.stream.write_u8(b'-').await?;
-                self.stream.write_all(val.as_bytes()).await?;
-                self.stream.write_all(b"\r\n").await?;
-            }
-            Frame::TxnFailed(val) => {
-                self.stream.write_u8(b'-').await?;
-                self.stream.write_all(val.as_bytes()).await?;
-                self.stream.write_all(b"\r\n").await?;
-            }
-            Frame::Integer(val) => {
-                self.stream.write_u8(b':').await?;
-                self.write_decimal(*val).await?;
-            }
-            Frame::Null => {
-                self.stream.write_all(b"$-1\r\n").await?;
-            }
-            Frame::Bulk(val) => {
-                let len = val.len();
+    /// Write a frame to the stream, arrays and maps are encoded by encoding
+    /// each entry. Async fns do not support recursion directly, so the future
+    /// is boxed.
+    fn write_value<'a>(&'a mut self, frame: &'a Frame) -> BoxFuture<'a, io::Result<()>> {
+        Box::pin(async move {
+            match frame {
+                Frame::Simple(val) => {
+                    self.stream.write_u8(b'+').await?;
+                    self.stream.write_all(val.as_bytes()).await?;
+                    self.stream.write_all(b"\r\n").await?;
+                }
+                Frame::Error(val) => {
+                    self.stream.write_u8(b'-').await?;
+                    self.stream.write_all(val.as_bytes()).await?;
+                    self.stream.write_all(b"\r\n").await?;
+                }
+                Frame::TxnFailed(val) => {
+                    self.stream.write_u8(b'-').await?;
+                    self.stream.write_all(val.as_bytes()).await?;
+                    self.stream.write_all(b"\r\n").await?;
+                }
+                Frame::Integer(val) => {
+                    self.stream.write_u8(b':').await?;
+                    self.write_decimal(*val).await?;
+                }
+                Frame::Null => {
+                    self.stream.write_all(b"$-1\r\n").await?;
+                }
+                Frame::Bulk(val) => {
+                    let len = val.len();
+
+                    self.stream.write_u8(b'$').await?;
+                    self.write_decimal(len as i64).await?;
+                    self.stream.write_all(val).await?;
+                    self.stream.write_all(b"\r\n").await?;
+                }
+                Frame::Double(val) => {
+                    let val = if val.is_infinite() {
+                        if val.is_sign_positive() {
+                            "inf"
+                        } else {
+                            "-inf"
+                        }
+                        .to_owned()
+                    } else {
+                        val.to_string()
+                    };
+                    if self.protocol >= 3 {
+                        self.stream.write_u8(b',').await?;
+                        self.stream.write_all(val.as_bytes()).await?;
+                        self.stream.write_all(b"\r\n").await?;
+                    } else {
+                        // RESP2 clients receive doubles as bulk strings.
+                        self.stream.write_u8(b'$').await?;
+                        self.write_decimal(val.len() as i64).await?;
+                        self.stream.write_all(val.as_bytes()).await?;
+                        self.stream.write_all(b"\r\n").await?;
+                    }
+                }
+                Frame::Array(val) => {
+                    // Encode the frame type prefix. For an array, it is `*`.
+                    self.stream.write_u8(b'*').await?;
 
-                self.stream.write_u8(b'$').await?;
-                self.write_decimal(len as i64).await?;
-                self.stream.write_all(val).await?;
-                self.stream.write_all(b"\r\n").await?;
+                    // Encode the length of the array.
+                    self.write_decimal(val.len() as i64).await?;
+
+                    // Iterate and encode each entry in the array.
+                    for entry in val {
+                        self.write_value(entry).await?;
+                    }
+                }
+                Frame::Map(pairs) => {
+                    if self.protocol >= 3 {
+                        // Encode the frame type prefix. For a map, it is `%`,
+                        // followed by the number of key value pairs.
+                        self.stream.write_u8(b'%').await?;
+                        self.write_decimal(pairs.len() as i64).await?;
+                    } else {
+                        // RESP2 clients receive a flat array of keys and values.
+                        self.stream.write_u8(b'*').await?;
+                        self.write_decimal(pairs.len() as i64 * 2).await?;
+                    }
+
+                    for (key, value) in pairs {
+                        self.write_value(key).await?;
+                        self.write_value(value).await?;
+                    }
+                }
             }
-            // Encoding an `Array` from within a value cannot be done using a
-            // recursive strategy. In general, async fns do not support
-            // recursion. Mapuche has not needed to encode nested arrays yet,
-            // so for now it is skipped.
-            Frame::Array(_val) => unreachable!(),
-        }
 
-        Ok(())
+            Ok(())
+        })
     }
 
     /// Write a decimal frame to the stream
@@ -1,10 +1,12 @@
 use tokio::sync::{broadcast, Notify};
 use tokio::time::{self, Duration, Instant};
 
-use crate::config::LOGGER;
+use crate::config::{slowlog_max_len_or_default, LOGGER};
+use crate::slowlog::SlowLog;
 use bytes::Bytes;
 use slog::debug;
 use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 /// A wrapper around a `Db` instance. This exists to allow orderly cleanup
@@ -34,6 +36,29 @@ pub(crate) struct Db {
     /// Handle to shared state. The background task will also have an
     /// `Arc<Shared>`.
     shared: Arc<Shared>,
+
+    /// Slow commands of all connections, see `SLOWLOG`.
+    slow_log: Arc<Mutex<SlowLog>>,
+
+    /// Clients are paused until the instant, see `CLIENT PAUSE`.
+    pause_until: Arc<Mutex<Option<ClientPause>>>,
+
+    /// Wakes the paused connections when the pause is cleared.
+    unpause: Arc<Notify>,
+
+    /// Set while the connections are near `max_connection`, connections
+    /// accepted meanwhile only serve PING and AUTH.
+    connections_near_limit: Arc<AtomicBool>,
+
+    /// Wakes the clients blocked on a list, by key, see `BRPOPLPUSH`.
+    list_waiters: Arc<Mutex<HashMap<String, Arc<Notify>>>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ClientPause {
+    until: Instant,
+    /// Only write commands are paused.
+    write_only: bool,
 }
 
 #[derive(Debug)]
@@ -68,6 +93,10 @@ struct State {
     /// and pub/sub. `mapuche` handles this by using a separate `HashMap`.
     pub_sub: HashMap<String, broadcast::Sender<Bytes>>,
 
+    /// The sharded pub/sub key-space of `SSUBSCRIBE` and `SPUBLISH`, it is
+    /// separate from `pub_sub` like in redis.
+    shard_pub_sub: HashMap<String, broadcast::Sender<Bytes>>,
+
     /// Tracks key TTLs.
     ///
     /// A `BTreeMap` is used to maintain expirations sorted by when they expire.
@@ -133,6 +162,7 @@ impl Db {
             state: Mutex::new(State {
                 entries: HashMap::new(),
                 pub_sub: HashMap::new(),
+                shard_pub_sub: HashMap::new(),
                 expirations: BTreeMap::new(),
                 next_id: 0,
                 shutdown: false,
@@ -143,7 +173,97 @@ impl Db {
         // Start the background task.
         tokio::spawn(purge_expired_tasks(shared.clone()));
 
-        Db { shared }
+        Db {
+            shared,
+            slow_log: Arc::new(Mutex::new(SlowLog::new(slowlog_max_len_or_default()))),
+            pause_until: Arc::new(Mutex::new(None)),
+            unpause: Arc::new(Notify::new()),
+            connections_near_limit: Arc::new(AtomicBool::new(false)),
+            list_waiters: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Returns the previous state, so crossing the limit can be counted once.
+    pub(crate) fn set_connections_near_limit(&self, near_limit: bool) -> bool {
+        self.connections_near_limit
+            .swap(near_limit, Ordering::Relaxed)
+    }
+
+    /// Handle to the slow log shared by all connections.
+    pub(crate) fn slow_log(&self) -> &Arc<Mutex<SlowLog>> {
+        &self.slow_log
+    }
+
+    /// Pause the clients for `timeout`. Like redis, an ongoing pause is only
+    /// extended: the later end and the more restrictive mode are kept.
+    pub(crate) fn pause_clients(&self, timeout: Duration, write_only: bool) {
+        let mut pause_until = self.pause_until.lock().unwrap();
+        let mut pause = ClientPause {
+            until: Instant::now() + timeout,
+            write_only,
+        };
+        if let Some(current) = pause_until.filter(|p| p.until > Instant::now()) {
+            pause.until = pause.until.max(current.until);
+            pause.write_only = pause.write_only && current.write_only;
+        }
+        *pause_until = Some(pause);
+    }
+
+    /// Clear the pause, the paused connections are resumed at once.
+    pub(crate) fn unpause_clients(&self) {
+        *self.pause_until.lock().unwrap() = None;
+        self.unpause.notify_waiters();
+    }
+
+    /// Wait until the command is no longer paused, reads are only paused by
+    /// `CLIENT PAUSE ALL`.
+    pub(crate) async fn wait_client_pause(&self, is_write: bool) {
+        loop {
+            // registered before the check, so an unpause in between is not lost
+            let unpaused = self.unpause.notified();
+            let until = match *self.pause_until.lock().unwrap() {
+                Some(pause) if pause.until > Instant::now() && (is_write || !pause.write_only) => {
+                    pause.until
+                }
+                _ => return,
+            };
+            tokio::select! {
+                _ = time::sleep_until(until) => {}
+                _ = unpaused => {}
+            }
+        }
+    }
+
+    /// Handle notified when elements are pushed to the list `key`. The
+    /// `Notified` future must be created before checking the list, so a push
+    /// in between is not lost.
+    pub(crate) fn list_waiter(&self, key: &str) -> Arc<Notify> {
+        self.list_waiters
+            .lock()
+            .unwrap()
+            .entry(key.to_owned())
+            .or_insert_with(|| Arc::new(Notify::new()))
+            .clone()
+    }
+
+    /// Drop the handle of a client which stops waiting, the entry of the key
+    /// is removed with the last handle.
+    pub(crate) fn release_list_waiter(&self, key: &str, waiter: Arc<Notify>) {
+        drop(waiter);
+        let mut waiters = self.list_waiters.lock().unwrap();
+        if waiters
+            .get(key)
+            .map_or(false, |w| Arc::strong_count(w) == 1)
+        {
+            waiters.remove(key);
+        }
+    }
+
+    /// Wake all the clients waiting on the list `key`.
+    pub(crate) fn notify_list_pushed(&self, key: &str) {
+        if let Some(waiter) = self.list_waiters.lock().unwrap().get(key) {
+            waiter.notify_waiters();
+        }
     }
 
     /// Get the value associated with a key.
@@ -230,11 +350,12 @@ impl Db {
         }
     }
 
-    /// Returns a `Receiver` for the requested channel.
+    /// Returns a `Receiver` for the requested channel, a sharded channel when
+    /// `sharded` is set.
     ///
     /// The returned `Receiver` is used to receive values broadcast by `PUBLISH`
-    /// commands.
-    pub(crate) fn subscribe(&self, key: String) -> broadcast::Receiver<Bytes> {
+    /// or `SPUBLISH` commands.
+    pub(crate) fn subscribe(&self, key: String, sharded: bool) -> broadcast::Receiver<Bytes> {
         use std::collections::hash_map::Entry;
 
         // Acquire the mutex
@@ -243,7 +364,7 @@ impl Db {
         // If there is no entry for the requested channel, then create a new
         // broadcast channel and associate it with the key. If one already
         // exists, return an associated receiver.
-        let entry = state.pub_sub.entry(key);
+        let entry = state.pub_sub_mut(sharded).entry(key);
         match entry {
             Entry::Occupied(e) => e.get().subscribe(),
             Entry::Vacant(e) => {
@@ -266,11 +387,11 @@ impl Db {
 
     /// Publish a message to the channel. Returns the number of subscribers
     /// listening on the channel.
-    pub(crate) fn publish(&self, key: &str, value: Bytes) -> usize {
+    pub(crate) fn publish(&self, key: &str, value: Bytes, sharded: bool) -> usize {
         let state = self.shared.state.lock().unwrap();
 
         state
-            .pub_sub
+            .pub_sub(sharded)
             .get(key)
             // On a successful message send on the broadcast channel, the number
             // of subscribers is returned. An error indicates there are no
@@ -281,6 +402,19 @@ impl Db {
             .unwrap_or(0)
     }
 
+    /// Active channels with their number of subscribers, channels whose
+    /// subscribers are all gone are not reported.
+    pub(crate) fn channels(&self, sharded: bool) -> HashMap<String, usize> {
+        let state = self.shared.state.lock().unwrap();
+
+        state
+            .pub_sub(sharded)
+            .iter()
+            .map(|(key, tx)| (key.clone(), tx.receiver_count()))
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+
     /// Signals the purge background task to shut down. This is called by the
     /// `DbShutdown`s `Drop` implementation.
     fn shutdown_purge_task(&self) {
@@ -350,6 +484,22 @@ impl State {
             .next()
             .map(|expiration| expiration.0)
     }
+
+    fn pub_sub(&self, sharded: bool) -> &HashMap<String, broadcast::Sender<Bytes>> {
+        if sharded {
+            &self.shard_pub_sub
+        } else {
+            &self.pub_sub
+        }
+    }
+
+    fn pub_sub_mut(&mut self, sharded: bool) -> &mut HashMap<String, broadcast::Sender<Bytes>> {
+        if sharded {
+            &mut self.shard_pub_sub
+        } else {
+            &mut self.pub_sub
+        }
+    }
 }
 
 /// Routine executed by the background task.
@@ -0,0 +1,433 @@
+use crate::cmd::{execute_replicated, Del};
+use crate::config::{
+    config_infra_or_default, lfu_decay_time_or_default, maxmemory_or_default,
+    maxmemory_policy_or_default, maxmemory_samples_or_default, LOGGER,
+};
+use crate::metrics::EVICTED_KEY_COUNTER;
+use crate::raft::get_raft_app;
+use crate::rocks::client::RocksClient;
+use crate::rocks::encoding::KeyDecoder;
+use crate::rocks::errors::RError;
+use crate::rocks::kv::bound_range::BoundRange;
+use crate::rocks::kv::key::Key;
+use crate::rocks::{get_client, Result as RocksResult, CF_NAME_ACCESS, CF_NAME_META, KEY_ENCODER};
+use crate::utils::{key_is_expired, notify_event, now_timestamp_in_millis};
+use crate::Command;
+use lazy_static::lazy_static;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use slog::{debug, error, info};
+use std::collections::{HashMap, HashSet};
+use std::ops::RangeFrom;
+use std::sync::Mutex;
+
+// initial lfu counter of a new key, so it will not be evicted immediately
+const LFU_INIT_VAL: u8 = 5;
+const LFU_LOG_FACTOR: f64 = 10.0;
+
+// bound the keys evicted in one check, live data size estimation is only
+// updated after compaction
+const MAX_EVICTIONS_PER_CHECK: usize = 16;
+
+// bound the memory of the accesses waiting to be written
+const MAX_PENDING_ACCESSES: usize = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    NoEviction,
+    AllKeysLru,
+    AllKeysLfu,
+    VolatileLru,
+    VolatileLfu,
+    AllKeysRandom,
+}
+
+impl EvictionPolicy {
+    pub fn is_lfu(&self) -> bool {
+        matches!(
+            self,
+            EvictionPolicy::AllKeysLfu | EvictionPolicy::VolatileLfu
+        )
+    }
+
+    fn volatile_only(&self) -> bool {
+        matches!(
+            self,
+            EvictionPolicy::VolatileLru | EvictionPolicy::VolatileLfu
+        )
+    }
+}
+
+impl From<&str> for EvictionPolicy {
+    fn from(value: &str) -> Self {
+        match value {
+            "allkeys-lru" => Self::AllKeysLru,
+            "allkeys-lfu" => Self::AllKeysLfu,
+            "volatile-lru" => Self::VolatileLru,
+            "volatile-lfu" => Self::VolatileLfu,
+            "allkeys-random" => Self::AllKeysRandom,
+            _ => Self::NoEviction,
+        }
+    }
+}
+
+lazy_static! {
+    // accesses recorded since the last flush, by key
+    static ref PENDING_ACCESSES: Mutex<HashMap<String, PendingAccess>> =
+        Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PendingAccess {
+    last_access: u64,
+    count: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Candidate {
+    key: Vec<u8>,
+    last_access: u64,
+    counter: u8,
+    volatile: bool,
+}
+
+// access value layout: last access timestamp in millis(8) | lfu counter(1)
+fn encode_access_value(last_access: u64, counter: u8) -> Vec<u8> {
+    let mut val = Vec::with_capacity(9);
+    val.extend_from_slice(&last_access.to_be_bytes());
+    val.push(counter);
+    val
+}
+
+fn decode_access_value(value: &[u8]) -> (u64, u8) {
+    (u64::from_be_bytes(value[..8].try_into().unwrap()), value[8])
+}
+
+// logarithmic counter increment, the more a key is accessed the less likely
+// the counter grows
+fn lfu_incr(counter: u8) -> u8 {
+    if counter == u8::MAX {
+        return counter;
+    }
+    let base = counter.saturating_sub(LFU_INIT_VAL) as f64;
+    let p = 1.0 / (base * LFU_LOG_FACTOR + 1.0);
+    if rand::thread_rng().gen::<f64>() < p {
+        counter + 1
+    } else {
+        counter
+    }
+}
+
+// decrement the counter by one for every `decay_time` minutes elapsed since
+// the last access
+fn lfu_decr(counter: u8, last_access: u64, now: u64, decay_time: u64) -> u8 {
+    if decay_time == 0 {
+        return counter;
+    }
+    let periods = now.saturating_sub(last_access) / (decay_time * 60 * 1000);
+    counter.saturating_sub(periods.min(u8::MAX as u64) as u8)
+}
+
+// access frequency counter decayed to now
+fn decayed_counter(value: &[u8]) -> u8 {
+    let (last_access, counter) = decode_access_value(value);
+    lfu_decr(
+        counter,
+        last_access,
+        now_timestamp_in_millis() as u64,
+        lfu_decay_time_or_default(),
+    )
+}
+
+// pick the victim among sampled candidates according to the policy
+fn pick_victim(policy: EvictionPolicy, candidates: &[Candidate]) -> Option<Vec<u8>> {
+    let candidates: Vec<&Candidate> = candidates
+        .iter()
+        .filter(|c| !policy.volatile_only() || c.volatile)
+        .collect();
+    let victim = match policy {
+        EvictionPolicy::NoEviction => None,
+        EvictionPolicy::AllKeysRandom => candidates.choose(&mut rand::thread_rng()).copied(),
+        EvictionPolicy::AllKeysLru | EvictionPolicy::VolatileLru => {
+            candidates.into_iter().min_by_key(|c| c.last_access)
+        }
+        EvictionPolicy::AllKeysLfu | EvictionPolicy::VolatileLfu => candidates
+            .into_iter()
+            .min_by_key(|c| (c.counter, c.last_access)),
+    };
+    victim.map(|c| c.key.clone())
+}
+
+/// Record an access of the key, used to pick eviction victims and to reply
+/// `OBJECT IDLETIME` and `OBJECT FREQ`. Accesses are recorded even if eviction
+/// is disabled, so the idle time is always accurate.
+///
+/// Only the pending accesses in memory are updated here, they are written to
+/// the access column family by `flush_access_records` with the eviction
+/// check. Accesses of new keys are dropped while `MAX_PENDING_ACCESSES` keys
+/// are pending.
+pub fn record_access(key: &str) {
+    let now = now_timestamp_in_millis() as u64;
+    let mut pending = PENDING_ACCESSES.lock().unwrap();
+    if let Some(access) = pending.get_mut(key) {
+        access.last_access = now;
+        access.count = access.count.saturating_add(1);
+    } else if pending.len() < MAX_PENDING_ACCESSES {
+        pending.insert(
+            key.to_owned(),
+            PendingAccess {
+                last_access: now,
+                count: 1,
+            },
+        );
+    }
+}
+
+/// Write the pending accesses to the access column family, accesses of keys
+/// which do not exist are dropped.
+pub fn flush_access_records() {
+    let pending = std::mem::take(&mut *PENDING_ACCESSES.lock().unwrap());
+    if pending.is_empty() {
+        return;
+    }
+    let client = get_client();
+    for (key, access) in pending {
+        if let Err(e) = update_access_record(&client, &key, access) {
+            debug!(LOGGER, "record access of key {} failed: {:?}", key, e);
+        }
+    }
+}
+
+// write the pending accesses of the key now, so they are part of the reply of
+// `OBJECT`
+fn flush_access_record(client: &RocksClient, key: &str) -> RocksResult<()> {
+    let access = PENDING_ACCESSES.lock().unwrap().remove(key);
+    match access {
+        Some(access) => update_access_record(client, key, access),
+        None => Ok(()),
+    }
+}
+
+fn update_access_record(client: &RocksClient, key: &str, access: PendingAccess) -> RocksResult<()> {
+    if !key_exists(client, key)? {
+        return Ok(());
+    }
+    let cf = client.cf_handle(CF_NAME_ACCESS)?;
+    let access_key: Key = key.as_bytes().to_vec().into();
+    let (mut counter, count) = match client.get(cf.clone(), access_key.clone())? {
+        Some(value) => (decayed_counter(&value), access.count),
+        None => (LFU_INIT_VAL, access.count - 1),
+    };
+    for _ in 0..count {
+        if counter == u8::MAX {
+            break;
+        }
+        counter = lfu_incr(counter);
+    }
+    let value = encode_access_value(access.last_access, counter);
+    client.put(cf, access_key, value)
+}
+
+// whether the key exists and is not expired
+fn key_exists(client: &RocksClient, key: &str) -> RocksResult<bool> {
+    let meta_key = KEY_ENCODER.encode_meta_key(key);
+    Ok(client
+        .get(client.cf_handle(CF_NAME_META)?, meta_key)?
+        .map_or(false, |meta_value| {
+            !key_is_expired(KeyDecoder::decode_key_ttl(&meta_value))
+        }))
+}
+
+/// Access frequency counter of the key, `None` if the key does not exist.
+pub fn access_frequency(key: &str) -> RocksResult<Option<u8>> {
+    let client = get_client();
+    if !key_exists(&client, key)? {
+        return Ok(None);
+    }
+    flush_access_record(&client, key)?;
+    let access_key: Key = key.as_bytes().to_vec().into();
+    let counter = client
+        .get(client.cf_handle(CF_NAME_ACCESS)?, access_key)?
+        .map_or(0, |value| decayed_counter(&value));
+    Ok(Some(counter))
+}
+
+/// Seconds since the last recorded access of the key, `None` if the key does
+/// not exist.
+pub fn idle_time(key: &str) -> RocksResult<Option<u64>> {
+    let client = get_client();
+    if !key_exists(&client, key)? {
+        return Ok(None);
+    }
+    flush_access_record(&client, key)?;
+    let access_key: Key = key.as_bytes().to_vec().into();
+    let idle = client
+        .get(client.cf_handle(CF_NAME_ACCESS)?, access_key)?
+        .map_or(0, |value| {
+            let (last_access, _) = decode_access_value(&value);
+            (now_timestamp_in_millis() as u64).saturating_sub(last_access) / 1000
+        });
+    Ok(Some(idle))
+}
+
+// sample keys from a random position of the access column family
+fn sample_candidates(client: &RocksClient, samples: usize) -> RocksResult<Vec<Candidate>> {
+    let access_cf = client.cf_handle(CF_NAME_ACCESS)?;
+    let meta_cf = client.cf_handle(CF_NAME_META)?;
+
+    let start: Key = vec![rand::thread_rng().gen::<u8>()].into();
+    let range: RangeFrom<Key> = start..;
+    let mut pairs: Vec<_> = client
+        .scan(access_cf.clone(), range, samples as u32)?
+        .collect();
+    if pairs.len() < samples {
+        // wrap around to the beginning of the column family
+        let range: RangeFrom<Key> = Key::from(vec![])..;
+        let from_range: BoundRange = range.into();
+        pairs.extend(client.scan(access_cf.clone(), from_range, samples as u32)?);
+    }
+
+    let mut seen = HashSet::new();
+    let mut candidates = Vec::with_capacity(samples);
+    for kv in pairs {
+        let key: Vec<u8> = kv.0.into();
+        if !seen.insert(key.clone()) {
+            continue;
+        }
+        let meta_key = KEY_ENCODER.encode_meta_key(&String::from_utf8_lossy(&key));
+        match client.get(meta_cf.clone(), meta_key)? {
+            Some(meta_value) => {
+                let last_access = decode_access_value(&kv.1).0;
+                candidates.push(Candidate {
+                    key,
+                    last_access,
+                    counter: decayed_counter(&kv.1),
+                    volatile: KeyDecoder::decode_key_ttl(&meta_value) > 0,
+                });
+            }
+            None => {
+                // key already deleted, remove the stale access record
+                client.del(access_cf.clone(), key.into())?;
+            }
+        }
+    }
+    Ok(candidates)
+}
+
+// with raft only the leader evicts, the victims are deleted on every node by
+// the replicated `DEL`
+async fn is_eviction_node() -> bool {
+    if !config_infra_or_default().need_raft() {
+        return true;
+    }
+    match get_raft_app() {
+        Some(app) => app.raft.is_leader().await.is_ok(),
+        None => false,
+    }
+}
+
+/// Evict keys according to the configured policy while the estimated live
+/// data size exceeds `maxmemory`, return the number of evicted keys.
+pub async fn evict_if_needed() -> RocksResult<usize> {
+    let maxmemory = maxmemory_or_default();
+    let policy = maxmemory_policy_or_default();
+    if maxmemory == 0 || policy == EvictionPolicy::NoEviction || !is_eviction_node().await {
+        return Ok(0);
+    }
+
+    let client = get_client();
+    let mut evicted = 0;
+    while evicted < MAX_EVICTIONS_PER_CHECK && client.estimate_live_data_size()? > maxmemory {
+        let candidates = sample_candidates(&client, maxmemory_samples_or_default())?;
+        let victim = match pick_victim(policy, &candidates) {
+            Some(victim) => String::from_utf8_lossy(&victim).to_string(),
+            None => break,
+        };
+
+        let mut del = Del::default();
+        del.add_key(victim.clone());
+        execute_replicated(Command::Del(del))
+            .await
+            .map_err(|e| RError::owned_error(e.to_string()))?;
+        // access records are local to the node, the ones of the other nodes
+        // are removed when sampled
+        client.del(
+            client.cf_handle(CF_NAME_ACCESS)?,
+            victim.as_bytes().to_vec().into(),
+        )?;
+        EVICTED_KEY_COUNTER.inc();
+        notify_event('e', "evicted", &victim);
+        evicted += 1;
+        debug!(LOGGER, "[Eviction] evict key {} by {:?}", victim, policy);
+    }
+    if evicted > 0 {
+        info!(
+            LOGGER,
+            "[Eviction] {} keys evicted by {:?}", evicted, policy
+        );
+    }
+    Ok(evicted)
+}
+
+pub async fn run_eviction_check() {
+    flush_access_records();
+    if let Err(e) = evict_if_needed().await {
+        error!(LOGGER, "[Eviction] check failed: {:?}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lfu_decr, lfu_incr, pick_victim, Candidate, EvictionPolicy, LFU_INIT_VAL};
+
+    fn candidate(key: &str, last_access: u64, counter: u8, volatile: bool) -> Candidate {
+        Candidate {
+            key: key.as_bytes().to_vec(),
+            last_access,
+            counter,
+            volatile,
+        }
+    }
+
+    #[test]
+    fn test_allkeys_lru_evicts_least_recently_used() {
+        let candidates = vec![
+            candidate("a", 300, 10, false),
+            candidate("b", 100, 20, false),
+            candidate("c", 200, 1, true),
+        ];
+        let victim = pick_victim(EvictionPolicy::AllKeysLru, &candidates);
+        assert_eq!(victim, Some(b"b".to_vec()));
+        let victim = pick_victim(EvictionPolicy::AllKeysLfu, &candidates);
+        assert_eq!(victim, Some(b"c".to_vec()));
+    }
+
+    #[test]
+    fn test_volatile_lru_only_evicts_keys_with_ttl() {
+        let candidates = vec![
+            candidate("a", 100, 5, false),
+            candidate("b", 300, 5, true),
+            candidate("c", 200, 5, true),
+        ];
+        let victim = pick_victim(EvictionPolicy::VolatileLru, &candidates);
+        assert_eq!(victim, Some(b"c".to_vec()));
+
+        let candidates = vec![candidate("a", 100, 5, false)];
+        assert_eq!(pick_victim(EvictionPolicy::VolatileLru, &candidates), None);
+        assert_eq!(pick_victim(EvictionPolicy::NoEviction, &candidates), None);
+    }
+
+    #[test]
+    fn test_lfu_counter_incr_and_decay() {
+        // the first increment from the initial value always happens
+        assert_eq!(lfu_incr(LFU_INIT_VAL), LFU_INIT_VAL + 1);
+        assert_eq!(lfu_incr(u8::MAX), u8::MAX);
+
+        let minute = 60 * 1000;
+        assert_eq!(lfu_decr(10, 0, 30 * 1000, 1), 10);
+        assert_eq!(lfu_decr(10, 0, 3 * minute, 1), 7);
+        assert_eq!(lfu_decr(10, 0, 3 * minute, 2), 9);
+        assert_eq!(lfu_decr(10, 0, 100 * minute, 1), 0);
+        assert_eq!(lfu_decr(10, 0, 100 * minute, 0), 10);
+    }
+}
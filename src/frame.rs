@@ -17,6 +17,10 @@ pub enum Frame {
     Bulk(Bytes),
     Null,
     Array(Vec<Frame>),
+    // RESP3 types, downgraded to RESP2 equivalents on connections which did
+    // not negotiate protocol 3 by HELLO
+    Map(Vec<(Frame, Frame)>),
+    Double(f64),
 }
 
 #[derive(Debug)]
@@ -62,6 +66,22 @@ impl Frame {
         }
     }
 
+    /// Convert a flat array of key value pairs to a map, other frames are
+    /// returned unchanged.
+    pub(crate) fn into_map(self) -> Frame {
+        match self {
+            Frame::Array(vec) => {
+                let mut iter = vec.into_iter();
+                let mut pairs = vec![];
+                while let (Some(k), Some(v)) = (iter.next(), iter.next()) {
+                    pairs.push((k, v));
+                }
+                Frame::Map(pairs)
+            }
+            frame => frame,
+        }
+    }
+
     /// Checks if an entire message can be decoded from `src`
     pub fn check(src: &mut Cursor<&[u8]>) -> Result<(), Error> {
         match get_u8(src)? {
@@ -98,6 +118,19 @@ impl Frame {
 
                 Ok(())
             }
+            b'%' => {
+                let len = get_decimal(src)?;
+
+                for _ in 0..len * 2 {
+                    Frame::check(src)?;
+                }
+
+                Ok(())
+            }
+            b',' => {
+                get_line(src)?;
+                Ok(())
+            }
             actual => Err(format!("protocol error; invalid frame type byte `{actual}`").into()),
         }
     }
@@ -163,11 +196,68 @@ impl Frame {
 
                 Ok(Frame::Array(out))
             }
+            b'%' => {
+                let len = get_decimal(src)?.try_into()?;
+                let mut out = Vec::with_capacity(len);
+
+                for _ in 0..len {
+                    let key = Frame::parse(src)?;
+                    let value = Frame::parse(src)?;
+                    out.push((key, value));
+                }
+
+                Ok(Frame::Map(out))
+            }
+            b',' => {
+                let line = get_line(src)?.to_vec();
+                let string = String::from_utf8(line)?;
+                let value = match string.as_str() {
+                    "inf" => f64::INFINITY,
+                    "-inf" => f64::NEG_INFINITY,
+                    _ => string
+                        .parse()
+                        .map_err(|_| "protocol error; invalid frame format")?,
+                };
+
+                Ok(Frame::Double(value))
+            }
             _ => unimplemented!(),
         }
     }
 }
 
+/// Inline requests longer than this without a newline are rejected.
+const INLINE_MAX_SIZE: usize = 64 * 1024;
+
+impl Frame {
+    /// Parses an inline command, space separated arguments terminated by a
+    /// newline as typed in telnet, to an array of bulk strings.
+    ///
+    /// Arguments may be quoted, double quoted ones support the usual escape
+    /// sequences like `\n` and `\x41`.
+    pub fn parse_inline<'a>(src: &mut Cursor<&'a [u8]>) -> Result<Frame, Error> {
+        let buf: &'a [u8] = *src.get_ref();
+        let start = src.position() as usize;
+        let end = match buf[start..].iter().position(|b| *b == b'\n') {
+            Some(pos) => start + pos,
+            None if buf.len() - start > INLINE_MAX_SIZE => {
+                return Err("protocol error; too big inline request".into());
+            }
+            None => return Err(Error::Incomplete),
+        };
+        src.set_position((end + 1) as u64);
+
+        let line = &buf[start..end];
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let args = split_inline_args(line).ok_or("protocol error; unbalanced quotes in request")?;
+        Ok(Frame::Array(
+            args.into_iter()
+                .map(|arg| Frame::Bulk(Bytes::from(arg)))
+                .collect(),
+        ))
+    }
+}
+
 impl PartialEq<&str> for Frame {
     fn eq(&self, other: &&str) -> bool {
         match self {
@@ -202,6 +292,17 @@ impl fmt::Display for Frame {
 
                 Ok(())
             }
+            Frame::Map(pairs) => {
+                for (i, (key, value)) in pairs.iter().enumerate() {
+                    if i > 0 {
+                        write!(fmt, " ")?;
+                    }
+                    write!(fmt, "{key}: {value}")?;
+                }
+
+                Ok(())
+            }
+            Frame::Double(num) => num.fmt(fmt),
         }
     }
 }
@@ -217,6 +318,66 @@ impl From<RError> for Frame {
     }
 }
 
+/// Split an inline command line into arguments, `None` if the quotes are
+/// unbalanced or a closing quote is not followed by a space.
+fn split_inline_args(line: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let mut args = vec![];
+    let mut i = 0;
+    loop {
+        while i < line.len() && line[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if i == line.len() {
+            return Some(args);
+        }
+
+        let mut arg = vec![];
+        let mut quote = None;
+        loop {
+            match (quote, line.get(i).copied()) {
+                (None, None) => break,
+                (None, Some(c)) if c.is_ascii_whitespace() => break,
+                (None, Some(c @ (b'"' | b'\''))) => quote = Some(c),
+                (None, Some(c)) => arg.push(c),
+                (Some(_), None) => return None,
+                (Some(q), Some(c)) if c == q => {
+                    if line.get(i + 1).map_or(false, |c| !c.is_ascii_whitespace()) {
+                        return None;
+                    }
+                    i += 1;
+                    break;
+                }
+                (Some(b'"'), Some(b'\\')) if i + 1 < line.len() => {
+                    i += 1;
+                    let hex = line
+                        .get(i + 1..i + 3)
+                        .and_then(|hex| std::str::from_utf8(hex).ok())
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                    match (line[i], hex) {
+                        (b'x', Some(byte)) => {
+                            arg.push(byte);
+                            i += 2;
+                        }
+                        (b'n', _) => arg.push(b'\n'),
+                        (b'r', _) => arg.push(b'\r'),
+                        (b't', _) => arg.push(b'\t'),
+                        (b'b', _) => arg.push(0x08),
+                        (b'a', _) => arg.push(0x07),
+                        (c, _) => arg.push(c),
+                    }
+                }
+                (Some(b'\''), Some(b'\\')) if line.get(i + 1) == Some(&b'\'') => {
+                    i += 1;
+                    arg.push(b'\'');
+                }
+                (Some(_), Some(c)) => arg.push(c),
+            }
+            i += 1;
+        }
+        args.push(arg);
+    }
+}
+
 fn peek_u8(src: &mut Cursor<&[u8]>) -> Result<u8, Error> {
     if !src.has_remaining() {
         return Err(Error::Incomplete);
@@ -1,8 +1,12 @@
 use crate::config::{
     async_deletion_enabled_or_default, async_gc_interval_or_default,
-    async_gc_worker_queue_size_or_default, LOGGER,
+    async_gc_max_keys_per_tick_or_default, async_gc_sleep_between_keys_us_or_default,
+    async_gc_worker_number_or_default, async_gc_worker_queue_size_or_default,
+    eviction_check_interval_or_default, LOGGER,
 };
-use crate::metrics::GC_TASK_QUEUE_COUNTER;
+use crate::eviction::run_eviction_check;
+use crate::latency::record_latency;
+use crate::metrics::{GC_SCAN_RATE, GC_TASK_QUEUE_COUNTER};
 use crate::rocks::client::RocksClient;
 use crate::rocks::encoding::{DataType, KeyDecoder};
 use crate::rocks::errors::RError;
@@ -10,15 +14,18 @@ use crate::rocks::hash::HashCommand;
 use crate::rocks::list::ListCommand;
 use crate::rocks::{get_client, TxnCommand, CF_NAME_GC, CF_NAME_GC_VERSION, KEY_ENCODER};
 use crc::{Crc, CRC_16_XMODEM};
+use lazy_static::lazy_static;
 use rocksdb::ColumnFamilyRef;
 use slog::{debug, error, info};
-use std::collections::HashSet;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::mpsc::{Receiver, Sender};
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::sync::{Mutex, Notify};
+use tokio::task::JoinHandle;
 use tokio::time;
-use tokio::time::MissedTickBehavior;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
 
 use crate::rocks::set::SetCommand;
 use crate::rocks::zset::ZsetCommand;
@@ -26,6 +33,31 @@ use crate::rocks::Result as RocksResult;
 
 const CRC16: Crc<u16> = Crc::<u16>::new(&CRC_16_XMODEM);
 
+lazy_static! {
+    // wakes up the gc master of the node, see `trigger_gc`
+    static ref GC_TRIGGER: Notify = Notify::new();
+}
+
+/// Skip the interval timer of the gc master and run one scan cycle
+/// immediately, used by `DEBUG GC`.
+pub fn trigger_gc() {
+    GC_TRIGGER.notify_one();
+}
+
+fn gc_interval() -> Duration {
+    Duration::from_millis(async_gc_interval_or_default())
+}
+
+// wait for the next scan cycle, at the interval or on `trigger_gc`, false if
+// the eviction check is due first
+async fn scan_due(next_scan: Instant, eviction_interval: &mut Interval) -> bool {
+    tokio::select! {
+        _ = time::sleep_until(next_scan) => true,
+        _ = GC_TRIGGER.notified() => true,
+        _ = eviction_interval.tick() => false,
+    }
+}
+
 pub struct GcCF<'a> {
     gc_cf: ColumnFamilyRef<'a>,
     gc_version_cf: ColumnFamilyRef<'a>,
@@ -45,14 +77,17 @@ pub struct GcTask {
     key_type: DataType,
     user_key: Vec<u8>,
     version: u16,
+    // number of sub items of the deleted key, larger keys are reclaimed first
+    estimated_size: u64,
 }
 
 impl GcTask {
-    fn new(key_type: DataType, user_key: Vec<u8>, version: u16) -> GcTask {
+    fn new(key_type: DataType, user_key: Vec<u8>, version: u16, estimated_size: u64) -> GcTask {
         GcTask {
             key_type,
             user_key,
             version,
+            estimated_size,
         }
     }
 
@@ -65,37 +100,148 @@ impl GcTask {
     }
 }
 
+impl PartialEq for GcTask {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for GcTask {}
+
+impl PartialOrd for GcTask {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for GcTask {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.estimated_size
+            .cmp(&other.estimated_size)
+            .then_with(|| self.user_key.cmp(&other.user_key))
+            .then_with(|| self.version.cmp(&other.version))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GcThrottle {
+    max_keys_per_tick: usize,
+    sleep_between_keys_us: u64,
+    processed: usize,
+}
+
+impl GcThrottle {
+    pub fn new(max_keys_per_tick: usize, sleep_between_keys_us: u64) -> Self {
+        GcThrottle {
+            max_keys_per_tick,
+            sleep_between_keys_us,
+            processed: 0,
+        }
+    }
+
+    // record one processed key, return true if the scan should pause
+    // before handling more keys
+    fn record(&mut self) -> bool {
+        self.processed += 1;
+        if self.max_keys_per_tick > 0 && self.processed >= self.max_keys_per_tick {
+            self.processed = 0;
+            return true;
+        }
+        false
+    }
+
+    // start counting for a new scan, with the limits of a reloaded config
+    fn reset(&mut self) {
+        self.max_keys_per_tick = async_gc_max_keys_per_tick_or_default();
+        self.sleep_between_keys_us = async_gc_sleep_between_keys_us_or_default();
+        self.processed = 0;
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GcMaster {
     workers: Vec<GcWorker>,
+    task_sets: Arc<Mutex<HashSet<Vec<u8>>>>,
+    started: bool,
+    throttle: GcThrottle,
 }
 
 impl GcMaster {
     pub fn new(worker_num: usize) -> Self {
         let mut workers = Vec::with_capacity(worker_num);
+        let task_sets = Arc::new(Mutex::new(HashSet::new()));
 
         // create workers pool
         for id in 0..worker_num {
-            let (tx, rx) = mpsc::channel::<GcTask>(async_gc_worker_queue_size_or_default());
-            let worker = GcWorker::new(id, rx, tx);
+            let worker = GcWorker::new(
+                id,
+                async_gc_worker_queue_size_or_default(),
+                task_sets.clone(),
+            );
             workers.push(worker);
         }
 
-        GcMaster { workers }
+        let throttle = GcThrottle::new(
+            async_gc_max_keys_per_tick_or_default(),
+            async_gc_sleep_between_keys_us_or_default(),
+        );
+
+        GcMaster {
+            workers,
+            task_sets,
+            started: false,
+            throttle,
+        }
     }
 
-    pub async fn start_workers(&self) {
+    pub async fn start_workers(&mut self) {
         // run all workers
         // worker will wait for task from channel
-        for worker in &self.workers {
-            worker.clone().run().await;
+        for worker in &mut self.workers {
+            worker.start();
         }
+        self.started = true;
+    }
+
+    // follow `async_gc_worker_number`, new workers are started right away,
+    // excess workers drain their queue and exit
+    pub fn resize(&mut self, new_count: usize) {
+        let new_count = new_count.max(1);
+        if new_count == self.workers.len() {
+            return;
+        }
+        info!(
+            LOGGER,
+            "[GC] resize gc workers from {} to {}",
+            self.workers.len(),
+            new_count
+        );
+        while self.workers.len() > new_count {
+            let worker = self.workers.pop().unwrap();
+            worker.stop();
+        }
+        while self.workers.len() < new_count {
+            let mut worker = GcWorker::new(
+                self.workers.len(),
+                async_gc_worker_queue_size_or_default(),
+                self.task_sets.clone(),
+            );
+            if self.started {
+                worker.start();
+            }
+            self.workers.push(worker);
+        }
+    }
+
+    // all the versions of a key go to the same worker, so they are never
+    // reclaimed concurrently
+    fn worker_index(&self, task: &GcTask) -> usize {
+        CRC16.checksum(&task.user_key) as usize % self.workers.len()
     }
 
     // dispatch task to a worker
     pub async fn dispatch_task(&mut self, task: GcTask) -> RocksResult<()> {
-        // calculate task hash and dispatch to worker
-        let idx = CRC16.checksum(&task.to_bytes()) as usize % self.workers.len();
+        let idx = self.worker_index(&task);
         debug!(LOGGER, "[GC] dispatch task {:?} to worker: {}", task, idx);
         self.workers[idx].add_task(task).await
     }
@@ -104,47 +250,62 @@ impl GcMaster {
     // create gc task for each version key
     // dispatch gc task to workers
     pub async fn run(&mut self) -> RocksResult<()> {
-        let mut interval = time::interval(Duration::from_millis(async_gc_interval_or_default()));
-        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
-        let client = get_client();
-        let gc_cfs = GcCF::new(&client);
+        // the interval is read again after every scan to follow config reloads
+        let mut next_scan = Instant::now() + gc_interval();
+        let mut eviction_interval =
+            time::interval(Duration::from_millis(eviction_check_interval_or_default()));
+        eviction_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
         loop {
-            interval.tick().await;
-            if !async_deletion_enabled_or_default() {
+            if !scan_due(next_scan, &mut eviction_interval).await {
+                run_eviction_check().await;
                 continue;
             }
+            // the worker number is read again to follow config reloads
+            self.resize(async_gc_worker_number_or_default());
+            if async_deletion_enabled_or_default() {
+                self.scan_once().await;
+            }
+            next_scan = Instant::now() + gc_interval();
+        }
+    }
 
-            let bound_range = KEY_ENCODER.encode_gc_version_key_range();
-
-            // TODO scan speed throttling
-            let iter_res = client.scan(gc_cfs.gc_version_cf.clone(), bound_range, u32::MAX);
-            if iter_res.is_err() {
-                error!(
-                    LOGGER,
-                    "[GC] scan gc version keys failed: {:?}",
-                    iter_res.err()
-                );
-                // retry next tick
-                continue;
+    async fn scan_once(&mut self) {
+        let start_at = Instant::now();
+        let client = get_client();
+        let gc_cfs = GcCF::new(&client);
+        let bound_range = KEY_ENCODER.encode_gc_version_key_range();
+
+        let iter_res = client.scan(gc_cfs.gc_version_cf.clone(), bound_range, u32::MAX);
+        if iter_res.is_err() {
+            error!(
+                LOGGER,
+                "[GC] scan gc version keys failed: {:?}",
+                iter_res.err()
+            );
+            // retry next tick
+            return;
+        }
+
+        self.throttle.reset();
+        let iter = iter_res.unwrap();
+        for kv in iter {
+            let (user_key, version) = KeyDecoder::decode_key_gc_userkey_version(kv.0);
+            let (key_type, estimated_size) = KeyDecoder::decode_gc_version_value(&kv.1);
+            let task = GcTask::new(key_type, user_key, version, estimated_size);
+            if let Err(e) = self.dispatch_task(task).await {
+                error!(LOGGER, "[GC] dispatch task failed: {:?}", e);
             }
+            GC_SCAN_RATE.inc();
 
-            let iter = iter_res.unwrap();
-            for kv in iter {
-                let (user_key, version) = KeyDecoder::decode_key_gc_userkey_version(kv.0);
-                let key_type = match kv.1[0] {
-                    0 => DataType::String,
-                    1 => DataType::Hash,
-                    2 => DataType::List,
-                    3 => DataType::Set,
-                    4 => DataType::Zset,
-                    _ => DataType::Null,
-                };
-                let task = GcTask::new(key_type, user_key, version);
-                if let Err(e) = self.dispatch_task(task).await {
-                    error!(LOGGER, "[GC] dispatch task failed: {:?}", e);
-                }
+            if self.throttle.sleep_between_keys_us > 0 {
+                time::sleep(Duration::from_micros(self.throttle.sleep_between_keys_us)).await;
+            }
+            if self.throttle.record() {
+                // budget of this tick exhausted, yield before scanning more keys
+                time::sleep(gc_interval()).await;
             }
         }
+        record_latency("gc-scan", start_at.elapsed());
     }
 
     pub fn shutdown(&self) {}
@@ -154,39 +315,49 @@ impl GcMaster {
 struct GcWorker {
     id: usize,
 
-    rx: Arc<Mutex<Receiver<GcTask>>>,
-    tx: Sender<GcTask>,
+    // pending tasks, the largest estimated size is popped first
+    queue: Arc<Mutex<BinaryHeap<GcTask>>>,
+    queue_size: usize,
+    notify: Arc<Notify>,
 
-    // check task already in queue, avoid duplicate task
+    // tasks queued or running in any worker, shared by all workers so a
+    // task is never handled twice at the same time
     task_sets: Arc<Mutex<HashSet<Vec<u8>>>>,
+
+    // set once the worker is started, a message makes it exit after its
+    // queue is drained
+    shutdown: Option<Sender<()>>,
 }
 
 impl GcWorker {
-    pub fn new(id: usize, rx: Receiver<GcTask>, tx: Sender<GcTask>) -> Self {
+    pub fn new(id: usize, queue_size: usize, task_sets: Arc<Mutex<HashSet<Vec<u8>>>>) -> Self {
         GcWorker {
             id,
-            rx: Arc::new(Mutex::new(rx)),
-            tx,
-            task_sets: Arc::new(Mutex::new(HashSet::new())),
+            queue: Arc::new(Mutex::new(BinaryHeap::new())),
+            queue_size,
+            notify: Arc::new(Notify::new()),
+            task_sets,
+            shutdown: None,
         }
     }
 
-    // queue task to channel
+    // queue task to priority queue
     pub async fn add_task(&mut self, task: GcTask) -> RocksResult<()> {
         let bytes = task.to_bytes();
         let mut task_sets = self.task_sets.lock().await;
         if !task_sets.contains(&bytes) {
+            let mut queue = self.queue.lock().await;
+            if queue.len() >= self.queue_size {
+                error!(LOGGER, "[GC] worker {} queue is full", self.id);
+                return Err(RError::String("ERR gc worker queue is full"));
+            }
             debug!(LOGGER, "[GC] add task: {:?}", task);
             task_sets.insert(bytes);
-            return if let Err(e) = self.tx.send(task).await {
-                error!(LOGGER, "[GC] send task to channel failed"; "error" => ?e);
-                Err(RError::Owned(e.to_string()))
-            } else {
-                GC_TASK_QUEUE_COUNTER
-                    .with_label_values(&[&self.id.to_string()])
-                    .inc();
-                Ok(())
-            };
+            queue.push(task);
+            GC_TASK_QUEUE_COUNTER
+                .with_label_values(&[&self.id.to_string()])
+                .inc();
+            self.notify.notify_one();
         }
         Ok(())
     }
@@ -230,7 +401,7 @@ impl GcWorker {
                     );
                     ZsetCommand::new(&client).txn_gc(txn, &client, &user_key, version)?;
                 }
-                DataType::Null => {
+                DataType::Hll | DataType::Null => {
                     panic!("unknown data type to do async deletion");
                 }
             }
@@ -261,24 +432,161 @@ impl GcWorker {
         })
     }
 
-    pub async fn run(self) {
-        tokio::spawn(async move {
-            info!(LOGGER, "[GC] start gc worker thread: {}", self.id);
-            while let Some(task) = self.rx.lock().await.recv().await {
-                match self.handle_task(task.clone()).await {
-                    Ok(_) => {
-                        debug!(LOGGER, "[GC] gc task done: {:?}", task);
-                        self.task_sets.lock().await.remove(&task.to_bytes());
-                        GC_TASK_QUEUE_COUNTER
-                            .with_label_values(&[&self.id.to_string()])
-                            .dec();
-                    }
-                    Err(e) => {
-                        error!(LOGGER, "[GC] handle task error: {:?}", e);
+    fn start(&mut self) -> JoinHandle<()> {
+        let (tx, rx) = mpsc::channel(1);
+        self.shutdown = Some(tx);
+        tokio::spawn(self.clone().run(rx))
+    }
+
+    fn stop(&self) {
+        if let Some(shutdown) = &self.shutdown {
+            let _ = shutdown.try_send(());
+        }
+    }
+
+    async fn run(self, mut shutdown: Receiver<()>) {
+        info!(LOGGER, "[GC] start gc worker thread: {}", self.id);
+        let mut stopping = false;
+        loop {
+            let next = self.queue.lock().await.pop();
+            let task = match next {
+                Some(task) => task,
+                None if stopping => break,
+                None => {
+                    tokio::select! {
+                        _ = self.notify.notified() => {}
+                        _ = shutdown.recv() => stopping = true,
                     }
+                    continue;
+                }
+            };
+            match self.handle_task(task.clone()).await {
+                Ok(_) => {
+                    debug!(LOGGER, "[GC] gc task done: {:?}", task);
+                }
+                Err(e) => {
+                    error!(LOGGER, "[GC] handle task error: {:?}", e);
                 }
             }
-            info!(LOGGER, "[GC] gc worker thread exit: {}", self.id);
-        });
+            // a failed task is dispatched again by the next scan
+            self.task_sets.lock().await.remove(&task.to_bytes());
+            GC_TASK_QUEUE_COUNTER
+                .with_label_values(&[&self.id.to_string()])
+                .dec();
+        }
+        info!(LOGGER, "[GC] stop gc worker thread: {}", self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{scan_due, trigger_gc, GcMaster, GcTask, GcThrottle, GcWorker};
+    use crate::rocks::encoding::DataType;
+    use std::collections::{BinaryHeap, HashSet};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::sync::Mutex;
+
+    #[test]
+    fn test_large_key_scheduled_first() {
+        let mut queue = BinaryHeap::new();
+        queue.push(GcTask::new(DataType::Hash, b"small".to_vec(), 0, 1200));
+        queue.push(GcTask::new(DataType::Set, b"large".to_vec(), 0, 2_000_000));
+        queue.push(GcTask::new(DataType::List, b"medium".to_vec(), 3, 50000));
+        assert_eq!(queue.pop().unwrap().user_key, b"large".to_vec());
+        assert_eq!(queue.pop().unwrap().user_key, b"medium".to_vec());
+        assert_eq!(queue.pop().unwrap().user_key, b"small".to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_trigger_skips_interval() {
+        let mut eviction_interval = tokio::time::interval(Duration::from_secs(3600));
+        // the first tick completes immediately
+        eviction_interval.tick().await;
+        let next_scan = tokio::time::Instant::now() + Duration::from_secs(3600);
+
+        trigger_gc();
+        let due = tokio::time::timeout(
+            Duration::from_secs(1),
+            scan_due(next_scan, &mut eviction_interval),
+        )
+        .await;
+        assert_eq!(due, Ok(true));
+    }
+
+    #[test]
+    fn test_throttle_pauses_after_max_keys() {
+        let mut throttle = GcThrottle::new(3, 0);
+        assert!(!throttle.record());
+        assert!(!throttle.record());
+        assert!(throttle.record());
+        // counter restarts after a pause
+        assert!(!throttle.record());
+        assert!(!throttle.record());
+        assert!(throttle.record());
+    }
+
+    #[test]
+    fn test_throttle_unlimited() {
+        let mut throttle = GcThrottle::new(0, 0);
+        for _ in 0..10000 {
+            assert!(!throttle.record());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_task_deduplicated_across_workers() {
+        let task_sets = Arc::new(Mutex::new(HashSet::new()));
+        let mut first = GcWorker::new(0, 10, task_sets.clone());
+        let mut second = GcWorker::new(1, 10, task_sets.clone());
+        let task = GcTask::new(DataType::Hash, b"key".to_vec(), 1, 100);
+
+        let (a, b) = tokio::join!(first.add_task(task.clone()), second.add_task(task));
+        assert!(a.is_ok() && b.is_ok());
+        let queued = first.queue.lock().await.len() + second.queue.lock().await.len();
+        assert_eq!(queued, 1);
+        assert_eq!(task_sets.lock().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_resize_workers() {
+        let mut master = GcMaster::new(2);
+        master.resize(4);
+        assert_eq!(master.workers.len(), 4);
+        for i in 0..64 {
+            let task = GcTask::new(DataType::Hash, format!("key{i}").into_bytes(), 1, 100);
+            master.dispatch_task(task).await.unwrap();
+        }
+        for worker in &master.workers {
+            assert!(!worker.queue.lock().await.is_empty());
+        }
+
+        master.resize(1);
+        assert_eq!(master.workers.len(), 1);
+        // at least one worker is kept
+        master.resize(0);
+        assert_eq!(master.workers.len(), 1);
+    }
+
+    #[test]
+    fn test_versions_of_a_key_dispatched_to_same_worker() {
+        let master = GcMaster::new(8);
+        for i in 0..32 {
+            let key = format!("key{i}").into_bytes();
+            let old = GcTask::new(DataType::Hash, key.clone(), 1, 100);
+            let new = GcTask::new(DataType::Set, key, 2, 100);
+            assert_eq!(master.worker_index(&old), master.worker_index(&new));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stopped_worker_exits() {
+        let mut worker = GcWorker::new(0, 10, Arc::new(Mutex::new(HashSet::new())));
+        let handle = worker.start();
+        worker.stop();
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .unwrap()
+            .unwrap();
     }
 }
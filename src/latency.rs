@@ -0,0 +1,128 @@
+use crate::utils::now_timestamp_in_millis;
+use lazy_static::lazy_static;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+// samples kept per event, one per second
+const LATENCY_SAMPLES_LEN: usize = 181;
+
+lazy_static! {
+    pub static ref LATENCY_MONITOR: Mutex<LatencyMonitor> = Mutex::new(LatencyMonitor::default());
+}
+
+#[derive(Debug, Default)]
+struct LatencyEvent {
+    // (unix timestamp in seconds, latency in milliseconds), oldest first
+    samples: VecDeque<(u64, u64)>,
+    max_ms: u64,
+}
+
+/// Latest latency samples of the named events, for `LATENCY HISTORY`,
+/// `LATEST` and `RESET`. Samples of the same second are merged, keeping the
+/// highest latency.
+#[derive(Debug, Default)]
+pub struct LatencyMonitor {
+    events: BTreeMap<String, LatencyEvent>,
+}
+
+impl LatencyMonitor {
+    pub fn record(&mut self, event: &str, timestamp: u64, latency_ms: u64) {
+        let event = self.events.entry(event.to_owned()).or_default();
+        event.max_ms = event.max_ms.max(latency_ms);
+        match event.samples.back_mut() {
+            Some((ts, ms)) if *ts == timestamp => *ms = (*ms).max(latency_ms),
+            _ => {
+                event.samples.push_back((timestamp, latency_ms));
+                if event.samples.len() > LATENCY_SAMPLES_LEN {
+                    event.samples.pop_front();
+                }
+            }
+        }
+    }
+
+    /// `(timestamp, latency_ms)` samples of the event, oldest first.
+    pub fn history(&self, event: &str) -> Vec<(u64, u64)> {
+        self.events
+            .get(event)
+            .map_or(vec![], |event| event.samples.iter().copied().collect())
+    }
+
+    /// `(event, timestamp, latest_ms, max_ms)` of every event.
+    pub fn latest(&self) -> Vec<(String, u64, u64, u64)> {
+        self.events
+            .iter()
+            .filter_map(|(name, event)| {
+                let (ts, ms) = event.samples.back()?;
+                Some((name.clone(), *ts, *ms, event.max_ms))
+            })
+            .collect()
+    }
+
+    /// Clear the samples of `events`, all of them if empty. Returns the
+    /// number of events cleared.
+    pub fn reset(&mut self, events: &[String]) -> usize {
+        if events.is_empty() {
+            let count = self.events.len();
+            self.events.clear();
+            return count;
+        }
+        events
+            .iter()
+            .filter_map(|event| self.events.remove(event))
+            .count()
+    }
+}
+
+/// Record a sample of `event` taking `duration` now.
+pub fn record_latency(event: &str, duration: Duration) {
+    let timestamp = (now_timestamp_in_millis() / 1000) as u64;
+    LATENCY_MONITOR
+        .lock()
+        .unwrap()
+        .record(event, timestamp, duration.as_millis() as u64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LatencyMonitor, LATENCY_SAMPLES_LEN};
+
+    #[test]
+    fn test_history_latest_and_reset() {
+        let mut monitor = LatencyMonitor::default();
+        monitor.record("command", 100, 5);
+        monitor.record("command", 101, 20);
+        monitor.record("command", 102, 7);
+        // merged with the sample of the same second
+        monitor.record("command", 102, 3);
+        monitor.record("gc-scan", 101, 1);
+
+        assert_eq!(
+            monitor.history("command"),
+            vec![(100, 5), (101, 20), (102, 7)]
+        );
+        assert_eq!(
+            monitor.latest(),
+            vec![
+                ("command".to_owned(), 102, 7, 20),
+                ("gc-scan".to_owned(), 101, 1, 1)
+            ]
+        );
+
+        assert_eq!(monitor.reset(&["command".to_owned()]), 1);
+        assert!(monitor.history("command").is_empty());
+        assert_eq!(monitor.reset(&[]), 1);
+        assert!(monitor.latest().is_empty());
+    }
+
+    #[test]
+    fn test_samples_are_bounded() {
+        let mut monitor = LatencyMonitor::default();
+        for ts in 0..200 {
+            monitor.record("command", ts, 1);
+        }
+        let history = monitor.history("command");
+        assert_eq!(history.len(), LATENCY_SAMPLES_LEN);
+        assert_eq!(history[0], (200 - LATENCY_SAMPLES_LEN as u64, 1));
+    }
+}
@@ -8,13 +8,22 @@ pub mod config;
 pub mod cmd;
 
 pub use cmd::Command;
+
+mod builder;
+
+pub use builder::{CommandBuilder, ZaddBuilder};
+pub use cmd::scan_keys;
+
+mod pool;
+
 use lazy_static::lazy_static;
+pub use pool::{Conn, ConnectionPool, PooledConn};
 use rand::{rngs::SmallRng, Rng, SeedableRng};
 use std::sync::atomic::{AtomicU16, Ordering};
 
 mod connection;
 
-pub use connection::Connection;
+pub use connection::{Connection, Stream};
 
 pub mod frame;
 
@@ -31,13 +40,20 @@ use parse::{Parse, ParseError};
 
 pub mod server;
 
+pub mod acl;
+pub mod audit;
+pub mod cache;
+pub mod eviction;
 pub mod gc;
 pub mod hash_ring;
+pub mod latency;
 pub mod metrics;
 pub mod p2p;
 pub mod raft;
 pub mod rocks;
 mod shutdown;
+pub mod slowlog;
+pub mod tls;
 pub mod utils;
 
 use crate::p2p::client::P2PClient;
@@ -112,6 +128,7 @@ lazy_static! {
 pub static mut P2P_CLIENT: Option<P2PClient> = None;
 pub static mut RING_NODES: Option<HashRing<NodeInfo>> = None;
 pub static mut RAFT_CLIENT: Option<RaftClient> = None;
+pub(crate) static mut PUBSUB_DB: Option<Db> = None;
 
 pub fn fetch_idx_and_add() -> u16 {
     // fetch_add wraps around on overflow, see https://github.com/rust-lang/rust/issues/34618
@@ -1,10 +1,21 @@
 use crate::config::LOGGER;
 use crate::metrics::{CURRENT_CONNECTION_COUNTER, INSTANCE_ID_GAUGER, REQUEST_COUNTER};
+use crate::raft::get_raft_app;
+use crate::rocks::get_client;
 use actix_web::{get, App, HttpResponse, HttpServer, Responder};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use prometheus::{Encoder, TextEncoder};
+use serde_json::json;
 use slog::info;
 
+// set once rocksdb is opened and the listener is bound
+static SERVER_READY: AtomicBool = AtomicBool::new(false);
+
+pub fn set_server_ready(ready: bool) {
+    SERVER_READY.store(ready, Ordering::Relaxed);
+}
+
 pub struct PrometheusServer {
     listen_addr: String,
 }
@@ -22,7 +33,8 @@ impl PrometheusServer {
         info!(LOGGER, "Prometheus Server Listen on: {}", &self.listen_addr);
 
         // Start the actix-web server.
-        let server = HttpServer::new(move || App::new().service(metric));
+        let server =
+            HttpServer::new(move || App::new().service(metric).service(health).service(ready));
 
         let x = server.bind(&self.listen_addr)?;
         x.run().await
@@ -37,3 +49,57 @@ async fn metric() -> impl Responder {
     encoder.encode(&metric_families, &mut buffer).unwrap();
     HttpResponse::Ok().body(buffer)
 }
+
+#[get("/health")]
+async fn health() -> impl Responder {
+    // failing to read the property is treated as not writable
+    let write_stalled = get_client().is_write_stalled().unwrap_or(true);
+    let leader = match get_raft_app() {
+        Some(app) => Some(app.raft.is_leader().await.is_ok()),
+        None => None,
+    };
+    health_response(write_stalled, leader)
+}
+
+#[get("/ready")]
+async fn ready() -> impl Responder {
+    if SERVER_READY.load(Ordering::Relaxed) {
+        HttpResponse::Ok().finish()
+    } else {
+        HttpResponse::ServiceUnavailable().finish()
+    }
+}
+
+// `leader` is None when raft is not initialized
+fn health_response(write_stalled: bool, leader: Option<bool>) -> HttpResponse {
+    let degraded = write_stalled || leader.is_none();
+    let body = json!({
+        "status": if degraded { "degraded" } else { "ok" },
+        "leader": leader.unwrap_or(false),
+    });
+    if degraded {
+        HttpResponse::ServiceUnavailable().json(body)
+    } else {
+        HttpResponse::Ok().json(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::health_response;
+    use actix_web::http::StatusCode;
+
+    #[test]
+    fn test_health_response() {
+        assert_eq!(health_response(false, Some(true)).status(), StatusCode::OK);
+        assert_eq!(health_response(false, Some(false)).status(), StatusCode::OK);
+        assert_eq!(
+            health_response(true, Some(true)).status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            health_response(false, None).status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+}
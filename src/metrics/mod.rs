@@ -1,10 +1,11 @@
 mod http;
 
 use prometheus::{
-    exponential_buckets, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
+    exponential_buckets, Gauge, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec,
 };
 
-pub use self::http::PrometheusServer;
+pub use self::http::{set_server_ready, PrometheusServer};
 use lazy_static::lazy_static;
 
 lazy_static! {
@@ -22,6 +23,11 @@ lazy_static! {
         "Current connection counter"
     )
     .unwrap();
+    pub static ref CONNECTIONS_NEAR_LIMIT: IntCounter = register_int_counter!(
+        "redis_connections_near_limit_total",
+        "Times the connections reached 90% of max_connection"
+    )
+    .unwrap();
     pub static ref REQUEST_CMD_COUNTER: IntCounterVec = register_int_counter_vec!(
         "redis_command_requests_total",
         "Request command counter",
@@ -41,6 +47,13 @@ lazy_static! {
         exponential_buckets(0.0005, 2.0, 20).unwrap()
     )
     .unwrap();
+    pub static ref REQUEST_CMD_TYPE_HANDLE_TIME: HistogramVec = register_histogram_vec!(
+        "redis_command_type_handle_time_duration_seconds",
+        "Bucketed histogram of command handle duration by data type",
+        &["cmd", "data_type"],
+        exponential_buckets(0.0005, 2.0, 20).unwrap()
+    )
+    .unwrap();
     pub static ref REMOVED_EXPIRED_KEY_COUNTER: IntCounterVec = register_int_counter_vec!(
         "redis_removed_expired_keys_count_total",
         "The number of expired keys that have been removed",
@@ -67,6 +80,12 @@ lazy_static! {
     )
     .unwrap();
     pub static ref TXN_RETRY_COUNTER: IntCounter = register_int_counter!("rocks_redis_txn_retry_count_total", "Transactions retry count").unwrap();
+    pub static ref TXN_RETRY_CMD_COUNTER: IntCounterVec = register_int_counter_vec!(
+        "rocks_redis_txn_retry_command_total",
+        "Transactions retry count by command",
+        &["cmd"]
+    )
+    .unwrap();
 
     // Raft
     pub static ref RAFT_REMOTE_COUNTER: IntCounter = register_int_counter!("redis_raft_remote_count_total", "Raft remote ops count").unwrap();
@@ -98,6 +117,21 @@ lazy_static! {
     )
     .unwrap();
 
+    // Cache
+    pub static ref STRING_CACHE_HIT_RATIO: Gauge = register_gauge!(
+        "redis_string_cache_hit_ratio",
+        "String cache hit ratio"
+    )
+    .unwrap();
+
+    // Keyspace
+    pub static ref KEYSPACE_KEY_COUNT: IntGaugeVec = register_int_gauge_vec!(
+        "redis_keyspace_keys",
+        "Keys stored by data type",
+        &["type"]
+    )
+    .unwrap();
+
     // GC
     pub static ref GC_TASK_QUEUE_COUNTER: IntGaugeVec = register_int_gauge_vec!(
         "redis_gc_task_queue_count",
@@ -105,4 +139,45 @@ lazy_static! {
         &["worker"]
     )
     .unwrap();
+    pub static ref GC_SCAN_RATE: IntCounter = register_int_counter!(
+        "redis_gc_scan_keys_total",
+        "GC version keys scanned, use rate() for keys per second"
+    )
+    .unwrap();
+
+    // Eviction
+    pub static ref EVICTED_KEY_COUNTER: IntCounter = register_int_counter!(
+        "redis_evicted_keys_total",
+        "Keys evicted because of maxmemory"
+    )
+    .unwrap();
+}
+
+/// Reset the counters for `CONFIG RESETSTAT`, gauges and histograms keep
+/// their values.
+pub fn reset_counters() {
+    for counter in [
+        &*TOTAL_CONNECTION_PROCESSED,
+        &*CONNECTIONS_NEAR_LIMIT,
+        &*REQUEST_COUNTER,
+        &*TXN_COUNTER,
+        &*TXN_RETRY_COUNTER,
+        &*RAFT_REMOTE_COUNTER,
+        &*GC_SCAN_RATE,
+        &*EVICTED_KEY_COUNTER,
+    ] {
+        counter.reset();
+    }
+    for counter in [
+        &*REQUEST_CMD_COUNTER,
+        &*REQUEST_CMD_FINISH_COUNTER,
+        &*REMOVED_EXPIRED_KEY_COUNTER,
+        &*REQUEST_CMD_ERROR_COUNTER,
+        &*REQUEST_CMD_REMOTE_COUNTER,
+        &*TXN_RETRY_CMD_COUNTER,
+        &*RAFT_CMD_COUNTER,
+        &*ROCKS_ERR_COUNTER,
+    ] {
+        counter.reset();
+    }
 }
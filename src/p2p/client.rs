@@ -1,12 +1,19 @@
+use crate::config::{
+    config_tls, p2p_connection_timeout_ms_or_default, p2p_pool_size_per_node_or_default,
+};
 use crate::p2p::message::Message;
 use crate::p2p::message::Message::PingMessage;
+use crate::p2p::pool::P2pConnectionPool;
+use crate::tls::{tls_connector, tls_server_name};
 use crate::utils::sleep;
+use crate::{MapucheError, Stream};
 use local_ip_address::local_ip;
 use std::collections::HashMap;
 
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt, ReadHalf, WriteHalf};
-use tokio::net::{TcpSocket, TcpStream};
+use tokio::net::TcpSocket;
 use tokio::sync::{broadcast, mpsc, Mutex};
 use tokio::{io, select, spawn};
 
@@ -15,12 +22,15 @@ type ClientConMap =
 
 pub struct P2PClient {
     client_con_map: ClientConMap,
+    // connections for the forwarded commands, by server url
+    pools: Arc<Mutex<HashMap<String, P2pConnectionPool>>>,
 }
 
 impl P2PClient {
     pub fn new() -> Self {
         Self {
             client_con_map: Arc::new(Default::default()),
+            pools: Arc::new(Default::default()),
         }
     }
 
@@ -37,9 +47,27 @@ impl P2PClient {
             .lock()
             .await
             .insert(server_url.to_string(), (signal_channel_tx, response_tx));
+        let pool = P2pConnectionPool::new(
+            server_url,
+            p2p_pool_size_per_node_or_default(),
+            Duration::from_millis(p2p_connection_timeout_ms_or_default()),
+        );
+        self.pools.lock().await.insert(server_url.to_string(), pool);
         Ok(())
     }
 
+    /// Send the message on a pooled connection and wait for the response.
+    pub async fn request(&self, server_url: &str, message: Message) -> crate::Result<Message> {
+        let pool = self
+            .pools
+            .lock()
+            .await
+            .get(server_url)
+            .cloned()
+            .ok_or(MapucheError::String("p2p client not connected to server"))?;
+        pool.call(message).await
+    }
+
     pub async fn call(&self, server_url: &str, message: Message) -> crate::Result<()> {
         if let Some(sender) = self.client_con_map.lock().await.get(server_url) {
             sender.0.send(message).await?
@@ -95,9 +123,7 @@ impl ClientCon {
     }
 
     async fn connect(&mut self) -> crate::Result<()> {
-        let socket = TcpSocket::new_v4()?;
-        let addr = self.server_url.parse()?;
-        let stream = socket.connect(addr).await?;
+        let stream = connect_stream(&self.server_url).await?;
         let (r, w) = io::split(stream);
         println!("Client connected to {}", self.server_url);
 
@@ -128,11 +154,7 @@ impl ClientCon {
         Ok(())
     }
 
-    fn start_socket_reader(
-        &self,
-        mut r: ReadHalf<TcpStream>,
-        socket_close_tx: broadcast::Sender<()>,
-    ) {
+    fn start_socket_reader(&self, mut r: ReadHalf<Stream>, socket_close_tx: broadcast::Sender<()>) {
         let response_tx = self.response_tx.clone();
         // Socket read handler thread, to handle message sent by server
         spawn(async move {
@@ -161,7 +183,7 @@ impl ClientCon {
 
     fn start_socket_writer(
         &self,
-        mut w: WriteHalf<TcpStream>,
+        mut w: WriteHalf<Stream>,
         mut channel_rx: mpsc::Receiver<Message>,
         mut socket_close_write_rx: broadcast::Receiver<()>,
     ) {
@@ -200,6 +222,21 @@ impl ClientCon {
     }
 }
 
+pub(crate) async fn connect_stream(server_url: &str) -> crate::Result<Stream> {
+    let socket = TcpSocket::new_v4()?;
+    let addr = server_url.parse()?;
+    let stream = socket.connect(addr).await?;
+    let stream = match config_tls() {
+        Some(config) => {
+            let connector = tls_connector(&config)?;
+            let tls_stream = connector.connect(tls_server_name(&config)?, stream).await?;
+            Stream::Tls(Box::new(tls_stream.into()))
+        }
+        None => Stream::Tcp(stream),
+    };
+    Ok(stream)
+}
+
 async fn ping(channel_tx: Arc<mpsc::Sender<Message>>) {
     loop {
         sleep(5000).await;
@@ -11,6 +11,9 @@ pub enum Message {
         cmd: Command,
         ts: i64,
         req_id: String,
+        // correlates the logs of the command on every node it passes through
+        #[serde(default)]
+        trace_id: Option<String>,
     },
     CmdRespMessage {
         address: String,
@@ -1,9 +1,13 @@
 pub mod client;
 pub mod message;
+pub mod pool;
 pub mod server;
 
 #[cfg(test)]
 mod tests {
+    use super::message::Message;
+    use crate::cmd::Ping;
+    use crate::Command;
     use local_ip_address::local_ip;
 
     #[test]
@@ -11,4 +15,29 @@ mod tests {
         let ip = local_ip().unwrap().to_string();
         println!("{}", ip);
     }
+
+    #[test]
+    fn test_cmd_req_message_trace_id() {
+        let message = Message::CmdReqMessage {
+            address: "127.0.0.1:6380".to_owned(),
+            cmd: Command::Ping(Ping::new(None)),
+            ts: 0,
+            req_id: "req".to_owned(),
+            trace_id: Some("trace".to_owned()),
+        };
+        let json = String::from(&message);
+        match Message::from(json.as_str()) {
+            Message::CmdReqMessage { trace_id, .. } => {
+                assert_eq!(trace_id, Some("trace".to_owned()))
+            }
+            _ => panic!("not a cmd req message"),
+        }
+
+        // messages from nodes which don't send a trace id yet
+        let legacy = json.replace(",\"trace_id\":\"trace\"", "");
+        match Message::from(legacy.as_str()) {
+            Message::CmdReqMessage { trace_id, .. } => assert_eq!(trace_id, None),
+            _ => panic!("not a cmd req message"),
+        }
+    }
 }
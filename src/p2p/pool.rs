@@ -0,0 +1,215 @@
+use crate::p2p::client::connect_stream;
+use crate::p2p::message::Message;
+use crate::{MapucheError, Stream};
+use bytes::BytesMut;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::{Mutex, Semaphore};
+use tokio::time;
+
+/// Connections to one remote node for the forwarded commands.
+///
+/// A connection carries one request at a time, so the response read from it
+/// always belongs to the request just sent. At most `pool_size` connections
+/// are open, callers wait up to `timeout` for one of them to be returned.
+/// A pooled connection found closed by the remote node before any byte of
+/// the response is discarded, and the request is sent again on a new one.
+#[derive(Clone)]
+pub struct P2pConnectionPool {
+    server_url: String,
+    timeout: Duration,
+    idle: Arc<Mutex<VecDeque<P2pConnection>>>,
+    permits: Arc<Semaphore>,
+}
+
+impl P2pConnectionPool {
+    pub fn new(server_url: &str, pool_size: usize, timeout: Duration) -> Self {
+        P2pConnectionPool {
+            server_url: server_url.to_owned(),
+            timeout,
+            idle: Arc::new(Mutex::new(VecDeque::with_capacity(pool_size))),
+            permits: Arc::new(Semaphore::new(pool_size.max(1))),
+        }
+    }
+
+    pub async fn call(&self, message: Message) -> crate::Result<Message> {
+        let _permit = time::timeout(self.timeout, self.permits.acquire())
+            .await
+            .map_err(|_| MapucheError::String("p2p connection pool timeout"))??;
+        let request: Vec<u8> = message.into();
+
+        let pooled = self.idle.lock().await.pop_front();
+        let reused = match pooled {
+            Some(mut con) => match time::timeout(self.timeout, con.exchange(&request)).await?? {
+                Some(response) => Some((con, response)),
+                // closed while idle, replaced below
+                None => None,
+            },
+            None => None,
+        };
+        let (con, response) = match reused {
+            Some(reused) => reused,
+            None => {
+                let stream =
+                    time::timeout(self.timeout, connect_stream(&self.server_url)).await??;
+                let mut con = P2pConnection::new(stream);
+                match time::timeout(self.timeout, con.exchange(&request)).await?? {
+                    Some(response) => (con, response),
+                    None => return Err(MapucheError::String("p2p connection closed").into()),
+                }
+            }
+        };
+        self.idle.lock().await.push_back(con);
+        Ok(response)
+    }
+}
+
+struct P2pConnection {
+    stream: Stream,
+    buf: BytesMut,
+}
+
+impl P2pConnection {
+    fn new(stream: Stream) -> Self {
+        P2pConnection {
+            stream,
+            buf: BytesMut::with_capacity(1024),
+        }
+    }
+
+    // send the request and read its response, `None` if the connection was
+    // closed before any byte of the response
+    async fn exchange(&mut self, request: &[u8]) -> crate::Result<Option<Message>> {
+        if self.stream.write_all(request).await.is_err() {
+            return Ok(None);
+        }
+        loop {
+            // messages are not framed, read until the json value is complete
+            if !self.buf.is_empty() {
+                match serde_json::from_slice::<Message>(&self.buf) {
+                    Ok(message) => {
+                        self.buf.clear();
+                        return Ok(Some(message));
+                    }
+                    Err(e) if e.is_eof() => {}
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            match self.stream.read_buf(&mut self.buf).await {
+                Ok(0) | Err(_) if self.buf.is_empty() => return Ok(None),
+                Ok(0) => return Err(MapucheError::String("p2p connection closed").into()),
+                Ok(_) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::P2pConnectionPool;
+    use crate::cmd::Ping;
+    use crate::p2p::message::Message;
+    use crate::{Command, Frame};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn request(req_id: &str) -> Message {
+        Message::CmdReqMessage {
+            address: "127.0.0.1:6380".to_owned(),
+            cmd: Command::Ping(Ping::new(None)),
+            ts: 0,
+            req_id: req_id.to_owned(),
+            trace_id: None,
+        }
+    }
+
+    // answer every request after `delay`, a connection is closed after its
+    // first response unless `keep_open`
+    async fn serve(delay: Duration, keep_open: bool) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = listener.local_addr().unwrap().to_string();
+        let accepted = Arc::new(AtomicUsize::new(0));
+        let counter = accepted.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = vec![0; 1024];
+                    loop {
+                        let n = socket.read(&mut buf).await.unwrap_or(0);
+                        if n == 0 {
+                            return;
+                        }
+                        let req_id = match Message::from(&buf[..n]) {
+                            Message::CmdReqMessage { req_id, .. } => req_id,
+                            _ => panic!("not a cmd req message"),
+                        };
+                        tokio::time::sleep(delay).await;
+                        let response: Vec<u8> = Message::CmdRespMessage {
+                            address: "127.0.0.1:6380".to_owned(),
+                            frame: Frame::Simple("PONG".to_owned()),
+                            ts: 0,
+                            req_id,
+                        }
+                        .into();
+                        socket.write_all(&response).await.unwrap();
+                        if !keep_open {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+        (url, accepted)
+    }
+
+    fn req_id(message: Message) -> String {
+        match message {
+            Message::CmdRespMessage { req_id, .. } => req_id,
+            _ => panic!("not a cmd resp message"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pool_size_serializes_calls() {
+        let (url, accepted) = serve(Duration::from_millis(200), true).await;
+        let pool = P2pConnectionPool::new(&url, 2, Duration::from_secs(2));
+
+        let (a, b, c) = tokio::join!(
+            pool.call(request("a")),
+            pool.call(request("b")),
+            pool.call(request("c"))
+        );
+        assert_eq!(req_id(a.unwrap()), "a");
+        assert_eq!(req_id(b.unwrap()), "b");
+        assert_eq!(req_id(c.unwrap()), "c");
+        // the third call waited for a pooled connection
+        assert_eq!(accepted.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_pool_timeout() {
+        let (url, _) = serve(Duration::from_millis(500), true).await;
+        let pool = P2pConnectionPool::new(&url, 1, Duration::from_millis(100));
+        assert!(pool.call(request("a")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dead_connection_replaced() {
+        let (url, accepted) = serve(Duration::ZERO, false).await;
+        let pool = P2pConnectionPool::new(&url, 1, Duration::from_secs(2));
+
+        assert_eq!(req_id(pool.call(request("a")).await.unwrap()), "a");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        // the pooled connection was closed by the remote node
+        assert_eq!(req_id(pool.call(request("b")).await.unwrap()), "b");
+        assert_eq!(accepted.load(Ordering::SeqCst), 2);
+    }
+}
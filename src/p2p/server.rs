@@ -1,16 +1,20 @@
-use crate::config::config_ring_port_or_default;
+use crate::config::{config_ring_port_or_default, config_tls, LOGGER};
 use crate::p2p::message::Message;
 use local_ip_address::linux::local_ip;
 use std::collections::HashMap;
 
 use crate::p2p::server::ServerConSignal::{ConnectionClose, ConnectionError};
+use crate::tls::tls_acceptor;
 use crate::utils::now_timestamp_in_millis;
+use crate::Stream;
+use slog::debug;
 use std::sync::Arc;
 use tokio::io::{AsyncReadExt, AsyncWriteExt, ReadHalf, WriteHalf};
-use tokio::net::{TcpListener, TcpStream};
+use tokio::net::TcpListener;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::sync::{mpsc, Mutex};
 use tokio::{io, spawn};
+use tokio_rustls::TlsAcceptor;
 
 type ConnectionReceiver = Arc<Mutex<Receiver<Message>>>;
 type ServerConMap = Arc<Mutex<HashMap<String, ServerCon>>>;
@@ -37,12 +41,18 @@ impl P2PServer {
         let local_ip = local_ip()?.to_string();
         let server_url = format!("{}:{}", local_ip, config_ring_port_or_default());
         let listener = TcpListener::bind(server_url).await?;
-        self.start_con_dispatcher(listener, tx);
+        let acceptor = config_tls().map(|c| tls_acceptor(&c)).transpose()?;
+        self.start_con_dispatcher(listener, acceptor, tx);
         self.start_channel_handler(rx);
         Ok(())
     }
 
-    fn start_con_dispatcher(&self, listener: TcpListener, tx: Sender<ServerConSignal>) {
+    fn start_con_dispatcher(
+        &self,
+        listener: TcpListener,
+        acceptor: Option<TlsAcceptor>,
+        tx: Sender<ServerConSignal>,
+    ) {
         let con_map = self.server_con_map.clone();
         spawn(async move {
             loop {
@@ -50,9 +60,20 @@ impl P2PServer {
                 let peer_addr = format!("{}", addr);
                 println!("Server gets new connection, {:?}", peer_addr);
 
+                let stream = match &acceptor {
+                    Some(acceptor) => match acceptor.accept(socket).await {
+                        Ok(tls_stream) => Stream::Tls(Box::new(tls_stream.into())),
+                        Err(e) => {
+                            println!("Tls handshake failed, {:?}, {:?}", peer_addr, e);
+                            continue;
+                        }
+                    },
+                    None => Stream::Tcp(socket),
+                };
+
                 let connection = ServerCon::new();
                 connection
-                    .start(tx.clone(), socket)
+                    .start(tx.clone(), stream, &peer_addr)
                     .await
                     .unwrap_or_default();
                 con_map.lock().await.insert(peer_addr, connection);
@@ -102,16 +123,16 @@ impl ServerCon {
     pub async fn start(
         &self,
         server_channel_tx: Sender<ServerConSignal>,
-        socket: TcpStream,
+        socket: Stream,
+        peer_addr: &str,
     ) -> crate::Result<()> {
-        let peer_addr = format!("{}", &socket.peer_addr()?);
         let (r, w) = io::split(socket);
         self.start_socket_writer(w);
-        self.start_socket_reader(r, server_channel_tx, &peer_addr);
+        self.start_socket_reader(r, server_channel_tx, peer_addr);
         Ok(())
     }
 
-    fn start_socket_writer(&self, mut w: WriteHalf<TcpStream>) {
+    fn start_socket_writer(&self, mut w: WriteHalf<Stream>) {
         let con_rx = self.con_rx.clone();
         spawn(async move {
             while let Some(message) = con_rx.lock().await.recv().await {
@@ -123,7 +144,7 @@ impl ServerCon {
 
     fn start_socket_reader(
         &self,
-        mut r: ReadHalf<TcpStream>,
+        mut r: ReadHalf<Stream>,
         server_channel_tx: Sender<ServerConSignal>,
         peer_addr: &str,
     ) {
@@ -148,8 +169,10 @@ impl ServerCon {
                             cmd,
                             ts: _,
                             req_id,
+                            trace_id,
                         } = message
                         {
+                            debug!(LOGGER, "cmd req from {}, {:?}", address, cmd; "trace_id" => &trace_id);
                             spawn(async move {
                                 let res = cmd.execute_for_remote().await;
                                 if let Ok(frame) = res {
@@ -0,0 +1,87 @@
+use crate::cmd::{execute_replicated, Command};
+use crate::frame::Frame;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// A logical connection to the embedded store, commands executed on it go
+/// through the same path as the ones sent by clients of the server.
+#[derive(Debug)]
+pub struct Conn {
+    id: usize,
+}
+
+impl Conn {
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub async fn execute(&mut self, cmd: Command) -> crate::Result<Frame> {
+        execute_replicated(cmd).await
+    }
+}
+
+/// A fixed number of `Conn`s shared by the tasks of an application embedding
+/// mapuche, `acquire` waits until one of them is free.
+#[derive(Debug, Clone)]
+pub struct ConnectionPool {
+    semaphore: Arc<Semaphore>,
+    conns: Arc<Mutex<Vec<Conn>>>,
+}
+
+impl ConnectionPool {
+    pub fn new(size: usize) -> ConnectionPool {
+        ConnectionPool {
+            semaphore: Arc::new(Semaphore::new(size)),
+            conns: Arc::new(Mutex::new((0..size).map(|id| Conn { id }).collect())),
+        }
+    }
+
+    pub async fn acquire(&self) -> PooledConn {
+        // the semaphore is never closed
+        let permit = self.semaphore.clone().acquire_owned().await.unwrap();
+        // holding a permit guarantees a free conn
+        let conn = self.conns.lock().unwrap().pop().unwrap();
+        PooledConn {
+            conn: Some(conn),
+            conns: self.conns.clone(),
+            _permit: permit,
+        }
+    }
+
+    /// Number of conns which are not acquired.
+    pub fn available(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+}
+
+/// A `Conn` taken from a `ConnectionPool`, given back on drop.
+#[derive(Debug)]
+pub struct PooledConn {
+    conn: Option<Conn>,
+    conns: Arc<Mutex<Vec<Conn>>>,
+    // dropped after the conn is given back
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Deref for PooledConn {
+    type Target = Conn;
+
+    fn deref(&self) -> &Conn {
+        self.conn.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledConn {
+    fn deref_mut(&mut self) -> &mut Conn {
+        self.conn.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledConn {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.conns.lock().unwrap().push(conn);
+        }
+    }
+}
@@ -1,11 +1,13 @@
 use actix_web::middleware::{self, Logger};
 use actix_web::{App, HttpServer};
-use openraft::{declare_raft_types, BasicNode, Config, Raft};
+use openraft::{declare_raft_types, BasicNode, Config, Raft, SnapshotPolicy};
+use slog::error;
 use std::path::Path;
 use std::thread;
 use tokio::runtime::Runtime;
 use tonic::transport::Server;
 
+use crate::config::{raft_max_log_entries_or_default, LOGGER};
 use crate::Command;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -48,8 +50,16 @@ pub static mut RAFT_APP: Option<Arc<MapucheRaftApp>> = None;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RaftRequest {
-    Set { key: String, value: String },
-    CmdLog { id: String, cmd: Command },
+    Set {
+        key: String,
+        value: String,
+    },
+    CmdLog {
+        id: String,
+        cmd: Command,
+        #[serde(default)]
+        trace_id: Option<String>,
+    },
 }
 
 impl From<&str> for RaftRequest {
@@ -76,6 +86,11 @@ where
         heartbeat_interval: 500,
         election_timeout_min: 1500,
         election_timeout_max: 3000,
+        // forced once the log grows this long, `raft_snapshot_interval_commands`
+        // usually triggers a snapshot before
+        snapshot_policy: SnapshotPolicy::LogsSinceLast(raft_max_log_entries_or_default()),
+        // logs covered by a snapshot are purged
+        max_in_snapshot_log_to_keep: 0,
         ..Default::default()
     };
 
@@ -104,6 +119,7 @@ where
     unsafe {
         RAFT_APP.replace(Arc::new(app.clone()));
     }
+    tokio::spawn(trigger_snapshots(app.clone()));
 
     let addr = addr.parse().unwrap();
     let rpc_service = RaftRpcService::default();
@@ -135,3 +151,15 @@ where
 
     x.run().await
 }
+
+// build a snapshot whenever the store applied `raft_snapshot_interval_commands`
+// commands, openraft then purges the logs up to the snapshot
+async fn trigger_snapshots(app: MapucheRaftApp) {
+    loop {
+        app.store.snapshot_wanted.notified().await;
+        if let Err(e) = app.raft.trigger_snapshot().await {
+            error!(LOGGER, "failed to trigger raft snapshot, {:?}", e);
+            return;
+        }
+    }
+}
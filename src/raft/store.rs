@@ -18,10 +18,15 @@ use std::path::Path;
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use rocksdb::{BoundColumnFamily, ColumnFamilyDescriptor, Direction, Options, DB};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Instant;
+use tokio::sync::{Notify, RwLock};
 
+use crate::config::{raft_snapshot_interval_commands_or_default, LOGGER};
+use crate::latency::record_latency;
 use crate::Frame;
+use slog::debug;
 
 type StorageResult<T> = Result<T, StorageError<MapucheNodeId>>;
 
@@ -208,6 +213,10 @@ pub struct RaftStore {
     db: Arc<DB>,
     /// The Raft state machine.
     pub state_machine: RwLock<RaftStateMachine>,
+    /// Write commands applied since the last snapshot was built.
+    pub commands_since_snapshot: AtomicU64,
+    /// Notified once `raft_snapshot_interval_commands` commands were applied.
+    pub snapshot_wanted: Notify,
 }
 
 impl RaftStore {
@@ -226,7 +235,28 @@ impl RaftStore {
 
         let db = Arc::new(db);
         let state_machine = RwLock::new(RaftStateMachine::new(db.clone()));
-        Arc::new(RaftStore { db, state_machine })
+        Arc::new(RaftStore {
+            db,
+            state_machine,
+            commands_since_snapshot: AtomicU64::new(0),
+            snapshot_wanted: Notify::new(),
+        })
+    }
+
+    /// Delete the application data of the state machine. The logs and vote
+    /// are owned by the running raft instance and kept.
+    pub async fn clear_state_machine_data(&self) -> Result<(), rocksdb::Error> {
+        let state_machine = self.state_machine.write().await;
+        let cf = state_machine.db.cf_handle("data").expect("cf_handle");
+        let mut batch = rocksdb::WriteBatch::default();
+        for res in state_machine
+            .db
+            .iterator_cf(&cf, rocksdb::IteratorMode::Start)
+        {
+            let (key, _) = res?;
+            batch.delete_cf(&cf, key);
+        }
+        state_machine.db.write(batch)
     }
 }
 
@@ -450,18 +480,21 @@ impl RaftSnapshotBuilder<TypeConfig, Cursor<Vec<u8>>> for Arc<RaftStore> {
         &mut self,
     ) -> Result<Snapshot<MapucheNodeId, BasicNode, Cursor<Vec<u8>>>, StorageError<MapucheNodeId>>
     {
+        let start_at = Instant::now();
         let data;
         let last_applied_log;
         let last_membership;
 
         {
             // Serialize the data of the state machine.
-            let state_machine =
-                SerializableRaftStateMachine::from(&*self.state_machine.read().await);
+            let sm = self.state_machine.read().await;
+            let state_machine = SerializableRaftStateMachine::from(&*sm);
             data = serde_json::to_vec(&state_machine).map_err(sm_r_err)?;
 
             last_applied_log = state_machine.last_applied_log;
             last_membership = state_machine.last_membership;
+            // no command is applied while the state machine is locked
+            self.commands_since_snapshot.store(0, Ordering::Relaxed);
         }
 
         // TODO: we probably want this to be atomic.
@@ -486,6 +519,7 @@ impl RaftSnapshotBuilder<TypeConfig, Cursor<Vec<u8>>> for Arc<RaftStore> {
         };
 
         self.set_current_snapshot(snapshot)?;
+        record_latency("rdb-save", start_at.elapsed());
 
         Ok(Snapshot {
             meta,
@@ -585,6 +619,7 @@ impl RaftStorage<TypeConfig> for Arc<RaftStore> {
         entries: &[&Entry<TypeConfig>],
     ) -> Result<Vec<RaftResponse>, StorageError<MapucheNodeId>> {
         let mut res = Vec::with_capacity(entries.len());
+        let mut commands = 0;
 
         let sm = self.state_machine.write().await;
 
@@ -598,7 +633,8 @@ impl RaftStorage<TypeConfig> for Arc<RaftStore> {
                         sm.insert(key.clone(), value.clone())?;
                         res.push(RaftResponse::OptString(Some(value.clone())))
                     }
-                    RaftRequest::CmdLog { id, cmd } => {
+                    RaftRequest::CmdLog { id, cmd, trace_id } => {
+                        debug!(LOGGER, "apply raft cmd log, {}", id; "trace_id" => trace_id);
                         let frame = cmd.clone().execute_for_remote().await.map_err(|e| {
                             StorageError::IO {
                                 source: StorageIOError::new(
@@ -610,6 +646,7 @@ impl RaftStorage<TypeConfig> for Arc<RaftStore> {
                         })?;
                         let cmd_str: String = cmd.into();
                         sm.insert(id.clone(), cmd_str.clone())?;
+                        commands += 1;
                         res.push(RaftResponse::Frame(frame))
                     }
                 },
@@ -622,6 +659,14 @@ impl RaftStorage<TypeConfig> for Arc<RaftStore> {
         }
 
         self.flush(ErrorSubject::StateMachine, ErrorVerb::Write)?;
+
+        let applied = self
+            .commands_since_snapshot
+            .fetch_add(commands, Ordering::Relaxed)
+            + commands;
+        if commands > 0 && applied >= raft_snapshot_interval_commands_or_default() {
+            self.snapshot_wanted.notify_one();
+        }
         Ok(res)
     }
 
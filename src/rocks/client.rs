@@ -1,8 +1,11 @@
 use crate::config::async_deletion_enabled_or_default;
-use crate::metrics::{ROCKS_ERR_COUNTER, TXN_COUNTER, TXN_DURATION};
+use crate::metrics::{KEYSPACE_KEY_COUNT, ROCKS_ERR_COUNTER, TXN_COUNTER, TXN_DURATION};
+use crate::rocks::encoding::{DataType, KeyDecoder};
 use rocksdb::{
-    ColumnFamilyRef, TransactionDB, TransactionOptions, WriteBatchWithTransaction, WriteOptions,
+    ColumnFamilyRef, IteratorMode, TransactionDB, TransactionOptions, WriteBatchWithTransaction,
+    WriteOptions,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use tokio::time::Instant;
@@ -13,7 +16,10 @@ use crate::rocks::kv::key::Key;
 use crate::rocks::kv::kvpair::KvPair;
 use crate::rocks::kv::value::Value;
 use crate::rocks::transaction::RocksTransaction;
-use crate::rocks::{Result as RocksResult, KEY_ENCODER};
+use crate::rocks::{
+    apply_key_count_deltas, key_count_deltas, Result as RocksResult, CF_NAMES, CF_NAME_META,
+    KEY_ENCODER,
+};
 use crate::server::duration_to_sec;
 
 pub struct RocksClient {
@@ -59,6 +65,24 @@ impl RocksClient {
         })
     }
 
+    /// Put the meta value of a key and update the keyspace key count.
+    pub fn put_meta(&self, cf: ColumnFamilyRef, key: Key, value: Value) -> RocksResult<()> {
+        let old_value = self.get(cf.clone(), key.clone())?;
+        let deltas = key_count_deltas(old_value.as_deref(), Some(&value));
+        self.put(cf, key, value)?;
+        apply_key_count_deltas(&deltas);
+        Ok(())
+    }
+
+    /// Delete the meta value of a key and update the keyspace key count.
+    pub fn del_meta(&self, cf: ColumnFamilyRef, key: Key) -> RocksResult<()> {
+        let old_value = self.get(cf.clone(), key.clone())?;
+        let deltas = key_count_deltas(old_value.as_deref(), None);
+        self.del(cf, key)?;
+        apply_key_count_deltas(&deltas);
+        Ok(())
+    }
+
     pub fn batch_get(&self, cf: ColumnFamilyRef, keys: Vec<Key>) -> RocksResult<Vec<KvPair>> {
         let client = self.client.as_ref();
 
@@ -90,6 +114,62 @@ impl RocksClient {
         Ok(kvpairs)
     }
 
+    /// Put the meta values of keys and update the keyspace key count.
+    pub fn batch_put_meta(&self, cf: ColumnFamilyRef, kvs: Vec<KvPair>) -> RocksResult<()> {
+        let mut deltas = vec![];
+        for kv in &kvs {
+            let old_value = self.get(cf.clone(), kv.0.clone())?;
+            deltas.extend(key_count_deltas(old_value.as_deref(), Some(&kv.1)));
+        }
+        self.batch_put(cf, kvs)?;
+        apply_key_count_deltas(&deltas);
+        Ok(())
+    }
+
+    // count the keys of every type stored in the meta column family, so the
+    // keyspace gauges start from the right values after a restart
+    pub fn reconcile_key_count(&self) -> RocksResult<()> {
+        let cf = self.cf_handle(CF_NAME_META)?;
+        let mut counts: HashMap<String, i64> = HashMap::new();
+        for kv in self.client.iterator_cf(&cf, IteratorMode::Start) {
+            let (key, value) = kv?;
+            let (_, is_meta_key) =
+                KeyDecoder::decode_key_userkey_from_metakey(&key.to_vec().into());
+            if is_meta_key {
+                *counts
+                    .entry(KeyDecoder::decode_key_type(&value).to_string())
+                    .or_default() += 1;
+            }
+        }
+        for data_type in [
+            DataType::String,
+            DataType::Hash,
+            DataType::List,
+            DataType::Set,
+            DataType::Zset,
+        ] {
+            let data_type = data_type.to_string();
+            KEYSPACE_KEY_COUNT
+                .with_label_values(&[&data_type])
+                .set(counts.get(&data_type).copied().unwrap_or(0));
+        }
+        Ok(())
+    }
+
+    // delete every key of all column families, the keyspace gauges are reset
+    pub fn flush_all(&self) -> RocksResult<()> {
+        for name in CF_NAMES {
+            let cf = self.cf_handle(name)?;
+            let mut write_batch = WriteBatchWithTransaction::default();
+            for kv in self.client.iterator_cf(&cf, IteratorMode::Start) {
+                let (key, _) = kv?;
+                write_batch.delete_cf(&cf, key);
+            }
+            self.client.write(write_batch)?;
+        }
+        self.reconcile_key_count()
+    }
+
     pub fn batch_put(&self, cf: ColumnFamilyRef, kvs: Vec<KvPair>) -> RocksResult<()> {
         let client = self.client.as_ref();
 
@@ -105,6 +185,49 @@ impl RocksClient {
         })
     }
 
+    // sum of the estimated live data size of all column families
+    pub fn estimate_live_data_size(&self) -> RocksResult<u64> {
+        let mut total = 0;
+        for name in CF_NAMES {
+            let cf = self.cf_handle(name)?;
+            total += self
+                .client
+                .property_int_value_cf(&cf, "rocksdb.estimate-live-data-size")?
+                .unwrap_or(0);
+        }
+        Ok(total)
+    }
+
+    // writes are stopped or being delayed by rocksdb in any column family
+    pub fn is_write_stalled(&self) -> RocksResult<bool> {
+        for name in CF_NAMES {
+            let cf = self.cf_handle(name)?;
+            let stopped = self
+                .client
+                .property_int_value_cf(&cf, "rocksdb.is-write-stopped")?
+                .unwrap_or(0);
+            let delayed_rate = self
+                .client
+                .property_int_value_cf(&cf, "rocksdb.actual-delayed-write-rate")?
+                .unwrap_or(0);
+            if stopped > 0 || delayed_rate > 0 {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    // write and sync the wal, needed with rocks_manual_wal_flush for the
+    // writes to survive a crash
+    pub fn flush_wal(&self) -> RocksResult<()> {
+        Ok(self.client.flush_wal(true)?)
+    }
+
+    pub fn property_int_value(&self, cf_name: &str, property: &str) -> RocksResult<Option<u64>> {
+        let cf = self.cf_handle(cf_name)?;
+        Ok(self.client.property_int_value_cf(&cf, property)?)
+    }
+
     pub fn cf_handle(&self, name: &str) -> RocksResult<ColumnFamilyRef> {
         self.client.cf_handle(name).ok_or(CF_NOT_EXISTS_ERR)
     }
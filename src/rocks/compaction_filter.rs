@@ -0,0 +1,42 @@
+use crate::rocks::encoding::{DataType, KeyDecoder};
+use crate::rocks::kv::key::Key;
+use crate::rocks::KEY_ENCODER;
+use crate::utils::{key_is_expired, notify_event};
+use rocksdb::compaction_filter::Decision;
+
+/// Name of the compaction filter registered on the meta column family.
+pub const EXPIRY_FILTER_NAME: &str = "mapuche_expiry";
+
+/// Compaction filter of the meta column family, expired string keys are
+/// dropped during compaction instead of waiting for a read or a gc scan, with
+/// the same `expired` keyspace event as the expire paths.
+///
+/// Only the meta key is removed, so keys of the other types are kept: their
+/// sub meta and data keys are removed with the meta key by the expire paths.
+pub fn expiry_filter(_level: u32, key: &[u8], value: &[u8]) -> Decision {
+    match expired_string_key(key, value) {
+        Some(ukey) => {
+            notify_event('x', "expired", &String::from_utf8_lossy(&ukey));
+            Decision::Remove
+        }
+        None => Decision::Keep,
+    }
+}
+
+// user key of an expired string meta key
+fn expired_string_key(key: &[u8], value: &[u8]) -> Option<Vec<u8>> {
+    // the meta column family also holds sub meta keys and keys of other
+    // instances, only meta keys of the keyspace are decoded
+    let keyspace_start: Vec<u8> = KEY_ENCODER.encode_keyspace_start().into();
+    if !key.starts_with(&keyspace_start) || value.len() < 9 {
+        return None;
+    }
+    let (ukey, is_meta_key) = KeyDecoder::decode_key_userkey_from_metakey(&Key::from(key.to_vec()));
+    let expired = is_meta_key
+        && matches!(
+            KeyDecoder::decode_key_type(value),
+            DataType::String | DataType::Hll
+        )
+        && key_is_expired(KeyDecoder::decode_key_ttl(value));
+    expired.then_some(ukey)
+}
@@ -1,5 +1,7 @@
 use crate::rocks::encoding::encode::DATA_TYPE_META;
-use crate::rocks::encoding::{DataType, ENC_GROUP_SIZE, ENC_MARKER, SIGN_MASK};
+use crate::rocks::encoding::{
+    DataType, COMPACT_ENCODING_FLAG, ENC_GROUP_SIZE, ENC_MARKER, SIGN_MASK,
+};
 use crate::rocks::kv::key::Key;
 use crate::rocks::kv::value::Value;
 use crate::rocks::KEY_ENCODER;
@@ -44,6 +46,7 @@ impl KeyDecoder {
             2 => DataType::List,
             3 => DataType::Set,
             4 => DataType::Zset,
+            6 => DataType::Hll,
             _ => panic!("no support data type"),
         }
     }
@@ -65,7 +68,7 @@ impl KeyDecoder {
     }
 
     pub fn decode_key_index_size(value: &[u8]) -> u16 {
-        u16::from_be_bytes(value[11..].try_into().unwrap())
+        u16::from_be_bytes(value[11..13].try_into().unwrap())
     }
 
     pub fn decode_key_meta(value: &[u8]) -> (i64, u16, u16) {
@@ -76,6 +79,35 @@ impl KeyDecoder {
         )
     }
 
+    /// return the entries stored inline after `offset`, None if the meta value
+    /// is not compact encoded
+    fn decode_compact_entries(value: &[u8], offset: usize) -> Option<Vec<Vec<u8>>> {
+        if value.len() <= offset || value[offset] != COMPACT_ENCODING_FLAG {
+            return None;
+        }
+        let mut entries = vec![];
+        let mut idx = offset + 1;
+        while idx < value.len() {
+            let len = u32::from_be_bytes(value[idx..idx + 4].try_into().unwrap()) as usize;
+            idx += 4;
+            entries.push(value[idx..idx + len].to_vec());
+            idx += len;
+        }
+        Some(entries)
+    }
+
+    /// return inline (field, value) pairs of a compact encoded hash
+    pub fn decode_key_hash_compact(value: &[u8]) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+        Self::decode_compact_entries(value, 13).map(|entries| {
+            let mut pairs = Vec::with_capacity(entries.len() / 2);
+            let mut iter = entries.into_iter();
+            while let (Some(field), Some(value)) = (iter.next(), iter.next()) {
+                pairs.push((field, value));
+            }
+            pairs
+        })
+    }
+
     pub fn decode_cmp_uint64_to_f64(u: u64) -> f64 {
         let mut score = u;
 
@@ -126,16 +158,52 @@ impl KeyDecoder {
         (ukey, version)
     }
 
+    /// return (type, estimated size), value written before the size was
+    /// introduced only contains the type byte
+    pub fn decode_gc_version_value(value: &[u8]) -> (DataType, u64) {
+        let size = if value.len() >= 9 {
+            u64::from_be_bytes(value[1..9].try_into().unwrap())
+        } else {
+            0
+        };
+        let key_type = match value[0] {
+            0 => DataType::String,
+            1 => DataType::Hash,
+            2 => DataType::List,
+            3 => DataType::Set,
+            4 => DataType::Zset,
+            _ => DataType::Null,
+        };
+        (key_type, size)
+    }
+
+    /// return inline (score, member) pairs of a compact encoded zset
+    pub fn decode_key_zset_compact(value: &[u8]) -> Option<Vec<(f64, Vec<u8>)>> {
+        Self::decode_compact_entries(value, 13).map(|entries| {
+            let mut members = Vec::with_capacity(entries.len() / 2);
+            let mut iter = entries.into_iter();
+            while let (Some(score), Some(member)) = (iter.next(), iter.next()) {
+                members.push((f64::from_be_bytes(score[..].try_into().unwrap()), member));
+            }
+            members
+        })
+    }
+
     /// return (ttl, version, left, right)
     pub fn decode_key_list_meta(value: &[u8]) -> (i64, u16, u64, u64) {
         (
             i64::from_be_bytes(value[1..9].try_into().unwrap()),
             u16::from_be_bytes(value[9..11].try_into().unwrap()),
             u64::from_be_bytes(value[11..19].try_into().unwrap()),
-            u64::from_be_bytes(value[19..].try_into().unwrap()),
+            u64::from_be_bytes(value[19..27].try_into().unwrap()),
         )
     }
 
+    /// return inline elements of a compact encoded list
+    pub fn decode_key_list_compact(value: &[u8]) -> Option<Vec<Vec<u8>>> {
+        Self::decode_compact_entries(value, 27)
+    }
+
     pub fn decode_key_list_idx_from_datakey(ukey: &str, key: Key) -> u64 {
         let key: Vec<u8> = key.into();
         let enc_ukey = KEY_ENCODER.encode_bytes(ukey.as_bytes());
@@ -1,5 +1,7 @@
 use crate::config::config_meta_key_number_or_default;
-use crate::rocks::encoding::{DataType, ENC_ASC_PADDING, ENC_GROUP_SIZE, ENC_MARKER, SIGN_MASK};
+use crate::rocks::encoding::{
+    DataType, COMPACT_ENCODING_FLAG, ENC_ASC_PADDING, ENC_GROUP_SIZE, ENC_MARKER, SIGN_MASK,
+};
 use crate::rocks::get_instance_id;
 use crate::rocks::kv::bound_range::BoundRange;
 use crate::rocks::kv::key::Key;
@@ -67,6 +69,7 @@ impl KeyEncoder {
             DataType::Set => 3,
             DataType::Zset => 4,
             DataType::Null => 5,
+            DataType::Hll => 6,
         }
     }
 
@@ -103,6 +106,13 @@ impl KeyEncoder {
         val
     }
 
+    /// Same layout as a string value, only the type byte differs.
+    pub fn encode_hll_value(&self, hll: &[u8], ttl: i64) -> Value {
+        let mut val = self.encode_string_slice(hll, ttl);
+        val[0] = self.get_type_bytes(DataType::Hll);
+        val
+    }
+
     pub fn encode_strings(&self, keys: &[String]) -> Vec<Key> {
         keys.iter().map(|ukey| self.encode_string(ukey)).collect()
     }
@@ -123,6 +133,14 @@ impl KeyEncoder {
         key.into()
     }
 
+    pub fn encode_keyspace_start(&self) -> Key {
+        let mut key = Vec::with_capacity(4);
+        key.push(TXN_KEY_PREFIX);
+        key.extend_from_slice(self.instance_id.as_slice());
+        key.push(DATA_TYPE_USER);
+        key.into()
+    }
+
     pub fn encode_keyspace_end(&self) -> Key {
         let mut key = Vec::with_capacity(4);
         key.push(TXN_KEY_PREFIX);
@@ -193,6 +211,14 @@ impl KeyEncoder {
         key.into()
     }
 
+    /// gc version value layout: type(1) | estimated size(8)
+    pub fn encode_gc_version_value(&self, dt: DataType, size: u64) -> Value {
+        let mut val = Vec::with_capacity(9);
+        val.push(self.get_type_bytes(dt));
+        val.extend_from_slice(&size.to_be_bytes());
+        val
+    }
+
     fn encode_type_data_key_prefix(
         &self,
         key_type: u8,
@@ -343,6 +369,19 @@ impl KeyEncoder {
         val
     }
 
+    pub fn encode_list_compact_meta_value(
+        &self,
+        ttl: i64,
+        version: u16,
+        left: u64,
+        right: u64,
+        values: &[Vec<u8>],
+    ) -> Value {
+        let mut val = self.encode_list_meta_value(ttl, version, left, right);
+        self.encode_compact_entries(&mut val, values.iter());
+        val
+    }
+
     pub fn encode_hash_data_key(&self, ukey: &str, field: &str, version: u16) -> Key {
         let enc_ukey = self.encode_bytes(ukey.as_bytes());
         let mut key = Vec::with_capacity(8 + enc_ukey.len() + field.len());
@@ -396,6 +435,36 @@ impl KeyEncoder {
         val
     }
 
+    // append the compact flag and length-prefixed entries to a meta value
+    fn encode_compact_entries<T: AsRef<[u8]>>(
+        &self,
+        val: &mut Value,
+        entries: impl Iterator<Item = T>,
+    ) {
+        val.push(COMPACT_ENCODING_FLAG);
+        for entry in entries {
+            let entry = entry.as_ref();
+            val.extend_from_slice(&(entry.len() as u32).to_be_bytes());
+            val.extend_from_slice(entry);
+        }
+    }
+
+    pub fn encode_hash_compact_meta_value(
+        &self,
+        ttl: i64,
+        version: u16,
+        pairs: &[(Vec<u8>, Vec<u8>)],
+    ) -> Value {
+        let mut val = self.encode_hash_meta_value(ttl, version, 0);
+        self.encode_compact_entries(
+            &mut val,
+            pairs
+                .iter()
+                .flat_map(|(field, value)| [field.as_slice(), value.as_slice()]),
+        );
+        val
+    }
+
     pub fn encode_zset_meta_value(&self, ttl: i64, version: u16, index_size: u16) -> Value {
         let dt = self.get_type_bytes(DataType::Zset);
         let mut val = Vec::with_capacity(13);
@@ -412,6 +481,22 @@ impl KeyEncoder {
         val
     }
 
+    pub fn encode_zset_compact_meta_value(
+        &self,
+        ttl: i64,
+        version: u16,
+        members: &[(f64, Vec<u8>)],
+    ) -> Value {
+        let mut val = self.encode_zset_meta_value(ttl, version, 0);
+        self.encode_compact_entries(
+            &mut val,
+            members
+                .iter()
+                .flat_map(|(score, member)| [score.to_be_bytes().to_vec(), member.clone()]),
+        );
+        val
+    }
+
     pub fn encode_zset_data_key(&self, ukey: &str, member: &str, version: u16) -> Key {
         let enc_ukey = self.encode_bytes(ukey.as_bytes());
         let mut key = Vec::with_capacity(8 + enc_ukey.len() + member.len());
@@ -8,6 +8,8 @@ pub enum DataType {
     List,
     Set,
     Zset,
+    // HyperLogLog, stored inline in the meta value like a string
+    Hll,
     Null,
 }
 
@@ -19,6 +21,7 @@ impl fmt::Display for DataType {
             DataType::List => write!(f, "list"),
             DataType::Set => write!(f, "set"),
             DataType::Zset => write!(f, "zset"),
+            DataType::Hll => write!(f, "string"),
             DataType::Null => write!(f, "none"),
         }
     }
@@ -29,6 +32,10 @@ pub use {decode::KeyDecoder, encode::KeyEncoder};
 
 const SIGN_MASK: u64 = 0x8000000000000000;
 
+// flag byte following the common meta value fields, marks that all the
+// elements are stored inline in the meta value
+const COMPACT_ENCODING_FLAG: u8 = b'c';
+
 const ENC_GROUP_SIZE: usize = 8;
 const ENC_MARKER: u8 = b'\xff';
 const ENC_ASC_PADDING: [u8; ENC_GROUP_SIZE] = [0; ENC_GROUP_SIZE];
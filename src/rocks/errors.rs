@@ -53,17 +53,29 @@ impl From<ParseFloatError> for RError {
 
 pub const REDIS_WRONG_TYPE_ERR: RError =
     RError::String("WRONGTYPE Operation against a key holding the wrong kind of value");
+pub const REDIS_INVALID_HLL_ERR: RError =
+    RError::String("WRONGTYPE Key is not a valid HyperLogLog string value.");
 pub const REDIS_VALUE_IS_NOT_INTEGER_ERR: RError =
     RError::String("ERR value is not an integer or out of range");
 pub const REDIS_VALUE_IS_NOT_VALID_FLOAT_ERR: RError =
     RError::String("ERR value is not a valid float");
+pub const REDIS_ZADD_INCR_PAIR_ERR: RError =
+    RError::String("ERR INCR option supports a single increment-element pair");
 pub const REDIS_BACKEND_NOT_CONNECTED_ERR: RError = RError::String("ERR backend not connected");
 pub const REDIS_COMPARE_AND_SWAP_EXHAUSTED_ERR: RError =
     RError::String("ERR compare-and-swap exhausted");
+pub const REDIS_LPOS_RANK_ZERO_ERR: RError = RError::String(
+    "ERR RANK can't be zero: use 1 to start from the first match, 2 from the second ... or use negative to start from the end of the list",
+);
+pub const REDIS_LPOS_COUNT_NEGATIVE_ERR: RError = RError::String("ERR COUNT can't be negative");
+pub const REDIS_LPOS_MAXLEN_NEGATIVE_ERR: RError = RError::String("ERR MAXLEN can't be negative");
+pub const REDIS_INVALID_CURSOR_ERR: RError = RError::String("ERR invalid cursor");
 pub const REDIS_NO_SUCH_KEY_ERR: RError = RError::String("ERR no such key");
 pub const REDIS_INDEX_OUT_OF_RANGE_ERR: RError = RError::String("ERR index out of range");
 pub const REDIS_LIST_TOO_LARGE_ERR: RError = RError::String("ERR list is too large to execute");
-pub const DECREMENT_OVERFLOW: RError = RError::String("Decrement would overflow");
+pub const DECREMENT_OVERFLOW: RError = RError::String("ERR decrement would overflow");
+pub const REDIS_INCR_OVERFLOW_ERR: RError =
+    RError::String("ERR increment or decrement would overflow");
 pub const TXN_ERROR: RError = RError::Txn("Txn commit failed");
 pub const KEY_VERSION_EXHUSTED_ERR: RError = RError::String("ERR key version exhausted");
 pub const CF_NOT_EXISTS_ERR: RError = RError::String("Column family not existed");
@@ -71,4 +83,70 @@ pub const REDIS_AUTH_WHEN_DISABLED_ERR: RError =
     RError::String("ERR Client sent AUTH, but no password is set");
 pub const REDIS_AUTH_INVALID_PASSWORD_ERR: RError = RError::String("ERR invalid password");
 pub const REDIS_AUTH_REQUIRED_ERR: RError = RError::String("NOAUTH Authentication required.");
+pub const REDIS_INVALID_CLIENT_NAME_ERR: RError =
+    RError::String("ERR Client names cannot contain spaces, newlines or special characters.");
+pub const REDIS_NO_SUCH_CLIENT_ERR: RError = RError::String("ERR No such client");
+pub const REDIS_NOPROTO_ERR: RError = RError::String("NOPROTO unsupported protocol version");
+pub const REDIS_AUTH_WRONGPASS_ERR: RError =
+    RError::String("WRONGPASS invalid username-password pair or user is disabled.");
+pub const REDIS_NOPERM_KEY_ERR: RError = RError::String(
+    "NOPERM this user has no permissions to access one of the keys used as arguments",
+);
+pub const REDIS_ACL_UNKNOWN_CATEGORY_ERR: RError = RError::String("ERR Unknown category");
+pub const REDIS_ACL_DEL_DEFAULT_USER_ERR: RError =
+    RError::String("ERR The 'default' user cannot be removed");
+pub const REDIS_NOSCRIPT_ERR: RError =
+    RError::String("NOSCRIPT No matching script. Please use EVAL.");
+pub const REDIS_TIMEOUT_NEGATIVE_ERR: RError = RError::String("ERR timeout is negative");
+pub const REDIS_NUMKEYS_NEGATIVE_ERR: RError =
+    RError::String("ERR Number of keys can't be negative");
+pub const REDIS_NUMKEYS_GREATER_ERR: RError =
+    RError::String("ERR Number of keys can't be greater than number of args");
+pub const REDIS_SCRIPT_UNKNOWN_CMD_ERR: RError =
+    RError::String("ERR Unknown Redis command called from script");
+pub const REDIS_SCRIPT_NOT_ALLOWED_CMD_ERR: RError =
+    RError::String("ERR This Redis command is not allowed from script");
+pub const REDIS_ACL_NO_ACLFILE_ERR: RError =
+    RError::String("ERR This instance is not configured to use an ACL file.");
+pub const REDIS_LFU_NOT_SELECTED_ERR: RError =
+    RError::String("ERR An LFU maxmemory policy is not selected, access frequency not tracked.");
+pub const REDIS_LFU_SELECTED_ERR: RError =
+    RError::String("ERR An LFU maxmemory policy is selected, idle time not tracked.");
 pub const REDIS_NOT_SUPPORTED_ERR: RError = RError::String("Cmd not supported.");
+pub const REDIS_DEBUG_NOT_ALLOWED_ERR: RError = RError::String(
+    "ERR DEBUG command not allowed. Set enable_debug_commands in the config file to allow it.",
+);
+pub const REDIS_INVALID_SLOT_ERR: RError = RError::String("ERR Invalid or out of range slot");
+pub const REDIS_INVALID_NUMBER_OF_KEYS_ERR: RError = RError::String("ERR Invalid number of keys");
+pub const REDIS_DUMP_PAYLOAD_ERR: RError =
+    RError::String("ERR DUMP payload version or checksum are wrong");
+pub const REDIS_BUSYKEY_ERR: RError = RError::String("BUSYKEY Target key name already exists.");
+pub const REDIS_BUSY_CONNECTIONS_ERR: RError =
+    RError::String("BUSY Server is near its connection limit, only PING and AUTH are served.");
+pub const REDIS_INVALID_TTL_ERR: RError = RError::String("ERR Invalid TTL value, must be >= 0");
+pub const REDIS_SORT_NOT_DOUBLE_ERR: RError =
+    RError::String("ERR One or more scores can't be converted into double");
+pub const REDIS_BITFIELD_RO_ERR: RError =
+    RError::String("ERR BITFIELD_RO only supports the GET subcommand");
+pub const REDIS_BITFIELD_TYPE_ERR: RError = RError::String(
+    "ERR Invalid bitfield type. Use something like i16 u8. Note that u64 is not supported but i64 is.",
+);
+pub const REDIS_VALUE_OUT_OF_RANGE_ERR: RError = RError::String("ERR value is out of range");
+pub const REDIS_BIT_OFFSET_ERR: RError =
+    RError::String("ERR bit offset is not an integer or out of range");
+pub const REDIS_CLUSTER_RESET_VOTER_ERR: RError =
+    RError::String("ERR CLUSTER RESET can't be called on a voting member of the raft cluster.");
+pub const REDIS_GETKEYS_INVALID_CMD_ERR: RError = RError::String("ERR Invalid command specified");
+pub const REDIS_GETKEYS_NO_KEYS_ERR: RError =
+    RError::String("ERR The command has no key arguments");
+pub const REDIS_GETKEYS_ARGS_ERR: RError =
+    RError::String("ERR Invalid arguments specified for command");
+pub const REDIS_MULTI_NESTED_ERR: RError = RError::String("ERR MULTI calls can not be nested");
+pub const REDIS_EXEC_WITHOUT_MULTI_ERR: RError = RError::String("ERR EXEC without MULTI");
+pub const REDIS_DISCARD_WITHOUT_MULTI_ERR: RError = RError::String("ERR DISCARD without MULTI");
+pub const REDIS_EXECABORT_ERR: RError =
+    RError::String("EXECABORT Transaction discarded because of previous errors.");
+pub const REDIS_MULTI_NOT_ALLOWED_ERR: RError =
+    RError::String("ERR Command not allowed inside a transaction");
+pub const REDIS_MULTI_REMOTE_KEY_ERR: RError =
+    RError::String("ERR Keys of a transaction must be served by the node receiving it");
@@ -1,11 +1,12 @@
 use crate::config::{
     async_del_hash_threshold_or_default, async_expire_hash_threshold_or_default,
-    config_meta_key_number_or_default, LOGGER,
+    config_meta_key_number_or_default, hash_max_ziplist_entries_or_default,
+    hash_max_ziplist_value_or_default, LOGGER,
 };
 use crate::metrics::REMOVED_EXPIRED_KEY_COUNTER;
 use crate::rocks::client::{get_version_for_new, RocksClient};
 use crate::rocks::encoding::{DataType, KeyDecoder};
-use crate::rocks::errors::{REDIS_VALUE_IS_NOT_INTEGER_ERR, REDIS_WRONG_TYPE_ERR};
+use crate::rocks::errors::{REDIS_VALUE_IS_NOT_INTEGER_ERR, REDIS_WRONG_TYPE_ERR, TXN_ERROR};
 use crate::rocks::kv::bound_range::BoundRange;
 use crate::rocks::kv::key::Key;
 use crate::rocks::kv::kvpair::KvPair;
@@ -16,7 +17,8 @@ use crate::rocks::{
     CF_NAME_HASH_DATA, CF_NAME_HASH_SUB_META, CF_NAME_META, KEY_ENCODER,
 };
 use crate::utils::{
-    count_unique_keys, key_is_expired, resp_array, resp_bulk, resp_err, resp_int, resp_nil, resp_ok,
+    count_unique_keys, key_is_expired, notify_event, resp_array, resp_bulk, resp_err, resp_int,
+    resp_nil, resp_ok, unique_keys,
 };
 use crate::Frame;
 use rocksdb::ColumnFamilyRef;
@@ -69,6 +71,9 @@ impl<'a> HashCommand<'a> {
         let meta_key = KEY_ENCODER.encode_meta_key(&key);
 
         let resp = client.exec_txn(|txn| {
+            if let Some(n) = self.txn_hset_compact(txn, &key, &fvs_copy, is_nx)? {
+                return Ok(n);
+            }
             match txn.get(cfs.meta_cf.clone(), meta_key.clone())? {
                 Some(meta_value) => {
                     // check key type is hash
@@ -122,11 +127,11 @@ impl<'a> HashCommand<'a> {
                             );
                             fields_data_key.push(datakey);
                         }
-                        // batch get
-                        let real_fields_count = count_unique_keys(&fields_data_key);
-                        added_count = real_fields_count as i64
+                        // batch get, a field set twice in one call is counted once
+                        let unique_data_keys = unique_keys(&fields_data_key);
+                        added_count = unique_data_keys.len() as i64
                             - txn
-                                .batch_get_for_update(cfs.data_cf.clone(), fields_data_key)?
+                                .batch_get_for_update(cfs.data_cf.clone(), unique_data_keys)?
                                 .len() as i64;
                     }
 
@@ -154,8 +159,9 @@ impl<'a> HashCommand<'a> {
                         let meta_size = config_meta_key_number_or_default();
                         let new_metaval =
                             KEY_ENCODER.encode_hash_meta_value(ttl, version, meta_size);
-                        txn.put(cfs.meta_cf.clone(), meta_key, new_metaval)?;
+                        txn.put_meta(cfs.meta_cf.clone(), meta_key, new_metaval)?;
                     }
+                    Ok(added_count as usize)
                 }
                 None => {
                     let version = get_version_for_new(
@@ -198,20 +204,21 @@ impl<'a> HashCommand<'a> {
                     // set meta key
                     let meta_size = config_meta_key_number_or_default();
                     let new_metaval = KEY_ENCODER.encode_hash_meta_value(ttl, version, meta_size);
-                    txn.put(cfs.meta_cf.clone(), meta_key, new_metaval)?;
+                    txn.put_meta(cfs.meta_cf.clone(), meta_key, new_metaval)?;
 
                     txn.put(
                         cfs.sub_meta_cf.clone(),
                         sub_meta_key,
                         real_fields_count.to_be_bytes().to_vec(),
                     )?;
+                    Ok(real_fields_count)
                 }
             }
-            Ok(fvs_len)
         });
 
         match resp {
             Ok(num) => {
+                notify_event('h', "hset", key);
                 if is_hmset {
                     Ok(resp_ok())
                 } else {
@@ -244,6 +251,11 @@ impl<'a> HashCommand<'a> {
                         return Ok(resp_nil());
                     }
 
+                    if let Some(pairs) = KeyDecoder::decode_key_hash_compact(&meta_value) {
+                        return Ok(Self::compact_field_value(&pairs, &field)
+                            .map_or_else(resp_nil, |data| resp_bulk(data.to_vec())));
+                    }
+
                     let data_key = KEY_ENCODER.encode_hash_data_key(&key, &field, version);
 
                     txn.get(cfs.data_cf.clone(), data_key)?
@@ -276,6 +288,13 @@ impl<'a> HashCommand<'a> {
                         return Ok(resp_int(0));
                     }
 
+                    if let Some(pairs) = KeyDecoder::decode_key_hash_compact(&meta_value) {
+                        return Ok(resp_int(
+                            Self::compact_field_value(&pairs, &field).map_or(0, |data| data.len())
+                                as i64,
+                        ));
+                    }
+
                     let data_key = KEY_ENCODER.encode_hash_data_key(&key, &field, version);
 
                     txn.get(cfs.data_cf.clone(), data_key)?
@@ -308,6 +327,11 @@ impl<'a> HashCommand<'a> {
                         return Ok(resp_int(0));
                     }
 
+                    if let Some(pairs) = KeyDecoder::decode_key_hash_compact(&meta_value) {
+                        let exists = Self::compact_field_value(&pairs, &field).is_some();
+                        return Ok(resp_int(exists as i64));
+                    }
+
                     let data_key = KEY_ENCODER.encode_hash_data_key(&key, &field, version);
 
                     if txn.get(cfs.data_cf.clone(), data_key)?.is_some() {
@@ -345,6 +369,16 @@ impl<'a> HashCommand<'a> {
                         return Ok(resp_array(vec![]));
                     }
 
+                    if let Some(pairs) = KeyDecoder::decode_key_hash_compact(&meta_value) {
+                        for field in &fields {
+                            match Self::compact_field_value(&pairs, field) {
+                                Some(data) => resp.push(resp_bulk(data.to_vec())),
+                                None => resp.push(resp_nil()),
+                            }
+                        }
+                        return Ok(resp_array(resp));
+                    }
+
                     let mut field_data_keys = Vec::with_capacity(fields.len());
                     for field in &fields {
                         let data_key = KEY_ENCODER.encode_hash_data_key(&key, field, version);
@@ -431,6 +465,23 @@ impl<'a> HashCommand<'a> {
                         return Ok(resp_nil());
                     }
 
+                    if let Some(pairs) = KeyDecoder::decode_key_hash_compact(&meta_value) {
+                        let resp = pairs
+                            .into_iter()
+                            .flat_map(|(field, value)| {
+                                let mut items = Vec::with_capacity(2);
+                                if with_field {
+                                    items.push(resp_bulk(field));
+                                }
+                                if with_value {
+                                    items.push(resp_bulk(value));
+                                }
+                                items
+                            })
+                            .collect();
+                        return Ok(resp_array(resp));
+                    }
+
                     let range: Range<Key> = KEY_ENCODER.encode_hash_data_key_start(&key, version)
                         ..KEY_ENCODER.encode_hash_data_key_end(&key, version);
                     let bound_range: BoundRange = range.into();
@@ -486,6 +537,23 @@ impl<'a> HashCommand<'a> {
                         return Ok(0);
                     }
 
+                    if KeyDecoder::decode_key_hash_compact(&meta_value).is_some() {
+                        // all fields are stored in meta value, lock it before modify
+                        let mut pairs = txn
+                            .get_for_update(cfs.meta_cf.clone(), meta_key.clone())?
+                            .and_then(|v| KeyDecoder::decode_key_hash_compact(&v))
+                            .ok_or(TXN_ERROR)?;
+                        let old_len = pairs.len();
+                        pairs.retain(|(f, _)| !fields.iter().any(|field| f == field.as_bytes()));
+                        let deleted = (old_len - pairs.len()) as i64;
+                        if pairs.is_empty() {
+                            txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
+                        } else if deleted > 0 {
+                            self.txn_save_pairs(txn, &key, ttl, version, pairs)?;
+                        }
+                        return Ok(deleted);
+                    }
+
                     let mut deleted: i64 = 0;
                     let data_keys: Vec<Key> = fields
                         .iter()
@@ -503,7 +571,7 @@ impl<'a> HashCommand<'a> {
 
                     // update sub meta key or clear all meta and sub meta key if needed
                     if old_size <= deleted {
-                        txn.del(cfs.meta_cf.clone(), meta_key)?;
+                        txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
                         let bound_range = KEY_ENCODER.encode_sub_meta_key_range(&key, version);
                         let iter = txn.scan_keys(cfs.sub_meta_cf.clone(), bound_range, u32::MAX)?;
                         for k in iter {
@@ -535,7 +603,12 @@ impl<'a> HashCommand<'a> {
             }
         });
         match resp {
-            Ok(n) => Ok(resp_int(n)),
+            Ok(n) => {
+                if n > 0 {
+                    notify_event('h', "hdel", key);
+                }
+                Ok(resp_int(n))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
@@ -561,6 +634,34 @@ impl<'a> HashCommand<'a> {
                     let mut expired = false;
                     let (ttl, mut version, _meta_size) = KeyDecoder::decode_key_meta(&meta_value);
 
+                    if !key_is_expired(ttl)
+                        && KeyDecoder::decode_key_hash_compact(&meta_value).is_some()
+                    {
+                        // all fields are stored in meta value, lock it before modify
+                        let mut pairs = txn
+                            .get_for_update(cfs.meta_cf.clone(), meta_key.clone())?
+                            .and_then(|v| KeyDecoder::decode_key_hash_compact(&v))
+                            .ok_or(TXN_ERROR)?;
+                        let new_int = match pairs.iter_mut().find(|(f, _)| f == field.as_bytes()) {
+                            Some((_, value)) => {
+                                let prev_int = String::from_utf8_lossy(value)
+                                    .parse::<i64>()
+                                    .map_err(|_| REDIS_VALUE_IS_NOT_INTEGER_ERR)?;
+                                *value = (prev_int + step).to_string().into_bytes();
+                                prev_int + step
+                            }
+                            None => {
+                                pairs.push((
+                                    field.clone().into_bytes(),
+                                    step.to_string().into_bytes(),
+                                ));
+                                step
+                            }
+                        };
+                        self.txn_save_pairs(txn, &key, ttl, version, pairs)?;
+                        return Ok(new_int);
+                    }
+
                     if key_is_expired(ttl) {
                         self.txn_expire_if_needed(txn, client, &key)?;
                         expired = true;
@@ -612,7 +713,7 @@ impl<'a> HashCommand<'a> {
                                 let meta_size = config_meta_key_number_or_default();
                                 let meta_value =
                                     KEY_ENCODER.encode_hash_meta_value(ttl, version, meta_size);
-                                txn.put(cfs.meta_cf.clone(), meta_key, meta_value)?;
+                                txn.put_meta(cfs.meta_cf.clone(), meta_key, meta_value)?;
                             }
                         }
                     }
@@ -625,20 +726,11 @@ impl<'a> HashCommand<'a> {
                         &key,
                     )?;
 
-                    prev_int = 0;
-                    // create new meta key first
-                    let meta_size = config_meta_key_number_or_default();
-                    let meta_value = KEY_ENCODER.encode_hash_meta_value(0, version, meta_size);
-                    txn.put(cfs.meta_cf.clone(), meta_key, meta_value)?;
-
-                    // add a sub meta key with a random index
-                    let sub_meta_key = KEY_ENCODER.encode_sub_meta_key(&key, version, idx);
-                    txn.put(
-                        cfs.sub_meta_cf.clone(),
-                        sub_meta_key,
-                        1_i64.to_be_bytes().to_vec(),
-                    )?;
-                    data_key = KEY_ENCODER.encode_hash_data_key(&key, &field, version);
+                    // lock meta key, a new hash starts with compact encoding
+                    txn.get_for_update(cfs.meta_cf.clone(), meta_key)?;
+                    let pairs = vec![(field.clone().into_bytes(), step.to_string().into_bytes())];
+                    self.txn_save_pairs(txn, &key, 0, version, pairs)?;
+                    return Ok(step);
                 }
             }
             let new_int = prev_int + step;
@@ -652,11 +744,140 @@ impl<'a> HashCommand<'a> {
             Ok(new_int)
         });
         match resp {
-            Ok(n) => Ok(resp_int(n)),
+            Ok(n) => {
+                notify_event('h', "hincrby", key);
+                Ok(resp_int(n))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
+    fn compact_field_value<'b>(pairs: &'b [(Vec<u8>, Vec<u8>)], field: &str) -> Option<&'b [u8]> {
+        pairs
+            .iter()
+            .find(|(f, _)| f == field.as_bytes())
+            .map(|(_, v)| v.as_slice())
+    }
+
+    fn fits_compact(pairs: &[(Vec<u8>, Vec<u8>)]) -> bool {
+        let max_value = hash_max_ziplist_value_or_default();
+        pairs.len() <= hash_max_ziplist_entries_or_default()
+            && pairs
+                .iter()
+                .all(|(field, value)| field.len() <= max_value && value.len() <= max_value)
+    }
+
+    // store the fields inline in meta value if they fit the compact thresholds,
+    // otherwise migrate them to the sub meta and data keys layout
+    fn txn_save_pairs(
+        &self,
+        txn: &RocksTransaction,
+        key: &str,
+        ttl: i64,
+        version: u16,
+        pairs: Vec<(Vec<u8>, Vec<u8>)>,
+    ) -> RocksResult<()> {
+        let cfs = HashCF::new(self.client);
+        let meta_key = KEY_ENCODER.encode_meta_key(key);
+        if Self::fits_compact(&pairs) {
+            let meta_value = KEY_ENCODER.encode_hash_compact_meta_value(ttl, version, &pairs);
+            txn.put_meta(cfs.meta_cf, meta_key, meta_value)?;
+            return Ok(());
+        }
+
+        debug!(LOGGER, "migrate compact hash {} to full layout", key);
+        let sub_meta_key = KEY_ENCODER.encode_sub_meta_key(key, version, gen_next_meta_index());
+        txn.put(
+            cfs.sub_meta_cf.clone(),
+            sub_meta_key,
+            (pairs.len() as i64).to_be_bytes().to_vec(),
+        )?;
+        for (field, value) in pairs {
+            let data_key =
+                KEY_ENCODER.encode_hash_data_key(key, &String::from_utf8_lossy(&field), version);
+            txn.put(cfs.data_cf.clone(), data_key, value)?;
+        }
+        let meta_size = config_meta_key_number_or_default();
+        let meta_value = KEY_ENCODER.encode_hash_meta_value(ttl, version, meta_size);
+        txn.put_meta(cfs.meta_cf, meta_key, meta_value)?;
+        Ok(())
+    }
+
+    // set fields of a new or compact encoded hash, return None if the hash
+    // should be handled in the full layout
+    fn txn_hset_compact(
+        &self,
+        txn: &RocksTransaction,
+        key: &str,
+        fvs: &[KvPair],
+        is_nx: bool,
+    ) -> RocksResult<Option<usize>> {
+        let client = self.client;
+        let cfs = HashCF::new(client);
+        let meta_key = KEY_ENCODER.encode_meta_key(key);
+
+        let (ttl, version, mut pairs, is_new) = match txn
+            .get(cfs.meta_cf.clone(), meta_key.clone())?
+        {
+            Some(meta_value) => {
+                if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Hash) {
+                    return Err(REDIS_WRONG_TYPE_ERR);
+                }
+                let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                if key_is_expired(ttl) {
+                    self.txn_expire_if_needed(txn, client, key)?;
+                    let version = get_version_for_new(
+                        txn,
+                        cfs.gc_cf.clone(),
+                        cfs.gc_version_cf.clone(),
+                        key,
+                    )?;
+                    (0, version, vec![], true)
+                } else if KeyDecoder::decode_key_hash_compact(&meta_value).is_some() {
+                    // all fields are stored in meta value, lock it before modify
+                    let pairs = txn
+                        .get_for_update(cfs.meta_cf.clone(), meta_key)?
+                        .and_then(|v| KeyDecoder::decode_key_hash_compact(&v))
+                        .ok_or(TXN_ERROR)?;
+                    (ttl, version, pairs, false)
+                } else {
+                    return Ok(None);
+                }
+            }
+            None => {
+                // lock meta key to avoid concurrent creation
+                txn.get_for_update(cfs.meta_cf.clone(), meta_key)?;
+                let version =
+                    get_version_for_new(txn, cfs.gc_cf.clone(), cfs.gc_version_cf.clone(), key)?;
+                (0, version, vec![], true)
+            }
+        };
+
+        let mut added = 0;
+        for kv in fvs {
+            let field: Vec<u8> = kv.key().clone().into();
+            match pairs.iter_mut().find(|(f, _)| *f == field) {
+                Some((_, value)) => {
+                    if is_nx {
+                        return Ok(Some(0));
+                    }
+                    *value = kv.value().clone();
+                }
+                None => {
+                    pairs.push((field, kv.value().clone()));
+                    added += 1;
+                }
+            }
+        }
+
+        if is_new && !Self::fits_compact(&pairs) {
+            // too large for compact encoding, create it in the full layout directly
+            return Ok(None);
+        }
+        self.txn_save_pairs(txn, key, ttl, version, pairs)?;
+        Ok(Some(added))
+    }
+
     fn sum_key_size(&self, key: &str, version: u16) -> RocksResult<i64> {
         let client = self.client;
         let cfs = HashCF::new(client);
@@ -671,6 +892,10 @@ impl<'a> HashCommand<'a> {
                         return Err(REDIS_WRONG_TYPE_ERR);
                     }
 
+                    if let Some(pairs) = KeyDecoder::decode_key_hash_compact(&meta_value) {
+                        return Ok(pairs.len() as i64);
+                    }
+
                     let bound_range = KEY_ENCODER.encode_sub_meta_key_range(&key, version);
                     let iter = txn.scan(cfs.sub_meta_cf.clone(), bound_range, u32::MAX)?;
 
@@ -683,10 +908,34 @@ impl<'a> HashCommand<'a> {
             }
         })
     }
+
+    /// Create the hash from the field and value pairs of a `RESTORE` payload,
+    /// the key must have been deleted in the transaction.
+    pub(crate) fn txn_restore(
+        &self,
+        txn: &RocksTransaction,
+        key: &str,
+        ttl: i64,
+        elements: Vec<Vec<u8>>,
+    ) -> RocksResult<()> {
+        let cfs = HashCF::new(self.client);
+        let version = get_version_for_new(txn, cfs.gc_cf.clone(), cfs.gc_version_cf.clone(), key)?;
+        let pairs = elements
+            .chunks(2)
+            .map(|fv| (fv[0].clone(), fv[1].clone()))
+            .collect();
+        self.txn_save_pairs(txn, key, ttl, version, pairs)
+    }
 }
 
 impl TxnCommand for HashCommand<'_> {
-    fn txn_del(&self, txn: &RocksTransaction, client: &RocksClient, key: &str) -> RocksResult<()> {
+    fn txn_del(
+        &self,
+        txn: &RocksTransaction,
+        client: &RocksClient,
+        key: &str,
+        always_async: bool,
+    ) -> RocksResult<()> {
         let key = key.to_owned();
         let meta_key = KEY_ENCODER.encode_meta_key(&key);
         let cfs = HashCF::new(client);
@@ -696,9 +945,9 @@ impl TxnCommand for HashCommand<'_> {
                 let (_, version, _) = KeyDecoder::decode_key_meta(&meta_value);
                 let meta_size = self.sum_key_size(&key, version)?;
 
-                if meta_size > async_del_hash_threshold_or_default() as i64 {
+                if always_async || meta_size > async_del_hash_threshold_or_default() as i64 {
                     // do async del
-                    txn.del(cfs.meta_cf.clone(), meta_key)?;
+                    txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
 
                     let gc_key = KEY_ENCODER.encode_gc_key(&key);
                     txn.put(cfs.gc_cf.clone(), gc_key, version.to_be_bytes().to_vec())?;
@@ -707,7 +956,7 @@ impl TxnCommand for HashCommand<'_> {
                     txn.put(
                         cfs.gc_version_cf.clone(),
                         gc_version_key,
-                        vec![KEY_ENCODER.get_type_bytes(DataType::Hash)],
+                        KEY_ENCODER.encode_gc_version_value(DataType::Hash, meta_size as u64),
                     )?;
                 } else {
                     let bound_range = KEY_ENCODER.encode_hash_data_key_range(&key, version);
@@ -725,7 +974,7 @@ impl TxnCommand for HashCommand<'_> {
                         txn.del(cfs.sub_meta_cf.clone(), k)?;
                     }
 
-                    txn.del(cfs.meta_cf.clone(), meta_key)?;
+                    txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
                 }
                 Ok(())
             }
@@ -753,7 +1002,7 @@ impl TxnCommand for HashCommand<'_> {
 
                 if meta_size > async_expire_hash_threshold_or_default() as i64 {
                     // do async del
-                    txn.del(cfs.meta_cf.clone(), meta_key)?;
+                    txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
 
                     let gc_key = KEY_ENCODER.encode_gc_key(&key);
                     txn.put(cfs.gc_cf.clone(), gc_key, version.to_be_bytes().to_vec())?;
@@ -762,7 +1011,7 @@ impl TxnCommand for HashCommand<'_> {
                     txn.put(
                         cfs.gc_version_cf.clone(),
                         gc_version_key,
-                        vec![KEY_ENCODER.get_type_bytes(DataType::Hash)],
+                        KEY_ENCODER.encode_gc_version_value(DataType::Hash, meta_size as u64),
                     )?;
                 } else {
                     let bound_range = KEY_ENCODER.encode_hash_data_key_range(&key, version);
@@ -780,11 +1029,12 @@ impl TxnCommand for HashCommand<'_> {
                         txn.del(cfs.sub_meta_cf.clone(), k)?;
                     }
 
-                    txn.del(cfs.meta_cf.clone(), meta_key)?;
+                    txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
                 }
                 REMOVED_EXPIRED_KEY_COUNTER
                     .with_label_values(&["hash"])
                     .inc();
+                notify_event('x', "expired", &key);
                 Ok(1)
             }
             None => Ok(0),
@@ -807,8 +1057,11 @@ impl TxnCommand for HashCommand<'_> {
             return Ok(0);
         }
         let version = KeyDecoder::decode_key_version(meta_value);
-        let new_meta_value = KEY_ENCODER.encode_hash_meta_value(timestamp, version, 0);
-        txn.put(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
+        let new_meta_value = match KeyDecoder::decode_key_hash_compact(meta_value) {
+            Some(pairs) => KEY_ENCODER.encode_hash_compact_meta_value(timestamp, version, &pairs),
+            None => KEY_ENCODER.encode_hash_meta_value(timestamp, version, 0),
+        };
+        txn.put_meta(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
         Ok(1)
     }
 
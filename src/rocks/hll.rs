@@ -0,0 +1,415 @@
+use crate::cache::cache_invalidate;
+use crate::rocks::client::RocksClient;
+use crate::rocks::encoding::{DataType, KeyDecoder};
+use crate::rocks::errors::{REDIS_INVALID_HLL_ERR, REDIS_WRONG_TYPE_ERR};
+use crate::rocks::transaction::RocksTransaction;
+use crate::rocks::{Result as RocksResult, CF_NAME_META, KEY_ENCODER};
+use crate::utils::{key_is_expired, notify_event, resp_err, resp_int};
+use crate::Frame;
+use rocksdb::ColumnFamilyRef;
+use std::hash::Hasher;
+use twox_hash::XxHash64;
+
+// 2^14 registers of 6 bits, same precision as redis
+const HLL_P: u32 = 14;
+const HLL_REGISTERS: usize = 1 << HLL_P;
+const HLL_BITS: usize = 6;
+const HLL_REGISTER_MAX: u8 = (1 << HLL_BITS) - 1;
+const HLL_DENSE_SIZE: usize = HLL_REGISTERS * HLL_BITS / 8;
+// bits of the hash left to count the run of zeroes
+const HLL_Q: u32 = 64 - HLL_P;
+const HLL_ALPHA_INF: f64 = 0.721_347_520_444_481_7;
+
+/// Sparse hyperloglogs are promoted to dense once they would grow over this
+/// number of bytes.
+pub const HLL_SPARSE_MAX_BYTES: usize = 3000;
+
+// first byte of the stored value
+const HLL_ENC_SPARSE: u8 = b's';
+const HLL_ENC_DENSE: u8 = b'd';
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Hll {
+    // (register, value) pairs sorted by register, registers not listed are 0
+    Sparse(Vec<(u16, u8)>),
+    // 6 bits per register, least significant bits first
+    Dense(Vec<u8>),
+}
+
+impl Default for Hll {
+    fn default() -> Self {
+        Hll::Sparse(vec![])
+    }
+}
+
+impl Hll {
+    /// Decode a value written by `encode`, `None` if it is corrupted.
+    pub fn decode(bytes: &[u8]) -> Option<Hll> {
+        let (enc, body) = bytes.split_first()?;
+        match *enc {
+            HLL_ENC_SPARSE if body.len() % 3 == 0 => {
+                let pairs: Vec<(u16, u8)> = body
+                    .chunks(3)
+                    .map(|c| (u16::from_be_bytes([c[0], c[1]]), c[2]))
+                    .collect();
+                let valid = pairs.windows(2).all(|w| w[0].0 < w[1].0)
+                    && pairs.iter().all(|(reg, val)| {
+                        (*reg as usize) < HLL_REGISTERS && *val > 0 && *val <= HLL_REGISTER_MAX
+                    });
+                valid.then_some(Hll::Sparse(pairs))
+            }
+            HLL_ENC_DENSE if body.len() == HLL_DENSE_SIZE => Some(Hll::Dense(body.to_vec())),
+            _ => None,
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            Hll::Sparse(pairs) => {
+                let mut buf = Vec::with_capacity(1 + pairs.len() * 3);
+                buf.push(HLL_ENC_SPARSE);
+                for (reg, val) in pairs {
+                    buf.extend_from_slice(&reg.to_be_bytes());
+                    buf.push(*val);
+                }
+                buf
+            }
+            Hll::Dense(registers) => {
+                let mut buf = Vec::with_capacity(1 + registers.len());
+                buf.push(HLL_ENC_DENSE);
+                buf.extend_from_slice(registers);
+                buf
+            }
+        }
+    }
+
+    pub fn is_sparse(&self) -> bool {
+        matches!(self, Hll::Sparse(_))
+    }
+
+    /// Add an element, returns true if a register was updated.
+    pub fn add(&mut self, element: &[u8]) -> bool {
+        let (reg, count) = hash_element(element);
+        self.set_max(reg, count)
+    }
+
+    fn set_max(&mut self, reg: usize, count: u8) -> bool {
+        match self {
+            Hll::Sparse(pairs) => {
+                match pairs.binary_search_by_key(&(reg as u16), |(r, _)| *r) {
+                    Ok(idx) if pairs[idx].1 >= count => return false,
+                    Ok(idx) => pairs[idx].1 = count,
+                    Err(idx) => pairs.insert(idx, (reg as u16, count)),
+                }
+                if pairs.len() * 3 > HLL_SPARSE_MAX_BYTES {
+                    self.promote();
+                }
+                true
+            }
+            Hll::Dense(registers) => {
+                if dense_get(registers, reg) >= count {
+                    return false;
+                }
+                dense_set(registers, reg, count);
+                true
+            }
+        }
+    }
+
+    /// Convert to the dense representation, the registers are unchanged.
+    pub fn promote(&mut self) {
+        if let Hll::Sparse(pairs) = self {
+            let mut registers = vec![0; HLL_DENSE_SIZE];
+            for (reg, val) in pairs.iter() {
+                dense_set(&mut registers, *reg as usize, *val);
+            }
+            *self = Hll::Dense(registers);
+        }
+    }
+
+    /// Merge the registers of `other`, keeping the max of both.
+    pub fn merge(&mut self, other: &Hll) {
+        match other {
+            Hll::Sparse(pairs) => {
+                for (reg, val) in pairs {
+                    self.set_max(*reg as usize, *val);
+                }
+            }
+            Hll::Dense(registers) => {
+                self.promote();
+                for reg in 0..HLL_REGISTERS {
+                    let val = dense_get(registers, reg);
+                    if val > 0 {
+                        self.set_max(reg, val);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Estimated cardinality, with the bias corrected estimator of Otmar
+    /// Ertl that redis uses instead of the empirical HLL++ tables.
+    pub fn count(&self) -> u64 {
+        let mut histogram = [0u32; 64];
+        match self {
+            Hll::Sparse(pairs) => {
+                histogram[0] = (HLL_REGISTERS - pairs.len()) as u32;
+                for (_, val) in pairs {
+                    histogram[*val as usize] += 1;
+                }
+            }
+            Hll::Dense(registers) => {
+                for reg in 0..HLL_REGISTERS {
+                    histogram[dense_get(registers, reg) as usize] += 1;
+                }
+            }
+        }
+
+        let m = HLL_REGISTERS as f64;
+        let q = HLL_Q as usize;
+        let mut z = m * tau((m - histogram[q + 1] as f64) / m);
+        for j in (1..=q).rev() {
+            z += histogram[j] as f64;
+            z *= 0.5;
+        }
+        z += m * sigma(histogram[0] as f64 / m);
+        (HLL_ALPHA_INF * m * m / z).round() as u64
+    }
+}
+
+// register index and length of the run of zeroes + 1 of the element hash
+fn hash_element(element: &[u8]) -> (usize, u8) {
+    let mut hasher = XxHash64::with_seed(0);
+    hasher.write(element);
+    let hash = hasher.finish();
+    let reg = (hash & (HLL_REGISTERS as u64 - 1)) as usize;
+    // the sentinel bit bounds the count to HLL_Q + 1
+    let count = ((hash >> HLL_P) | (1 << HLL_Q)).trailing_zeros() + 1;
+    (reg, count as u8)
+}
+
+fn dense_get(registers: &[u8], reg: usize) -> u8 {
+    let bit = reg * HLL_BITS;
+    let (byte, shift) = (bit / 8, bit % 8);
+    let lo = registers[byte] as u16;
+    let hi = registers.get(byte + 1).copied().unwrap_or(0) as u16;
+    (((lo | (hi << 8)) >> shift) as u8) & HLL_REGISTER_MAX
+}
+
+fn dense_set(registers: &mut [u8], reg: usize, val: u8) {
+    let bit = reg * HLL_BITS;
+    let (byte, shift) = (bit / 8, bit % 8);
+    let mask = (HLL_REGISTER_MAX as u16) << shift;
+    let val = (val as u16) << shift;
+    registers[byte] = (registers[byte] & !(mask as u8)) | val as u8;
+    if byte + 1 < registers.len() {
+        let (mask, val) = ((mask >> 8) as u8, (val >> 8) as u8);
+        registers[byte + 1] = (registers[byte + 1] & !mask) | val;
+    }
+}
+
+fn sigma(mut x: f64) -> f64 {
+    if x == 1.0 {
+        return f64::INFINITY;
+    }
+    let mut y = 1.0;
+    let mut z = x;
+    loop {
+        x *= x;
+        let z_prime = z;
+        z += x * y;
+        y += y;
+        if z_prime == z {
+            return z;
+        }
+    }
+}
+
+fn tau(mut x: f64) -> f64 {
+    if x == 0.0 || x == 1.0 {
+        return 0.0;
+    }
+    let mut y = 1.0;
+    let mut z = 1.0 - x;
+    loop {
+        x = x.sqrt();
+        let z_prime = z;
+        y *= 0.5;
+        z -= (1.0 - x).powi(2) * y;
+        if z_prime == z {
+            return z / 3.0;
+        }
+    }
+}
+
+pub struct HllCF<'a> {
+    meta_cf: ColumnFamilyRef<'a>,
+}
+
+impl<'a> HllCF<'a> {
+    pub fn new(client: &'a RocksClient) -> Self {
+        HllCF {
+            meta_cf: client.cf_handle(CF_NAME_META).unwrap(),
+        }
+    }
+}
+
+pub struct HllCommand<'a> {
+    client: &'a RocksClient,
+}
+
+impl<'a> HllCommand<'a> {
+    pub fn new(client: &'a RocksClient) -> Self {
+        Self { client }
+    }
+
+    pub async fn pfadd(self, key: &str, elements: &[String]) -> RocksResult<Frame> {
+        let client = self.client;
+        let cfs = HllCF::new(client);
+        let ekey = KEY_ENCODER.encode_string(key);
+
+        let resp = client.exec_txn(|txn| {
+            let (mut hll, ttl, created) =
+                match txn.get_for_update(cfs.meta_cf.clone(), ekey.clone())? {
+                    Some(val) => {
+                        if !matches!(KeyDecoder::decode_key_type(&val), DataType::Hll) {
+                            return Err(REDIS_WRONG_TYPE_ERR);
+                        }
+                        let ttl = KeyDecoder::decode_key_ttl(&val);
+                        if key_is_expired(ttl) {
+                            // overwritten below
+                            (Hll::default(), 0, true)
+                        } else {
+                            let hll = Hll::decode(KeyDecoder::decode_key_string_slice(&val))
+                                .ok_or(REDIS_INVALID_HLL_ERR)?;
+                            (hll, ttl, false)
+                        }
+                    }
+                    None => (Hll::default(), 0, true),
+                };
+
+            let mut updated = false;
+            for ele in elements {
+                updated |= hll.add(ele.as_bytes());
+            }
+            if !updated && !created {
+                return Ok(0);
+            }
+            let val = KEY_ENCODER.encode_hll_value(&hll.encode(), ttl);
+            txn.put_meta(cfs.meta_cf.clone(), ekey.clone(), val)?;
+            Ok(1)
+        });
+        cache_invalidate(ekey.as_ref());
+
+        match resp {
+            Ok(v) => {
+                if v > 0 {
+                    notify_event('$', "pfadd", key);
+                }
+                Ok(resp_int(v))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    /// Cardinality of the union of the hyperloglogs, missing keys are empty.
+    pub async fn pfcount(self, keys: &[String]) -> RocksResult<Frame> {
+        let client = self.client;
+        let cfs = HllCF::new(client);
+
+        let mut union: Option<Hll> = None;
+        for key in keys {
+            let ekey = KEY_ENCODER.encode_string(key);
+            let hll = match client.get(cfs.meta_cf.clone(), ekey)? {
+                Some(val) if !key_is_expired(KeyDecoder::decode_key_ttl(&val)) => {
+                    if !matches!(KeyDecoder::decode_key_type(&val), DataType::Hll) {
+                        return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                    }
+                    match Hll::decode(KeyDecoder::decode_key_string_slice(&val)) {
+                        Some(hll) => hll,
+                        None => return Ok(resp_err(REDIS_INVALID_HLL_ERR)),
+                    }
+                }
+                _ => continue,
+            };
+            match union.as_mut() {
+                Some(union) => union.merge(&hll),
+                None => union = Some(hll),
+            }
+        }
+        Ok(resp_int(union.map_or(0, |hll| hll.count()) as i64))
+    }
+
+    /// Write a hyperloglog value of a `RESTORE` payload as is, the key must
+    /// have been deleted in the transaction.
+    pub(crate) fn txn_restore(
+        &self,
+        txn: &RocksTransaction,
+        key: &str,
+        ttl: i64,
+        val: &[u8],
+    ) -> RocksResult<()> {
+        if Hll::decode(val).is_none() {
+            return Err(REDIS_INVALID_HLL_ERR);
+        }
+        let cfs = HllCF::new(self.client);
+        let ekey = KEY_ENCODER.encode_string(key);
+        let eval = KEY_ENCODER.encode_hll_value(val, ttl);
+        txn.put_meta(cfs.meta_cf, ekey, eval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hll, HLL_SPARSE_MAX_BYTES};
+
+    fn hll_of(range: std::ops::Range<u32>) -> Hll {
+        let mut hll = Hll::default();
+        for i in range {
+            hll.add(format!("element:{i}").as_bytes());
+        }
+        hll
+    }
+
+    #[test]
+    fn test_sparse_below_threshold() {
+        let hll = hll_of(0..100);
+        assert!(hll.is_sparse());
+        assert!(hll.encode().len() <= HLL_SPARSE_MAX_BYTES + 1);
+        assert_eq!(Hll::decode(&hll.encode()), Some(hll.clone()));
+        let count = hll.count();
+        assert!((97..=103).contains(&count), "count {count}");
+
+        // adding the same elements again changes nothing
+        let mut again = hll.clone();
+        assert!(!again.add(b"element:0"));
+        assert_eq!(again, hll);
+    }
+
+    #[test]
+    fn test_promotion_keeps_count() {
+        let mut hll = hll_of(0..900);
+        assert!(hll.is_sparse());
+        let before = hll.count();
+        let mut promoted = hll.clone();
+        promoted.promote();
+        assert!(!promoted.is_sparse());
+        assert_eq!(promoted.count(), before);
+
+        // adding more elements promotes on its own
+        for i in 900..5000 {
+            hll.add(format!("element:{i}").as_bytes());
+        }
+        assert!(!hll.is_sparse());
+        assert_eq!(Hll::decode(&hll.encode()), Some(hll.clone()));
+        let count = hll.count() as f64;
+        assert!((count - 5000.0).abs() / 5000.0 < 0.02, "count {count}");
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut hll = hll_of(0..2000);
+        hll.merge(&hll_of(1000..3000));
+        assert_eq!(hll, hll_of(0..3000));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::config::{
     async_del_list_threshold_or_default, async_expire_list_threshold_or_default,
     cmd_linsert_length_limit_or_default, cmd_lrem_length_limit_or_default,
+    list_max_ziplist_size_or_default, list_max_ziplist_value_or_default, LOGGER,
 };
 use crate::metrics::REMOVED_EXPIRED_KEY_COUNTER;
 use crate::rocks::client::{get_version_for_new, RocksClient};
@@ -17,11 +18,15 @@ use crate::rocks::{
     Result as RocksResult, TxnCommand, CF_NAME_GC, CF_NAME_GC_VERSION, CF_NAME_LIST_DATA,
     CF_NAME_META, KEY_ENCODER,
 };
-use crate::utils::{key_is_expired, resp_array, resp_bulk, resp_err, resp_int, resp_nil, resp_ok};
+use crate::utils::{
+    key_is_expired, notify_event, notify_list_pushed, resp_array, resp_bulk, resp_err, resp_int,
+    resp_nil, resp_ok,
+};
 use crate::Frame;
 use bytes::Bytes;
 use rocksdb::ColumnFamilyRef;
-use std::ops::RangeFrom;
+use slog::debug;
+use std::ops::{Range, RangeFrom};
 
 const INIT_INDEX: u64 = 1 << 32;
 
@@ -54,186 +59,52 @@ impl<'a> ListCommand<'a> {
 
     pub async fn push(self, key: &str, values: &Vec<Bytes>, op_left: bool) -> RocksResult<Frame> {
         let client = self.client;
-        let cfs = ListCF::new(client);
-        let key = key.to_owned();
-        let values = values.to_owned();
+        let resp = client.exec_txn(|txn| self.txn_push(txn, key, values, op_left));
 
-        let meta_key = KEY_ENCODER.encode_meta_key(&key);
+        match resp {
+            Ok(n) => {
+                notify_event('l', if op_left { "lpush" } else { "rpush" }, key);
+                notify_list_pushed(key);
+                Ok(resp_int(n as i64))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
 
+    /// `RPOPLPUSH`, move the tail element of `source` to the head of
+    /// `destination` in one transaction. Nil is replied if `source` is empty.
+    pub async fn rpoplpush(self, source: &str, destination: &str) -> RocksResult<Frame> {
+        let client = self.client;
         let resp = client.exec_txn(|txn| {
-            match txn.get_for_update(cfs.meta_cf.clone(), meta_key.clone())? {
-                Some(meta_value) => {
-                    // check key type and ttl
-                    if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
-                        return Err(REDIS_WRONG_TYPE_ERR);
-                    }
-                    let (ttl, mut version, mut left, mut right) =
-                        KeyDecoder::decode_key_list_meta(&meta_value);
-                    if key_is_expired(ttl) {
-                        self.txn_expire_if_needed(txn, client, &key)?;
-                        left = INIT_INDEX;
-                        right = INIT_INDEX;
-                        version = get_version_for_new(
-                            txn,
-                            cfs.gc_cf.clone(),
-                            cfs.gc_version_cf.clone(),
-                            &key,
-                        )?;
-                    }
-
-                    let mut idx: u64;
-                    for value in values {
-                        if op_left {
-                            left -= 1;
-                            idx = left;
-                        } else {
-                            idx = right;
-                            right += 1;
-                        }
-
-                        let data_key = KEY_ENCODER.encode_list_data_key(&key, idx, version);
-                        txn.put(cfs.data_cf.clone(), data_key, value.to_vec())?;
-                    }
-
-                    // update meta key
-                    let new_meta_value =
-                        KEY_ENCODER.encode_list_meta_value(ttl, version, left, right);
-                    txn.put(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
-
-                    Ok(right - left)
-                }
-                None => {
-                    // get next version available for new key
-                    let version = get_version_for_new(
-                        txn,
-                        cfs.gc_cf.clone(),
-                        cfs.gc_version_cf.clone(),
-                        &key,
-                    )?;
-
-                    let mut left = INIT_INDEX;
-                    let mut right = INIT_INDEX;
-                    let mut idx: u64;
-
-                    for value in values {
-                        if op_left {
-                            left -= 1;
-                            idx = left
-                        } else {
-                            idx = right;
-                            right += 1;
-                        }
-
-                        // add data key
-                        let data_key = KEY_ENCODER.encode_list_data_key(&key, idx, version);
-                        txn.put(cfs.data_cf.clone(), data_key, value.to_vec())?;
-                    }
-
-                    // add meta key
-                    let meta_value = KEY_ENCODER.encode_list_meta_value(0, version, left, right);
-                    txn.put(cfs.meta_cf.clone(), meta_key, meta_value)?;
-
-                    Ok(right - left)
-                }
-            }
+            let value = match self.txn_pop(txn, source, false, 1)?.pop() {
+                Some(Frame::Bulk(value)) => value,
+                _ => return Ok(None),
+            };
+            self.txn_push(txn, destination, &[value.clone()], true)?;
+            Ok(Some(value))
         });
 
         match resp {
-            Ok(n) => Ok(resp_int(n as i64)),
+            Ok(Some(value)) => {
+                notify_event('l', "rpop", source);
+                notify_event('l', "lpush", destination);
+                notify_list_pushed(destination);
+                Ok(Frame::Bulk(value))
+            }
+            Ok(None) => Ok(resp_nil()),
             Err(e) => Ok(resp_err(e)),
         }
     }
 
     pub async fn pop(self, key: &str, op_left: bool, count: i64) -> RocksResult<Frame> {
         let client = self.client;
-        let cfs = ListCF::new(client);
-        let key = key.to_owned();
-
-        let meta_key = KEY_ENCODER.encode_meta_key(&key);
-        let resp = client.exec_txn(|txn| {
-            let mut values = Vec::new();
-            match txn.get_for_update(cfs.meta_cf.clone(), meta_key.clone())? {
-                Some(meta_value) => {
-                    // check key type and ttl
-                    if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
-                        return Err(REDIS_WRONG_TYPE_ERR);
-                    }
-                    let (ttl, version, mut left, mut right) =
-                        KeyDecoder::decode_key_list_meta(&meta_value);
-                    if key_is_expired(ttl) {
-                        self.txn_expire_if_needed(txn, client, &key)?;
-                        return Ok(values);
-                    }
-
-                    let mut idx: u64;
-                    if count == 1 {
-                        if op_left {
-                            idx = left;
-                            left += 1;
-                        } else {
-                            right -= 1;
-                            idx = right;
-                        }
-                        let data_key = KEY_ENCODER.encode_list_data_key(&key, idx, version);
-                        // get data and delete
-                        let value = txn
-                            .get(cfs.data_cf.clone(), data_key.clone())
-                            .unwrap()
-                            .unwrap();
-                        values.push(resp_bulk(value));
-
-                        txn.del(cfs.data_cf.clone(), data_key)?;
-
-                        if left == right {
-                            // delete meta key
-                            txn.del(cfs.meta_cf.clone(), meta_key)?;
-                        } else {
-                            // update meta key
-                            let new_meta_value =
-                                KEY_ENCODER.encode_list_meta_value(ttl, version, left, right);
-                            txn.put(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
-                        }
-                        Ok(values)
-                    } else {
-                        let mut real_count = count as u64;
-                        if real_count > right - left {
-                            real_count = right - left;
-                        }
-
-                        let mut data_keys = Vec::with_capacity(real_count as usize);
-                        for _ in 0..real_count {
-                            if op_left {
-                                idx = left;
-                                left += 1;
-                            } else {
-                                idx = right - 1;
-                                right -= 1;
-                            }
-                            data_keys.push(KEY_ENCODER.encode_list_data_key(&key, idx, version));
-                        }
-                        for pair in txn.batch_get(cfs.data_cf.clone(), data_keys)? {
-                            values.push(resp_bulk(pair.1));
-                            txn.del(cfs.data_cf.clone(), pair.0)?;
-                        }
-
-                        if left == right {
-                            // all elements popped, just delete meta key
-                            txn.del(cfs.meta_cf.clone(), meta_key)?;
-                        } else {
-                            // update meta key
-                            let new_meta_value =
-                                KEY_ENCODER.encode_list_meta_value(ttl, version, left, right);
-                            txn.put(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
-                        }
-                        Ok(values)
-                    }
-                }
-                None => Ok(values),
-            }
-        });
+        let resp = client.exec_txn(|txn| self.txn_pop(txn, key, op_left, count));
 
         match resp {
             Ok(values) => {
+                if !values.is_empty() {
+                    notify_event('l', if op_left { "lpop" } else { "rpop" }, key);
+                }
                 if values.is_empty() {
                     Ok(resp_nil())
                 } else if values.len() == 1 {
@@ -246,6 +117,33 @@ impl<'a> ListCommand<'a> {
         }
     }
 
+    /// Pop up to `count` elements from the first non-empty list of `keys`,
+    /// reply the key and the popped elements.
+    pub async fn lmpop(self, keys: &[String], op_left: bool, count: i64) -> RocksResult<Frame> {
+        let client = self.client;
+        let resp = client.exec_txn(|txn| {
+            for key in keys {
+                let values = self.txn_pop(txn, key, op_left, count)?;
+                if !values.is_empty() {
+                    return Ok(Some((key, values)));
+                }
+            }
+            Ok(None)
+        });
+
+        match resp {
+            Ok(Some((key, values))) => {
+                notify_event('l', if op_left { "lpop" } else { "rpop" }, key);
+                Ok(resp_array(vec![
+                    resp_bulk(key.as_bytes().to_vec()),
+                    resp_array(values),
+                ]))
+            }
+            Ok(None) => Ok(resp_nil()),
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
     pub async fn ltrim(self, key: &str, mut start: i64, mut end: i64) -> RocksResult<Frame> {
         let client = self.client;
         let cfs = ListCF::new(client);
@@ -266,6 +164,10 @@ impl<'a> ListCommand<'a> {
                         return Ok(());
                     }
 
+                    if let Some(elements) = KeyDecoder::decode_key_list_compact(&meta_value) {
+                        self.txn_migrate_compact(txn, &key, ttl, version, left, elements)?;
+                    }
+
                     // convert start and end to positive
                     let len = (right - left) as i64;
                     if start < 0 {
@@ -317,12 +219,12 @@ impl<'a> ListCommand<'a> {
                     // check key if empty
                     if left >= right {
                         // delete meta key
-                        txn.del(cfs.meta_cf.clone(), meta_key)?;
+                        txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
                     } else {
                         // update meta key
                         let new_meta_value =
                             KEY_ENCODER.encode_list_meta_value(ttl, version, left, right);
-                        txn.put(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
+                        txn.put_meta(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
                     }
                     Ok(())
                 }
@@ -331,7 +233,10 @@ impl<'a> ListCommand<'a> {
         });
 
         match resp {
-            Ok(_) => Ok(resp_ok()),
+            Ok(_) => {
+                notify_event('l', "ltrim", key);
+                Ok(resp_ok())
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
@@ -374,6 +279,16 @@ impl<'a> ListCommand<'a> {
                         real_length = llen;
                     }
 
+                    if let Some(elements) = KeyDecoder::decode_key_list_compact(&meta_value) {
+                        let resp = elements
+                            .into_iter()
+                            .skip(r_left.max(0) as usize)
+                            .take(real_length as usize)
+                            .map(resp_bulk)
+                            .collect();
+                        return Ok(resp_array(resp));
+                    }
+
                     let data_key_start =
                         KEY_ENCODER.encode_list_data_key(&key, real_left as u64, version);
                     let range: RangeFrom<Key> = data_key_start..;
@@ -445,6 +360,13 @@ impl<'a> ListCommand<'a> {
                         idx += len as i64;
                     }
 
+                    if let Some(mut elements) = KeyDecoder::decode_key_list_compact(&meta_value) {
+                        if idx < 0 || idx as u64 >= len {
+                            return Ok(resp_nil());
+                        }
+                        return Ok(resp_bulk(elements.swap_remove(idx as usize)));
+                    }
+
                     let real_idx = left as i64 + idx;
 
                     // get value from data key
@@ -460,6 +382,105 @@ impl<'a> ListCommand<'a> {
         })
     }
 
+    /// Positions of `ele` in the list. A negative `rank` searches from the
+    /// tail, `maxlen` limits the compared elements from the starting end and
+    /// `count` 0 returns all matches. Without `count` only the first matched
+    /// position is replied.
+    pub async fn lpos(
+        self,
+        key: &str,
+        ele: &Bytes,
+        rank: i64,
+        count: Option<usize>,
+        maxlen: usize,
+    ) -> RocksResult<Frame> {
+        let client = self.client;
+        let cfs = ListCF::new(client);
+        let key = key.to_owned();
+        let not_found = || match count {
+            Some(_) => resp_array(vec![]),
+            None => resp_nil(),
+        };
+
+        let meta_key = KEY_ENCODER.encode_meta_key(&key);
+        client.exec_txn(|txn| {
+            match txn.get(cfs.meta_cf.clone(), meta_key.clone())? {
+                Some(meta_value) => {
+                    // check key type and ttl
+                    if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
+                        return Err(REDIS_WRONG_TYPE_ERR);
+                    }
+                    let (ttl, version, left, right) = KeyDecoder::decode_key_list_meta(&meta_value);
+                    if key_is_expired(ttl) {
+                        self.txn_expire_if_needed(txn, client, &key)?;
+                        return Ok(not_found());
+                    }
+
+                    // positions of the compared window, from the head or the tail
+                    let len = right - left;
+                    let window = if maxlen == 0 {
+                        len
+                    } else {
+                        len.min(maxlen as u64)
+                    };
+                    let (start, end) = if rank > 0 {
+                        (0, window)
+                    } else {
+                        (len - window, len)
+                    };
+
+                    let elements: Vec<(u64, Vec<u8>)> =
+                        match KeyDecoder::decode_key_list_compact(&meta_value) {
+                            Some(elements) => (start..end)
+                                .zip(elements.into_iter().skip(start as usize))
+                                .collect(),
+                            None => {
+                                let data_key_start =
+                                    KEY_ENCODER.encode_list_data_key(&key, left + start, version);
+                                let data_key_end =
+                                    KEY_ENCODER.encode_list_data_key(&key, left + end, version);
+                                let range: Range<Key> = data_key_start..data_key_end;
+                                let bound_range: BoundRange = range.into();
+                                txn.scan(cfs.data_cf.clone(), bound_range, window as u32)?
+                                    .map(|kv| {
+                                        let idx = KeyDecoder::decode_key_list_idx_from_datakey(
+                                            &key, kv.0,
+                                        );
+                                        (idx - left, kv.1)
+                                    })
+                                    .collect()
+                            }
+                        };
+
+                    let matched = elements.into_iter().filter(|(_, v)| v == ele.as_ref());
+                    let skip = (rank.unsigned_abs() - 1) as usize;
+                    let positions: Vec<u64> = if rank > 0 {
+                        matched.skip(skip).map(|(idx, _)| idx).collect()
+                    } else {
+                        matched.rev().skip(skip).map(|(idx, _)| idx).collect()
+                    };
+
+                    match count {
+                        Some(count) => {
+                            let take = if count == 0 { positions.len() } else { count };
+                            Ok(resp_array(
+                                positions
+                                    .into_iter()
+                                    .take(take)
+                                    .map(|idx| resp_int(idx as i64))
+                                    .collect(),
+                            ))
+                        }
+                        None => Ok(positions
+                            .first()
+                            .map_or_else(resp_nil, |idx| resp_int(*idx as i64))),
+                    }
+                }
+                None => Ok(not_found()),
+            }
+        })
+    }
+
     pub async fn lset(self, key: &str, mut idx: i64, ele: &Bytes) -> RocksResult<Frame> {
         let client = self.client;
         let cfs = ListCF::new(client);
@@ -490,6 +511,12 @@ impl<'a> ListCommand<'a> {
                         return Err(REDIS_INDEX_OUT_OF_RANGE_ERR);
                     }
 
+                    if let Some(mut elements) = KeyDecoder::decode_key_list_compact(&meta_value) {
+                        elements[idx as usize] = ele.to_vec();
+                        self.txn_save_elements(txn, &key, ttl, version, left, elements)?;
+                        return Ok(());
+                    }
+
                     let data_key = KEY_ENCODER.encode_list_data_key(&key, uidx as u64, version);
                     // data keys exists, update it to new value
                     txn.put(cfs.data_cf.clone(), data_key, ele.to_vec())?;
@@ -500,7 +527,10 @@ impl<'a> ListCommand<'a> {
         });
 
         match resp {
-            Ok(_) => Ok(resp_ok()),
+            Ok(_) => {
+                notify_event('l', "lset", key);
+                Ok(resp_ok())
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
@@ -533,6 +563,19 @@ impl<'a> ListCommand<'a> {
                         return Ok(0);
                     }
 
+                    if let Some(mut elements) = KeyDecoder::decode_key_list_compact(&meta_value) {
+                        // the list is left untouched if the pivot is not found
+                        let idx = match elements.iter().position(|e| e[..] == pivot[..]) {
+                            Some(idx) => idx,
+                            None => return Ok(-1),
+                        };
+                        let idx_op = if before_pivot { idx } else { idx + 1 };
+                        elements.insert(idx_op, element.to_vec());
+                        let len = elements.len() as i64;
+                        self.txn_save_elements(txn, &key, ttl, version, left, elements)?;
+                        return Ok(len);
+                    }
+
                     // check list length is not too long
                     let limit_len = cmd_linsert_length_limit_or_default();
                     if limit_len > 0 && right - left > limit_len as u64 {
@@ -542,17 +585,13 @@ impl<'a> ListCommand<'a> {
                     // get list items bound range
                     let bound_range = KEY_ENCODER.encode_list_data_key_range(&key, version);
 
-                    // iter will only return the matched kvpair
+                    // iter will only return the matched kvpair, data keys are
+                    // scanned by index so the first one is the leftmost pivot
                     let mut iter = txn
                         .scan(cfs.data_cf.clone(), bound_range, u32::MAX)?
-                        .filter(|kv| {
-                            if kv.1 == pivot.to_vec() {
-                                return true;
-                            }
-                            false
-                        });
+                        .filter(|kv| kv.1[..] == pivot[..]);
 
-                    // yeild the first matched kvpair
+                    // yield the first matched kvpair
                     if let Some(kv) = iter.next() {
                         // decode the idx from data key
                         let idx = KeyDecoder::decode_key_list_idx_from_datakey(&key, kv.0);
@@ -620,7 +659,7 @@ impl<'a> ListCommand<'a> {
                         // update meta key
                         let new_meta_value =
                             KEY_ENCODER.encode_list_meta_value(ttl, version, left, right);
-                        txn.put(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
+                        txn.put_meta(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
 
                         let len = (right - left) as i64;
                         Ok(len)
@@ -634,7 +673,12 @@ impl<'a> ListCommand<'a> {
         });
 
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(v) => {
+                if v > 0 {
+                    notify_event('l', "linsert", key);
+                }
+                Ok(resp_int(v))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
@@ -665,6 +709,10 @@ impl<'a> ListCommand<'a> {
                         return Ok(0);
                     }
 
+                    if let Some(elements) = KeyDecoder::decode_key_list_compact(&meta_value) {
+                        self.txn_migrate_compact(txn, &key, ttl, version, left, elements)?;
+                    }
+
                     let len = right - left;
 
                     // check list length is not too long
@@ -735,7 +783,7 @@ impl<'a> ListCommand<'a> {
 
                         // update meta key or delete it if no element left
                         if len == removed_count as u64 {
-                            txn.del(cfs.meta_cf.clone(), meta_key)?;
+                            txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
                         } else {
                             let new_meta_value = KEY_ENCODER.encode_list_meta_value(
                                 ttl,
@@ -743,7 +791,7 @@ impl<'a> ListCommand<'a> {
                                 left,
                                 right - removed_count as u64,
                             );
-                            txn.put(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
+                            txn.put_meta(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
                         }
                     } else {
                         let iter = txn.scan_reverse(cfs.data_cf.clone(), bound_range, u32::MAX)?;
@@ -778,7 +826,7 @@ impl<'a> ListCommand<'a> {
 
                         // update meta key or delete it if no element left
                         if len == removed_count as u64 {
-                            txn.del(cfs.meta_cf.clone(), meta_key)?;
+                            txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
                         } else {
                             let new_meta_value = KEY_ENCODER.encode_list_meta_value(
                                 ttl,
@@ -786,7 +834,7 @@ impl<'a> ListCommand<'a> {
                                 left + removed_count as u64,
                                 right,
                             );
-                            txn.put(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
+                            txn.put_meta(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
                         }
                     }
                     Ok(removed_count as i64)
@@ -796,14 +844,330 @@ impl<'a> ListCommand<'a> {
         });
 
         match resp {
-            Ok(_) => Ok(resp_ok()),
+            Ok(n) => {
+                if n > 0 {
+                    notify_event('l', "lrem", key);
+                }
+                Ok(resp_ok())
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 }
 
+impl ListCommand<'_> {
+    // pop up to `count` elements of the list, empty if the key does not exist
+    fn txn_push(
+        &self,
+        txn: &RocksTransaction,
+        key: &str,
+        values: &[Bytes],
+        op_left: bool,
+    ) -> RocksResult<u64> {
+        let client = self.client;
+        let cfs = ListCF::new(client);
+        let key = key.to_owned();
+        let meta_key = KEY_ENCODER.encode_meta_key(&key);
+
+        match txn.get_for_update(cfs.meta_cf.clone(), meta_key.clone())? {
+            Some(meta_value) => {
+                // check key type and ttl
+                if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
+                    return Err(REDIS_WRONG_TYPE_ERR);
+                }
+                let (ttl, version, mut left, mut right) =
+                    KeyDecoder::decode_key_list_meta(&meta_value);
+                if key_is_expired(ttl) {
+                    self.txn_expire_if_needed(txn, client, &key)?;
+                    let version = get_version_for_new(
+                        txn,
+                        cfs.gc_cf.clone(),
+                        cfs.gc_version_cf.clone(),
+                        &key,
+                    )?;
+                    let (left, elements) = Self::push_elements(vec![], INIT_INDEX, values, op_left);
+                    let len = elements.len() as u64;
+                    self.txn_save_elements(txn, &key, 0, version, left, elements)?;
+                    return Ok(len);
+                }
+
+                if let Some(elements) = KeyDecoder::decode_key_list_compact(&meta_value) {
+                    let (left, elements) = Self::push_elements(elements, left, values, op_left);
+                    let len = elements.len() as u64;
+                    self.txn_save_elements(txn, &key, ttl, version, left, elements)?;
+                    return Ok(len);
+                }
+
+                let mut idx: u64;
+                for value in values {
+                    if op_left {
+                        left -= 1;
+                        idx = left;
+                    } else {
+                        idx = right;
+                        right += 1;
+                    }
+
+                    let data_key = KEY_ENCODER.encode_list_data_key(&key, idx, version);
+                    txn.put(cfs.data_cf.clone(), data_key, value.to_vec())?;
+                }
+
+                // update meta key
+                let new_meta_value = KEY_ENCODER.encode_list_meta_value(ttl, version, left, right);
+                txn.put_meta(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
+
+                Ok(right - left)
+            }
+            None => {
+                // get next version available for new key
+                let version =
+                    get_version_for_new(txn, cfs.gc_cf.clone(), cfs.gc_version_cf.clone(), &key)?;
+
+                if Self::fits_compact(values.iter()) {
+                    let (left, elements) = Self::push_elements(vec![], INIT_INDEX, values, op_left);
+                    let len = elements.len() as u64;
+                    self.txn_save_elements(txn, &key, 0, version, left, elements)?;
+                    return Ok(len);
+                }
+
+                let mut left = INIT_INDEX;
+                let mut right = INIT_INDEX;
+                let mut idx: u64;
+
+                for value in values {
+                    if op_left {
+                        left -= 1;
+                        idx = left
+                    } else {
+                        idx = right;
+                        right += 1;
+                    }
+
+                    // add data key
+                    let data_key = KEY_ENCODER.encode_list_data_key(&key, idx, version);
+                    txn.put(cfs.data_cf.clone(), data_key, value.to_vec())?;
+                }
+
+                // add meta key
+                let meta_value = KEY_ENCODER.encode_list_meta_value(0, version, left, right);
+                txn.put_meta(cfs.meta_cf.clone(), meta_key, meta_value)?;
+
+                Ok(right - left)
+            }
+        }
+    }
+
+    fn txn_pop(
+        &self,
+        txn: &RocksTransaction,
+        key: &str,
+        op_left: bool,
+        count: i64,
+    ) -> RocksResult<Vec<Frame>> {
+        let client = self.client;
+        let cfs = ListCF::new(client);
+        let key = key.to_owned();
+        let meta_key = KEY_ENCODER.encode_meta_key(&key);
+
+        let mut values = Vec::new();
+        match txn.get_for_update(cfs.meta_cf.clone(), meta_key.clone())? {
+            Some(meta_value) => {
+                // check key type and ttl
+                if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
+                    return Err(REDIS_WRONG_TYPE_ERR);
+                }
+                let (ttl, version, mut left, mut right) =
+                    KeyDecoder::decode_key_list_meta(&meta_value);
+                if key_is_expired(ttl) {
+                    self.txn_expire_if_needed(txn, client, &key)?;
+                    return Ok(values);
+                }
+
+                if let Some(mut elements) = KeyDecoder::decode_key_list_compact(&meta_value) {
+                    let real_count = (count as usize).min(elements.len());
+                    let poped: Vec<Vec<u8>> = if op_left {
+                        left += real_count as u64;
+                        elements.drain(..real_count).collect()
+                    } else {
+                        elements
+                            .drain(elements.len() - real_count..)
+                            .rev()
+                            .collect()
+                    };
+                    values.extend(poped.into_iter().map(resp_bulk));
+                    self.txn_save_elements(txn, &key, ttl, version, left, elements)?;
+                    return Ok(values);
+                }
+
+                let mut idx: u64;
+                if count == 1 {
+                    if op_left {
+                        idx = left;
+                        left += 1;
+                    } else {
+                        right -= 1;
+                        idx = right;
+                    }
+                    let data_key = KEY_ENCODER.encode_list_data_key(&key, idx, version);
+                    // get data and delete
+                    let value = txn
+                        .get(cfs.data_cf.clone(), data_key.clone())
+                        .unwrap()
+                        .unwrap();
+                    values.push(resp_bulk(value));
+
+                    txn.del(cfs.data_cf.clone(), data_key)?;
+
+                    if left == right {
+                        // delete meta key
+                        txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
+                    } else {
+                        // update meta key
+                        let new_meta_value =
+                            KEY_ENCODER.encode_list_meta_value(ttl, version, left, right);
+                        txn.put_meta(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
+                    }
+                    Ok(values)
+                } else {
+                    let mut real_count = count as u64;
+                    if real_count > right - left {
+                        real_count = right - left;
+                    }
+
+                    let mut data_keys = Vec::with_capacity(real_count as usize);
+                    for _ in 0..real_count {
+                        if op_left {
+                            idx = left;
+                            left += 1;
+                        } else {
+                            idx = right - 1;
+                            right -= 1;
+                        }
+                        data_keys.push(KEY_ENCODER.encode_list_data_key(&key, idx, version));
+                    }
+                    for pair in txn.batch_get(cfs.data_cf.clone(), data_keys)? {
+                        values.push(resp_bulk(pair.1));
+                        txn.del(cfs.data_cf.clone(), pair.0)?;
+                    }
+
+                    if left == right {
+                        // all elements popped, just delete meta key
+                        txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
+                    } else {
+                        // update meta key
+                        let new_meta_value =
+                            KEY_ENCODER.encode_list_meta_value(ttl, version, left, right);
+                        txn.put_meta(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
+                    }
+                    Ok(values)
+                }
+            }
+            None => Ok(values),
+        }
+    }
+
+    fn fits_compact<T: AsRef<[u8]>>(mut elements: impl Iterator<Item = T>) -> bool {
+        let max_size = list_max_ziplist_size_or_default();
+        let max_value = list_max_ziplist_value_or_default();
+        let mut len = 0;
+        elements.all(|e| {
+            len += 1;
+            len <= max_size && e.as_ref().len() <= max_value
+        })
+    }
+
+    // push values to the inline elements, return the new left index and elements
+    fn push_elements(
+        elements: Vec<Vec<u8>>,
+        left: u64,
+        values: &[Bytes],
+        op_left: bool,
+    ) -> (u64, Vec<Vec<u8>>) {
+        if op_left {
+            let mut pushed: Vec<Vec<u8>> = values.iter().rev().map(|v| v.to_vec()).collect();
+            pushed.extend(elements);
+            (left - values.len() as u64, pushed)
+        } else {
+            let mut elements = elements;
+            elements.extend(values.iter().map(|v| v.to_vec()));
+            (left, elements)
+        }
+    }
+
+    // store elements inline in meta value if they fit the compact thresholds,
+    // otherwise migrate them to data keys
+    fn txn_save_elements(
+        &self,
+        txn: &RocksTransaction,
+        key: &str,
+        ttl: i64,
+        version: u16,
+        left: u64,
+        elements: Vec<Vec<u8>>,
+    ) -> RocksResult<()> {
+        let cfs = ListCF::new(self.client);
+        let meta_key = KEY_ENCODER.encode_meta_key(key);
+        if elements.is_empty() {
+            txn.del_meta(cfs.meta_cf, meta_key)?;
+            return Ok(());
+        }
+        if Self::fits_compact(elements.iter()) {
+            let right = left + elements.len() as u64;
+            let meta_value =
+                KEY_ENCODER.encode_list_compact_meta_value(ttl, version, left, right, &elements);
+            txn.put_meta(cfs.meta_cf, meta_key, meta_value)?;
+            return Ok(());
+        }
+        self.txn_migrate_compact(txn, key, ttl, version, left, elements)
+    }
+
+    // write elements to data keys starting from left index, keep the list meta
+    // in the full layout
+    fn txn_migrate_compact(
+        &self,
+        txn: &RocksTransaction,
+        key: &str,
+        ttl: i64,
+        version: u16,
+        left: u64,
+        elements: Vec<Vec<u8>>,
+    ) -> RocksResult<()> {
+        debug!(LOGGER, "migrate compact list {} to full layout", key);
+        let cfs = ListCF::new(self.client);
+        let right = left + elements.len() as u64;
+        for (idx, element) in (left..right).zip(elements) {
+            let data_key = KEY_ENCODER.encode_list_data_key(key, idx, version);
+            txn.put(cfs.data_cf.clone(), data_key, element)?;
+        }
+        let meta_key = KEY_ENCODER.encode_meta_key(key);
+        let meta_value = KEY_ENCODER.encode_list_meta_value(ttl, version, left, right);
+        txn.put_meta(cfs.meta_cf, meta_key, meta_value)?;
+        Ok(())
+    }
+
+    /// Create the list from the elements of a `RESTORE` payload, the key must
+    /// have been deleted in the transaction.
+    pub(crate) fn txn_restore(
+        &self,
+        txn: &RocksTransaction,
+        key: &str,
+        ttl: i64,
+        elements: Vec<Vec<u8>>,
+    ) -> RocksResult<()> {
+        let cfs = ListCF::new(self.client);
+        let version = get_version_for_new(txn, cfs.gc_cf.clone(), cfs.gc_version_cf.clone(), key)?;
+        self.txn_save_elements(txn, key, ttl, version, INIT_INDEX, elements)
+    }
+}
+
 impl TxnCommand for ListCommand<'_> {
-    fn txn_del(&self, txn: &RocksTransaction, client: &RocksClient, key: &str) -> RocksResult<()> {
+    fn txn_del(
+        &self,
+        txn: &RocksTransaction,
+        client: &RocksClient,
+        key: &str,
+        always_async: bool,
+    ) -> RocksResult<()> {
         let key = key.to_owned();
         let meta_key = KEY_ENCODER.encode_meta_key(&key);
         let cfs = ListCF::new(client);
@@ -813,10 +1177,10 @@ impl TxnCommand for ListCommand<'_> {
                 let (_, version, left, right) = KeyDecoder::decode_key_list_meta(&meta_value);
                 let len = right - left;
 
-                if len >= async_del_list_threshold_or_default() as u64 {
+                if always_async || len >= async_del_list_threshold_or_default() as u64 {
                     // async delete
                     // delete meta key and create gc key and gc version key with the version
-                    txn.del(cfs.meta_cf.clone(), meta_key)?;
+                    txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
 
                     let gc_key = KEY_ENCODER.encode_gc_key(&key);
                     txn.put(cfs.gc_cf.clone(), gc_key, version.to_be_bytes().to_vec())?;
@@ -825,7 +1189,7 @@ impl TxnCommand for ListCommand<'_> {
                     txn.put(
                         cfs.gc_version_cf.clone(),
                         gc_version_key,
-                        vec![KEY_ENCODER.get_type_bytes(DataType::List)],
+                        KEY_ENCODER.encode_gc_version_value(DataType::List, len as u64),
                     )?;
                 } else {
                     let bound_range = KEY_ENCODER.encode_list_data_key_range(&key, version);
@@ -834,7 +1198,7 @@ impl TxnCommand for ListCommand<'_> {
                     for k in iter {
                         txn.del(cfs.data_cf.clone(), k)?;
                     }
-                    txn.del(cfs.meta_cf.clone(), meta_key)?;
+                    txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
                 }
                 Ok(())
             }
@@ -863,7 +1227,7 @@ impl TxnCommand for ListCommand<'_> {
                 if len >= async_expire_list_threshold_or_default() as u64 {
                     // async delete
                     // delete meta key and create gc key and gc version key with the version
-                    txn.del(cfs.meta_cf.clone(), meta_key)?;
+                    txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
 
                     let gc_key = KEY_ENCODER.encode_gc_key(&key);
                     txn.put(cfs.gc_cf.clone(), gc_key, version.to_be_bytes().to_vec())?;
@@ -872,7 +1236,7 @@ impl TxnCommand for ListCommand<'_> {
                     txn.put(
                         cfs.gc_version_cf.clone(),
                         gc_version_key,
-                        vec![KEY_ENCODER.get_type_bytes(DataType::List)],
+                        KEY_ENCODER.encode_gc_version_value(DataType::List, len as u64),
                     )?;
                 } else {
                     let bound_range = KEY_ENCODER.encode_list_data_key_range(&key, version);
@@ -881,12 +1245,13 @@ impl TxnCommand for ListCommand<'_> {
                     for k in iter {
                         txn.del(cfs.data_cf.clone(), k)?;
                     }
-                    txn.del(cfs.meta_cf.clone(), meta_key)?;
+                    txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
                 }
 
                 REMOVED_EXPIRED_KEY_COUNTER
                     .with_label_values(&["list"])
                     .inc();
+                notify_event('x', "expired", &key);
                 Ok(1)
             }
             None => Ok(0),
@@ -909,8 +1274,12 @@ impl TxnCommand for ListCommand<'_> {
             return Ok(0);
         }
         let (_, version, left, right) = KeyDecoder::decode_key_list_meta(meta_value);
-        let new_meta_value = KEY_ENCODER.encode_list_meta_value(timestamp, version, left, right);
-        txn.put(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
+        let new_meta_value = match KeyDecoder::decode_key_list_compact(meta_value) {
+            Some(elements) => KEY_ENCODER
+                .encode_list_compact_meta_value(timestamp, version, left, right, &elements),
+            None => KEY_ENCODER.encode_list_meta_value(timestamp, version, left, right),
+        };
+        txn.put_meta(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
         Ok(1)
     }
 
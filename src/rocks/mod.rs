@@ -1,18 +1,32 @@
-use crate::config::{config_meta_key_number_or_default, data_store_dir_or_default};
+use crate::config::{
+    config_meta_key_number_or_default, data_store_dir_or_default,
+    enable_compaction_filter_or_default, rocks_block_cache_mb_or_default, rocks_cf_block_cache_mb,
+    rocks_compression_type_or_default, rocks_level0_file_num_compaction_trigger_or_default,
+    rocks_manual_wal_flush_or_default, rocks_max_write_buffer_number_or_default,
+    rocks_wal_bytes_per_sync_or_default, rocks_wal_recovery_mode_or_default,
+    rocks_write_buffer_size_mb_or_default,
+};
 use crate::fetch_idx_and_add;
+use crate::metrics::KEYSPACE_KEY_COUNT;
 use crate::rocks::client::RocksClient;
-use crate::rocks::encoding::KeyEncoder;
+use crate::rocks::compaction_filter::{expiry_filter, EXPIRY_FILTER_NAME};
+use crate::rocks::encoding::{KeyDecoder, KeyEncoder};
 use crate::rocks::errors::RError;
 use crate::rocks::kv::value::Value;
 use crate::rocks::transaction::RocksTransaction;
 use lazy_static::lazy_static;
-use rocksdb::{MultiThreaded, Options, TransactionDB, TransactionDBOptions};
+use rocksdb::{
+    BlockBasedOptions, Cache, ColumnFamilyDescriptor, DBCompressionType, DBRecoveryMode,
+    MultiThreaded, Options, TransactionDB, TransactionDBOptions,
+};
 use std::sync::Arc;
 
 pub mod client;
+pub mod compaction_filter;
 pub mod encoding;
 pub mod errors;
 pub mod hash;
+pub mod hll;
 pub mod kv;
 pub mod list;
 pub mod set;
@@ -31,9 +45,27 @@ pub const CF_NAME_HASH_DATA: &str = "hash_data";
 pub const CF_NAME_ZSET_SUB_META: &str = "zset_sub_meta";
 pub const CF_NAME_ZSET_DATA: &str = "zset_data";
 pub const CF_NAME_ZSET_SCORE: &str = "zset_score";
+pub const CF_NAME_ACCESS: &str = "access";
+
+pub const CF_NAMES: [&str; 12] = [
+    CF_NAME_META,
+    CF_NAME_GC,
+    CF_NAME_GC_VERSION,
+    CF_NAME_SET_SUB_META,
+    CF_NAME_SET_DATA,
+    CF_NAME_LIST_DATA,
+    CF_NAME_HASH_SUB_META,
+    CF_NAME_HASH_DATA,
+    CF_NAME_ZSET_SUB_META,
+    CF_NAME_ZSET_DATA,
+    CF_NAME_ZSET_SCORE,
+    CF_NAME_ACCESS,
+];
 
 pub type Result<T> = anyhow::Result<T, RError>;
 
+const MB: usize = 1024 * 1024;
+
 pub static mut INSTANCE_ID: u64 = 0;
 
 lazy_static! {
@@ -42,7 +74,15 @@ lazy_static! {
 }
 
 pub trait TxnCommand {
-    fn txn_del(&self, txn: &RocksTransaction, client: &RocksClient, key: &str) -> Result<()>;
+    /// Delete the key, collections above the async deletion threshold, or
+    /// all of them if `always_async`, are left to the gc workers.
+    fn txn_del(
+        &self,
+        txn: &RocksTransaction,
+        client: &RocksClient,
+        key: &str,
+        always_async: bool,
+    ) -> Result<()>;
 
     fn txn_expire_if_needed(
         &self,
@@ -71,36 +111,63 @@ pub trait TxnCommand {
 
 fn new_client() -> Result<RocksClient> {
     let db = new_db()?;
-    Ok(RocksClient::new(Arc::new(db)))
+    let client = RocksClient::new(Arc::new(db));
+    client.reconcile_key_count()?;
+    Ok(client)
 }
 
 fn new_db() -> Result<TransactionDB<MultiThreaded>> {
-    let mut opts = Options::default();
+    let shared_cache = Cache::new_lru_cache(rocks_block_cache_mb_or_default() * MB)?;
+    let mut opts = cf_options("default", &shared_cache)?;
     let transaction_opts = TransactionDBOptions::default();
     opts.create_if_missing(true);
     opts.create_missing_column_families(true);
+    opts.set_wal_bytes_per_sync(rocks_wal_bytes_per_sync_or_default());
+    opts.set_manual_wal_flush(rocks_manual_wal_flush_or_default());
+    opts.set_wal_recovery_mode(match rocks_wal_recovery_mode_or_default().as_str() {
+        "tolerate_data_loss" => DBRecoveryMode::SkipAnyCorruptedRecord,
+        "tolerate_corrupted_tail_records" => DBRecoveryMode::TolerateCorruptedTailRecords,
+        "absolute_consistency" => DBRecoveryMode::AbsoluteConsistency,
+        _ => DBRecoveryMode::PointInTime,
+    });
 
-    let cf_names = vec![
-        CF_NAME_META,
-        CF_NAME_GC,
-        CF_NAME_GC_VERSION,
-        CF_NAME_SET_SUB_META,
-        CF_NAME_SET_DATA,
-        CF_NAME_LIST_DATA,
-        CF_NAME_HASH_SUB_META,
-        CF_NAME_HASH_DATA,
-        CF_NAME_ZSET_SUB_META,
-        CF_NAME_ZSET_DATA,
-        CF_NAME_ZSET_SCORE,
-    ];
-
-    TransactionDB::open_cf(
-        &opts,
-        &transaction_opts,
-        data_store_dir_or_default(),
-        cf_names,
-    )
-    .map_err(|e| e.into())
+    let mut cfs = Vec::with_capacity(CF_NAMES.len());
+    for name in CF_NAMES {
+        cfs.push(ColumnFamilyDescriptor::new(
+            name,
+            cf_options(name, &shared_cache)?,
+        ));
+    }
+    TransactionDB::open_cf_descriptors(&opts, &transaction_opts, data_store_dir_or_default(), cfs)
+        .map_err(|e| e.into())
+}
+
+// options of a column family tuned by the rocks_* config options
+fn cf_options(name: &str, shared_cache: &Cache) -> Result<Options> {
+    let cache = match rocks_cf_block_cache_mb(name) {
+        Some(mb) => Cache::new_lru_cache(mb * MB)?,
+        None => shared_cache.clone(),
+    };
+    let mut table_opts = BlockBasedOptions::default();
+    table_opts.set_block_cache(&cache);
+
+    let mut opts = Options::default();
+    opts.set_block_based_table_factory(&table_opts);
+    opts.set_write_buffer_size(rocks_write_buffer_size_mb_or_default(name) * MB);
+    opts.set_max_write_buffer_number(rocks_max_write_buffer_number_or_default(name));
+    opts.set_level_zero_file_num_compaction_trigger(
+        rocks_level0_file_num_compaction_trigger_or_default(name),
+    );
+    opts.set_compression_type(match rocks_compression_type_or_default(name).as_str() {
+        "none" => DBCompressionType::None,
+        "lz4" => DBCompressionType::Lz4,
+        "zstd" => DBCompressionType::Zstd,
+        _ => DBCompressionType::Snappy,
+    });
+    if name == CF_NAME_META && enable_compaction_filter_or_default() {
+        opts.set_compaction_filter(EXPIRY_FILTER_NAME, expiry_filter);
+    }
+    Ok(opts)
 }
 
 pub fn set_instance_id(id: u64) {
@@ -121,11 +188,54 @@ pub fn gen_next_meta_index() -> u16 {
     fetch_idx_and_add() % config_meta_key_number_or_default()
 }
 
+// key count changes by type when the meta value `old` of a key is replaced by
+// `new`, `None` for a missing key
+pub(crate) fn key_count_deltas(old: Option<&[u8]>, new: Option<&[u8]>) -> Vec<(String, i64)> {
+    let old_type = old.map(|v| KeyDecoder::decode_key_type(v).to_string());
+    let new_type = new.map(|v| KeyDecoder::decode_key_type(v).to_string());
+    if old_type == new_type {
+        return vec![];
+    }
+    old_type
+        .map(|t| (t, -1))
+        .into_iter()
+        .chain(new_type.map(|t| (t, 1)))
+        .collect()
+}
+
+pub(crate) fn apply_key_count_deltas(deltas: &[(String, i64)]) {
+    for (data_type, delta) in deltas {
+        KEYSPACE_KEY_COUNT
+            .with_label_values(&[data_type])
+            .add(*delta);
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::key_count_deltas;
     use crate::config::data_store_dir_or_default;
     use rocksdb::{Direction, IteratorMode, TransactionDB, WriteBatchWithTransaction};
 
+    #[test]
+    fn test_key_count_deltas() {
+        let (string, hash) = (vec![0u8], vec![1u8]);
+        assert_eq!(
+            key_count_deltas(None, Some(&string)),
+            vec![("string".to_owned(), 1)]
+        );
+        // updating a key of the same type keeps the count
+        assert!(key_count_deltas(Some(&hash), Some(&hash)).is_empty());
+        assert_eq!(
+            key_count_deltas(Some(&hash), Some(&string)),
+            vec![("hash".to_owned(), -1), ("string".to_owned(), 1)]
+        );
+        assert_eq!(
+            key_count_deltas(Some(&hash), None),
+            vec![("hash".to_owned(), -1)]
+        );
+    }
+
     #[test]
     fn test_rocksdb() {
         let db: TransactionDB = TransactionDB::open_default(data_store_dir_or_default()).unwrap();
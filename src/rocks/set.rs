@@ -1,3 +1,4 @@
+use crate::cache::cache_invalidate;
 use crate::config::{
     async_del_set_threshold_or_default, async_expire_set_threshold_or_default, LOGGER,
 };
@@ -5,15 +6,19 @@ use crate::metrics::REMOVED_EXPIRED_KEY_COUNTER;
 use crate::rocks::client::{get_version_for_new, RocksClient};
 use crate::rocks::encoding::{DataType, KeyDecoder};
 use crate::rocks::errors::REDIS_WRONG_TYPE_ERR;
+use crate::rocks::hash::HashCommand;
 use crate::rocks::kv::key::Key;
 use crate::rocks::kv::value::Value;
+use crate::rocks::list::ListCommand;
 use crate::rocks::transaction::RocksTransaction;
+use crate::rocks::zset::ZsetCommand;
 use crate::rocks::{
     gen_next_meta_index, Result as RocksResult, TxnCommand, CF_NAME_GC, CF_NAME_GC_VERSION,
     CF_NAME_META, CF_NAME_SET_DATA, CF_NAME_SET_SUB_META, KEY_ENCODER,
 };
 use crate::utils::{
-    count_unique_keys, key_is_expired, resp_array, resp_bulk, resp_err, resp_int, resp_nil,
+    count_unique_keys, key_is_expired, notify_event, resp_array, resp_bulk, resp_err, resp_int,
+    resp_nil, unique_keys,
 };
 use crate::Frame;
 use rand::rngs::SmallRng;
@@ -23,8 +28,6 @@ use rocksdb::ColumnFamilyRef;
 use slog::info;
 use std::collections::HashMap;
 
-const RANDOM_BASE: i64 = 100;
-
 pub struct SetCF<'a> {
     meta_cf: ColumnFamilyRef<'a>,
     sub_meta_cf: ColumnFamilyRef<'a>,
@@ -94,9 +97,10 @@ impl<'a> SetCommand<'a> {
                     }
                     // batch get
                     // count the unique members
-                    let real_member_count = count_unique_keys(&member_data_keys);
+                    let unique_data_keys = unique_keys(&member_data_keys);
+                    let real_member_count = unique_data_keys.len();
                     let values_count = txn
-                        .batch_get_for_update(cfs.data_cf.clone(), member_data_keys)?
+                        .batch_get_for_update(cfs.data_cf.clone(), unique_data_keys)?
                         .len();
                     let added = real_member_count as i64 - values_count as i64;
                     for m in &members {
@@ -120,7 +124,7 @@ impl<'a> SetCommand<'a> {
                     // create a new meta key if key already expired above
                     if expired {
                         let new_meta_value = KEY_ENCODER.encode_set_meta_value(0, version, 0);
-                        txn.put(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
+                        txn.put_meta(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
                     }
 
                     Ok(added)
@@ -146,7 +150,7 @@ impl<'a> SetCommand<'a> {
                     }
                     // create meta key
                     let meta_value = KEY_ENCODER.encode_set_meta_value(0, version, 0);
-                    txn.put(cfs.meta_cf.clone(), meta_key, meta_value)?;
+                    txn.put_meta(cfs.meta_cf.clone(), meta_key, meta_value)?;
 
                     let added = count_unique_keys(&members) as i64;
 
@@ -161,7 +165,12 @@ impl<'a> SetCommand<'a> {
         });
 
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(v) => {
+                if v > 0 {
+                    notify_event('s', "sadd", key);
+                }
+                Ok(resp_int(v))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
@@ -268,7 +277,7 @@ impl<'a> SetCommand<'a> {
         })
     }
 
-    pub async fn srandmemeber(
+    pub async fn srandmember(
         self,
         key: &str,
         count: i64,
@@ -294,19 +303,16 @@ impl<'a> SetCommand<'a> {
                         return Ok(resp_array(vec![]));
                     }
 
-                    // create random
-                    let mut rng = SmallRng::from_entropy();
-                    let mut ele_count = RANDOM_BASE;
-                    if count > RANDOM_BASE {
-                        ele_count = count;
+                    if array_resp && count == 0 {
+                        return Ok(resp_array(vec![]));
                     }
 
+                    // create random
+                    let mut rng = SmallRng::from_entropy();
+                    // pick from all the members, so that every member of a
+                    // large set can be returned
                     let bound_range = KEY_ENCODER.encode_set_data_key_range(&key, version);
-                    let iter = txn.scan_keys(
-                        cfs.data_cf.clone(),
-                        bound_range,
-                        ele_count.try_into().unwrap(),
-                    )?;
+                    let iter = txn.scan_keys(cfs.data_cf.clone(), bound_range, u32::MAX)?;
                     let mut resp: Vec<Frame> = iter
                         .map(|k| {
                             // decode member from data key
@@ -315,10 +321,14 @@ impl<'a> SetCommand<'a> {
                         })
                         .collect();
 
-                    // shuffle the resp vector
-                    resp.shuffle(&mut rng);
-
                     let resp_len = resp.len();
+                    if resp_len == 0 {
+                        return Ok(if array_resp {
+                            resp_array(vec![])
+                        } else {
+                            resp_nil()
+                        });
+                    }
                     if !array_resp {
                         // called with no count argument, return bulk reply
                         // choose a random from resp
@@ -326,13 +336,17 @@ impl<'a> SetCommand<'a> {
                         return Ok(resp[rand_idx].clone());
                     }
 
-                    // check resp is enough when repeatable is set, fill it with random element in resp vector
-                    while repeatable && (resp.len() as i64) < count {
-                        let rand_idx = rng.gen_range(0..resp_len);
-                        resp.push(resp[rand_idx].clone());
+                    // negative count, each element is picked independently so
+                    // it may be repeated
+                    if repeatable {
+                        let picked = (0..count)
+                            .map(|_| resp[rng.gen_range(0..resp_len)].clone())
+                            .collect();
+                        return Ok(resp_array(picked));
                     }
 
-                    // if count is less than resp.len(), truncate it
+                    // shuffle the resp vector, then keep at most count distinct elements
+                    resp.shuffle(&mut rng);
                     if count < resp_len as i64 {
                         resp.truncate(count.try_into().unwrap());
                     }
@@ -387,6 +401,174 @@ impl<'a> SetCommand<'a> {
         })
     }
 
+    pub async fn sintercard(self, keys: &[String], limit: u64) -> RocksResult<Frame> {
+        let client = self.client;
+        let cfs = SetCF::new(client);
+        let keys = keys.to_owned();
+
+        client.exec_txn(|txn| {
+            // (key, version, size) of every set, an absent key makes the intersection empty
+            let mut sets = Vec::with_capacity(keys.len());
+            let mut empty = false;
+            for key in &keys {
+                let meta_key = KEY_ENCODER.encode_meta_key(key);
+                match txn.get(cfs.meta_cf.clone(), meta_key)? {
+                    Some(meta_value) => {
+                        // check key type and ttl
+                        if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Set) {
+                            return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                        }
+
+                        let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                        if key_is_expired(ttl) {
+                            self.txn_expire_if_needed(txn, client, key)?;
+                            empty = true;
+                            continue;
+                        }
+                        let size = self.sum_key_size(key, version)?;
+                        sets.push((key, version, size));
+                    }
+                    None => empty = true,
+                }
+            }
+            if empty {
+                return Ok(resp_int(0));
+            }
+
+            // iterate the smallest set and probe the others
+            sets.sort_by_key(|(_, _, size)| *size);
+            let (smallest, version, _) = sets[0];
+            let bound_range = KEY_ENCODER.encode_set_data_key_range(smallest, version);
+            let iter = txn.scan_keys(cfs.data_cf.clone(), bound_range, u32::MAX)?;
+
+            let mut count = 0;
+            for k in iter {
+                if limit > 0 && count >= limit {
+                    break;
+                }
+                let member = KeyDecoder::decode_key_set_member_from_datakey(smallest, k);
+                let member = String::from_utf8_lossy(&member);
+                let mut in_all = true;
+                for (key, version, _) in &sets[1..] {
+                    let data_key = KEY_ENCODER.encode_set_data_key(key, &member, *version);
+                    if txn.get(cfs.data_cf.clone(), data_key)?.is_none() {
+                        in_all = false;
+                        break;
+                    }
+                }
+                if in_all {
+                    count += 1;
+                }
+            }
+            Ok(resp_int(count as i64))
+        })
+    }
+
+    pub async fn sinterstore(self, dst: &str, keys: &[String]) -> RocksResult<Frame> {
+        let client = self.client;
+        let cfs = SetCF::new(client);
+        let dst = dst.to_owned();
+        let keys = keys.to_owned();
+        let meta_key = KEY_ENCODER.encode_meta_key(&dst);
+        let rand_idx = gen_next_meta_index();
+
+        let resp = client.exec_txn(|txn| {
+            // read all the sources before destination is touched, it may be one of them
+            let mut sets = Vec::with_capacity(keys.len());
+            let mut empty = false;
+            for key in &keys {
+                let meta_key = KEY_ENCODER.encode_meta_key(key);
+                match txn.get(cfs.meta_cf.clone(), meta_key)? {
+                    Some(meta_value) => {
+                        if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Set) {
+                            return Err(REDIS_WRONG_TYPE_ERR);
+                        }
+
+                        let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                        if key_is_expired(ttl) {
+                            self.txn_expire_if_needed(txn, client, key)?;
+                            empty = true;
+                            continue;
+                        }
+                        let size = self.sum_key_size(key, version)?;
+                        sets.push((key, version, size));
+                    }
+                    None => empty = true,
+                }
+            }
+
+            let mut members = vec![];
+            if !empty {
+                sets.sort_by_key(|(_, _, size)| *size);
+                let (smallest, version, _) = sets[0];
+                let bound_range = KEY_ENCODER.encode_set_data_key_range(smallest, version);
+                let iter = txn.scan_keys(cfs.data_cf.clone(), bound_range, u32::MAX)?;
+                for k in iter {
+                    let member = KeyDecoder::decode_key_set_member_from_datakey(smallest, k);
+                    let member = String::from_utf8_lossy(&member).to_string();
+                    let mut in_all = true;
+                    for (key, version, _) in &sets[1..] {
+                        let data_key = KEY_ENCODER.encode_set_data_key(key, &member, *version);
+                        if txn.get(cfs.data_cf.clone(), data_key)?.is_none() {
+                            in_all = false;
+                            break;
+                        }
+                    }
+                    if in_all {
+                        members.push(member);
+                    }
+                }
+            }
+
+            // destination is overwritten whatever its type
+            if let Some(meta_value) = txn.get_for_update(cfs.meta_cf.clone(), meta_key.clone())? {
+                match KeyDecoder::decode_key_type(&meta_value) {
+                    DataType::String => {
+                        txn.del_meta(cfs.meta_cf.clone(), meta_key.clone())?;
+                    }
+                    DataType::Set => self.txn_del(txn, client, &dst, false)?,
+                    DataType::List => ListCommand::new(client).txn_del(txn, client, &dst, false)?,
+                    DataType::Hash => HashCommand::new(client).txn_del(txn, client, &dst, false)?,
+                    DataType::Zset => ZsetCommand::new(client).txn_del(txn, client, &dst, false)?,
+                    _ => {}
+                }
+            }
+            if members.is_empty() {
+                return Ok(0);
+            }
+
+            let version =
+                get_version_for_new(txn, cfs.gc_cf.clone(), cfs.gc_version_cf.clone(), &dst)?;
+            for m in &members {
+                let data_key = KEY_ENCODER.encode_set_data_key(&dst, m, version);
+                txn.put(cfs.data_cf.clone(), data_key, vec![0])?;
+            }
+            let meta_value = KEY_ENCODER.encode_set_meta_value(0, version, 0);
+            txn.put_meta(cfs.meta_cf.clone(), meta_key.clone(), meta_value)?;
+
+            let added = members.len() as i64;
+            let sub_meta_key = KEY_ENCODER.encode_sub_meta_key(&dst, version, rand_idx);
+            txn.put(
+                cfs.sub_meta_cf.clone(),
+                sub_meta_key,
+                added.to_be_bytes().to_vec(),
+            )?;
+            Ok(added)
+        });
+        // a string destination was overwritten
+        cache_invalidate(meta_key.as_ref());
+
+        match resp {
+            Ok(v) => {
+                if v > 0 {
+                    notify_event('s', "sinterstore", &dst);
+                }
+                Ok(resp_int(v))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
     pub async fn srem(self, key: &str, members: &Vec<String>) -> RocksResult<Frame> {
         let client = self.client;
         let cfs = SetCF::new(client);
@@ -424,7 +606,7 @@ impl<'a> SetCommand<'a> {
 
                     // check if all items cleared, delete meta key and all sub meta keys if needed
                     if removed >= size {
-                        txn.del(cfs.meta_cf, meta_key)?;
+                        txn.del_meta(cfs.meta_cf, meta_key)?;
                         let meta_bound_range = KEY_ENCODER.encode_sub_meta_key_range(&key, version);
                         let iter =
                             txn.scan_keys(cfs.sub_meta_cf.clone(), meta_bound_range, u32::MAX)?;
@@ -457,7 +639,12 @@ impl<'a> SetCommand<'a> {
             }
         });
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(v) => {
+                if v > 0 {
+                    notify_event('s', "srem", key);
+                }
+                Ok(resp_int(v))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
@@ -509,7 +696,7 @@ impl<'a> SetCommand<'a> {
                     // update or delete meta key
                     if poped_count >= size {
                         // delete meta key
-                        txn.del(cfs.meta_cf, meta_key)?;
+                        txn.del_meta(cfs.meta_cf, meta_key)?;
                         // delete all sub meta keys
                         let meta_bound_range = KEY_ENCODER.encode_sub_meta_key_range(&key, version);
                         let iter =
@@ -543,6 +730,9 @@ impl<'a> SetCommand<'a> {
         });
         match resp {
             Ok(mut v) => {
+                if !v.is_empty() {
+                    notify_event('s', "spop", key);
+                }
                 if count == 1 {
                     if v.is_empty() {
                         Ok(resp_nil())
@@ -581,10 +771,46 @@ impl<'a> SetCommand<'a> {
             }
         })
     }
+
+    /// Create the set from the members of a `RESTORE` payload, the key must
+    /// have been deleted in the transaction.
+    pub(crate) fn txn_restore(
+        &self,
+        txn: &RocksTransaction,
+        key: &str,
+        ttl: i64,
+        members: Vec<Vec<u8>>,
+    ) -> RocksResult<()> {
+        let cfs = SetCF::new(self.client);
+        let version = get_version_for_new(txn, cfs.gc_cf.clone(), cfs.gc_version_cf.clone(), key)?;
+        for member in &members {
+            let member = String::from_utf8_lossy(member);
+            let data_key = KEY_ENCODER.encode_set_data_key(key, &member, version);
+            txn.put(cfs.data_cf.clone(), data_key, vec![0])?;
+        }
+        let sub_meta_key = KEY_ENCODER.encode_sub_meta_key(key, version, gen_next_meta_index());
+        txn.put(
+            cfs.sub_meta_cf.clone(),
+            sub_meta_key,
+            (members.len() as i64).to_be_bytes().to_vec(),
+        )?;
+        let meta_value = KEY_ENCODER.encode_set_meta_value(ttl, version, 0);
+        txn.put_meta(
+            cfs.meta_cf.clone(),
+            KEY_ENCODER.encode_meta_key(key),
+            meta_value,
+        )
+    }
 }
 
 impl TxnCommand for SetCommand<'_> {
-    fn txn_del(&self, txn: &RocksTransaction, client: &RocksClient, key: &str) -> RocksResult<()> {
+    fn txn_del(
+        &self,
+        txn: &RocksTransaction,
+        client: &RocksClient,
+        key: &str,
+        always_async: bool,
+    ) -> RocksResult<()> {
         let key = key.to_owned();
         let meta_key = KEY_ENCODER.encode_meta_key(&key);
         let cfs = SetCF::new(client);
@@ -594,9 +820,9 @@ impl TxnCommand for SetCommand<'_> {
                 let version = KeyDecoder::decode_key_version(&meta_value);
                 let size = self.sum_key_size(&key, version)?;
 
-                if size > async_del_set_threshold_or_default() as i64 {
+                if always_async || size > async_del_set_threshold_or_default() as i64 {
                     // async del set
-                    txn.del(cfs.meta_cf.clone(), meta_key)?;
+                    txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
 
                     let gc_key = KEY_ENCODER.encode_gc_key(&key);
                     txn.put(cfs.gc_cf.clone(), gc_key, version.to_be_bytes().to_vec())?;
@@ -605,7 +831,7 @@ impl TxnCommand for SetCommand<'_> {
                     txn.put(
                         cfs.gc_version_cf.clone(),
                         gc_version_key,
-                        vec![KEY_ENCODER.get_type_bytes(DataType::Set)],
+                        KEY_ENCODER.encode_gc_version_value(DataType::Set, size as u64),
                     )?;
                 } else {
                     let sub_meta_range = KEY_ENCODER.encode_sub_meta_key_range(&key, version);
@@ -620,7 +846,7 @@ impl TxnCommand for SetCommand<'_> {
                         txn.del(cfs.data_cf.clone(), k)?;
                     }
 
-                    txn.del(cfs.meta_cf, meta_key)?;
+                    txn.del_meta(cfs.meta_cf, meta_key)?;
                 }
                 Ok(())
             }
@@ -647,7 +873,7 @@ impl TxnCommand for SetCommand<'_> {
                 let size = self.sum_key_size(&key, version)?;
                 if size > async_expire_set_threshold_or_default() as i64 {
                     // async del set
-                    txn.del(cfs.meta_cf.clone(), meta_key)?;
+                    txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
 
                     let gc_key = KEY_ENCODER.encode_gc_key(&key);
                     txn.put(cfs.gc_cf.clone(), gc_key, version.to_be_bytes().to_vec())?;
@@ -656,7 +882,7 @@ impl TxnCommand for SetCommand<'_> {
                     txn.put(
                         cfs.gc_version_cf.clone(),
                         gc_version_key,
-                        vec![KEY_ENCODER.get_type_bytes(DataType::Set)],
+                        KEY_ENCODER.encode_gc_version_value(DataType::Set, size as u64),
                     )?;
                 } else {
                     let sub_meta_range = KEY_ENCODER.encode_sub_meta_key_range(&key, version);
@@ -672,11 +898,12 @@ impl TxnCommand for SetCommand<'_> {
                         txn.del(cfs.data_cf.clone(), k)?;
                     }
 
-                    txn.del(cfs.meta_cf.clone(), meta_key)?;
+                    txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
                 }
                 REMOVED_EXPIRED_KEY_COUNTER
                     .with_label_values(&["set"])
                     .inc();
+                notify_event('x', "expired", &key);
                 Ok(1)
             }
             None => Ok(0),
@@ -700,7 +927,7 @@ impl TxnCommand for SetCommand<'_> {
         }
         let version = KeyDecoder::decode_key_version(meta_value);
         let new_meta_value = KEY_ENCODER.encode_set_meta_value(timestamp, version, 0);
-        txn.put(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
+        txn.put_meta(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
         Ok(1)
     }
 
@@ -1,15 +1,21 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::str;
+use std::time::Instant;
 
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
 use bytes::Bytes;
-use glob::Pattern;
-use regex::bytes::Regex;
 
-use crate::metrics::REMOVED_EXPIRED_KEY_COUNTER;
+use crate::cache::{cache_generation, cache_get, cache_invalidate, cache_put};
+use crate::metrics::{REMOVED_EXPIRED_KEY_COUNTER, REQUEST_CMD_TYPE_HANDLE_TIME};
 use crate::rocks::client::RocksClient;
 use crate::rocks::encoding::{DataType, KeyDecoder};
-use crate::rocks::errors::{RError, REDIS_WRONG_TYPE_ERR};
+use crate::rocks::errors::{
+    RError, REDIS_BUSYKEY_ERR, REDIS_DUMP_PAYLOAD_ERR, REDIS_INCR_OVERFLOW_ERR,
+    REDIS_INVALID_CURSOR_ERR, REDIS_SORT_NOT_DOUBLE_ERR, REDIS_WRONG_TYPE_ERR,
+};
 use crate::rocks::hash::HashCommand;
+use crate::rocks::hll::HllCommand;
 use crate::rocks::kv::bound_range::BoundRange;
 use crate::rocks::{TxnCommand, CF_NAME_META, KEY_ENCODER};
 use crate::Frame;
@@ -24,10 +30,28 @@ use crate::rocks::transaction::RocksTransaction;
 use crate::rocks::zset::ZsetCommand;
 use crate::rocks::Result as RocksResult;
 use crate::utils::{
-    key_is_expired, resp_array, resp_bulk, resp_err, resp_int, resp_nil, resp_ok, resp_str,
-    ttl_from_timestamp,
+    glob_pattern, key_is_expired, notify_event, resp_array, resp_bulk, resp_err, resp_int,
+    resp_nil, resp_ok, resp_str, slot_for_key, ttl_from_timestamp, ttl_secs_from_millis,
 };
 
+// `bits` bits at bit `offset` of `data`, most significant bit first like redis
+fn get_bits(data: &[u8], offset: u64, bits: u32, signed: bool) -> i64 {
+    let mut value = 0u64;
+    for pos in offset..offset + bits as u64 {
+        let byte = data.get((pos >> 3) as usize).copied().unwrap_or(0);
+        value = (value << 1) | ((byte >> (7 - (pos & 7))) & 1) as u64;
+    }
+    if signed && bits < 64 && (value >> (bits - 1)) & 1 == 1 {
+        // sign extend
+        value as i64 - (1i64 << bits)
+    } else {
+        value as i64
+    }
+}
+
+// strings up to this length are reported as embstr
+const EMBSTR_SIZE_LIMIT: usize = 44;
+
 pub struct StringCF<'a> {
     meta_cf: ColumnFamilyRef<'a>,
 }
@@ -53,20 +77,34 @@ impl<'a> StringCommand<'a> {
         let client = self.client;
         let cfs = StringCF::new(client);
         let ekey = KEY_ENCODER.encode_string(key);
-        match client.get(cfs.meta_cf.clone(), ekey.clone())? {
+        let cached = cache_get(ekey.as_ref());
+        let hit = cached.is_some();
+        // taken before the read, a write invalidating the key meanwhile
+        // keeps the value read out of the cache
+        let generation = cache_generation();
+        let val = match cached {
+            Some(val) => Some(val),
+            None => client.get(cfs.meta_cf.clone(), ekey.clone())?,
+        };
+        match val {
             Some(val) => {
                 let dt = KeyDecoder::decode_key_type(&val);
-                if !matches!(dt, DataType::String) {
+                // hyperloglogs are read as their raw encoding
+                if !matches!(dt, DataType::String | DataType::Hll) {
                     return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
                 }
                 // ttl saved in milliseconds
                 let ttl = KeyDecoder::decode_key_ttl(&val);
                 if key_is_expired(ttl) {
                     // delete key
-                    client.del(cfs.meta_cf, ekey)?;
+                    cache_invalidate(ekey.as_ref());
+                    client.del_meta(cfs.meta_cf, ekey)?;
                     return Ok(resp_nil());
                 }
                 let data = KeyDecoder::decode_key_string_value(&val);
+                if !hit {
+                    cache_put(ekey.into(), val, generation);
+                }
                 Ok(resp_bulk(data))
             }
             None => Ok(Frame::Null),
@@ -74,6 +112,11 @@ impl<'a> StringCommand<'a> {
     }
 
     pub async fn get_type(&self, key: &str) -> RocksResult<Frame> {
+        Ok(resp_str(&self.key_type(key)?.to_string()))
+    }
+
+    /// Type of the key, `DataType::Null` if it does not exist or is expired.
+    pub fn key_type(&self, key: &str) -> RocksResult<DataType> {
         let client = self.client;
         let cfs = StringCF::new(client);
         let ekey = KEY_ENCODER.encode_string(key);
@@ -83,12 +126,109 @@ impl<'a> StringCommand<'a> {
                 let ttl = KeyDecoder::decode_key_ttl(&val);
                 if key_is_expired(ttl) {
                     // delete key
-                    client.del(cfs.meta_cf.clone(), ekey)?;
-                    return Ok(resp_str(&DataType::Null.to_string()));
+                    client.del_meta(cfs.meta_cf.clone(), ekey)?;
+                    return Ok(DataType::Null);
                 }
-                Ok(resp_str(&KeyDecoder::decode_key_type(&val).to_string()))
+                Ok(KeyDecoder::decode_key_type(&val))
+            }
+            None => Ok(DataType::Null),
+        }
+    }
+
+    pub async fn object_encoding(&self, key: &str) -> RocksResult<Frame> {
+        let client = self.client;
+        let cfs = StringCF::new(client);
+        let ekey = KEY_ENCODER.encode_string(key);
+        match client.get(cfs.meta_cf.clone(), ekey)? {
+            Some(val) => {
+                if key_is_expired(KeyDecoder::decode_key_ttl(&val)) {
+                    return Ok(resp_nil());
+                }
+                // names follow redis, compact encoded values are reported
+                // as ziplist
+                let encoding = match KeyDecoder::decode_key_type(&val) {
+                    DataType::String => {
+                        let data = KeyDecoder::decode_key_string_value(&val);
+                        // like redis, only the canonical form of an i64 is an
+                        // int, "+42" or "042" are kept as strings
+                        let is_int = str::from_utf8(&data).map_or(false, |s| {
+                            s.parse::<i64>().map_or(false, |i| i.to_string() == s)
+                        });
+                        if is_int {
+                            "int"
+                        } else if data.len() <= EMBSTR_SIZE_LIMIT {
+                            "embstr"
+                        } else {
+                            "raw"
+                        }
+                    }
+                    DataType::Hash => match KeyDecoder::decode_key_hash_compact(&val) {
+                        Some(_) => "ziplist",
+                        None => "hashtable",
+                    },
+                    DataType::List => match KeyDecoder::decode_key_list_compact(&val) {
+                        Some(_) => "ziplist",
+                        None => "quicklist",
+                    },
+                    DataType::Zset => match KeyDecoder::decode_key_zset_compact(&val) {
+                        Some(_) => "ziplist",
+                        None => "skiplist",
+                    },
+                    DataType::Set => "hashtable",
+                    DataType::Hll => "raw",
+                    DataType::Null => return Ok(resp_nil()),
+                };
+                Ok(resp_str(encoding))
+            }
+            None => Ok(resp_nil()),
+        }
+    }
+
+    pub async fn object_refcount(&self, key: &str) -> RocksResult<Frame> {
+        let client = self.client;
+        let cfs = StringCF::new(client);
+        let ekey = KEY_ENCODER.encode_string(key);
+        match client.get(cfs.meta_cf.clone(), ekey)? {
+            // values are never shared between keys
+            Some(val) if !key_is_expired(KeyDecoder::decode_key_ttl(&val)) => Ok(resp_int(1)),
+            _ => Ok(resp_nil()),
+        }
+    }
+
+    /// Integers read from the string at `(signed, bits, offset)`, bits past
+    /// the end of the string are zeros.
+    pub async fn bitfield_get(&self, key: &str, gets: &[(bool, u32, u64)]) -> RocksResult<Frame> {
+        let client = self.client;
+        let cfs = StringCF::new(client);
+        let ekey = KEY_ENCODER.encode_string(key);
+        let val = match client.get(cfs.meta_cf.clone(), ekey)? {
+            Some(val) if !key_is_expired(KeyDecoder::decode_key_ttl(&val)) => val,
+            _ => vec![],
+        };
+        if !val.is_empty() && !matches!(KeyDecoder::decode_key_type(&val), DataType::String) {
+            return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+        }
+        let data = if val.is_empty() {
+            &[][..]
+        } else {
+            KeyDecoder::decode_key_string_slice(&val)
+        };
+        let values = gets
+            .iter()
+            .map(|(signed, bits, offset)| resp_int(get_bits(data, *offset, *bits, *signed)))
+            .collect();
+        Ok(resp_array(values))
+    }
+
+    pub async fn object_version(&self, key: &str) -> RocksResult<Frame> {
+        let client = self.client;
+        let cfs = StringCF::new(client);
+        let ekey = KEY_ENCODER.encode_string(key);
+        match client.get(cfs.meta_cf.clone(), ekey)? {
+            Some(val) if !key_is_expired(KeyDecoder::decode_key_ttl(&val)) => {
+                Ok(resp_int(KeyDecoder::decode_key_version(&val) as i64))
             }
-            None => Ok(resp_str(&DataType::Null.to_string())),
+            _ => Ok(resp_nil()),
         }
     }
 
@@ -106,7 +246,7 @@ impl<'a> StringCommand<'a> {
                 let ttl = KeyDecoder::decode_key_ttl(&val);
                 if key_is_expired(ttl) {
                     // delete key
-                    client.del(cfs.meta_cf, ekey)?;
+                    client.del_meta(cfs.meta_cf, ekey)?;
                     return Ok(resp_int(0));
                 }
                 let data = KeyDecoder::decode_key_string_value(&val);
@@ -121,7 +261,9 @@ impl<'a> StringCommand<'a> {
         let cfs = StringCF::new(client);
         let ekey = KEY_ENCODER.encode_string(key);
         let eval = KEY_ENCODER.encode_string_value(&mut val.to_vec(), timestamp);
-        client.put(cfs.meta_cf, ekey, eval)?;
+        client.put_meta(cfs.meta_cf, ekey.clone(), eval)?;
+        cache_invalidate(ekey.as_ref());
+        notify_event('$', "set", key);
         Ok(resp_ok())
     }
 
@@ -143,7 +285,7 @@ impl<'a> StringCommand<'a> {
                         if key_is_expired(ttl) {
                             // delete key
                             client
-                                .del(cfs.meta_cf.clone(), k)
+                                .del_meta(cfs.meta_cf.clone(), k)
                                 .expect("remove outdated data failed");
                             Frame::Null
                         } else {
@@ -161,7 +303,13 @@ impl<'a> StringCommand<'a> {
     pub async fn batch_put(self, kvs: Vec<KvPair>) -> RocksResult<Frame> {
         let client = self.client;
         let cfs = StringCF::new(client);
-        client.batch_put(cfs.meta_cf, kvs)?;
+        let ekeys: Vec<Key> = kvs.iter().map(|kv| kv.key().clone()).collect();
+        client.batch_put_meta(cfs.meta_cf, kvs)?;
+        for ekey in ekeys {
+            cache_invalidate(ekey.as_ref());
+            let (ukey, _) = KeyDecoder::decode_key_userkey_from_metakey(&ekey);
+            notify_event('$', "set", &String::from_utf8_lossy(&ukey));
+        }
         Ok(resp_ok())
     }
 
@@ -177,24 +325,26 @@ impl<'a> StringCommand<'a> {
                     let ttl = KeyDecoder::decode_key_ttl(v);
                     if key_is_expired(ttl) {
                         // no need to delete, just overwrite
-                        txn.put(cfs.meta_cf, ekey, eval)?;
+                        txn.put_meta(cfs.meta_cf, ekey.clone(), eval)?;
                         Ok(1)
                     } else {
                         Ok(0)
                     }
                 }
                 None => {
-                    txn.put(cfs.meta_cf, ekey, eval)?;
+                    txn.put_meta(cfs.meta_cf, ekey.clone(), eval)?;
                     Ok(1)
                 }
             }
         });
 
+        cache_invalidate(ekey.as_ref());
         match resp {
             Ok(n) => {
                 if n == 0 {
                     Ok(resp_nil())
                 } else {
+                    notify_event('$', "set", key);
                     Ok(resp_ok())
                 }
             }
@@ -202,6 +352,45 @@ impl<'a> StringCommand<'a> {
         }
     }
 
+    /// Set the key without ttl and reply its previous value. A key of another
+    /// type is left untouched.
+    pub async fn getset(self, key: &str, value: &Bytes) -> RocksResult<Frame> {
+        let client = self.client;
+        let cfs = StringCF::new(client);
+        let ekey = KEY_ENCODER.encode_string(key);
+        let eval = KEY_ENCODER.encode_string_value(&mut value.to_vec(), -1);
+
+        let resp = client.exec_txn(|txn| {
+            let prev = match txn.get_for_update(cfs.meta_cf.clone(), ekey.clone())? {
+                Some(val) => {
+                    let dt = KeyDecoder::decode_key_type(&val);
+                    // hyperloglogs are read as their raw encoding
+                    if !matches!(dt, DataType::String | DataType::Hll) {
+                        return Err(REDIS_WRONG_TYPE_ERR);
+                    }
+                    let ttl = KeyDecoder::decode_key_ttl(&val);
+                    if key_is_expired(ttl) {
+                        None
+                    } else {
+                        Some(KeyDecoder::decode_key_string_value(&val))
+                    }
+                }
+                None => None,
+            };
+            txn.put_meta(cfs.meta_cf, ekey.clone(), eval)?;
+            Ok(prev)
+        });
+
+        cache_invalidate(ekey.as_ref());
+        match resp {
+            Ok(prev) => {
+                notify_event('$', "set", key);
+                Ok(prev.map_or(Frame::Null, resp_bulk))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
     pub async fn exists(self, keys: &[String]) -> RocksResult<Frame> {
         let client = self.client;
         let cfs = StringCF::new(client);
@@ -216,7 +405,7 @@ impl<'a> StringCommand<'a> {
                 let ttl = KeyDecoder::decode_key_ttl(val);
                 if key_is_expired(ttl) {
                     // delete key
-                    client.del(cfs.meta_cf.clone(), k)?;
+                    client.del_meta(cfs.meta_cf.clone(), k)?;
                 } else {
                     nums += 1;
                 }
@@ -232,7 +421,7 @@ impl<'a> StringCommand<'a> {
         let ekey = KEY_ENCODER.encode_string(key);
         let the_key = ekey.clone();
 
-        client.exec_txn(|txn| {
+        let resp = client.exec_txn(|txn| {
             let pair = match txn.get_for_update(cfs.meta_cf.clone(), the_key.clone())? {
                 Some(val) => {
                     let dt = KeyDecoder::decode_key_type(&val);
@@ -243,7 +432,7 @@ impl<'a> StringCommand<'a> {
                     let ttl = KeyDecoder::decode_key_ttl(&val);
                     if key_is_expired(ttl) {
                         // delete key
-                        txn.del(cfs.meta_cf.clone(), the_key)?;
+                        txn.del_meta(cfs.meta_cf.clone(), the_key)?;
                         (0, None)
                     } else {
                         let current_value = KeyDecoder::decode_key_string_slice(&val);
@@ -259,12 +448,20 @@ impl<'a> StringCommand<'a> {
 
             let (prev_int, _) = pair;
 
-            let new_int = prev_int + step;
+            let new_int = prev_int.checked_add(step).ok_or(REDIS_INCR_OVERFLOW_ERR)?;
             let new_val = new_int.to_string();
             let eval = KEY_ENCODER.encode_string_value(&mut new_val.as_bytes().to_vec(), 0);
-            txn.put(cfs.meta_cf, ekey, eval)?;
+            txn.put_meta(cfs.meta_cf, ekey.clone(), eval)?;
             Ok(resp_int(new_int))
-        })
+        });
+        cache_invalidate(ekey.as_ref());
+        match resp {
+            Ok(frame) => {
+                notify_event('$', "incrby", key);
+                Ok(frame)
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
     }
 
     pub async fn expire(self, key: &str, timestamp: i64) -> RocksResult<Frame> {
@@ -281,7 +478,7 @@ impl<'a> StringCommand<'a> {
                     }
                     let dt = KeyDecoder::decode_key_type(&meta_value);
                     match dt {
-                        DataType::String => {
+                        DataType::String | DataType::Hll => {
                             let ttl = KeyDecoder::decode_key_ttl(&meta_value);
                             // check key expired
                             if key_is_expired(ttl) {
@@ -289,8 +486,11 @@ impl<'a> StringCommand<'a> {
                                 return Ok(0);
                             }
                             let value = KeyDecoder::decode_key_string_slice(&meta_value);
-                            let new_meta_value = KEY_ENCODER.encode_string_slice(value, timestamp);
-                            txn.put(cfs.meta_cf.clone(), ekey, new_meta_value)?;
+                            let mut new_meta_value =
+                                KEY_ENCODER.encode_string_slice(value, timestamp);
+                            // keep the type byte of hyperloglogs
+                            new_meta_value[0] = meta_value[0];
+                            txn.put_meta(cfs.meta_cf.clone(), ekey.clone(), new_meta_value)?;
                             Ok(1)
                         }
                         DataType::Set => SetCommand::new(client).txn_expire(
@@ -327,8 +527,14 @@ impl<'a> StringCommand<'a> {
                 None => Ok(0),
             }
         });
+        cache_invalidate(ekey.as_ref());
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(v) => {
+                if v > 0 {
+                    notify_event('g', "expire", key);
+                }
+                Ok(resp_int(v))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
@@ -336,31 +542,12 @@ impl<'a> StringCommand<'a> {
     pub async fn ttl(self, key: &str, is_millis: bool) -> RocksResult<Frame> {
         let client = self.client;
         let cfs = StringCF::new(client);
-        let key = key.to_owned();
-        let ekey = KEY_ENCODER.encode_string(&key);
+        let ekey = KEY_ENCODER.encode_string(key);
         client.exec_txn(|txn| match txn.get(cfs.meta_cf.clone(), ekey.clone())? {
             Some(meta_value) => {
-                let dt = KeyDecoder::decode_key_type(&meta_value);
                 let ttl = KeyDecoder::decode_key_ttl(&meta_value);
                 if key_is_expired(ttl) {
-                    match dt {
-                        DataType::String => {
-                            self.txn_expire_if_needed(txn, client, &ekey, &meta_value)?;
-                        }
-                        DataType::Set => {
-                            SetCommand::new(client).txn_expire_if_needed(txn, client, &key)?;
-                        }
-                        DataType::List => {
-                            ListCommand::new(client).txn_expire_if_needed(txn, client, &key)?;
-                        }
-                        DataType::Hash => {
-                            HashCommand::new(client).txn_expire_if_needed(txn, client, &key)?;
-                        }
-                        DataType::Zset => {
-                            ZsetCommand::new(client).txn_expire_if_needed(txn, client, &key)?;
-                        }
-                        _ => {}
-                    }
+                    self.txn_expire_key(txn, client, &ekey, &meta_value)?;
                     return Ok(resp_int(-2));
                 }
                 if ttl == 0 {
@@ -368,7 +555,7 @@ impl<'a> StringCommand<'a> {
                 } else {
                     let mut ttl = ttl_from_timestamp(ttl);
                     if !is_millis {
-                        ttl /= 1000;
+                        ttl = ttl_secs_from_millis(ttl);
                     }
                     Ok(resp_int(ttl))
                 }
@@ -377,12 +564,83 @@ impl<'a> StringCommand<'a> {
         })
     }
 
+    /// `RESTORE` the decoded payload of `data_type` as `key`, an existing key
+    /// is only overwritten with `replace`. The key is checked, deleted and
+    /// written in one transaction.
+    pub async fn restore(
+        self,
+        key: &str,
+        data_type: DataType,
+        elements: Vec<Vec<u8>>,
+        ttl: i64,
+        replace: bool,
+    ) -> RocksResult<Frame> {
+        let client = self.client;
+        let cfs = StringCF::new(client);
+        let ekey = KEY_ENCODER.encode_string(key);
+        let resp = client.exec_txn(|txn| {
+            if let Some(meta_value) = txn.get_for_update(cfs.meta_cf.clone(), ekey.clone())? {
+                if !replace && !key_is_expired(KeyDecoder::decode_key_ttl(&meta_value)) {
+                    return Err(REDIS_BUSYKEY_ERR);
+                }
+                self.txn_del_any(txn, key, &meta_value)?;
+            }
+            match data_type {
+                DataType::String => {
+                    let value = KEY_ENCODER.encode_string_slice(&elements[0], ttl);
+                    txn.put_meta(cfs.meta_cf.clone(), ekey.clone(), value)
+                }
+                DataType::Hll => HllCommand::new(client).txn_restore(txn, key, ttl, &elements[0]),
+                DataType::List => ListCommand::new(client).txn_restore(txn, key, ttl, elements),
+                DataType::Set => SetCommand::new(client).txn_restore(txn, key, ttl, elements),
+                DataType::Hash => HashCommand::new(client).txn_restore(txn, key, ttl, elements),
+                DataType::Zset => ZsetCommand::new(client).txn_restore(txn, key, ttl, elements),
+                DataType::Null => Err(REDIS_DUMP_PAYLOAD_ERR),
+            }
+        });
+        cache_invalidate(ekey.as_ref());
+        match resp {
+            Ok(()) => {
+                notify_event('g', "restore", key);
+                Ok(resp_ok())
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    // delete the key of any type, `meta_value` is its current meta value
+    fn txn_del_any(&self, txn: &RocksTransaction, key: &str, meta_value: &[u8]) -> RocksResult<()> {
+        let client = self.client;
+        match KeyDecoder::decode_key_type(meta_value) {
+            DataType::Set => SetCommand::new(client).txn_del(txn, client, key, false),
+            DataType::List => ListCommand::new(client).txn_del(txn, client, key, false),
+            DataType::Hash => HashCommand::new(client).txn_del(txn, client, key, false),
+            DataType::Zset => ZsetCommand::new(client).txn_del(txn, client, key, false),
+            _ => txn.del_meta(
+                StringCF::new(client).meta_cf,
+                KEY_ENCODER.encode_string(key),
+            ),
+        }
+    }
+
     pub async fn del(self, keys: &Vec<String>) -> RocksResult<Frame> {
+        self.remove_keys(keys, false).await
+    }
+
+    /// Same as `del` but collections are always deleted by the gc workers.
+    pub async fn unlink(self, keys: &Vec<String>) -> RocksResult<Frame> {
+        self.remove_keys(keys, true).await
+    }
+
+    async fn remove_keys(self, keys: &Vec<String>, unlink: bool) -> RocksResult<Frame> {
+        let cmd_name = if unlink { "unlink" } else { "del" };
+        let start_at = Instant::now();
         let client = self.client;
         let cfs = StringCF::new(client);
         let keys = keys.to_owned();
+        let ekeys = KEY_ENCODER.encode_strings(&keys);
         let resp = client.exec_txn(|txn| {
-            let ekeys = KEY_ENCODER.encode_strings(&keys);
+            let ekeys = ekeys.clone();
             let ekey_map: HashMap<Key, String> = ekeys.clone().into_iter().zip(keys).collect();
             let cf = cfs.meta_cf.clone();
             let pairs = txn.batch_get(cf, ekeys.clone())?;
@@ -391,51 +649,232 @@ impl<'a> StringCommand<'a> {
                 .map(|pair| (pair.0, KeyDecoder::decode_key_type(pair.1.as_slice())))
                 .collect();
 
-            let mut resp = 0;
+            let mut deleted = vec![];
             for ekey in ekeys {
                 match dts.get(&ekey) {
                     Some(DataType::String) => {
-                        txn.del(cfs.meta_cf.clone(), ekey.clone())?;
-                        resp += 1;
+                        txn.del_meta(cfs.meta_cf.clone(), ekey.clone())?;
+                        deleted.push((ekey_map[&ekey].clone(), DataType::String));
+                    }
+                    Some(DataType::Hll) => {
+                        txn.del_meta(cfs.meta_cf.clone(), ekey.clone())?;
+                        deleted.push((ekey_map[&ekey].clone(), DataType::Hll));
                     }
                     Some(DataType::Set) => {
-                        SetCommand::new(client).txn_del(txn, client, &ekey_map[&ekey])?;
-                        resp += 1;
+                        SetCommand::new(client).txn_del(txn, client, &ekey_map[&ekey], unlink)?;
+                        deleted.push((ekey_map[&ekey].clone(), DataType::Set));
                     }
                     Some(DataType::List) => {
-                        ListCommand::new(client).txn_del(txn, client, &ekey_map[&ekey])?;
-                        resp += 1;
+                        ListCommand::new(client).txn_del(txn, client, &ekey_map[&ekey], unlink)?;
+                        deleted.push((ekey_map[&ekey].clone(), DataType::List));
                     }
                     Some(DataType::Hash) => {
-                        HashCommand::new(client).txn_del(txn, client, &ekey_map[&ekey])?;
-                        resp += 1;
+                        HashCommand::new(client).txn_del(txn, client, &ekey_map[&ekey], unlink)?;
+                        deleted.push((ekey_map[&ekey].clone(), DataType::Hash));
                     }
                     Some(DataType::Zset) => {
-                        ZsetCommand::new(client).txn_del(txn, client, &ekey_map[&ekey])?;
-                        resp += 1;
+                        ZsetCommand::new(client).txn_del(txn, client, &ekey_map[&ekey], unlink)?;
+                        deleted.push((ekey_map[&ekey].clone(), DataType::Zset));
                     }
                     _ => {}
                 }
             }
-            Ok(resp)
+            Ok(deleted)
         });
+        // invalidated once committed, so that a concurrent read can not cache
+        // the deleted value again
+        for ekey in &ekeys {
+            cache_invalidate(ekey.as_ref());
+        }
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(deleted) => {
+                let duration = start_at.elapsed().as_secs_f64();
+                let mut types = HashSet::new();
+                for (key, data_type) in &deleted {
+                    notify_event('g', "del", key);
+                    // observe once per deleted key type
+                    if types.insert(data_type.to_string()) {
+                        REQUEST_CMD_TYPE_HANDLE_TIME
+                            .with_label_values(&[cmd_name, &data_type.to_string()])
+                            .observe(duration);
+                    }
+                }
+                Ok(resp_int(deleted.len() as i64))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
-    pub async fn keys(self, regex: &str) -> RocksResult<Frame> {
+    /// `SORT` elements of a list, set or zset. Weights and `GET` values are
+    /// read from other keys by replacing the first `*` of the pattern with
+    /// the element, `key->field` reads a hash field, and a pattern without
+    /// `*` skips sorting.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn sort(
+        self,
+        key: &str,
+        by: Option<&str>,
+        limit: Option<(i64, i64)>,
+        get: &[String],
+        desc: bool,
+        alpha: bool,
+        store: Option<&str>,
+    ) -> RocksResult<Frame> {
+        let client = self.client;
+        let frame = match self.key_type(key)? {
+            DataType::List => ListCommand::new(client).lrange(key, 0, -1).await?,
+            DataType::Set => SetCommand::new(client).smembers(key).await?,
+            DataType::Zset => {
+                ZsetCommand::new(client)
+                    .zrange(key, 0, -1, false, false)
+                    .await?
+            }
+            DataType::Null => resp_array(vec![]),
+            _ => return Ok(resp_err(REDIS_WRONG_TYPE_ERR)),
+        };
+        let elements: Vec<Vec<u8>> = match frame {
+            Frame::Array(items) => items
+                .into_iter()
+                .filter_map(|item| match item {
+                    Frame::Bulk(val) => Some(val.to_vec()),
+                    _ => None,
+                })
+                .collect(),
+            other => return Ok(other),
+        };
+
+        let mut elements = match by {
+            Some(pattern) if !pattern.contains('*') => elements,
+            _ => {
+                let mut weighted = Vec::with_capacity(elements.len());
+                for ele in elements {
+                    let weight = match by {
+                        Some(pattern) => self.sort_lookup(pattern, &ele).await?,
+                        None => Some(ele.clone()),
+                    };
+                    weighted.push((weight, ele));
+                }
+                let mut sorted: Vec<Vec<u8>> = if alpha {
+                    weighted.sort_by(|a, b| a.0.cmp(&b.0));
+                    weighted.into_iter().map(|(_, ele)| ele).collect()
+                } else {
+                    let mut scored = Vec::with_capacity(weighted.len());
+                    for (weight, ele) in weighted {
+                        // missing weights sort as 0
+                        let score = match weight {
+                            Some(w) => {
+                                match str::from_utf8(&w).ok().and_then(|w| w.parse::<f64>().ok()) {
+                                    Some(score) => score,
+                                    None => return Ok(resp_err(REDIS_SORT_NOT_DOUBLE_ERR)),
+                                }
+                            }
+                            None => 0.0,
+                        };
+                        scored.push((score, ele));
+                    }
+                    scored.sort_by(|a, b| a.0.total_cmp(&b.0));
+                    scored.into_iter().map(|(_, ele)| ele).collect()
+                };
+                if desc {
+                    sorted.reverse();
+                }
+                sorted
+            }
+        };
+
+        if let Some((offset, count)) = limit {
+            let len = elements.len() as i64;
+            let start = offset.clamp(0, len);
+            let end = if count < 0 {
+                len
+            } else {
+                (start + count).min(len)
+            };
+            elements = elements.drain(start as usize..end as usize).collect();
+        }
+
+        let mut values: Vec<Option<Vec<u8>>> = Vec::with_capacity(elements.len() * get.len());
+        if get.is_empty() {
+            values = elements.into_iter().map(Some).collect();
+        } else {
+            for ele in &elements {
+                for pattern in get {
+                    values.push(self.sort_lookup(pattern, ele).await?);
+                }
+            }
+        }
+
+        match store {
+            Some(dst) => {
+                // missing values are stored as empty strings
+                let values: Vec<Vec<u8>> =
+                    values.into_iter().map(|v| v.unwrap_or_default()).collect();
+                let len = values.len() as i64;
+                let meta_cf = StringCF::new(client).meta_cf;
+                let ekey = KEY_ENCODER.encode_string(dst);
+                let resp = client.exec_txn(|txn| {
+                    let existed = match txn.get_for_update(meta_cf, ekey.clone())? {
+                        Some(meta_value) => {
+                            self.txn_del_any(txn, dst, &meta_value)?;
+                            true
+                        }
+                        None => false,
+                    };
+                    ListCommand::new(client).txn_restore(txn, dst, 0, values)?;
+                    Ok(existed)
+                });
+                cache_invalidate(ekey.as_ref());
+                let existed = resp?;
+                if len > 0 {
+                    notify_event('l', "sortstore", dst);
+                } else if existed {
+                    notify_event('g', "del", dst);
+                }
+                Ok(resp_int(len))
+            }
+            None => Ok(resp_array(
+                values
+                    .into_iter()
+                    .map(|v| v.map_or_else(resp_nil, resp_bulk))
+                    .collect(),
+            )),
+        }
+    }
+
+    // value referenced by a SORT BY or GET pattern, keys of other types are
+    // treated as missing
+    async fn sort_lookup(&self, pattern: &str, ele: &[u8]) -> RocksResult<Option<Vec<u8>>> {
+        if pattern == "#" {
+            return Ok(Some(ele.to_vec()));
+        }
+        let ele = String::from_utf8_lossy(ele);
+        let (key_pattern, field) = match pattern.find("->") {
+            Some(idx) if idx + 2 < pattern.len() => (&pattern[..idx], Some(&pattern[idx + 2..])),
+            _ => (pattern, None),
+        };
+        let key = key_pattern.replacen('*', &ele, 1);
+        let frame = match field {
+            Some(field) => HashCommand::new(self.client).hget(&key, field).await,
+            None => self.get(&key).await,
+        };
+        match frame {
+            Ok(Frame::Bulk(val)) => Ok(Some(val.to_vec())),
+            Ok(_) | Err(RError::String(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn keys(self, pattern: &str) -> RocksResult<Frame> {
         let client = self.client;
         let cfs = StringCF::new(client);
-        let ekey = KEY_ENCODER.encode_string("");
-        let re = Pattern::new(regex).unwrap();
+        let re = glob_pattern(pattern);
 
         client.exec_txn(|txn| {
             let mut keys = vec![];
+            let mut expired = vec![];
             let mut last_round_iter_count = 1;
 
-            let mut left_bound = ekey.clone();
+            let mut left_bound = KEY_ENCODER.encode_keyspace_start();
 
             loop {
                 if last_round_iter_count == 0 {
@@ -464,40 +903,108 @@ impl<'a> StringCommand<'a> {
                     }
 
                     let ttl = KeyDecoder::decode_key_ttl(&kv.1);
-                    // delete it if it is expired
+                    // delete it after the scan if it is expired
                     if key_is_expired(ttl) {
+                        expired.push(kv);
                         continue;
                     }
-                    if re.matches(str::from_utf8(&userkey).unwrap()) {
+                    if re.matches(&String::from_utf8_lossy(&userkey)) {
                         keys.push(resp_bulk(userkey));
                     }
                 }
             }
+            for kv in expired {
+                self.txn_expire_key(txn, client, &kv.0, &kv.1)?;
+            }
             Ok(resp_array(keys))
         })
     }
 
-    pub async fn scan(self, start: &str, count: u32, regex: &str) -> RocksResult<Frame> {
+    /// Up to `count` unexpired keys whose hash slot is `slot`, every meta key
+    /// of the keyspace is visited so this is only meant for admin usage.
+    pub async fn keys_in_slot(self, slot: u16, count: usize) -> RocksResult<Vec<Vec<u8>>> {
         let client = self.client;
         let cfs = StringCF::new(client);
-        let ekey = KEY_ENCODER.encode_string(start);
-        let re = Regex::new(regex).unwrap();
 
         client.exec_txn(|txn| {
             let mut keys = vec![];
+            let mut expired = vec![];
+            let mut last_round_iter_count = 1;
+
+            let mut left_bound = KEY_ENCODER.encode_keyspace_start();
+
+            while keys.len() < count && last_round_iter_count > 0 {
+                let range = left_bound.clone()..KEY_ENCODER.encode_keyspace_end();
+                let bound_range: BoundRange = range.into();
+
+                let iter = txn.scan(cfs.meta_cf.clone(), bound_range, 100)?;
+                // reset count to zero
+                last_round_iter_count = 0;
+                for kv in iter {
+                    // skip the left bound key, this should be exclusive
+                    if kv.0 == left_bound {
+                        continue;
+                    }
+                    left_bound = kv.0.clone();
+                    // left bound key is exclusive
+                    last_round_iter_count += 1;
+
+                    let (userkey, is_meta_key) = KeyDecoder::decode_key_userkey_from_metakey(&kv.0);
+
+                    // skip it if it is not a meta key
+                    if !is_meta_key {
+                        continue;
+                    }
+
+                    if slot_for_key(&String::from_utf8_lossy(&userkey)) != slot {
+                        continue;
+                    }
+                    let ttl = KeyDecoder::decode_key_ttl(&kv.1);
+                    // delete it after the scan if it is expired
+                    if key_is_expired(ttl) {
+                        expired.push(kv);
+                        continue;
+                    }
+                    keys.push(userkey);
+                    if keys.len() >= count {
+                        break;
+                    }
+                }
+            }
+            for kv in expired {
+                self.txn_expire_key(txn, client, &kv.0, &kv.1)?;
+            }
+            Ok(keys)
+        })
+    }
+
+    /// Scan `count` keys after the cursor, the cursor is the base64 of the
+    /// last returned meta key, "0" starts a new iteration and is returned
+    /// when the iteration completes.
+    pub async fn scan(self, cursor: &str, count: u32, pattern: &str) -> RocksResult<Frame> {
+        let client = self.client;
+        let cfs = StringCF::new(client);
+        let re = glob_pattern(pattern);
+        let start: Key = if cursor == "0" {
+            KEY_ENCODER.encode_keyspace_start()
+        } else {
+            match BASE64.decode(cursor) {
+                Ok(key) => key.into(),
+                Err(_) => return Ok(resp_err(REDIS_INVALID_CURSOR_ERR)),
+            }
+        };
+
+        client.exec_txn(|txn| {
+            let mut keys = vec![];
+            let mut expired = vec![];
             let mut retrieved_key_count = 0;
-            let mut next_key = vec![];
+            let mut next_cursor = None;
 
-            let mut left_bound = ekey.clone();
+            let mut left_bound = start.clone();
 
             // set to a non-zore value before loop
             let mut last_round_iter_count = 1;
-            while retrieved_key_count < count as usize {
-                if last_round_iter_count == 0 {
-                    next_key = vec![];
-                    break;
-                }
-
+            while next_cursor.is_none() && last_round_iter_count > 0 {
                 let range = left_bound.clone()..KEY_ENCODER.encode_keyspace_end();
                 let bound_range: BoundRange = range.into();
 
@@ -523,31 +1030,64 @@ impl<'a> StringCommand<'a> {
                     }
 
                     let ttl = KeyDecoder::decode_key_ttl(&kv.1);
-                    // delete it if it is expired
+                    // delete it after the scan if it is expired
                     if key_is_expired(ttl) {
+                        expired.push(kv);
                         continue;
                     }
-                    if retrieved_key_count == (count - 1) as usize {
-                        next_key = userkey.clone();
-                        retrieved_key_count += 1;
-                        if re.is_match(&userkey) {
-                            keys.push(resp_bulk(userkey));
-                        }
-                        break;
-                    }
                     retrieved_key_count += 1;
-                    if re.is_match(&userkey) {
+                    if re.matches(&String::from_utf8_lossy(&userkey)) {
                         keys.push(resp_bulk(userkey));
                     }
+                    if retrieved_key_count == count as usize {
+                        next_cursor = Some(BASE64.encode(Vec::<u8>::from(kv.0)));
+                        break;
+                    }
                 }
             }
-            let resp_next_key = resp_bulk(next_key);
-            let resp_keys = resp_array(keys);
+            for kv in expired {
+                self.txn_expire_key(txn, client, &kv.0, &kv.1)?;
+            }
+            let next_cursor = next_cursor.unwrap_or_else(|| "0".to_owned());
 
-            Ok(resp_array(vec![resp_next_key, resp_keys]))
+            Ok(resp_array(vec![
+                resp_bulk(next_cursor.into_bytes()),
+                resp_array(keys),
+            ]))
         })
     }
 
+    // delete the expired key of any type
+    fn txn_expire_key(
+        &self,
+        txn: &RocksTransaction,
+        client: &RocksClient,
+        ekey: &Key,
+        meta_value: &Value,
+    ) -> RocksResult<()> {
+        let (userkey, _) = KeyDecoder::decode_key_userkey_from_metakey(ekey);
+        let key = String::from_utf8_lossy(&userkey).to_string();
+        match KeyDecoder::decode_key_type(meta_value) {
+            DataType::String | DataType::Hll => {
+                self.txn_expire_if_needed(txn, client, ekey, meta_value)?;
+            }
+            DataType::Set => {
+                SetCommand::new(client).txn_expire_if_needed(txn, client, &key)?;
+            }
+            DataType::List => {
+                ListCommand::new(client).txn_expire_if_needed(txn, client, &key)?;
+            }
+            DataType::Hash => {
+                HashCommand::new(client).txn_expire_if_needed(txn, client, &key)?;
+            }
+            DataType::Zset => {
+                ZsetCommand::new(client).txn_expire_if_needed(txn, client, &key)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     fn txn_expire_if_needed(
         &self,
         txn: &RocksTransaction,
@@ -558,10 +1098,12 @@ impl<'a> StringCommand<'a> {
         let cfs = StringCF::new(client);
         let ttl = KeyDecoder::decode_key_ttl(meta_value);
         if key_is_expired(ttl) {
-            txn.del(cfs.meta_cf.clone(), ekey.to_owned())?;
+            txn.del_meta(cfs.meta_cf.clone(), ekey.to_owned())?;
             REMOVED_EXPIRED_KEY_COUNTER
                 .with_label_values(&["string"])
                 .inc();
+            let (ukey, _) = KeyDecoder::decode_key_userkey_from_metakey(ekey);
+            notify_event('x', "expired", &String::from_utf8_lossy(&ukey));
             return Ok(1);
         }
         Ok(0)
@@ -1,20 +1,26 @@
 use crate::metrics::ROCKS_ERR_COUNTER;
 use rocksdb::{ColumnFamilyRef, Direction, IteratorMode, Transaction, TransactionDB};
+use std::cell::RefCell;
 
 use crate::rocks::errors::TXN_ERROR;
 use crate::rocks::kv::bound_range::BoundRange;
 use crate::rocks::kv::key::Key;
 use crate::rocks::kv::kvpair::KvPair;
 use crate::rocks::kv::value::Value;
-use crate::rocks::Result as RocksResult;
+use crate::rocks::{apply_key_count_deltas, key_count_deltas, Result as RocksResult};
 
 pub struct RocksTransaction<'a> {
     inner_txn: Transaction<'a, TransactionDB>,
+    // keyspace key count changes, applied once the transaction is committed
+    key_count_deltas: RefCell<Vec<(String, i64)>>,
 }
 
 impl<'a> RocksTransaction<'a> {
     pub fn new(txn: Transaction<'a, TransactionDB>) -> Self {
-        Self { inner_txn: txn }
+        Self {
+            inner_txn: txn,
+            key_count_deltas: RefCell::new(vec![]),
+        }
     }
 
     pub fn get(&self, cf: ColumnFamilyRef, key: Key) -> RocksResult<Option<Value>> {
@@ -60,6 +66,31 @@ impl<'a> RocksTransaction<'a> {
         })
     }
 
+    /// Put the meta value of a key, counting the key as created if it is new
+    /// or was stored as another type.
+    pub fn put_meta(
+        &self,
+        cf: ColumnFamilyRef,
+        key: Key,
+        value: impl Into<Value>,
+    ) -> RocksResult<()> {
+        let value: Vec<u8> = value.into();
+        let old_value = self.get(cf.clone(), key.clone())?;
+        self.key_count_deltas
+            .borrow_mut()
+            .extend(key_count_deltas(old_value.as_deref(), Some(&value)));
+        self.put(cf, key, value)
+    }
+
+    /// Delete the meta value of a key, counting the key as removed.
+    pub fn del_meta(&self, cf: ColumnFamilyRef, key: Key) -> RocksResult<()> {
+        let old_value = self.get(cf.clone(), key.clone())?;
+        self.key_count_deltas
+            .borrow_mut()
+            .extend(key_count_deltas(old_value.as_deref(), None));
+        self.del(cf, key)
+    }
+
     pub fn batch_get(&self, cf: ColumnFamilyRef, keys: Vec<Key>) -> RocksResult<Vec<KvPair>> {
         let cf_key_pairs = keys
             .clone()
@@ -133,7 +164,9 @@ impl<'a> RocksTransaction<'a> {
                 .with_label_values(&["txn_client_error_commit"])
                 .inc();
             TXN_ERROR
-        })
+        })?;
+        apply_key_count_deltas(&self.key_count_deltas.into_inner());
+        Ok(())
     }
 
     pub fn scan(
@@ -1,8 +1,13 @@
-use crate::config::{async_del_zset_threshold_or_default, async_expire_zset_threshold_or_default};
+use crate::config::{
+    async_del_zset_threshold_or_default, async_expire_zset_threshold_or_default,
+    zset_max_ziplist_entries_or_default, zset_max_ziplist_value_or_default, LOGGER,
+};
 use crate::metrics::REMOVED_EXPIRED_KEY_COUNTER;
 use crate::rocks::client::{get_version_for_new, RocksClient};
 use crate::rocks::encoding::{DataType, KeyDecoder};
-use crate::rocks::errors::{REDIS_VALUE_IS_NOT_VALID_FLOAT_ERR, REDIS_WRONG_TYPE_ERR};
+use crate::rocks::errors::{
+    REDIS_DUMP_PAYLOAD_ERR, REDIS_VALUE_IS_NOT_VALID_FLOAT_ERR, REDIS_WRONG_TYPE_ERR, TXN_ERROR,
+};
 use crate::rocks::kv::bound_range::BoundRange;
 use crate::rocks::kv::key::Key;
 use crate::rocks::kv::value::Value;
@@ -11,9 +16,12 @@ use crate::rocks::{
     gen_next_meta_index, Result as RocksResult, TxnCommand, CF_NAME_GC, CF_NAME_GC_VERSION,
     CF_NAME_META, CF_NAME_ZSET_DATA, CF_NAME_ZSET_SCORE, CF_NAME_ZSET_SUB_META, KEY_ENCODER,
 };
-use crate::utils::{key_is_expired, resp_array, resp_bulk, resp_err, resp_int, resp_nil};
+use crate::utils::{
+    key_is_expired, notify_event, resp_array, resp_bulk, resp_err, resp_int, resp_nil, unique_pairs,
+};
 use crate::Frame;
 use rocksdb::ColumnFamilyRef;
+use slog::debug;
 use std::collections::HashMap;
 
 pub struct ZsetCF<'a> {
@@ -38,6 +46,12 @@ impl<'a> ZsetCF<'a> {
     }
 }
 
+// scores are compared by their bits, so 0 and -0, which are encoded to
+// different score keys, are different scores
+fn score_changed(old: f64, new: f64) -> bool {
+    old.total_cmp(&new) != std::cmp::Ordering::Equal
+}
+
 pub struct ZsetCommand<'a> {
     client: &'a RocksClient,
 }
@@ -54,17 +68,26 @@ impl<'a> ZsetCommand<'a> {
         scores: &Vec<f64>,
         exists: Option<bool>,
         changed_only: bool,
-        _incr: bool,
+        incr: bool,
     ) -> RocksResult<Frame> {
+        if incr {
+            // the command checked there is a single score and member pair
+            return self.incr_score(key, scores[0], &members[0], exists).await;
+        }
         let client = self.client;
         let cfs = ZsetCF::new(client);
         let key = key.to_owned();
-        let members = members.to_owned();
-        let scores = scores.to_owned();
+        // a member given more than once is added once, with its last score
+        let (members, scores) = unique_pairs(members, scores);
         let meta_key = KEY_ENCODER.encode_meta_key(&key);
         let rand_idx = gen_next_meta_index();
 
         let resp = client.exec_txn(|txn| {
+            if let Some(n) =
+                self.txn_zadd_compact(txn, &key, &members, &scores, exists, changed_only)?
+            {
+                return Ok(n);
+            }
             match txn.get(cfs.meta_cf.clone(), meta_key.clone())? {
                 Some(meta_value) => {
                     // check key type and ttl
@@ -113,82 +136,19 @@ impl<'a> ZsetCommand<'a> {
                             &members[idx],
                             version,
                         );
-                        let mut member_exists = false;
-                        let old_data_value = data_map.get(&data_key);
-                        let mut old_data_value_data: Vec<u8> = vec![];
-                        if let Some(v) = old_data_value {
-                            member_exists = true;
-                            old_data_value_data = v.clone();
+                        let old_score = data_map
+                            .get(&data_key)
+                            .map(|v| KeyDecoder::decode_key_zset_data_value(v));
+                        // XX Only update elements that already exists
+                        // NX Only add elements that not exists
+                        if exists.map_or(false, |xx| xx != old_score.is_some()) {
+                            continue;
                         }
-
-                        if let Some(v) = exists {
-                            // NX|XX
-                            if (v && member_exists) || (!v && !member_exists) {
-                                if !member_exists {
-                                    added_count += 1;
-                                }
-                                // XX Only update elements that already exists
-                                // NX Only add elements that not exists
-                                if changed_only {
-                                    if !member_exists {
-                                        updated_count += 1;
-                                    } else {
-                                        // check if score updated
-                                        let old_score = KeyDecoder::decode_key_zset_data_value(
-                                            &old_data_value_data,
-                                        );
-                                        if old_score != new_score {
-                                            updated_count += 1;
-                                        }
-                                    }
-                                }
-                                let data_value = KEY_ENCODER.encode_zset_data_value(new_score);
-                                txn.put(cfs.data_cf.clone(), data_key, data_value)?;
-
-                                // delete old score key if exists
-                                if member_exists {
-                                    let old_score = KeyDecoder::decode_key_zset_data_value(
-                                        &old_data_value_data,
-                                    );
-                                    if old_score != new_score {
-                                        let old_score_key = KEY_ENCODER.encode_zset_score_key(
-                                            &key,
-                                            old_score,
-                                            &members[idx],
-                                            version,
-                                        );
-                                        txn.del(cfs.score_cf.clone(), old_score_key)?;
-                                    }
-                                }
-                                txn.put(cfs.score_cf.clone(), score_key, members[idx].clone())?;
-                            }
-                        } else {
-                            if !member_exists {
-                                added_count += 1;
-                            }
-                            // no NX|XX argument
-                            if changed_only {
-                                if !member_exists {
+                        match old_score {
+                            Some(old_score) => {
+                                if score_changed(old_score, new_score) {
                                     updated_count += 1;
-                                } else {
-                                    // check if score updated
-                                    let old_score = KeyDecoder::decode_key_zset_data_value(
-                                        &old_data_value_data,
-                                    );
-                                    if old_score != new_score {
-                                        updated_count += 1;
-                                    }
-                                }
-                            }
-                            let data_value = KEY_ENCODER.encode_zset_data_value(new_score);
-                            let member = members[idx].clone();
-                            txn.put(cfs.data_cf.clone(), data_key, data_value)?;
-
-                            // delete old score key if it exists
-                            if member_exists {
-                                let old_score =
-                                    KeyDecoder::decode_key_zset_data_value(&old_data_value_data);
-                                if old_score != new_score {
+                                    // delete old score key
                                     let old_score_key = KEY_ENCODER.encode_zset_score_key(
                                         &key,
                                         old_score,
@@ -198,8 +158,14 @@ impl<'a> ZsetCommand<'a> {
                                     txn.del(cfs.score_cf.clone(), old_score_key)?;
                                 }
                             }
-                            txn.put(cfs.score_cf.clone(), score_key, member)?;
+                            None => {
+                                added_count += 1;
+                                updated_count += 1;
+                            }
                         }
+                        let data_value = KEY_ENCODER.encode_zset_data_value(new_score);
+                        txn.put(cfs.data_cf.clone(), data_key, data_value)?;
+                        txn.put(cfs.score_cf.clone(), score_key, members[idx].clone())?;
                     }
 
                     // update or add sub meta key
@@ -221,7 +187,7 @@ impl<'a> ZsetCommand<'a> {
                     // add meta key if key expired above
                     if expired {
                         let new_meta_value = KEY_ENCODER.encode_zset_meta_value(ttl, version, 0);
-                        txn.put(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
+                        txn.put_meta(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
                     }
 
                     if changed_only {
@@ -272,14 +238,17 @@ impl<'a> ZsetCommand<'a> {
                     // add meta key
                     let size = members.len() as i64;
                     let new_meta_value = KEY_ENCODER.encode_zset_meta_value(0, version, 0);
-                    txn.put(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
+                    txn.put_meta(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
                     Ok(size)
                 }
             }
         });
 
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(v) => {
+                notify_event('z', "zadd", key);
+                Ok(resp_int(v))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
@@ -333,6 +302,15 @@ impl<'a> ZsetCommand<'a> {
                         return Ok(resp_nil());
                     }
 
+                    if let Some(members) = KeyDecoder::decode_key_zset_compact(&meta_value) {
+                        return Ok(members
+                            .into_iter()
+                            .find(|(_, m)| m == member.as_bytes())
+                            .map_or_else(resp_nil, |(score, _)| {
+                                resp_bulk(score.to_string().as_bytes().to_vec())
+                            }));
+                    }
+
                     let data_key = KEY_ENCODER.encode_zset_data_key(&key, &member, version);
                     match txn.get(cfs.data_cf.clone(), data_key)? {
                         Some(data_value) => {
@@ -378,6 +356,16 @@ impl<'a> ZsetCommand<'a> {
                         return Ok(resp_int(0));
                     }
 
+                    if let Some(members) = KeyDecoder::decode_key_zset_compact(&meta_value) {
+                        let count = members
+                            .iter()
+                            .filter(|(score, _)| {
+                                Self::score_in_range(*score, min, min_inclusive, max, max_inclusive)
+                            })
+                            .count();
+                        return Ok(resp_int(count as i64));
+                    }
+
                     let start_key = KEY_ENCODER.encode_zset_score_key_score_start(
                         &key,
                         min,
@@ -445,6 +433,16 @@ impl<'a> ZsetCommand<'a> {
                         max = r_max;
                     }
 
+                    if let Some(members) = KeyDecoder::decode_key_zset_compact(&meta_value) {
+                        let members = members
+                            .into_iter()
+                            .enumerate()
+                            .filter(|(idx, _)| *idx as i64 >= min && *idx as i64 <= max)
+                            .map(|(_, item)| item)
+                            .collect();
+                        return Ok(Self::compact_range_resp(members, with_scores, reverse));
+                    }
+
                     let bound_range = KEY_ENCODER.encode_zset_score_key_range(&key, version);
                     let iter =
                         txn.scan(cfs.score_cf.clone(), bound_range, size.try_into().unwrap())?;
@@ -524,6 +522,16 @@ impl<'a> ZsetCommand<'a> {
                         return Ok(resp_array(vec![]));
                     }
 
+                    if let Some(members) = KeyDecoder::decode_key_zset_compact(&meta_value) {
+                        let members = members
+                            .into_iter()
+                            .filter(|(score, _)| {
+                                Self::score_in_range(*score, min, min_inclusive, max, max_inclusive)
+                            })
+                            .collect();
+                        return Ok(Self::compact_range_resp(members, with_scores, reverse));
+                    }
+
                     let size = self.sum_key_size(&key, version)?;
 
                     let start_key = KEY_ENCODER.encode_zset_score_key_score_start(
@@ -569,120 +577,45 @@ impl<'a> ZsetCommand<'a> {
 
     pub async fn zpop(self, key: &str, from_min: bool, count: u64) -> RocksResult<Frame> {
         let client = self.client;
-        let cfs = ZsetCF::new(client);
-        let key = key.to_owned();
-        let meta_key = KEY_ENCODER.encode_meta_key(&key);
-        let rand_idx = gen_next_meta_index();
+        let resp = client.exec_txn(|txn| self.txn_zpop(txn, key, from_min, count));
+        match resp {
+            Ok(v) => {
+                if !v.is_empty() {
+                    notify_event('z', if from_min { "zpopmin" } else { "zpopmax" }, key);
+                }
+                Ok(resp_array(v))
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
 
+    /// Pop up to `count` members from the first non-empty sorted set of
+    /// `keys`, reply the key and the popped member and score pairs.
+    pub async fn zmpop(self, keys: &[String], from_min: bool, count: u64) -> RocksResult<Frame> {
+        let client = self.client;
         let resp = client.exec_txn(|txn| {
-            match txn.get(cfs.meta_cf.clone(), meta_key.clone())? {
-                Some(meta_value) => {
-                    // check key type and ttl
-                    if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Zset) {
-                        return Err(REDIS_WRONG_TYPE_ERR);
-                    }
-
-                    let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
-                    if key_is_expired(ttl) {
-                        self.txn_expire_if_needed(txn, client, &key)?;
-                        return Ok(vec![]);
-                    }
-
-                    let mut poped_count = 0;
-                    let mut resp = vec![];
-                    let bound_range = KEY_ENCODER.encode_zset_score_key_range(&key, version);
-                    if from_min {
-                        let iter = txn.scan_keys(
-                            cfs.score_cf.clone(),
-                            bound_range,
-                            count.try_into().unwrap(),
-                        )?;
-                        for k in iter {
-                            let member =
-                                KeyDecoder::decode_key_zset_member_from_scorekey(&key, k.clone());
-                            let data_key = KEY_ENCODER.encode_zset_data_key(
-                                &key,
-                                &String::from_utf8_lossy(&member),
-                                version,
-                            );
-
-                            // push member to resp
-                            resp.push(resp_bulk(member));
-                            // push score to resp
-                            let score =
-                                KeyDecoder::decode_key_zset_score_from_scorekey(&key, k.clone());
-                            resp.push(resp_bulk(score.to_string().as_bytes().to_vec()));
-
-                            txn.del(cfs.data_cf.clone(), data_key)?;
-                            txn.del(cfs.score_cf.clone(), k)?;
-                            poped_count += 1;
-                        }
-                    } else {
-                        let iter = txn.scan_keys_reverse(
-                            cfs.score_cf.clone(),
-                            bound_range,
-                            count.try_into().unwrap(),
-                        )?;
-                        for k in iter {
-                            let member =
-                                KeyDecoder::decode_key_zset_member_from_scorekey(&key, k.clone());
-                            let data_key = KEY_ENCODER.encode_zset_data_key(
-                                &key,
-                                &String::from_utf8_lossy(&member),
-                                version,
-                            );
-
-                            // push member to resp
-                            resp.push(resp_bulk(member));
-                            // push score to resp
-                            let score =
-                                KeyDecoder::decode_key_zset_score_from_scorekey(&key, k.clone());
-                            resp.push(resp_bulk(score.to_string().as_bytes().to_vec()));
-
-                            txn.del(cfs.data_cf.clone(), data_key)?;
-                            txn.del(cfs.score_cf.clone(), k)?;
-                            poped_count += 1;
-                        }
-                    }
-
-                    let size = self.sum_key_size(&key, version)?;
-
-                    // delete all sub meta keys and meta key if all members poped
-                    if poped_count >= size {
-                        let bound_range = KEY_ENCODER.encode_sub_meta_key_range(&key, version);
-                        let iter = txn.scan_keys(cfs.sub_meta_cf.clone(), bound_range, u32::MAX)?;
-                        for k in iter {
-                            txn.del(cfs.sub_meta_cf.clone(), k)?;
-                        }
-
-                        txn.del(cfs.meta_cf.clone(), meta_key)?;
-                    } else {
-                        // update size to a random sub meta key
-                        let sub_meta_key = KEY_ENCODER.encode_sub_meta_key(&key, version, rand_idx);
-                        let new_sub_meta_value = txn
-                            .get_for_update(cfs.sub_meta_cf.clone(), sub_meta_key.clone())?
-                            .map_or_else(
-                                || -poped_count,
-                                |v| {
-                                    let old_sub_meta_value =
-                                        i64::from_be_bytes(v.try_into().unwrap());
-                                    old_sub_meta_value - poped_count
-                                },
-                            );
-                        txn.put(
-                            cfs.sub_meta_cf.clone(),
-                            sub_meta_key,
-                            new_sub_meta_value.to_be_bytes().to_vec(),
-                        )?;
-                    }
-
-                    Ok(resp)
+            for key in keys {
+                let values = self.txn_zpop(txn, key, from_min, count)?;
+                if !values.is_empty() {
+                    return Ok(Some((key, values)));
                 }
-                None => Ok(vec![]),
             }
+            Ok(None)
         });
+
         match resp {
-            Ok(v) => Ok(resp_array(v)),
+            Ok(Some((key, values))) => {
+                notify_event('z', if from_min { "zpopmin" } else { "zpopmax" }, key);
+                let pairs = values
+                    .chunks(2)
+                    .map(|pair| resp_array(pair.to_vec()))
+                    .collect();
+                Ok(resp_array(vec![
+                    resp_bulk(key.as_bytes().to_vec()),
+                    resp_array(pairs),
+                ]))
+            }
+            Ok(None) => Ok(resp_nil()),
             Err(e) => Ok(resp_err(e)),
         }
     }
@@ -708,6 +641,13 @@ impl<'a> ZsetCommand<'a> {
                         return Ok(resp_nil());
                     }
 
+                    if let Some(members) = KeyDecoder::decode_key_zset_compact(&meta_value) {
+                        return Ok(members
+                            .iter()
+                            .position(|(_, m)| m == member.as_bytes())
+                            .map_or_else(resp_nil, |rank| resp_int(rank as i64)));
+                    }
+
                     let data_key = KEY_ENCODER.encode_zset_data_key(&key, &member, version);
                     match txn.get(cfs.data_cf.clone(), data_key)? {
                         Some(data_value) => {
@@ -739,6 +679,18 @@ impl<'a> ZsetCommand<'a> {
     }
 
     pub async fn zincrby(self, key: &str, step: f64, member: &str) -> RocksResult<Frame> {
+        self.incr_score(key, step, member, None).await
+    }
+
+    // `exists` is the NX/XX flag of `ZADD INCR`, nil is replied when it
+    // prevents the update
+    async fn incr_score(
+        self,
+        key: &str,
+        step: f64,
+        member: &str,
+        exists: Option<bool>,
+    ) -> RocksResult<Frame> {
         if step.is_nan() {
             return Ok(resp_err(REDIS_VALUE_IS_NOT_VALID_FLOAT_ERR));
         }
@@ -750,6 +702,9 @@ impl<'a> ZsetCommand<'a> {
         let meta_key = KEY_ENCODER.encode_meta_key(&key);
 
         let resp = client.exec_txn(|txn| {
+            if let Some(new_score) = self.txn_zincrby_compact(txn, &key, &member, step, exists)? {
+                return Ok(new_score);
+            }
             let prev_score;
             let data_key;
             let mut version;
@@ -778,6 +733,8 @@ impl<'a> ZsetCommand<'a> {
                     data_key = KEY_ENCODER.encode_zset_data_key(&key, &member, version);
 
                     match txn.get_for_update(cfs.data_cf.clone(), data_key.clone())? {
+                        Some(_) if exists == Some(false) => return Ok(None),
+                        None if exists == Some(true) => return Ok(None),
                         Some(data_value) => {
                             prev_score = KeyDecoder::decode_key_zset_data_value(&data_value);
                             let prev_score_key = KEY_ENCODER
@@ -811,11 +768,12 @@ impl<'a> ZsetCommand<'a> {
                             if expired {
                                 let new_meta_value =
                                     KEY_ENCODER.encode_zset_meta_value(ttl, version, 0);
-                                txn.put(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
+                                txn.put_meta(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
                             }
                         }
                     }
                 }
+                None if exists == Some(true) => return Ok(None),
                 None => {
                     version = get_version_for_new(
                         txn,
@@ -825,7 +783,7 @@ impl<'a> ZsetCommand<'a> {
                     )?;
                     prev_score = 0f64;
                     let meta_value = KEY_ENCODER.encode_zset_meta_value(0, version, 0);
-                    txn.put(cfs.meta_cf.clone(), meta_key, meta_value)?;
+                    txn.put_meta(cfs.meta_cf.clone(), meta_key, meta_value)?;
                     data_key = KEY_ENCODER.encode_zset_data_key(&key, &member, version);
                     let sub_meta_key =
                         KEY_ENCODER.encode_sub_meta_key(&key, version, gen_next_meta_index());
@@ -843,11 +801,15 @@ impl<'a> ZsetCommand<'a> {
             txn.put(cfs.data_cf.clone(), data_key, data_value)?;
             txn.put(cfs.score_cf.clone(), score_key, member)?;
 
-            Ok(new_score)
+            Ok(Some(new_score))
         });
 
         match resp {
-            Ok(new_score) => Ok(resp_bulk(new_score.to_string().as_bytes().to_vec())),
+            Ok(Some(new_score)) => {
+                notify_event('z', "zincr", key);
+                Ok(resp_bulk(new_score.to_string().as_bytes().to_vec()))
+            }
+            Ok(None) => Ok(resp_nil()),
             Err(e) => Ok(resp_err(e)),
         }
     }
@@ -874,6 +836,18 @@ impl<'a> ZsetCommand<'a> {
                         return Ok(0);
                     }
 
+                    if KeyDecoder::decode_key_zset_compact(&meta_value).is_some() {
+                        let mut compact = self.txn_lock_compact(txn, &meta_key)?;
+                        let old_len = compact.len();
+                        compact
+                            .retain(|(_, m)| !members.iter().any(|member| m == member.as_bytes()));
+                        let removed_count = (old_len - compact.len()) as i64;
+                        if removed_count > 0 {
+                            self.txn_save_members(txn, &key, ttl, version, compact)?;
+                        }
+                        return Ok(removed_count);
+                    }
+
                     let data_keys: Vec<Key> = members
                         .iter()
                         .map(|member| KEY_ENCODER.encode_zset_data_key(&key, member, version))
@@ -909,7 +883,7 @@ impl<'a> ZsetCommand<'a> {
                         for k in iter {
                             txn.del(cfs.sub_meta_cf.clone(), k)?;
                         }
-                        txn.del(cfs.meta_cf.clone(), meta_key)?;
+                        txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
                     } else {
                         let sub_meta_key = KEY_ENCODER.encode_sub_meta_key(&key, version, rand_idx);
                         let new_sub_meta_value = txn
@@ -936,7 +910,12 @@ impl<'a> ZsetCommand<'a> {
         });
 
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(v) => {
+                if v > 0 {
+                    notify_event('z', "zrem", key);
+                }
+                Ok(resp_int(v))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
@@ -977,6 +956,22 @@ impl<'a> ZsetCommand<'a> {
                         max += size;
                     }
 
+                    if KeyDecoder::decode_key_zset_compact(&meta_value).is_some() {
+                        let members = self.txn_lock_compact(txn, &meta_key)?;
+                        let old_len = members.len();
+                        let members: Vec<(f64, Vec<u8>)> = members
+                            .into_iter()
+                            .enumerate()
+                            .filter(|(idx, _)| (*idx as i64) < min || (*idx as i64) > max)
+                            .map(|(_, item)| item)
+                            .collect();
+                        removed_count = (old_len - members.len()) as i64;
+                        if removed_count > 0 {
+                            self.txn_save_members(txn, &key, ttl, version, members)?;
+                        }
+                        return Ok(removed_count);
+                    }
+
                     let bound_range = KEY_ENCODER.encode_zset_score_key_range(&key, version);
                     let iter =
                         txn.scan(cfs.score_cf.clone(), bound_range, size.try_into().unwrap())?;
@@ -1010,7 +1005,7 @@ impl<'a> ZsetCommand<'a> {
                         for k in iter {
                             txn.del(cfs.sub_meta_cf.clone(), k)?;
                         }
-                        txn.del(cfs.meta_cf.clone(), meta_key)?;
+                        txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
                     } else {
                         let sub_meta_key = KEY_ENCODER.encode_sub_meta_key(&key, version, rand_idx);
                         let new_sub_meta_value = txn
@@ -1037,7 +1032,12 @@ impl<'a> ZsetCommand<'a> {
         });
 
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(v) => {
+                if v > 0 {
+                    notify_event('z', "zremrangebyrank", key);
+                }
+                Ok(resp_int(v))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
@@ -1067,6 +1067,19 @@ impl<'a> ZsetCommand<'a> {
                         return Ok(0);
                     }
 
+                    if KeyDecoder::decode_key_zset_compact(&meta_value).is_some() {
+                        let mut members = self.txn_lock_compact(txn, &meta_key)?;
+                        let old_len = members.len();
+                        members.retain(|(score, _)| {
+                            !Self::score_in_range(*score, min, true, max, true)
+                        });
+                        let removed_count = (old_len - members.len()) as i64;
+                        if removed_count > 0 {
+                            self.txn_save_members(txn, &key, ttl, version, members)?;
+                        }
+                        return Ok(removed_count);
+                    }
+
                     // generate score key range to remove, inclusive
                     let score_key_start =
                         KEY_ENCODER.encode_zset_score_key_score_start(&key, min, true, version);
@@ -1103,7 +1116,7 @@ impl<'a> ZsetCommand<'a> {
                         for k in iter {
                             txn.del(cfs.sub_meta_cf.clone(), k)?;
                         }
-                        txn.del(cfs.meta_cf.clone(), meta_key)?;
+                        txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
                     } else {
                         // update a random sub meta key
                         let sub_meta_key = KEY_ENCODER.encode_sub_meta_key(&key, version, rand_idx);
@@ -1131,11 +1144,389 @@ impl<'a> ZsetCommand<'a> {
         });
 
         match resp {
-            Ok(v) => Ok(resp_int(v)),
+            Ok(v) => {
+                if v > 0 {
+                    notify_event('z', "zremrangebyscore", key);
+                }
+                Ok(resp_int(v))
+            }
             Err(e) => Ok(resp_err(e)),
         }
     }
 
+    fn score_in_range(
+        score: f64,
+        min: f64,
+        min_inclusive: bool,
+        max: f64,
+        max_inclusive: bool,
+    ) -> bool {
+        let above_min = if min_inclusive {
+            score >= min
+        } else {
+            score > min
+        };
+        let below_max = if max_inclusive {
+            score <= max
+        } else {
+            score < max
+        };
+        above_min && below_max
+    }
+
+    // build range response from members sorted by score ascending
+    fn compact_range_resp(members: Vec<(f64, Vec<u8>)>, with_scores: bool, reverse: bool) -> Frame {
+        let mut resp = Vec::with_capacity(members.len() * 2);
+        let mut push = |(score, member): (f64, Vec<u8>)| {
+            resp.push(resp_bulk(member));
+            if with_scores {
+                resp.push(resp_bulk(score.to_string().as_bytes().to_vec()));
+            }
+        };
+        if reverse {
+            members.into_iter().rev().for_each(&mut push);
+        } else {
+            members.into_iter().for_each(&mut push);
+        }
+        resp_array(resp)
+    }
+
+    fn fits_compact(members: &[(f64, Vec<u8>)]) -> bool {
+        let max_value = zset_max_ziplist_value_or_default();
+        members.len() <= zset_max_ziplist_entries_or_default()
+            && members.iter().all(|(_, member)| member.len() <= max_value)
+    }
+
+    // pop up to `count` members with the lowest or highest scores, reply
+    // member and score pairs flattened, empty if the key does not exist
+    fn txn_zpop(
+        &self,
+        txn: &RocksTransaction,
+        key: &str,
+        from_min: bool,
+        count: u64,
+    ) -> RocksResult<Vec<Frame>> {
+        let client = self.client;
+        let cfs = ZsetCF::new(client);
+        let key = key.to_owned();
+        let meta_key = KEY_ENCODER.encode_meta_key(&key);
+        let rand_idx = gen_next_meta_index();
+
+        match txn.get(cfs.meta_cf.clone(), meta_key.clone())? {
+            Some(meta_value) => {
+                // check key type and ttl
+                if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Zset) {
+                    return Err(REDIS_WRONG_TYPE_ERR);
+                }
+
+                let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                if key_is_expired(ttl) {
+                    self.txn_expire_if_needed(txn, client, &key)?;
+                    return Ok(vec![]);
+                }
+
+                if KeyDecoder::decode_key_zset_compact(&meta_value).is_some() {
+                    let mut members = self.txn_lock_compact(txn, &meta_key)?;
+                    let count = (count as usize).min(members.len());
+                    let poped: Vec<(f64, Vec<u8>)> = if from_min {
+                        members.drain(..count).collect()
+                    } else {
+                        members.drain(members.len() - count..).rev().collect()
+                    };
+                    self.txn_save_members(txn, &key, ttl, version, members)?;
+                    return Ok(poped
+                        .into_iter()
+                        .flat_map(|(score, member)| {
+                            [
+                                resp_bulk(member),
+                                resp_bulk(score.to_string().as_bytes().to_vec()),
+                            ]
+                        })
+                        .collect());
+                }
+
+                let mut poped_count = 0;
+                let mut resp = vec![];
+                let bound_range = KEY_ENCODER.encode_zset_score_key_range(&key, version);
+                if from_min {
+                    let iter = txn.scan_keys(
+                        cfs.score_cf.clone(),
+                        bound_range,
+                        count.try_into().unwrap(),
+                    )?;
+                    for k in iter {
+                        let member =
+                            KeyDecoder::decode_key_zset_member_from_scorekey(&key, k.clone());
+                        let data_key = KEY_ENCODER.encode_zset_data_key(
+                            &key,
+                            &String::from_utf8_lossy(&member),
+                            version,
+                        );
+
+                        // push member to resp
+                        resp.push(resp_bulk(member));
+                        // push score to resp
+                        let score =
+                            KeyDecoder::decode_key_zset_score_from_scorekey(&key, k.clone());
+                        resp.push(resp_bulk(score.to_string().as_bytes().to_vec()));
+
+                        txn.del(cfs.data_cf.clone(), data_key)?;
+                        txn.del(cfs.score_cf.clone(), k)?;
+                        poped_count += 1;
+                    }
+                } else {
+                    let iter = txn.scan_keys_reverse(
+                        cfs.score_cf.clone(),
+                        bound_range,
+                        count.try_into().unwrap(),
+                    )?;
+                    for k in iter {
+                        let member =
+                            KeyDecoder::decode_key_zset_member_from_scorekey(&key, k.clone());
+                        let data_key = KEY_ENCODER.encode_zset_data_key(
+                            &key,
+                            &String::from_utf8_lossy(&member),
+                            version,
+                        );
+
+                        // push member to resp
+                        resp.push(resp_bulk(member));
+                        // push score to resp
+                        let score =
+                            KeyDecoder::decode_key_zset_score_from_scorekey(&key, k.clone());
+                        resp.push(resp_bulk(score.to_string().as_bytes().to_vec()));
+
+                        txn.del(cfs.data_cf.clone(), data_key)?;
+                        txn.del(cfs.score_cf.clone(), k)?;
+                        poped_count += 1;
+                    }
+                }
+
+                let size = self.sum_key_size(&key, version)?;
+
+                // delete all sub meta keys and meta key if all members poped
+                if poped_count >= size {
+                    let bound_range = KEY_ENCODER.encode_sub_meta_key_range(&key, version);
+                    let iter = txn.scan_keys(cfs.sub_meta_cf.clone(), bound_range, u32::MAX)?;
+                    for k in iter {
+                        txn.del(cfs.sub_meta_cf.clone(), k)?;
+                    }
+
+                    txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
+                } else {
+                    // update size to a random sub meta key
+                    let sub_meta_key = KEY_ENCODER.encode_sub_meta_key(&key, version, rand_idx);
+                    let new_sub_meta_value = txn
+                        .get_for_update(cfs.sub_meta_cf.clone(), sub_meta_key.clone())?
+                        .map_or_else(
+                            || -poped_count,
+                            |v| {
+                                let old_sub_meta_value = i64::from_be_bytes(v.try_into().unwrap());
+                                old_sub_meta_value - poped_count
+                            },
+                        );
+                    txn.put(
+                        cfs.sub_meta_cf.clone(),
+                        sub_meta_key,
+                        new_sub_meta_value.to_be_bytes().to_vec(),
+                    )?;
+                }
+
+                Ok(resp)
+            }
+            None => Ok(vec![]),
+        }
+    }
+
+    // lock the meta key and load members of a compact encoded zset
+    fn txn_lock_compact(
+        &self,
+        txn: &RocksTransaction,
+        meta_key: &Key,
+    ) -> RocksResult<Vec<(f64, Vec<u8>)>> {
+        let cfs = ZsetCF::new(self.client);
+        txn.get_for_update(cfs.meta_cf, meta_key.clone())?
+            .and_then(|v| KeyDecoder::decode_key_zset_compact(&v))
+            .ok_or(TXN_ERROR)
+    }
+
+    // load a new or compact encoded zset for update, expired key is treated as new,
+    // return None if the zset is stored in the full layout
+    #[allow(clippy::type_complexity)]
+    fn txn_load_compact(
+        &self,
+        txn: &RocksTransaction,
+        key: &str,
+    ) -> RocksResult<Option<(i64, u16, Vec<(f64, Vec<u8>)>, bool)>> {
+        let client = self.client;
+        let cfs = ZsetCF::new(client);
+        let meta_key = KEY_ENCODER.encode_meta_key(key);
+
+        match txn.get(cfs.meta_cf.clone(), meta_key.clone())? {
+            Some(meta_value) => {
+                if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Zset) {
+                    return Err(REDIS_WRONG_TYPE_ERR);
+                }
+                let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                if key_is_expired(ttl) {
+                    self.txn_expire_if_needed(txn, client, key)?;
+                    let version = get_version_for_new(
+                        txn,
+                        cfs.gc_cf.clone(),
+                        cfs.gc_version_cf.clone(),
+                        key,
+                    )?;
+                    Ok(Some((0, version, vec![], true)))
+                } else if KeyDecoder::decode_key_zset_compact(&meta_value).is_some() {
+                    let members = self.txn_lock_compact(txn, &meta_key)?;
+                    Ok(Some((ttl, version, members, false)))
+                } else {
+                    Ok(None)
+                }
+            }
+            None => {
+                // lock meta key to avoid concurrent creation
+                txn.get_for_update(cfs.meta_cf.clone(), meta_key)?;
+                let version =
+                    get_version_for_new(txn, cfs.gc_cf.clone(), cfs.gc_version_cf.clone(), key)?;
+                Ok(Some((0, version, vec![], true)))
+            }
+        }
+    }
+
+    // store members inline in meta value if they fit the compact thresholds,
+    // otherwise migrate them to the score and data keys layout
+    fn txn_save_members(
+        &self,
+        txn: &RocksTransaction,
+        key: &str,
+        ttl: i64,
+        version: u16,
+        mut members: Vec<(f64, Vec<u8>)>,
+    ) -> RocksResult<()> {
+        let cfs = ZsetCF::new(self.client);
+        let meta_key = KEY_ENCODER.encode_meta_key(key);
+        if members.is_empty() {
+            txn.del_meta(cfs.meta_cf, meta_key)?;
+            return Ok(());
+        }
+        members.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+        if Self::fits_compact(&members) {
+            let meta_value = KEY_ENCODER.encode_zset_compact_meta_value(ttl, version, &members);
+            txn.put_meta(cfs.meta_cf, meta_key, meta_value)?;
+            return Ok(());
+        }
+
+        debug!(LOGGER, "migrate compact zset {} to full layout", key);
+        let sub_meta_key = KEY_ENCODER.encode_sub_meta_key(key, version, gen_next_meta_index());
+        txn.put(
+            cfs.sub_meta_cf.clone(),
+            sub_meta_key,
+            (members.len() as i64).to_be_bytes().to_vec(),
+        )?;
+        for (score, member) in members {
+            let member = String::from_utf8_lossy(&member).to_string();
+            let data_key = KEY_ENCODER.encode_zset_data_key(key, &member, version);
+            let data_value = KEY_ENCODER.encode_zset_data_value(score);
+            txn.put(cfs.data_cf.clone(), data_key, data_value)?;
+            let score_key = KEY_ENCODER.encode_zset_score_key(key, score, &member, version);
+            txn.put(cfs.score_cf.clone(), score_key, member)?;
+        }
+        let meta_value = KEY_ENCODER.encode_zset_meta_value(ttl, version, 0);
+        txn.put_meta(cfs.meta_cf, meta_key, meta_value)?;
+        Ok(())
+    }
+
+    // add members to a new or compact encoded zset, return None if the zset
+    // should be handled in the full layout
+    fn txn_zadd_compact(
+        &self,
+        txn: &RocksTransaction,
+        key: &str,
+        members: &[String],
+        scores: &[f64],
+        exists: Option<bool>,
+        changed_only: bool,
+    ) -> RocksResult<Option<i64>> {
+        let (ttl, version, mut compact, is_new) = match self.txn_load_compact(txn, key)? {
+            Some(loaded) => loaded,
+            None => return Ok(None),
+        };
+        if is_new && exists == Some(true) {
+            // xx flag specified, do not create new key
+            return Ok(Some(0));
+        }
+
+        let mut added_count = 0;
+        let mut updated_count = 0;
+        for (member, score) in members.iter().zip(scores) {
+            match compact.iter_mut().find(|(_, m)| m == member.as_bytes()) {
+                Some((old_score, _)) => {
+                    // NX Only add elements that not exists
+                    if exists == Some(false) {
+                        continue;
+                    }
+                    if score_changed(*old_score, *score) {
+                        *old_score = *score;
+                        updated_count += 1;
+                    }
+                }
+                None => {
+                    // XX Only update elements that already exists
+                    if exists == Some(true) {
+                        continue;
+                    }
+                    compact.push((*score, member.as_bytes().to_vec()));
+                    added_count += 1;
+                    updated_count += 1;
+                }
+            }
+        }
+
+        if is_new && !Self::fits_compact(&compact) {
+            // too large for compact encoding, create it in the full layout directly
+            return Ok(None);
+        }
+        self.txn_save_members(txn, key, ttl, version, compact)?;
+        if changed_only {
+            Ok(Some(updated_count))
+        } else {
+            Ok(Some(added_count))
+        }
+    }
+
+    fn txn_zincrby_compact(
+        &self,
+        txn: &RocksTransaction,
+        key: &str,
+        member: &str,
+        step: f64,
+        exists: Option<bool>,
+    ) -> RocksResult<Option<Option<f64>>> {
+        let (ttl, version, mut compact, is_new) = match self.txn_load_compact(txn, key)? {
+            Some(loaded) => loaded,
+            None => return Ok(None),
+        };
+        let new_score = match compact.iter_mut().find(|(_, m)| m == member.as_bytes()) {
+            // NX/XX prevents the update
+            Some(_) if exists == Some(false) => return Ok(Some(None)),
+            None if exists == Some(true) => return Ok(Some(None)),
+            Some((score, _)) => {
+                *score += step;
+                *score
+            }
+            None => {
+                compact.push((step, member.as_bytes().to_vec()));
+                step
+            }
+        };
+        if is_new && !Self::fits_compact(&compact) {
+            return Ok(None);
+        }
+        self.txn_save_members(txn, key, ttl, version, compact)?;
+        Ok(Some(Some(new_score)))
+    }
+
     fn sum_key_size(&self, key: &str, version: u16) -> RocksResult<i64> {
         let client = self.client;
         let cfs = ZsetCF::new(client);
@@ -1149,6 +1540,9 @@ impl<'a> ZsetCommand<'a> {
                     if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Zset) {
                         return Err(REDIS_WRONG_TYPE_ERR);
                     }
+                    if let Some(members) = KeyDecoder::decode_key_zset_compact(&meta_value) {
+                        return Ok(members.len() as i64);
+                    }
                     let bound_range = KEY_ENCODER.encode_sub_meta_key_range(&key, version);
                     let iter = txn.scan(cfs.sub_meta_cf.clone(), bound_range, u32::MAX)?;
 
@@ -1161,10 +1555,37 @@ impl<'a> ZsetCommand<'a> {
             }
         })
     }
+
+    /// Create the zset from the member and score pairs of a `RESTORE`
+    /// payload, the key must have been deleted in the transaction.
+    pub(crate) fn txn_restore(
+        &self,
+        txn: &RocksTransaction,
+        key: &str,
+        ttl: i64,
+        elements: Vec<Vec<u8>>,
+    ) -> RocksResult<()> {
+        let cfs = ZsetCF::new(self.client);
+        let mut members = Vec::with_capacity(elements.len() / 2);
+        for ms in elements.chunks(2) {
+            let score = String::from_utf8_lossy(&ms[1])
+                .parse::<f64>()
+                .map_err(|_| REDIS_DUMP_PAYLOAD_ERR)?;
+            members.push((score, ms[0].clone()));
+        }
+        let version = get_version_for_new(txn, cfs.gc_cf.clone(), cfs.gc_version_cf.clone(), key)?;
+        self.txn_save_members(txn, key, ttl, version, members)
+    }
 }
 
 impl TxnCommand for ZsetCommand<'_> {
-    fn txn_del(&self, txn: &RocksTransaction, client: &RocksClient, key: &str) -> RocksResult<()> {
+    fn txn_del(
+        &self,
+        txn: &RocksTransaction,
+        client: &RocksClient,
+        key: &str,
+        always_async: bool,
+    ) -> RocksResult<()> {
         let key = key.to_owned();
         let meta_key = KEY_ENCODER.encode_meta_key(&key);
         let cfs = ZsetCF::new(client);
@@ -1174,9 +1595,9 @@ impl TxnCommand for ZsetCommand<'_> {
                 let version = KeyDecoder::decode_key_version(&meta_value);
                 let size = self.sum_key_size(&key, version)?;
 
-                if size > async_del_zset_threshold_or_default() as i64 {
+                if always_async || size > async_del_zset_threshold_or_default() as i64 {
                     // async del zset
-                    txn.del(cfs.meta_cf.clone(), meta_key)?;
+                    txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
 
                     let gc_key = KEY_ENCODER.encode_gc_key(&key);
                     txn.put(cfs.gc_cf.clone(), gc_key, version.to_be_bytes().to_vec())?;
@@ -1185,7 +1606,7 @@ impl TxnCommand for ZsetCommand<'_> {
                     txn.put(
                         cfs.gc_version_cf.clone(),
                         gc_version_key,
-                        vec![KEY_ENCODER.get_type_bytes(DataType::Zset)],
+                        KEY_ENCODER.encode_gc_version_value(DataType::Zset, size as u64),
                     )?;
                 } else {
                     let bound_range = KEY_ENCODER.encode_zset_data_key_range(&key, version);
@@ -1214,7 +1635,7 @@ impl TxnCommand for ZsetCommand<'_> {
                     for k in iter {
                         txn.del(cfs.sub_meta_cf.clone(), k)?;
                     }
-                    txn.del(cfs.meta_cf.clone(), meta_key)?;
+                    txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
                 }
                 Ok(())
             }
@@ -1245,7 +1666,7 @@ impl TxnCommand for ZsetCommand<'_> {
 
                 if size > async_expire_zset_threshold_or_default() as i64 {
                     // async del zset
-                    txn.del(cfs.meta_cf.clone(), meta_key)?;
+                    txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
 
                     let gc_key = KEY_ENCODER.encode_gc_key(&key);
                     txn.put(cfs.gc_cf.clone(), gc_key, version.to_be_bytes().to_vec())?;
@@ -1254,7 +1675,7 @@ impl TxnCommand for ZsetCommand<'_> {
                     txn.put(
                         cfs.gc_version_cf.clone(),
                         gc_version_key,
-                        vec![KEY_ENCODER.get_type_bytes(DataType::Zset)],
+                        KEY_ENCODER.encode_gc_version_value(DataType::Zset, size as u64),
                     )?;
                 } else {
                     let bound_range = KEY_ENCODER.encode_zset_data_key_range(&key, version);
@@ -1283,11 +1704,12 @@ impl TxnCommand for ZsetCommand<'_> {
                     for k in iter {
                         txn.del(cfs.sub_meta_cf.clone(), k)?;
                     }
-                    txn.del(cfs.meta_cf.clone(), meta_key)?;
+                    txn.del_meta(cfs.meta_cf.clone(), meta_key)?;
                 }
                 REMOVED_EXPIRED_KEY_COUNTER
                     .with_label_values(&["set"])
                     .inc();
+                notify_event('x', "expired", &key);
                 Ok(1)
             }
             None => Ok(0),
@@ -1310,8 +1732,13 @@ impl TxnCommand for ZsetCommand<'_> {
             return Ok(0);
         }
         let version = KeyDecoder::decode_key_version(meta_value);
-        let new_meta_value = KEY_ENCODER.encode_zset_meta_value(timestamp, version, 0);
-        txn.put(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
+        let new_meta_value = match KeyDecoder::decode_key_zset_compact(meta_value) {
+            Some(members) => {
+                KEY_ENCODER.encode_zset_compact_meta_value(timestamp, version, &members)
+            }
+            None => KEY_ENCODER.encode_zset_meta_value(timestamp, version, 0),
+        };
+        txn.put_meta(cfs.meta_cf.clone(), meta_key, new_meta_value)?;
         Ok(1)
     }
 
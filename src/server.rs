@@ -1,42 +1,59 @@
 use crate::{
-    Command, Connection, Db, DbDropGuard, MapucheError, Shutdown, P2P_CLIENT, RAFT_CLIENT,
-    RING_NODES,
+    Command, Connection, Db, DbDropGuard, Frame, MapucheError, Shutdown, Stream, P2P_CLIENT,
+    PUBSUB_DB, RAFT_CLIENT, RING_NODES,
 };
 use std::collections::HashMap;
 
 use crate::client::Client;
 use crate::config::{
-    async_gc_worker_number_or_default, config_cluster_or_default, config_infra_or_default,
-    config_local_pool_number, config_max_connection, is_auth_enabled, is_auth_matched, LOGGER,
+    async_gc_worker_number_or_default, audit_log_or_default, config_infra_or_default,
+    config_local_pool_number, config_max_connection, config_ring_port_or_default, config_tls,
+    config_unixsocket, is_auth_enabled, slowlog_threshold_us_or_default, LOGGER,
 };
+use crate::eviction::record_access;
 use crate::gc::GcMaster;
+use crate::latency::record_latency;
+use crate::metrics::set_server_ready;
 use crate::metrics::{
-    CURRENT_CONNECTION_COUNTER, RAFT_REMOTE_COUNTER, RAFT_REMOTE_DURATION, REQUEST_CMD_COUNTER,
-    REQUEST_CMD_ERROR_COUNTER, REQUEST_CMD_FINISH_COUNTER, REQUEST_CMD_HANDLE_TIME,
-    REQUEST_CMD_REMOTE_COUNTER, REQUEST_COUNTER, TOTAL_CONNECTION_PROCESSED,
+    CONNECTIONS_NEAR_LIMIT, CURRENT_CONNECTION_COUNTER, RAFT_REMOTE_COUNTER, RAFT_REMOTE_DURATION,
+    REQUEST_CMD_COUNTER, REQUEST_CMD_ERROR_COUNTER, REQUEST_CMD_FINISH_COUNTER,
+    REQUEST_CMD_HANDLE_TIME, REQUEST_CMD_REMOTE_COUNTER, REQUEST_CMD_TYPE_HANDLE_TIME,
+    REQUEST_COUNTER, TOTAL_CONNECTION_PROCESSED,
 };
 use crate::p2p::message::Message;
 use crate::rocks::errors::{
-    REDIS_AUTH_INVALID_PASSWORD_ERR, REDIS_AUTH_REQUIRED_ERR, REDIS_AUTH_WHEN_DISABLED_ERR,
+    RError, REDIS_AUTH_INVALID_PASSWORD_ERR, REDIS_AUTH_REQUIRED_ERR, REDIS_AUTH_WHEN_DISABLED_ERR,
+    REDIS_AUTH_WRONGPASS_ERR, REDIS_BUSY_CONNECTIONS_ERR, REDIS_DISCARD_WITHOUT_MULTI_ERR,
+    REDIS_EXECABORT_ERR, REDIS_EXEC_WITHOUT_MULTI_ERR, REDIS_INVALID_CLIENT_NAME_ERR,
+    REDIS_MULTI_NESTED_ERR, REDIS_MULTI_NOT_ALLOWED_ERR, REDIS_MULTI_REMOTE_KEY_ERR,
+    REDIS_NOPERM_KEY_ERR, REDIS_NOPROTO_ERR,
+};
+use crate::rocks::get_client;
+use crate::slowlog::frame_args;
+use crate::tls::tls_acceptor;
+use crate::utils::{
+    now_timestamp_in_millis, resp_array, resp_err, resp_int, resp_invalid_arguments, resp_ok,
+    resp_str,
 };
-use crate::utils::{now_timestamp_in_millis, resp_err, resp_invalid_arguments, resp_ok};
 use local_ip_address::local_ip;
-use slog::{debug, error, info};
+use slog::{debug, error, info, warn};
 use std::future::Future;
 use std::sync::Arc;
-use tokio::net::{TcpListener, TcpStream};
+use tokio::net::{TcpListener, UnixListener};
 
-use crate::cmd::CommandType;
+use crate::acl::{ACL_STORE, DEFAULT_USER};
+use crate::audit::{audit, AuditEntry};
+use crate::cmd::{execute_replicated, valid_client_name, Auth, CommandType, Hello, SCRIPT_LOCK};
 use crate::raft::store::RaftResponse;
 use crate::raft::RaftRequest;
 use tokio::sync::{broadcast, mpsc, Mutex, Semaphore};
 use tokio::time::{self, Duration, Instant};
+use tokio_rustls::TlsAcceptor;
 use tokio_util::task::LocalPoolHandle;
 use uuid::Uuid;
 
 /// Server listener state. Created in the `run` call. It includes a `run` method
 /// which performs the TCP listening and initialization of per-connection state.
-#[derive(Debug)]
 struct Listener {
     /// Shared database handle.
     ///
@@ -47,10 +64,12 @@ struct Listener {
     /// retrieved and passed into the per connection state (`Handler`).
     db_holder: DbDropGuard,
 
-    /// TCP listener supplied by the `run` caller.
-    listener: TcpListener,
+    /// TCP listener supplied by the `run` caller, or the Unix domain socket
+    /// listener bound from `unixsocket` config.
+    listener: SocketListener,
 
     limit_connections: Arc<Semaphore>,
+    max_connections: usize,
     clients: Arc<Mutex<HashMap<u64, Arc<Mutex<Client>>>>>,
 
     /// Broadcasts a shutdown signal to all active connections.
@@ -75,8 +94,31 @@ struct Listener {
     /// complete, all clones of the `Sender` are also dropped. This results in
     /// `shutdown_complete_rx.recv()` completing with `None`. At this point, it
     /// is safe to exit the server process.
-    shutdown_complete_rx: mpsc::Receiver<()>,
     shutdown_complete_tx: mpsc::Sender<()>,
+
+    /// Wraps accepted sockets with TLS when the `tls` section is configured.
+    tls_acceptor: Option<TlsAcceptor>,
+}
+
+/// Source of inbound sockets of a `Listener`.
+enum SocketListener {
+    Tcp(TcpListener),
+    Unix(UnixListener),
+}
+
+impl SocketListener {
+    async fn accept(&self) -> std::io::Result<Stream> {
+        match self {
+            SocketListener::Tcp(listener) => listener
+                .accept()
+                .await
+                .map(|(socket, _)| Stream::Tcp(socket)),
+            SocketListener::Unix(listener) => listener
+                .accept()
+                .await
+                .map(|(socket, _)| Stream::Unix(socket)),
+        }
+    }
 }
 
 /// Per-connection handler. Reads requests from `connection` and applies the
@@ -89,6 +131,15 @@ struct Handler {
     connection: Connection,
     shutdown: Shutdown,
     authorized: bool,
+    // acl user of the connection, `default` until authenticated as another
+    user: String,
+    // commands queued since MULTI, `None` outside of a transaction
+    multi: Option<Vec<Command>>,
+    // a command could not be queued, EXEC discards the transaction
+    txn_error: bool,
+    // accepted while the connections were near the limit, only PING and AUTH
+    // are served
+    busy: bool,
     _shutdown_complete: mpsc::Sender<()>,
 }
 
@@ -108,23 +159,79 @@ pub async fn run(listener: TcpListener, shutdown: impl Future) {
     // a receiver is needed, the subscribe() method on the sender is used to create
     // one.
     let (notify_shutdown, _) = broadcast::channel(1);
-    let (shutdown_complete_tx, shutdown_complete_rx) = mpsc::channel(1);
+    let (shutdown_complete_tx, mut shutdown_complete_rx) = mpsc::channel(1);
     let db_holder = DbDropGuard::new();
+    unsafe {
+        PUBSUB_DB.replace(db_holder.db());
+    }
+
+    let tls_acceptor = match config_tls().map(|c| tls_acceptor(&c)).transpose() {
+        Ok(acceptor) => acceptor,
+        Err(err) => {
+            error!(
+                LOGGER,
+                "failed to load tls config, case {}",
+                err.to_string()
+            );
+            return;
+        }
+    };
 
     // Initialize the listener state
+    let max_connections = config_max_connection();
     let mut server = Listener {
-        listener,
+        listener: SocketListener::Tcp(listener),
         db_holder: db_holder.clone(),
-        limit_connections: Arc::new(Semaphore::new(config_max_connection())),
+        limit_connections: Arc::new(Semaphore::new(max_connections)),
+        max_connections,
         clients: Arc::new(Mutex::new(HashMap::new())),
         notify_shutdown,
         shutdown_complete_tx,
-        shutdown_complete_rx,
+        tls_acceptor,
+    };
+
+    // The Unix domain socket listener shares the db, connection limit and
+    // shutdown channels with the TCP one.
+    let mut unix_server = match config_unixsocket() {
+        Some(path) => {
+            // remove the socket file left by a previous run
+            let _ = std::fs::remove_file(&path);
+            match UnixListener::bind(&path) {
+                Ok(listener) => {
+                    info!(LOGGER, "listening on unix socket {}", path);
+                    Some(Listener {
+                        listener: SocketListener::Unix(listener),
+                        db_holder: db_holder.clone(),
+                        limit_connections: server.limit_connections.clone(),
+                        max_connections,
+                        clients: server.clients.clone(),
+                        notify_shutdown: server.notify_shutdown.clone(),
+                        shutdown_complete_tx: server.shutdown_complete_tx.clone(),
+                        tls_acceptor: None,
+                    })
+                }
+                Err(err) => {
+                    error!(LOGGER, "failed to bind unix socket {}, case {}", path, err);
+                    return;
+                }
+            }
+        }
+        None => None,
+    };
+    let unix_run = async {
+        match unix_server.as_mut() {
+            Some(server) => server.run().await,
+            None => std::future::pending().await,
+        }
     };
 
     let mut gc_master = GcMaster::new(async_gc_worker_number_or_default());
     gc_master.start_workers().await;
 
+    // rocksdb is opened lazily, open it before reporting ready
+    get_client();
+    set_server_ready(true);
+
     tokio::select! {
         res = server.run() => {
             // If an error is received here, accepting connections from the TCP
@@ -137,6 +244,11 @@ pub async fn run(listener: TcpListener, shutdown: impl Future) {
                 error!(LOGGER, "failed to accept, case {}", err.to_string());
             }
         }
+        res = unix_run => {
+            if let Err(err) = res {
+                error!(LOGGER, "failed to accept unix socket, case {}", err.to_string());
+            }
+        }
         _ = gc_master.run() => {
             error!(LOGGER, "gc master exit");
         }
@@ -145,16 +257,17 @@ pub async fn run(listener: TcpListener, shutdown: impl Future) {
             info!(LOGGER, "shutting down");
         }
     }
+    set_server_ready(false);
 
     // Extract the `shutdown_complete` receiver and transmitter
     // explicitly drop `shutdown_transmitter`. This is important, as the
     // `.await` below would otherwise never complete.
     let Listener {
-        mut shutdown_complete_rx,
         shutdown_complete_tx,
         notify_shutdown,
         ..
     } = server;
+    drop(unix_server);
 
     // When `notify_shutdown` is dropped, all tasks which have `subscribe`d will
     // receive the shutdown signal and can exit
@@ -200,8 +313,12 @@ impl Listener {
                 .unwrap();
 
             let socket = self.accept().await?;
+            let db = self.db_holder.db();
+            // the connections open before the limit is near are served as usual
+            let busy =
+                update_connections_near_limit(&db, &self.limit_connections, self.max_connections);
             let (kill_tx, kill_rx) = mpsc::channel(1);
-            let client = Client::new(&socket, kill_tx);
+            let client = Client::new(&socket, socket.peer_addr(), kill_tx);
             let client_id = client.id();
             let arc_client = Arc::new(Mutex::new(client));
             self.clients
@@ -209,17 +326,43 @@ impl Listener {
                 .await
                 .insert(client_id, arc_client.clone());
 
-            // Create the necessary per-connection handler state.
-            let mut handler = Handler {
-                db: self.db_holder.db(),
-                cur_client: arc_client.clone(),
-                clients: self.clients.clone(),
-                connection: Connection::new(socket),
-                shutdown: Shutdown::new(self.notify_shutdown.subscribe(), kill_rx),
-                authorized: !is_auth_enabled(),
-                _shutdown_complete: self.shutdown_complete_tx.clone(),
-            };
-            local_pool.spawn_pinned(|| async move {
+            let limit_connections = self.limit_connections.clone();
+            let max_connections = self.max_connections;
+            let clients = self.clients.clone();
+            let shutdown = Shutdown::new(self.notify_shutdown.subscribe(), kill_rx);
+            let shutdown_complete = self.shutdown_complete_tx.clone();
+            let tls_acceptor = self.tls_acceptor.clone();
+            local_pool.spawn_pinned(move || async move {
+                // Finish the tls handshake in the connection task, so a slow
+                // client does not block accepting others.
+                let stream = match (tls_acceptor, socket) {
+                    (Some(acceptor), Stream::Tcp(socket)) => match acceptor.accept(socket).await {
+                        Ok(tls_stream) => Stream::Tls(Box::new(tls_stream.into())),
+                        Err(err) => {
+                            error!(LOGGER, "tls handshake error {:?}", err);
+                            clients.lock().await.remove(&client_id);
+                            drop(permit);
+                            return;
+                        }
+                    },
+                    (_, socket) => socket,
+                };
+
+                // Create the necessary per-connection handler state.
+                let mut handler = Handler {
+                    db,
+                    cur_client: arc_client,
+                    clients,
+                    connection: Connection::new(stream),
+                    shutdown,
+                    authorized: !is_auth_enabled(),
+                    user: DEFAULT_USER.to_owned(),
+                    multi: None,
+                    txn_error: false,
+                    busy,
+                    _shutdown_complete: shutdown_complete,
+                };
+
                 // Process the connection. If an error is encountered, log it.
                 CURRENT_CONNECTION_COUNTER.inc();
                 TOTAL_CONNECTION_PROCESSED.inc();
@@ -232,7 +375,8 @@ impl Listener {
                     .await
                     .remove(&handler.cur_client.lock().await.id());
                 CURRENT_CONNECTION_COUNTER.dec();
-                drop(permit)
+                drop(permit);
+                update_connections_near_limit(&handler.db, &limit_connections, max_connections);
             });
         }
     }
@@ -244,7 +388,7 @@ impl Listener {
     /// After the second failure, the task waits for 2 seconds. Each subsequent
     /// failure doubles the wait time. If accepting fails on the 6th try after
     /// waiting for 64 seconds, then this function returns with an error.
-    async fn accept(&mut self) -> crate::Result<TcpStream> {
+    async fn accept(&mut self) -> crate::Result<Stream> {
         let mut backoff = 1;
 
         // Try to accept a few times
@@ -252,7 +396,7 @@ impl Listener {
             // Perform the accept operation. If a socket is successfully
             // accepted, return it. Otherwise, save the error.
             match self.listener.accept().await {
-                Ok((socket, _)) => return Ok(socket),
+                Ok(socket) => return Ok(socket),
                 Err(err) => {
                     error!(LOGGER, "Accept Error! {:?}", &err);
                     if backoff > 64 {
@@ -307,70 +451,264 @@ impl Handler {
                 None => return Ok(()),
             };
 
+            // Arguments are kept for the slow log before the frame is consumed
+            let args = frame_args(&frame);
+
             // Convert the redis frame into a command struct. This returns an
             // error if the frame is not a valid redis command or it is an
             // unsupported command.
-            let cmd = Command::from_frame(frame)?;
+            let cmd = match Command::from_frame(frame) {
+                Ok(cmd) => cmd,
+                // a malformed command of a transaction discards it at EXEC
+                Err(e) if self.multi.is_some() => {
+                    self.txn_error = true;
+                    self.connection
+                        .write_frame(&Frame::Error(format!("ERR {e}")))
+                        .await?;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
             let cmd_name = cmd.get_name().to_owned();
+            let data_type = cmd.data_type();
+            let trace_id = Uuid::new_v4().to_string();
 
             {
                 let mut w_client = self.cur_client.lock().await;
-                w_client.interact(&cmd_name);
+                w_client.interact(&cmd_name, &trace_id);
+            }
+
+            // CLIENT is never paused, or the pause could not be cleared.
+            // Queued commands are paused with their EXEC.
+            let queuing = self.multi.is_some() && !matches!(cmd, Command::Exec(_));
+            if !matches!(cmd, Command::ClientCmd(_)) && !queuing {
+                let is_write = match (&cmd, &self.multi) {
+                    (Command::Exec(_), Some(queued)) => queued
+                        .iter()
+                        .any(|c| matches!(c.cmd_type(), CommandType::WRITE)),
+                    _ => matches!(cmd.cmd_type(), CommandType::WRITE),
+                };
+                tokio::select! {
+                    _ = self.db.wait_client_pause(is_write) => {}
+                    _ = self.shutdown.recv() => return Ok(()),
+                }
             }
 
             let start_at = Instant::now();
             REQUEST_COUNTER.inc();
             REQUEST_CMD_COUNTER.with_label_values(&[&cmd_name]).inc();
 
-            debug!(LOGGER, "req, {:?}", cmd);
+            debug!(LOGGER, "req, {:?}", cmd; "trace_id" => &trace_id);
 
-            match cmd {
+            // the key is taken before the command is consumed by its execution
+            let audit_key = audit_log_or_default().then(|| cmd.hash_ring_key().ok());
+            let mut failed = false;
+            match &cmd {
+                _ if self.busy && !matches!(cmd, Command::Ping(_) | Command::Auth(_)) => {
+                    failed = true;
+                    self.connection
+                        .write_frame(&resp_err(REDIS_BUSY_CONNECTIONS_ERR))
+                        .await?;
+                }
+                _ if self.multi.is_some()
+                    && !matches!(
+                        cmd,
+                        Command::Multi(_)
+                            | Command::Exec(_)
+                            | Command::Discard(_)
+                            | Command::Reset(_)
+                    ) =>
+                {
+                    let frame = self.queue_command(cmd.clone());
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::Multi(_) => {
+                    let frame = match self.check_permission(&cmd) {
+                        Some(denied) => denied,
+                        None => self.multi(),
+                    };
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::Exec(_) => {
+                    let frame = match self.check_permission(&cmd) {
+                        Some(denied) => denied,
+                        None => self.exec().await,
+                    };
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::Discard(_) => {
+                    let frame = match self.check_permission(&cmd) {
+                        Some(denied) => denied,
+                        None => self.discard(),
+                    };
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
                 Command::Auth(c) => {
-                    if !c.valid() {
-                        self.connection
-                            .write_frame(&resp_invalid_arguments())
-                            .await?;
-                    } else if !is_auth_enabled() {
-                        // check password and update connection authorized flag
-                        self.connection
-                            .write_frame(&resp_err(REDIS_AUTH_WHEN_DISABLED_ERR))
-                            .await?;
-                    } else if is_auth_matched(c.passwd()) {
-                        self.connection.write_frame(&resp_ok()).await?;
-                        self.authorized = true;
-                    } else {
-                        self.connection
-                            .write_frame(&resp_err(REDIS_AUTH_INVALID_PASSWORD_ERR))
-                            .await?;
-                    }
+                    let frame = self.auth(c);
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::Hello(c) => {
+                    let frame = self.hello(c).await;
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::Reset(_) => {
+                    let frame = self.reset().await;
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::ClientCmd(c) => {
+                    let frame = match self.check_permission(&cmd) {
+                        Some(denied) => denied,
+                        None => c.execute(&self.cur_client, &self.clients, &self.db).await,
+                    };
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::Acl(c) => {
+                    let frame = match self.check_permission(&cmd) {
+                        Some(denied) => denied,
+                        None => c.execute(&self.user),
+                    };
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::Slowlog(c) => {
+                    let frame = match self.check_permission(&cmd) {
+                        Some(denied) => denied,
+                        None => c.execute(&self.db),
+                    };
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::Latency(c) => {
+                    let frame = match self.check_permission(&cmd) {
+                        Some(denied) => denied,
+                        None => c.execute(),
+                    };
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::CommandInfo(c) => {
+                    let frame = match self.check_permission(&cmd) {
+                        Some(denied) => denied,
+                        None => c.execute(),
+                    };
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::ConfigCmd(c) => {
+                    let frame = match self.check_permission(&cmd) {
+                        Some(denied) => denied,
+                        None => c.execute(&self.db),
+                    };
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::DebugCmd(c) => {
+                    let frame = match self.check_permission(&cmd) {
+                        Some(denied) => denied,
+                        None => c.execute().await,
+                    };
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::Cluster(c) if c.is_node_local() => {
+                    let frame = match self.check_permission(&cmd) {
+                        Some(denied) => denied,
+                        None => c.cluster().await.unwrap_or_else(resp_err),
+                    };
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::Brpoplpush(c) => {
+                    let frame = match self.check_permission(&cmd) {
+                        Some(denied) => denied,
+                        None => c.execute(&self.db).await,
+                    };
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::Eval(c) => {
+                    let frame = match self.check_permission(&cmd) {
+                        Some(denied) => denied,
+                        None => c.eval(false, &self.user).await,
+                    };
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::Evalsha(c) => {
+                    let frame = match self.check_permission(&cmd) {
+                        Some(denied) => denied,
+                        None => c.eval(true, &self.user).await,
+                    };
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::Script(c) => {
+                    let frame = match self.check_permission(&cmd) {
+                        Some(denied) => denied,
+                        None => c.execute(),
+                    };
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
+                }
+                Command::Wait(c) => {
+                    let frame = match self.check_permission(&cmd) {
+                        Some(denied) => denied,
+                        None => c.execute().await,
+                    };
+                    failed = matches!(frame, Frame::Error(_));
+                    self.connection.write_frame(&frame).await?;
                 }
                 _ => {
-                    if !self.authorized {
-                        self.connection
-                            .write_frame(&resp_err(REDIS_AUTH_REQUIRED_ERR))
-                            .await?;
+                    if let Some(denied) = self.check_permission(&cmd) {
+                        failed = true;
+                        self.connection.write_frame(&denied).await?;
                     } else {
-                        let execute_res = if config_cluster_or_default().is_empty() {
-                            self.execute_locally(cmd).await
+                        // the hash ring is dropped by CLUSTER RESET
+                        let execute_res = if unsafe { RING_NODES.is_none() } {
+                            self.execute_locally(cmd, &trace_id).await
                         } else {
-                            self.execute_on_ring(cmd).await
+                            self.execute_on_ring(cmd, &trace_id).await
                         };
                         match execute_res {
                             Ok(_) => (),
                             Err(e) => {
+                                failed = true;
                                 REQUEST_CMD_ERROR_COUNTER
                                     .with_label_values(&[&cmd_name])
                                     .inc();
-                                return Err(e);
+                                // storage errors such as WRONGTYPE are replied to the
+                                // client, others close the connection
+                                match e.downcast::<RError>() {
+                                    Ok(e) => self.connection.write_frame(&resp_err(*e)).await?,
+                                    Err(e) => return Err(e),
+                                }
                             }
                         }
                     }
                 }
             }
             let duration = Instant::now() - start_at;
+            self.finish_command(args, &trace_id, duration).await;
+            if let Some(key) = audit_key {
+                self.audit_command(&cmd_name, key, failed).await;
+            }
             REQUEST_CMD_HANDLE_TIME
                 .with_label_values(&[&cmd_name])
                 .observe(duration_to_sec(duration));
+            record_latency("command", duration);
+            if let Some(data_type) = data_type {
+                REQUEST_CMD_TYPE_HANDLE_TIME
+                    .with_label_values(&[&cmd_name, &data_type.to_string()])
+                    .observe(duration_to_sec(duration));
+            }
             REQUEST_CMD_FINISH_COUNTER
                 .with_label_values(&[&cmd_name])
                 .inc();
@@ -379,8 +717,225 @@ impl Handler {
         Ok(())
     }
 
+    // update stats of the connection and log the command if it is slower
+    // than `slowlog_threshold_us`
+    async fn finish_command(&mut self, args: Vec<String>, trace_id: &str, duration: Duration) {
+        let mut client = self.cur_client.lock().await;
+        client.finish(duration);
+        let duration_us = duration.as_micros() as u64;
+        if duration_us >= slowlog_threshold_us_or_default() {
+            let timestamp = (now_timestamp_in_millis() / 1000) as u64;
+            self.db.slow_log().lock().unwrap().push(
+                timestamp,
+                duration_us,
+                args,
+                client.addr(),
+                client.name(),
+                trace_id,
+            );
+        }
+    }
+
+    // append the command to the audit log, the command failed if an error was
+    // replied
+    async fn audit_command(&self, cmd_name: &str, key: Option<String>, failed: bool) {
+        let (client_ip, client_id) = {
+            let client = self.cur_client.lock().await;
+            (client.addr().to_owned(), client.id())
+        };
+        audit(AuditEntry {
+            ts: now_timestamp_in_millis() as u64,
+            client_ip: &client_ip,
+            client_id,
+            cmd: cmd_name,
+            key,
+            result: if failed { "error" } else { "ok" },
+        })
+        .await;
+    }
+
+    // `AUTH password` authenticates the default user as before acl is
+    // introduced, `AUTH username password` authenticates any acl user
+    fn auth(&mut self, cmd: &Auth) -> Frame {
+        if !cmd.valid() {
+            return resp_invalid_arguments();
+        }
+        match cmd.username() {
+            None if !is_auth_enabled() => resp_err(REDIS_AUTH_WHEN_DISABLED_ERR),
+            None => match ACL_STORE.authenticate(DEFAULT_USER, cmd.passwd()) {
+                Some(_) => {
+                    self.authorized = true;
+                    self.user = DEFAULT_USER.to_owned();
+                    resp_ok()
+                }
+                None => resp_err(REDIS_AUTH_INVALID_PASSWORD_ERR),
+            },
+            Some(username) => match ACL_STORE.authenticate(username, cmd.passwd()) {
+                Some(_) => {
+                    self.authorized = true;
+                    self.user = username.to_owned();
+                    resp_ok()
+                }
+                None => resp_err(REDIS_AUTH_WRONGPASS_ERR),
+            },
+        }
+    }
+
+    // check the current user is allowed to run the command on its key, the
+    // denied reply is returned if not
+    fn check_permission(&self, cmd: &Command) -> Option<Frame> {
+        if !self.authorized {
+            return Some(resp_err(REDIS_AUTH_REQUIRED_ERR));
+        }
+        if let Command::Unknown(_) = cmd {
+            return None;
+        }
+        let user = ACL_STORE.get_user(&self.user).filter(|u| u.enabled);
+        match user {
+            Some(user) if user.can_execute(cmd.get_name()) => {
+                if cmd.keys().iter().all(|key| user.can_access_key(key)) {
+                    None
+                } else {
+                    Some(resp_err(REDIS_NOPERM_KEY_ERR))
+                }
+            }
+            _ => Some(resp_err(RError::owned_error(format!(
+                "NOPERM User {} has no permissions to run the '{}' command",
+                self.user,
+                cmd.get_name()
+            )))),
+        }
+    }
+
+    // switch protocol, the reply is encoded with the new protocol version
+    async fn hello(&mut self, cmd: &Hello) -> Frame {
+        if !cmd.valid() {
+            return resp_invalid_arguments();
+        }
+        let version = cmd.version().unwrap_or_else(|| self.connection.protocol());
+        if version != 2 && version != 3 {
+            return resp_err(REDIS_NOPROTO_ERR);
+        }
+        if let Some((username, passwd)) = cmd.auth() {
+            match ACL_STORE.authenticate(username, passwd) {
+                Some(_) => {
+                    self.authorized = true;
+                    self.user = username.to_owned();
+                }
+                None => return resp_err(REDIS_AUTH_WRONGPASS_ERR),
+            }
+        }
+        if !self.authorized {
+            return resp_err(REDIS_AUTH_REQUIRED_ERR);
+        }
+        if let Some(name) = cmd.setname() {
+            if !valid_client_name(name) {
+                return resp_err(REDIS_INVALID_CLIENT_NAME_ERR);
+            }
+            self.cur_client.lock().await.set_name(name);
+        }
+        self.connection.set_protocol(version);
+
+        let mode = if unsafe { RING_NODES.is_none() } {
+            "standalone"
+        } else {
+            "cluster"
+        };
+        let id = self.cur_client.lock().await.id();
+        Frame::Map(vec![
+            (resp_str("server"), resp_str("mapuche")),
+            (resp_str("version"), resp_str(env!("CARGO_PKG_VERSION"))),
+            (resp_str("proto"), resp_int(version as i64)),
+            (resp_str("id"), resp_int(id as i64)),
+            (resp_str("mode"), resp_str(mode)),
+            (resp_str("role"), resp_str("master")),
+            (resp_str("modules"), resp_array(vec![])),
+        ])
+    }
+
+    // back to the state of a newly accepted connection
+    async fn reset(&mut self) -> Frame {
+        self.authorized = !is_auth_enabled();
+        self.user = DEFAULT_USER.to_owned();
+        self.multi = None;
+        self.txn_error = false;
+        self.cur_client.lock().await.set_name("");
+        self.connection.set_protocol(2);
+        Frame::Simple("RESET".to_owned())
+    }
+
+    // start a transaction, commands are queued until EXEC or DISCARD
+    fn multi(&mut self) -> Frame {
+        if self.multi.is_some() {
+            return resp_err(REDIS_MULTI_NESTED_ERR);
+        }
+        self.multi = Some(vec![]);
+        self.txn_error = false;
+        resp_ok()
+    }
+
+    // queue a command of the transaction, a command which can not be queued
+    // is replied with an error and makes EXEC discard the transaction
+    fn queue_command(&mut self, cmd: Command) -> Frame {
+        let denied = match &cmd {
+            Command::Unknown(c) => Some(c.response()),
+            _ if !cmd.queueable() => Some(resp_err(REDIS_MULTI_NOT_ALLOWED_ERR)),
+            _ => self
+                .check_permission(&cmd)
+                .or_else(|| check_served_locally(&cmd)),
+        };
+        if let Some(frame) = denied {
+            self.txn_error = true;
+            return frame;
+        }
+        if let Some(queued) = &mut self.multi {
+            queued.push(cmd);
+        }
+        resp_str("QUEUED")
+    }
+
+    // run the queued commands one after another, errors of the commands are
+    // replied in their place. Transactions and scripts of the node do not
+    // interleave, but the commands of other clients may run between the
+    // queued commands and a failed command does not roll back the others.
+    async fn exec(&mut self) -> Frame {
+        let queued = match self.multi.take() {
+            Some(queued) => queued,
+            None => return resp_err(REDIS_EXEC_WITHOUT_MULTI_ERR),
+        };
+        if std::mem::take(&mut self.txn_error) {
+            return resp_err(REDIS_EXECABORT_ERR);
+        }
+        let _guard = SCRIPT_LOCK.lock().await;
+        let mut frames = Vec::with_capacity(queued.len());
+        for cmd in queued {
+            if !matches!(cmd, Command::Object(_)) {
+                if let Ok(key) = cmd.hash_ring_key() {
+                    record_access(&key);
+                }
+            }
+            let frame = match execute_replicated(cmd).await {
+                Ok(frame) => frame,
+                Err(e) => match e.downcast::<RError>() {
+                    Ok(e) => resp_err(*e),
+                    Err(e) => Frame::Error(format!("ERR {e}")),
+                },
+            };
+            frames.push(frame);
+        }
+        resp_array(frames)
+    }
+
+    fn discard(&mut self) -> Frame {
+        if self.multi.take().is_none() {
+            return resp_err(REDIS_DISCARD_WITHOUT_MULTI_ERR);
+        }
+        self.txn_error = false;
+        resp_ok()
+    }
+
     #[allow(dead_code)]
-    async fn execute_on_ring(&mut self, cmd: Command) -> crate::Result<()> {
+    async fn execute_on_ring(&mut self, cmd: Command, trace_id: &str) -> crate::Result<()> {
         let hash_ring_key = cmd.hash_ring_key()?;
         let local_address = local_ip()?.to_string();
         let message = Message::CmdReqMessage {
@@ -388,6 +943,7 @@ impl Handler {
             cmd: cmd.clone(),
             ts: now_timestamp_in_millis(),
             req_id: Uuid::new_v4().to_string(),
+            trace_id: Some(trace_id.to_owned()),
         };
         unsafe {
             if let Some(hash_ring) = &RING_NODES {
@@ -396,13 +952,15 @@ impl Handler {
                     .ok_or(MapucheError::String("hash ring node not matched"))?;
                 let remote_url: String = remote_node.into();
                 if local_address == remote_url {
-                    self.execute_locally(cmd).await?;
+                    self.execute_locally(cmd, trace_id).await?;
                 } else {
                     let cmd_name = cmd.get_name().to_owned();
                     REQUEST_CMD_REMOTE_COUNTER
                         .with_label_values(&[&cmd_name])
                         .inc();
-                    self.do_remote_execute(message, &remote_url).await?;
+                    debug!(LOGGER, "forward {} to {}", cmd_name, remote_url; "trace_id" => trace_id);
+                    self.do_remote_execute(message, &remote_url, trace_id)
+                        .await?;
                 }
             } else {
                 Err(MapucheError::String("hash ring not inited"))?
@@ -415,14 +973,10 @@ impl Handler {
         &mut self,
         message: Message,
         remote_url: &str,
+        trace_id: &str,
     ) -> crate::Result<()> {
         if let Some(client) = &P2P_CLIENT {
-            let rec = client.subscribe(remote_url).await;
-            client.call(remote_url, message).await?;
-            let res = rec
-                .ok_or(MapucheError::String("p2p client not inited"))?
-                .recv()
-                .await?;
+            let res = client.request(remote_url, message).await?;
             if let Message::CmdRespMessage {
                 address,
                 frame,
@@ -430,7 +984,7 @@ impl Handler {
                 req_id: _,
             } = res
             {
-                debug!(LOGGER, "res from remote address, {:?}, {}", frame, address);
+                debug!(LOGGER, "res from remote address, {:?}, {}", frame, address; "trace_id" => trace_id);
                 self.connection.write_frame(&frame).await?;
             }
         } else {
@@ -439,7 +993,13 @@ impl Handler {
         Ok(())
     }
 
-    async fn execute_locally(&mut self, cmd: Command) -> crate::Result<()> {
+    async fn execute_locally(&mut self, cmd: Command, trace_id: &str) -> crate::Result<()> {
+        // OBJECT inspects the key without touching its access record
+        if !matches!(cmd, Command::Object(_)) {
+            if let Ok(key) = cmd.hash_ring_key() {
+                record_access(&key);
+            }
+        }
         if !config_infra_or_default().need_raft() {
             return cmd
                 .apply(&self.db, &mut self.connection, &mut self.shutdown)
@@ -453,12 +1013,13 @@ impl Handler {
                     .write(&RaftRequest::CmdLog {
                         id: Uuid::new_v4().to_string(),
                         cmd,
+                        trace_id: Some(trace_id.to_owned()),
                     })
                     .await?;
                 let duration = Instant::now() - start_at;
                 RAFT_REMOTE_DURATION.observe(duration_to_sec(duration));
                 if let RaftResponse::Frame(frame) = response.data {
-                    debug!(LOGGER, "res from raft, {:?}", frame);
+                    debug!(LOGGER, "res from raft, {:?}", frame; "trace_id" => trace_id);
                     self.connection.write_frame(&frame).await?;
                 }
             } else {
@@ -470,8 +1031,63 @@ impl Handler {
     }
 }
 
+// commands of a transaction run on the node receiving EXEC, so in cluster
+// mode their key must be served by this node
+fn check_served_locally(cmd: &Command) -> Option<Frame> {
+    let hash_ring = unsafe { RING_NODES.as_ref() }?;
+    let node: Option<String> = cmd
+        .hash_ring_key()
+        .ok()
+        .and_then(|key| hash_ring.get_node(key))
+        .map(|node| node.into());
+    let local = local_ip()
+        .ok()
+        .map(|ip| format!("{}:{}", ip, config_ring_port_or_default()));
+    match (node, local) {
+        (Some(node), Some(local)) if node == local => None,
+        _ => Some(resp_err(REDIS_MULTI_REMOTE_KEY_ERR)),
+    }
+}
+
+/// Connections are near the limit once 90% of `max_connections` are in use.
+fn near_connection_limit(max_connections: usize, available: usize) -> bool {
+    let in_use = max_connections.saturating_sub(available);
+    in_use * 10 >= max_connections * 9
+}
+
+/// Returns whether the connections are near the limit.
+fn update_connections_near_limit(
+    db: &Db,
+    limit_connections: &Semaphore,
+    max_connections: usize,
+) -> bool {
+    let near_limit = near_connection_limit(max_connections, limit_connections.available_permits());
+    if near_limit && !db.set_connections_near_limit(true) {
+        CONNECTIONS_NEAR_LIMIT.inc();
+        warn!(LOGGER, "connections near the limit of {}", max_connections);
+    } else if !near_limit {
+        db.set_connections_near_limit(false);
+    }
+    near_limit
+}
+
 #[inline]
 pub fn duration_to_sec(d: Duration) -> f64 {
     let nanos = f64::from(d.subsec_nanos());
     d.as_secs() as f64 + (nanos / 1_000_000_000.0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::near_connection_limit;
+
+    #[test]
+    fn test_near_connection_limit() {
+        assert!(!near_connection_limit(10000, 10000));
+        assert!(!near_connection_limit(10000, 1001));
+        assert!(near_connection_limit(10000, 1000));
+        assert!(near_connection_limit(10000, 0));
+        assert!(!near_connection_limit(10, 2));
+        assert!(near_connection_limit(10, 1));
+    }
+}
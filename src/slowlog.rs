@@ -0,0 +1,186 @@
+use crate::utils::{resp_array, resp_bulk, resp_int};
+use crate::Frame;
+use std::collections::VecDeque;
+
+// same as redis, long commands are trimmed before being logged
+const SLOWLOG_ENTRY_MAX_ARGC: usize = 32;
+const SLOWLOG_ENTRY_MAX_STRING: usize = 128;
+
+#[derive(Debug, Clone)]
+pub struct SlowLogEntry {
+    id: u64,
+    // unix timestamp in seconds the command was processed at
+    timestamp: u64,
+    duration_us: u64,
+    args: Vec<String>,
+    client_addr: String,
+    client_name: String,
+    trace_id: String,
+}
+
+impl SlowLogEntry {
+    fn to_frame(&self) -> Frame {
+        resp_array(vec![
+            resp_int(self.id as i64),
+            resp_int(self.timestamp as i64),
+            resp_int(self.duration_us as i64),
+            resp_array(
+                self.args
+                    .iter()
+                    .map(|arg| resp_bulk(arg.as_bytes().to_vec()))
+                    .collect(),
+            ),
+            resp_bulk(self.client_addr.as_bytes().to_vec()),
+            resp_bulk(self.client_name.as_bytes().to_vec()),
+            resp_bulk(self.trace_id.as_bytes().to_vec()),
+        ])
+    }
+}
+
+/// Commands which took longer than `slowlog_threshold_us` to execute, newest
+/// first. Only the latest `max_len` entries are kept.
+#[derive(Debug)]
+pub struct SlowLog {
+    entries: VecDeque<SlowLogEntry>,
+    next_id: u64,
+    max_len: usize,
+}
+
+impl SlowLog {
+    pub fn new(max_len: usize) -> SlowLog {
+        SlowLog {
+            entries: VecDeque::new(),
+            next_id: 0,
+            max_len,
+        }
+    }
+
+    pub fn push(
+        &mut self,
+        timestamp: u64,
+        duration_us: u64,
+        args: Vec<String>,
+        client_addr: &str,
+        client_name: &str,
+        trace_id: &str,
+    ) {
+        self.entries.push_front(SlowLogEntry {
+            id: self.next_id,
+            timestamp,
+            duration_us,
+            args,
+            client_addr: client_addr.to_owned(),
+            client_name: client_name.to_owned(),
+            trace_id: trace_id.to_owned(),
+        });
+        self.next_id += 1;
+        self.entries.truncate(self.max_len);
+    }
+
+    /// The latest `count` entries as replied by `SLOWLOG GET`.
+    pub fn get(&self, count: usize) -> Frame {
+        resp_array(
+            self.entries
+                .iter()
+                .take(count)
+                .map(|entry| entry.to_frame())
+                .collect(),
+        )
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn reset(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Arguments of a request frame as logged in the slow log.
+pub fn frame_args(frame: &Frame) -> Vec<String> {
+    let parts = match frame {
+        Frame::Array(parts) => parts,
+        _ => return vec![],
+    };
+    let mut args: Vec<String> = parts
+        .iter()
+        .take(SLOWLOG_ENTRY_MAX_ARGC)
+        .map(|part| {
+            let arg = match part {
+                Frame::Bulk(b) => String::from_utf8_lossy(b).to_string(),
+                Frame::Simple(s) => s.clone(),
+                Frame::Integer(i) => i.to_string(),
+                _ => "".to_owned(),
+            };
+            if arg.len() > SLOWLOG_ENTRY_MAX_STRING {
+                let more = arg.len() - SLOWLOG_ENTRY_MAX_STRING;
+                let end = (0..=SLOWLOG_ENTRY_MAX_STRING)
+                    .rev()
+                    .find(|i| arg.is_char_boundary(*i))
+                    .unwrap_or(0);
+                format!("{}... ({} more bytes)", &arg[..end], more)
+            } else {
+                arg
+            }
+        })
+        .collect();
+    if parts.len() > SLOWLOG_ENTRY_MAX_ARGC {
+        let more = parts.len() - SLOWLOG_ENTRY_MAX_ARGC + 1;
+        args[SLOWLOG_ENTRY_MAX_ARGC - 1] = format!("... ({} more arguments)", more);
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{frame_args, SlowLog};
+    use crate::Frame;
+    use bytes::Bytes;
+
+    #[test]
+    fn test_slowlog_keeps_latest_entries() {
+        let mut slowlog = SlowLog::new(2);
+        for i in 0..3 {
+            slowlog.push(
+                0,
+                i,
+                vec![format!("cmd{}", i)],
+                "127.0.0.1:1234",
+                "",
+                "trace",
+            );
+        }
+        assert_eq!(slowlog.len(), 2);
+        match slowlog.get(10) {
+            Frame::Array(entries) => {
+                assert_eq!(entries.len(), 2);
+                // newest first
+                assert!(
+                    matches!(&entries[0], Frame::Array(e) if matches!(e[0], Frame::Integer(2)))
+                );
+            }
+            _ => panic!("not an array frame"),
+        }
+        slowlog.reset();
+        assert!(slowlog.is_empty());
+    }
+
+    #[test]
+    fn test_frame_args_trimmed() {
+        let parts: Vec<Frame> = (0..40)
+            .map(|i| Frame::Bulk(Bytes::from(format!("{}", i))))
+            .collect();
+        let args = frame_args(&Frame::Array(parts));
+        assert_eq!(args.len(), 32);
+        assert_eq!(args[31], "... (9 more arguments)");
+
+        let long = Frame::Bulk(Bytes::from("a".repeat(200)));
+        let args = frame_args(&Frame::Array(vec![long]));
+        assert_eq!(args[0], format!("{}... (72 more bytes)", "a".repeat(128)));
+    }
+}
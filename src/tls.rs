@@ -0,0 +1,82 @@
+use crate::config::TlsConfig;
+use crate::MapucheError;
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Arc;
+use tokio_rustls::rustls::server::AllowAnyAuthenticatedClient;
+use tokio_rustls::rustls::{
+    Certificate, ClientConfig, PrivateKey, RootCertStore, ServerConfig, ServerName,
+};
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+fn load_certs(path: &str) -> crate::Result<Vec<Certificate>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader)?;
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn load_key(path: &str) -> crate::Result<PrivateKey> {
+    let mut reader = BufReader::new(File::open(path)?);
+    for item in rustls_pemfile::read_all(&mut reader)? {
+        match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => return Ok(PrivateKey(key)),
+            _ => {}
+        }
+    }
+    Err(MapucheError::Owned(format!("no private key found in {path}")).into())
+}
+
+fn load_roots(path: &str) -> crate::Result<RootCertStore> {
+    let mut roots = RootCertStore::empty();
+    for cert in load_certs(path)? {
+        roots.add(&cert)?;
+    }
+    Ok(roots)
+}
+
+/// Build the acceptor used to wrap inbound sockets, client certificates are
+/// verified against `ca_file` if `require_client_cert` is set.
+pub fn tls_acceptor(config: &TlsConfig) -> crate::Result<TlsAcceptor> {
+    let certs = load_certs(&config.cert_file)?;
+    let key = load_key(&config.key_file)?;
+    let builder = ServerConfig::builder().with_safe_defaults();
+
+    let server_config = if config.require_client_cert.unwrap_or(false) {
+        let ca_file = config.ca_file.as_ref().ok_or(MapucheError::String(
+            "ca_file is required to verify client certificates",
+        ))?;
+        let verifier = AllowAnyAuthenticatedClient::new(load_roots(ca_file)?);
+        builder
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(certs, key)?
+    } else {
+        builder.with_no_client_auth().with_single_cert(certs, key)?
+    };
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+/// Build the connector used by cluster node connections, the local cert is
+/// presented as client certificate if `require_client_cert` is set.
+pub fn tls_connector(config: &TlsConfig) -> crate::Result<TlsConnector> {
+    let roots = match &config.ca_file {
+        Some(ca_file) => load_roots(ca_file)?,
+        None => RootCertStore::empty(),
+    };
+    let builder = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots);
+
+    let client_config = if config.require_client_cert.unwrap_or(false) {
+        builder.with_single_cert(load_certs(&config.cert_file)?, load_key(&config.key_file)?)?
+    } else {
+        builder.with_no_client_auth()
+    };
+    Ok(TlsConnector::from(Arc::new(client_config)))
+}
+
+pub fn tls_server_name(config: &TlsConfig) -> crate::Result<ServerName> {
+    let name = config.server_name.as_deref().unwrap_or("localhost");
+    Ok(ServerName::try_from(name)?)
+}
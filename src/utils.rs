@@ -1,11 +1,56 @@
+use crate::config::notify_keyspace_events_or_default;
+use crate::db::Db;
 use crate::rocks::errors::RError;
-use crate::Frame;
-use std::collections::HashSet;
+use crate::{Frame, PUBSUB_DB};
+use bytes::Bytes;
+use crc::{Crc, CRC_16_XMODEM};
+use glob::Pattern;
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet};
 use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 const TIMESTAMP_FORMAT: &str = "%Y/%m/%d %H:%M:%S%.3f %:z";
 
+// keyspace event classes, same letters as notify-keyspace-events of redis
+const NOTIFY_KEYSPACE: u16 = 1 << 0; // K
+const NOTIFY_KEYEVENT: u16 = 1 << 1; // E
+const NOTIFY_GENERIC: u16 = 1 << 2; // g
+const NOTIFY_STRING: u16 = 1 << 3; // $
+const NOTIFY_LIST: u16 = 1 << 4; // l
+const NOTIFY_SET: u16 = 1 << 5; // s
+const NOTIFY_HASH: u16 = 1 << 6; // h
+const NOTIFY_ZSET: u16 = 1 << 7; // z
+const NOTIFY_EXPIRED: u16 = 1 << 8; // x
+const NOTIFY_EVICTED: u16 = 1 << 9; // e
+const NOTIFY_STREAM: u16 = 1 << 10; // t
+const NOTIFY_KEY_MISS: u16 = 1 << 11; // m
+const NOTIFY_MODULE: u16 = 1 << 12; // d
+const NOTIFY_ALL: u16 = NOTIFY_GENERIC
+    | NOTIFY_STRING
+    | NOTIFY_LIST
+    | NOTIFY_SET
+    | NOTIFY_HASH
+    | NOTIFY_ZSET
+    | NOTIFY_EXPIRED
+    | NOTIFY_EVICTED
+    | NOTIFY_STREAM
+    | NOTIFY_MODULE; // A
+
+// cleared by `DEBUG SET-ACTIVE-EXPIRE 0`, keys are then never seen as expired
+static ACTIVE_EXPIRE_ENABLED: AtomicBool = AtomicBool::new(true);
+
+lazy_static! {
+    static ref KEYSPACE_EVENTS: u16 = parse_keyspace_events(&notify_keyspace_events_or_default());
+}
+
+/// Glob pattern of KEYS, SCAN MATCH and PUBSUB CHANNELS, invalid patterns are
+/// matched literally.
+pub fn glob_pattern(pattern: &str) -> Pattern {
+    Pattern::new(pattern).unwrap_or_else(|_| Pattern::new(&Pattern::escape(pattern)).unwrap())
+}
+
 pub fn resp_ok() -> Frame {
     Frame::Simple("OK".to_string())
 }
@@ -49,8 +94,12 @@ pub fn now_timestamp_in_millis() -> i64 {
     (d.as_secs() * 1000 + d.subsec_millis() as u64) as i64
 }
 
+pub fn set_active_expire(enabled: bool) {
+    ACTIVE_EXPIRE_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
 pub fn key_is_expired(ttl: i64) -> bool {
-    if ttl < 0 {
+    if ttl < 0 || !ACTIVE_EXPIRE_ENABLED.load(Ordering::Relaxed) {
         return false;
     }
     let d = SystemTime::now()
@@ -69,6 +118,11 @@ pub fn ttl_from_timestamp(timestamp: i64) -> i64 {
     }
 }
 
+// remaining seconds rounded up, so a key expiring in 1500ms has a ttl of 2
+pub fn ttl_secs_from_millis(ttl: i64) -> i64 {
+    (ttl + 999) / 1000
+}
+
 pub async fn sleep(ms: u32) {
     tokio::time::sleep(Duration::from_millis(ms as u64)).await;
 }
@@ -77,7 +131,194 @@ pub fn count_unique_keys<T: std::hash::Hash + Eq>(keys: &[T]) -> usize {
     keys.iter().collect::<HashSet<&T>>().len()
 }
 
+// keys without duplicates, in the order of their first occurrence
+pub fn unique_keys<T: std::hash::Hash + Eq + Clone>(keys: &[T]) -> Vec<T> {
+    let mut seen = HashSet::new();
+    keys.iter().filter(|k| seen.insert(*k)).cloned().collect()
+}
+
+// pairs without duplicated keys, in the order of their first occurrence, the
+// value of the last occurrence wins like repeated writes of the same key
+pub fn unique_pairs<K: std::hash::Hash + Eq + Clone, V: Clone>(
+    keys: &[K],
+    values: &[V],
+) -> (Vec<K>, Vec<V>) {
+    let mut positions: HashMap<&K, usize> = HashMap::new();
+    let mut unique_keys = vec![];
+    let mut unique_values: Vec<V> = vec![];
+    for (key, value) in keys.iter().zip(values) {
+        match positions.get(key) {
+            Some(pos) => unique_values[*pos] = value.clone(),
+            None => {
+                positions.insert(key, unique_keys.len());
+                unique_keys.push(key.clone());
+                unique_values.push(value.clone());
+            }
+        }
+    }
+    (unique_keys, unique_values)
+}
+
+/// Number of hash slots of a redis cluster.
+pub const CLUSTER_SLOTS: u16 = 16384;
+
+const SLOT_CRC16: Crc<u16> = Crc::<u16>::new(&CRC_16_XMODEM);
+
+/// Hash slot of the key, computed like redis cluster: CRC16 of the key
+/// modulo 16384, only the `{tag}` part is hashed if the key has a non-empty
+/// hash tag.
+pub fn slot_for_key(key: &str) -> u16 {
+    let bytes = key.as_bytes();
+    let hashed = match bytes.iter().position(|b| *b == b'{') {
+        Some(open) => match bytes[open + 1..].iter().position(|b| *b == b'}') {
+            Some(len) if len > 0 => &bytes[open + 1..open + 1 + len],
+            _ => bytes,
+        },
+        None => bytes,
+    };
+    SLOT_CRC16.checksum(hashed) % CLUSTER_SLOTS
+}
+
 pub fn timestamp_local(io: &mut dyn io::Write) -> io::Result<()> {
     let now = chrono::Local::now().format(TIMESTAMP_FORMAT);
     write!(io, "{now}")
 }
+
+fn keyspace_event_flag(c: char) -> u16 {
+    match c {
+        'K' => NOTIFY_KEYSPACE,
+        'E' => NOTIFY_KEYEVENT,
+        'g' => NOTIFY_GENERIC,
+        '$' => NOTIFY_STRING,
+        'l' => NOTIFY_LIST,
+        's' => NOTIFY_SET,
+        'h' => NOTIFY_HASH,
+        'z' => NOTIFY_ZSET,
+        'x' => NOTIFY_EXPIRED,
+        'e' => NOTIFY_EVICTED,
+        't' => NOTIFY_STREAM,
+        'm' => NOTIFY_KEY_MISS,
+        'd' => NOTIFY_MODULE,
+        'A' => NOTIFY_ALL,
+        _ => 0,
+    }
+}
+
+pub fn parse_keyspace_events(flags: &str) -> u16 {
+    flags.chars().fold(0, |acc, c| acc | keyspace_event_flag(c))
+}
+
+fn publish_keyspace_event(db: &Db, flags: u16, event_type: char, event_name: &str, key: &str) {
+    if flags & keyspace_event_flag(event_type) == 0 {
+        return;
+    }
+    if flags & NOTIFY_KEYSPACE != 0 {
+        let channel = format!("__keyspace@0__:{key}");
+        db.publish(&channel, Bytes::from(event_name.to_owned()), false);
+    }
+    if flags & NOTIFY_KEYEVENT != 0 {
+        let channel = format!("__keyevent@0__:{event_name}");
+        db.publish(&channel, Bytes::from(key.to_owned()), false);
+    }
+}
+
+/// Publish a keyspace event of `event_type` class if it is enabled by
+/// `notify_keyspace_events` config.
+pub(crate) fn notify_keyspace_event(db: &Db, event_type: char, event_name: &str, key: &str) {
+    publish_keyspace_event(db, *KEYSPACE_EVENTS, event_type, event_name, key);
+}
+
+/// Same as `notify_keyspace_event`, publish to the server pub/sub db, used by
+/// the storage layer after a successful write.
+pub fn notify_event(event_type: char, event_name: &str, key: &str) {
+    if *KEYSPACE_EVENTS == 0 {
+        return;
+    }
+    unsafe {
+        if let Some(db) = &PUBSUB_DB {
+            notify_keyspace_event(db, event_type, event_name, key);
+        }
+    }
+}
+
+/// Wake the clients blocked on the list `key` of the server db, used by the
+/// storage layer after elements are pushed.
+pub fn notify_list_pushed(key: &str) {
+    unsafe {
+        if let Some(db) = &PUBSUB_DB {
+            db.notify_list_pushed(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_keyspace_events, publish_keyspace_event, slot_for_key, ttl_secs_from_millis,
+        unique_keys, unique_pairs, CLUSTER_SLOTS, SLOT_CRC16,
+    };
+    use crate::db::Db;
+
+    #[tokio::test]
+    async fn test_keyspace_event_published_by_flags() {
+        let db = Db::new();
+        let flags = parse_keyspace_events("KE$");
+        let mut keyevent_rx = db.subscribe("__keyevent@0__:set".to_owned(), false);
+        let mut keyspace_rx = db.subscribe("__keyspace@0__:mykey".to_owned(), false);
+
+        publish_keyspace_event(&db, flags, '$', "set", "mykey");
+        assert_eq!(&keyevent_rx.recv().await.unwrap()[..], b"mykey");
+        assert_eq!(&keyspace_rx.recv().await.unwrap()[..], b"set");
+
+        // read commands publish nothing, key miss events are not enabled
+        publish_keyspace_event(&db, flags, 'm', "keymiss", "mykey");
+        assert!(keyspace_rx.try_recv().is_err());
+        // generic events are not enabled either
+        publish_keyspace_event(&db, flags, 'g', "del", "mykey");
+        assert!(keyspace_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_unique_keys_keep_first_occurrence() {
+        assert_eq!(
+            unique_keys(&["f1", "f2", "f1", "f3"]),
+            vec!["f1", "f2", "f3"]
+        );
+    }
+
+    #[test]
+    fn test_unique_pairs_keep_last_value() {
+        let (members, scores) = unique_pairs(&["m1", "m2", "m1"], &[1.0, 2.0, 3.0]);
+        assert_eq!(members, vec!["m1", "m2"]);
+        assert_eq!(scores, vec![3.0, 2.0]);
+    }
+
+    #[test]
+    fn test_ttl_secs_rounded_up() {
+        assert_eq!(ttl_secs_from_millis(0), 0);
+        assert_eq!(ttl_secs_from_millis(1), 1);
+        assert_eq!(ttl_secs_from_millis(1000), 1);
+        assert_eq!(ttl_secs_from_millis(1001), 2);
+        assert_eq!(ttl_secs_from_millis(1999), 2);
+    }
+
+    #[test]
+    fn test_slot_for_key() {
+        assert_eq!(slot_for_key("123456789"), 0x31C3);
+        assert_eq!(slot_for_key("foo"), 12182);
+        assert_eq!(slot_for_key("bar"), 5061);
+        assert_eq!(
+            slot_for_key("{user1000}.following"),
+            slot_for_key("{user1000}.followers")
+        );
+        assert_eq!(
+            slot_for_key("{user1000}.following"),
+            slot_for_key("user1000")
+        );
+        // empty hash tags hash the whole key
+        assert_eq!(
+            slot_for_key("{}foo"),
+            SLOT_CRC16.checksum(b"{}foo") % CLUSTER_SLOTS
+        );
+    }
+}
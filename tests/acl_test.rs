@@ -0,0 +1,148 @@
+use redis::{AsyncCommands, Client, RedisResult};
+
+#[tokio::test]
+async fn acl_restricts_commands() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_acl_key";
+    let _: () = con.set(key, "v").await.unwrap();
+
+    let _: () = redis::cmd("ACL")
+        .arg(&["SETUSER", "test_acl_user", "on", ">secret", "~*"])
+        .arg(&["-@all", "+get", "+acl"])
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let whoami: String = redis::cmd("ACL")
+        .arg("WHOAMI")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(whoami, "default");
+
+    let mut user_con = client.get_async_connection().await.unwrap();
+    let res: RedisResult<()> = redis::cmd("AUTH")
+        .arg(&["test_acl_user", "wrong"])
+        .query_async(&mut user_con)
+        .await;
+    assert!(res.is_err());
+    let _: () = redis::cmd("AUTH")
+        .arg(&["test_acl_user", "secret"])
+        .query_async(&mut user_con)
+        .await
+        .unwrap();
+    let whoami: String = redis::cmd("ACL")
+        .arg("WHOAMI")
+        .query_async(&mut user_con)
+        .await
+        .unwrap();
+    assert_eq!(whoami, "test_acl_user");
+
+    let v: String = user_con.get(key).await.unwrap();
+    assert_eq!(v, "v");
+    let res: RedisResult<()> = user_con.set(key, "v2").await;
+    assert!(res.unwrap_err().to_string().contains("NOPERM"));
+
+    let deleted: i64 = redis::cmd("ACL")
+        .arg(&["DELUSER", "test_acl_user"])
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(deleted, 1);
+    let _: () = con.del(key).await.unwrap();
+}
+
+#[tokio::test]
+async fn acl_save_and_load() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let user = "test_acl_save_user";
+
+    let _: () = redis::cmd("ACL")
+        .arg(&["SETUSER", user, "on", ">secret", "~*", "+get"])
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let res: RedisResult<()> = redis::cmd("ACL").arg("SAVE").query_async(&mut con).await;
+    if let Err(e) = res {
+        // the server is started without aclfile
+        assert!(e.to_string().contains("ACL file"));
+        let _: () = redis::cmd("ACL")
+            .arg(&["DELUSER", user])
+            .query_async(&mut con)
+            .await
+            .unwrap();
+        return;
+    }
+
+    let _: () = redis::cmd("ACL")
+        .arg(&["SETUSER", user, "off"])
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let _: () = redis::cmd("ACL")
+        .arg("LOAD")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let list: Vec<String> = redis::cmd("ACL")
+        .arg("LIST")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let saved = list
+        .iter()
+        .find(|line| line.starts_with(&format!("user {user} ")))
+        .unwrap();
+    assert!(saved.contains(" on "));
+    assert!(saved.ends_with("+get"));
+
+    let _: () = redis::cmd("ACL")
+        .arg(&["DELUSER", user])
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let _: () = redis::cmd("ACL")
+        .arg("SAVE")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn acl_checks_every_key_of_multi_key_commands() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let (allowed, secret) = ("cache:test_acl_multi", "test_acl_multi_secret");
+    let _: () = con.set(allowed, "v").await.unwrap();
+    let _: () = con.set(secret, "s").await.unwrap();
+
+    let user = "test_acl_multi_user";
+    let _: () = redis::cmd("ACL")
+        .arg(&["SETUSER", user, "on", ">secret", "~cache:*", "+@all"])
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let mut user_con = client.get_async_connection().await.unwrap();
+    let _: () = redis::cmd("AUTH")
+        .arg(&[user, "secret"])
+        .query_async(&mut user_con)
+        .await
+        .unwrap();
+
+    let v: Vec<String> = user_con.get(&[allowed]).await.unwrap();
+    assert_eq!(v, vec!["v"]);
+    let res: RedisResult<Vec<String>> = user_con.get(&[allowed, secret]).await;
+    assert!(res.unwrap_err().to_string().contains("NOPERM"));
+    let res: RedisResult<i64> = user_con.del(&[allowed, secret]).await;
+    assert!(res.unwrap_err().to_string().contains("NOPERM"));
+    let exists: bool = con.exists(secret).await.unwrap();
+    assert!(exists);
+
+    let _: i64 = redis::cmd("ACL")
+        .arg(&["DELUSER", user])
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let _: () = con.del(&[allowed, secret]).await.unwrap();
+}
@@ -0,0 +1,51 @@
+use redis::{AsyncCommands, Client, RedisResult};
+
+#[tokio::test]
+async fn bitfield_ro_get() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_bitfield_ro";
+    let _: () = con.del(key).await.unwrap();
+
+    // missing keys read as zeros
+    let values: Vec<i64> = redis::cmd("BITFIELD_RO")
+        .arg(key)
+        .arg(&["GET", "u8", "0"])
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(values, vec![0]);
+
+    // 0xff 0x01
+    let _: () = con.set(key, &[0xffu8, 0x01][..]).await.unwrap();
+    let values: Vec<i64> = redis::cmd("BITFIELD_RO")
+        .arg(key)
+        .arg(&["GET", "u8", "0", "GET", "i8", "0", "GET", "u4", "#3"])
+        .arg(&["GET", "i16", "0", "GET", "u8", "16"])
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(values, vec![255, -1, 1, -255, 0]);
+
+    let res: RedisResult<Vec<i64>> = redis::cmd("BITFIELD_RO")
+        .arg(key)
+        .arg(&["GET", "u8", "0", "SET", "u8", "0", "1"])
+        .query_async(&mut con)
+        .await;
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .contains("only supports the GET"));
+
+    let res: RedisResult<Vec<i64>> = redis::cmd("BITFIELD_RO")
+        .arg(key)
+        .arg(&["GET", "u64", "0"])
+        .query_async(&mut con)
+        .await;
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .contains("Invalid bitfield type"));
+
+    let _: () = con.del(key).await.unwrap();
+}
@@ -0,0 +1,61 @@
+use redis::{AsyncCommands, Client};
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn brpoplpush_moves_element() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let (src, dst) = ("test_brpoplpush_src", "test_brpoplpush_dst");
+    let _: () = con.del(&[src, dst]).await.unwrap();
+    let _: () = con.rpush(src, &["a", "b"]).await.unwrap();
+    let _: () = con.rpush(dst, "c").await.unwrap();
+
+    let moved: Option<String> = con.brpoplpush(src, dst, 1).await.unwrap();
+    assert_eq!(moved, Some("b".to_owned()));
+    let src_items: Vec<String> = con.lrange(src, 0, -1).await.unwrap();
+    assert_eq!(src_items, vec!["a"]);
+    let dst_items: Vec<String> = con.lrange(dst, 0, -1).await.unwrap();
+    assert_eq!(dst_items, vec!["b", "c"]);
+    let _: () = con.del(&[src, dst]).await.unwrap();
+}
+
+#[tokio::test]
+async fn brpoplpush_unblocked_by_push() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let mut pusher = client.get_async_connection().await.unwrap();
+    let (src, dst) = ("test_brpoplpush_block_src", "test_brpoplpush_block_dst");
+    let _: () = con.del(&[src, dst]).await.unwrap();
+
+    let push = async {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let _: () = pusher.lpush(src, "v").await.unwrap();
+    };
+    let (moved, _): (redis::RedisResult<Option<String>>, ()) =
+        tokio::join!(con.brpoplpush(src, dst, 5), push);
+    assert_eq!(moved.unwrap(), Some("v".to_owned()));
+    let dst_items: Vec<String> = con.lrange(dst, 0, -1).await.unwrap();
+    assert_eq!(dst_items, vec!["v"]);
+    let _: () = con.del(&[src, dst]).await.unwrap();
+}
+
+#[tokio::test]
+async fn brpoplpush_timeout() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let (src, dst) = ("test_brpoplpush_timeout_src", "test_brpoplpush_timeout_dst");
+    let _: () = con.del(&[src, dst]).await.unwrap();
+
+    let start = Instant::now();
+    let moved: Option<String> = redis::cmd("BRPOPLPUSH")
+        .arg(src)
+        .arg(dst)
+        .arg(0.3)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(moved, None);
+    assert!(start.elapsed() >= Duration::from_millis(300));
+    let exists: bool = con.exists(dst).await.unwrap();
+    assert!(!exists);
+}
@@ -0,0 +1,159 @@
+use redis::{AsyncCommands, Client, RedisResult};
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn client_id_setname_getname() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+
+    let id: u64 = redis::cmd("CLIENT")
+        .arg("ID")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert!(id > 0);
+
+    let name: Option<String> = redis::cmd("CLIENT")
+        .arg("GETNAME")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(name, None);
+
+    let _: () = redis::cmd("CLIENT")
+        .arg("SETNAME")
+        .arg("mapuche-test")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let name: Option<String> = redis::cmd("CLIENT")
+        .arg("GETNAME")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(name.as_deref(), Some("mapuche-test"));
+
+    let list: String = redis::cmd("CLIENT")
+        .arg("LIST")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let line = list
+        .lines()
+        .find(|line| line.starts_with(&format!("id={id} ")))
+        .unwrap();
+    assert!(line.contains("name=mapuche-test"));
+    assert!(line.contains("cmd=client"));
+
+    let res: RedisResult<()> = redis::cmd("CLIENT")
+        .arg("SETNAME")
+        .arg("bad name")
+        .query_async(&mut con)
+        .await;
+    assert!(res.is_err());
+}
+
+#[tokio::test]
+async fn client_kill() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let mut target = client.get_async_connection().await.unwrap();
+
+    let target_id: u64 = redis::cmd("CLIENT")
+        .arg("ID")
+        .query_async(&mut target)
+        .await
+        .unwrap();
+    let killed: i64 = redis::cmd("CLIENT")
+        .arg("KILL")
+        .arg("ID")
+        .arg(target_id)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(killed, 1);
+    let res: RedisResult<String> = redis::cmd("PING").query_async(&mut target).await;
+    assert!(res.is_err());
+
+    let killed: i64 = redis::cmd("CLIENT")
+        .arg("KILL")
+        .arg("ID")
+        .arg(u32::MAX)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(killed, 0);
+
+    // kill self by address, OK is replied before the connection is closed
+    let list: String = redis::cmd("CLIENT")
+        .arg("LIST")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let id: u64 = redis::cmd("CLIENT")
+        .arg("ID")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let addr = list
+        .lines()
+        .find(|line| line.starts_with(&format!("id={id} ")))
+        .and_then(|line| line.split(' ').find_map(|f| f.strip_prefix("addr=")))
+        .unwrap()
+        .to_owned();
+    let _: () = redis::cmd("CLIENT")
+        .arg("KILL")
+        .arg(&addr)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let res: RedisResult<String> = redis::cmd("PING").query_async(&mut con).await;
+    assert!(res.is_err());
+}
+
+async fn client_pause(con: &mut redis::aio::Connection, timeout_ms: u64, mode: &str) {
+    let _: () = redis::cmd("CLIENT")
+        .arg("PAUSE")
+        .arg(timeout_ms)
+        .arg(mode)
+        .query_async(con)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn client_pause_and_unpause() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut admin = client.get_async_connection().await.unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_client_pause";
+
+    client_pause(&mut admin, 500, "ALL").await;
+    let start = Instant::now();
+    let _: Option<String> = con.get(key).await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(400));
+
+    // reads pass through a write pause, writes wait
+    client_pause(&mut admin, 500, "WRITE").await;
+    let start = Instant::now();
+    let _: Option<String> = con.get(key).await.unwrap();
+    assert!(start.elapsed() < Duration::from_millis(400));
+    let _: () = con.set(key, "v").await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(400));
+
+    client_pause(&mut admin, 10000, "ALL").await;
+    let paused = tokio::spawn(async move {
+        let start = Instant::now();
+        let _: Option<String> = con.get(key).await.unwrap();
+        start.elapsed()
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let _: () = redis::cmd("CLIENT")
+        .arg("UNPAUSE")
+        .query_async(&mut admin)
+        .await
+        .unwrap();
+    assert!(paused.await.unwrap() < Duration::from_secs(5));
+
+    let _: () = admin.del(key).await.unwrap();
+}
@@ -0,0 +1,18 @@
+use redis::Client;
+
+#[tokio::test]
+async fn cluster_myid() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+
+    let id: String = redis::cmd("CLUSTER")
+        .arg("MYID")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(id.len(), 40);
+    assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+    // the instance id of the default config, right padded with zeros
+    assert_eq!(u64::from_str_radix(&id[..16], 16).unwrap(), 1);
+    assert_eq!(&id[16..], "0".repeat(24));
+}
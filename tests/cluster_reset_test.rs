@@ -0,0 +1,58 @@
+use redis::{AsyncCommands, Client, RedisResult};
+
+#[tokio::test]
+async fn cluster_reset_soft_keeps_keys() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_cluster_reset_soft";
+    let _: () = con.set(key, "v").await.unwrap();
+
+    let res: RedisResult<String> = redis::cmd("CLUSTER")
+        .arg("RESET")
+        .arg("SOFT")
+        .query_async(&mut con)
+        .await;
+    match res {
+        Ok(reply) => assert_eq!(reply, "OK"),
+        // the server is a voting member of its raft cluster
+        Err(e) => {
+            assert!(e.to_string().contains("voting member"));
+            return;
+        }
+    }
+    let value: String = con.get(key).await.unwrap();
+    assert_eq!(value, "v");
+
+    let res: RedisResult<String> = redis::cmd("CLUSTER")
+        .arg("RESET")
+        .arg("MEDIUM")
+        .query_async(&mut con)
+        .await;
+    assert!(res.is_err());
+    let _: () = con.del(key).await.unwrap();
+}
+
+// deletes every key of the server, run it alone with `--ignored`
+#[tokio::test]
+#[ignore]
+async fn cluster_reset_hard_flushes_keys() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_cluster_reset_hard";
+    let _: () = con.set(key, "v").await.unwrap();
+
+    let res: RedisResult<String> = redis::cmd("CLUSTER")
+        .arg("RESET")
+        .arg("HARD")
+        .query_async(&mut con)
+        .await;
+    if let Err(e) = res {
+        assert!(e.to_string().contains("voting member"));
+        let value: String = con.get(key).await.unwrap();
+        assert_eq!(value, "v");
+        let _: () = con.del(key).await.unwrap();
+        return;
+    }
+    let exists: bool = con.exists(key).await.unwrap();
+    assert!(!exists);
+}
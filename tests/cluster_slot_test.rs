@@ -0,0 +1,62 @@
+use redis::{AsyncCommands, Client};
+
+#[tokio::test]
+async fn cluster_keys_in_slot() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    // keys sharing a hash tag are in the same slot, 5061 is the slot of "bar"
+    let keys = ["{bar}.k1", "{bar}.k2", "{bar}.k3"];
+    let other = "foo";
+    let _: () = con.del(&keys).await.unwrap();
+    let _: () = con.del(other).await.unwrap();
+
+    let before: i64 = redis::cmd("CLUSTER")
+        .arg("COUNTKEYSINSLOT")
+        .arg(5061)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    for key in keys {
+        let _: () = con.set(key, "v").await.unwrap();
+    }
+    let _: () = con.set(other, "v").await.unwrap();
+
+    let count: i64 = redis::cmd("CLUSTER")
+        .arg("COUNTKEYSINSLOT")
+        .arg(5061)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(count, before + 3);
+
+    let in_slot: Vec<String> = redis::cmd("CLUSTER")
+        .arg("GETKEYSINSLOT")
+        .arg(5061)
+        .arg(1000)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    for key in keys {
+        assert!(in_slot.contains(&key.to_string()));
+    }
+    assert!(!in_slot.contains(&other.to_string()));
+
+    let limited: Vec<String> = redis::cmd("CLUSTER")
+        .arg("GETKEYSINSLOT")
+        .arg(5061)
+        .arg(2)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(limited.len(), 2);
+
+    let res: redis::RedisResult<i64> = redis::cmd("CLUSTER")
+        .arg("COUNTKEYSINSLOT")
+        .arg(16384)
+        .query_async(&mut con)
+        .await;
+    assert!(res.is_err());
+
+    let _: () = con.del(&keys).await.unwrap();
+    let _: () = con.del(other).await.unwrap();
+}
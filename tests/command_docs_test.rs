@@ -0,0 +1,53 @@
+use redis::{Client, Value};
+use std::collections::HashMap;
+
+// RESP2 replies the maps as flat arrays of keys and values
+fn to_map(value: Value) -> HashMap<String, Value> {
+    match value {
+        Value::Bulk(items) => items
+            .chunks(2)
+            .map(|pair| {
+                let key = match &pair[0] {
+                    Value::Data(b) => String::from_utf8_lossy(b).to_string(),
+                    Value::Status(s) => s.clone(),
+                    v => panic!("unexpected key {v:?}"),
+                };
+                (key, pair[1].clone())
+            })
+            .collect(),
+        v => panic!("unexpected reply {v:?}"),
+    }
+}
+
+#[tokio::test]
+async fn command_docs() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+
+    let reply: Value = redis::cmd("COMMAND")
+        .arg("DOCS")
+        .arg("GET")
+        .arg("nosuchcommand")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let docs = to_map(reply);
+    assert_eq!(docs.len(), 2);
+    let get = to_map(docs["get"].clone());
+    assert_eq!(
+        get["summary"],
+        Value::Data(b"Returns the string value of a key.".to_vec())
+    );
+    assert_eq!(get["group"], Value::Data(b"string".to_vec()));
+    assert!(to_map(docs["nosuchcommand"].clone()).is_empty());
+
+    // every supported command without arguments
+    let reply: Value = redis::cmd("COMMAND")
+        .arg("DOCS")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let docs = to_map(reply);
+    assert!(docs.contains_key("set"));
+    assert!(docs.contains_key("zadd"));
+}
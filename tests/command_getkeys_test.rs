@@ -0,0 +1,51 @@
+use redis::{Client, RedisResult};
+
+async fn getkeys(con: &mut redis::aio::Connection, args: &[&str]) -> RedisResult<Vec<String>> {
+    redis::cmd("COMMAND")
+        .arg("GETKEYS")
+        .arg(args)
+        .query_async(con)
+        .await
+}
+
+#[tokio::test]
+async fn command_getkeys() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+
+    assert_eq!(
+        getkeys(&mut con, &["GET", "foo"]).await.unwrap(),
+        vec!["foo"]
+    );
+    assert_eq!(
+        getkeys(&mut con, &["MGET", "a", "b", "c"]).await.unwrap(),
+        vec!["a", "b", "c"]
+    );
+    assert_eq!(
+        getkeys(&mut con, &["MSET", "a", "1", "b", "2"])
+            .await
+            .unwrap(),
+        vec!["a", "b"]
+    );
+    assert_eq!(
+        getkeys(&mut con, &["ZADD", "z", "1", "m"]).await.unwrap(),
+        vec!["z"]
+    );
+    assert_eq!(
+        getkeys(&mut con, &["EVAL", "return 1", "2", "k1", "k2", "arg1"])
+            .await
+            .unwrap(),
+        vec!["k1", "k2"]
+    );
+
+    let err = getkeys(&mut con, &["EVAL", "return 1", "3", "k1"])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Invalid arguments"));
+    let err = getkeys(&mut con, &["PING"]).await.unwrap_err();
+    assert!(err.to_string().contains("no key arguments"));
+    let err = getkeys(&mut con, &["NOSUCHCOMMAND", "a"])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Invalid command"));
+}
@@ -0,0 +1,133 @@
+use redis::{AsyncCommands, Client};
+use std::collections::HashMap;
+
+#[tokio::test]
+async fn hash_compact_encoding() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_compact_hash";
+    let _: () = con.del(key).await.unwrap();
+
+    // small hash stored inline
+    for i in 0..10 {
+        let _: i64 = con.hset(key, format!("f{i}"), i).await.unwrap();
+    }
+    let len: i64 = con.hlen(key).await.unwrap();
+    assert_eq!(len, 10);
+    // overwriting a field is not counted as an add
+    let added: i64 = con.hset(key, "f1", "one").await.unwrap();
+    assert_eq!(added, 0);
+    let _: i64 = con.hset(key, "f1", 1).await.unwrap();
+    let v: String = con.hget(key, "f3").await.unwrap();
+    assert_eq!(v, "3");
+    let n: i64 = con.hincr(key, "f3", 10).await.unwrap();
+    assert_eq!(n, 13);
+    let deleted: i64 = con.hdel(key, "f0").await.unwrap();
+    assert_eq!(deleted, 1);
+
+    // exceeding the entries threshold migrates to the full layout
+    for i in 10..300 {
+        let _: i64 = con.hset(key, format!("f{i}"), i).await.unwrap();
+    }
+    let len: i64 = con.hlen(key).await.unwrap();
+    assert_eq!(len, 299);
+    let all: HashMap<String, String> = con.hgetall(key).await.unwrap();
+    assert_eq!(all.len(), 299);
+    assert_eq!(all["f3"], "13");
+    for i in 10..300 {
+        assert_eq!(all[&format!("f{i}")], i.to_string());
+    }
+    let exists: bool = con.hexists(key, "f0").await.unwrap();
+    assert!(!exists);
+
+    // a long value also triggers migration
+    let long_key = "test_compact_hash_long";
+    let _: () = con.del(long_key).await.unwrap();
+    let _: i64 = con.hset(long_key, "short", "v").await.unwrap();
+    let long_value = "x".repeat(1024);
+    let _: i64 = con.hset(long_key, "long", &long_value).await.unwrap();
+    let v: String = con.hget(long_key, "long").await.unwrap();
+    assert_eq!(v, long_value);
+    let v: String = con.hget(long_key, "short").await.unwrap();
+    assert_eq!(v, "v");
+
+    let _: () = con.del(&[key, long_key]).await.unwrap();
+}
+
+#[tokio::test]
+async fn zset_compact_encoding() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_compact_zset";
+    let _: () = con.del(key).await.unwrap();
+
+    // small zset stored inline
+    for i in 0..10 {
+        let _: i64 = con.zadd(key, format!("m{i}"), i).await.unwrap();
+    }
+    let card: i64 = con.zcard(key).await.unwrap();
+    assert_eq!(card, 10);
+    let score: f64 = con.zscore(key, "m3").await.unwrap();
+    assert_eq!(score, 3.0);
+    let score: f64 = con.zincr(key, "m3", 10).await.unwrap();
+    assert_eq!(score, 13.0);
+    let rank: i64 = con.zrank(key, "m3").await.unwrap();
+    assert_eq!(rank, 9);
+    let range: Vec<String> = con.zrange(key, 0, 2).await.unwrap();
+    assert_eq!(range, vec!["m0", "m1", "m2"]);
+    let removed: i64 = con.zrem(key, "m0").await.unwrap();
+    assert_eq!(removed, 1);
+
+    // exceeding the entries threshold migrates to the full layout
+    for i in 10..300 {
+        let _: i64 = con.zadd(key, format!("m{i}"), i).await.unwrap();
+    }
+    let card: i64 = con.zcard(key).await.unwrap();
+    assert_eq!(card, 299);
+    let score: f64 = con.zscore(key, "m3").await.unwrap();
+    assert_eq!(score, 13.0);
+    let range: Vec<String> = con.zrange(key, 0, 1).await.unwrap();
+    assert_eq!(range, vec!["m1", "m2"]);
+    let count: i64 = con.zcount(key, 10, 20).await.unwrap();
+    assert_eq!(count, 11);
+
+    let _: () = con.del(key).await.unwrap();
+}
+
+#[tokio::test]
+async fn list_compact_encoding() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_compact_list";
+    let _: () = con.del(key).await.unwrap();
+
+    // small list stored inline
+    let len: i64 = con.rpush(key, &["b", "c", "d"]).await.unwrap();
+    assert_eq!(len, 3);
+    let len: i64 = con.lpush(key, "a").await.unwrap();
+    assert_eq!(len, 4);
+    let v: String = con.lpop(key, None).await.unwrap();
+    assert_eq!(v, "a");
+    let v: String = con.rpop(key, None).await.unwrap();
+    assert_eq!(v, "d");
+    let _: () = con.lset(key, 0, "B").await.unwrap();
+    let v: String = con.lindex(key, -2).await.unwrap();
+    assert_eq!(v, "B");
+
+    // exceeding the size threshold migrates to the full layout
+    for i in 0..300 {
+        let _: i64 = con.rpush(key, i).await.unwrap();
+    }
+    let len: i64 = con.llen(key).await.unwrap();
+    assert_eq!(len, 302);
+    let range: Vec<String> = con.lrange(key, 0, 3).await.unwrap();
+    assert_eq!(range, vec!["B", "c", "0", "1"]);
+    let v: String = con.lindex(key, -1).await.unwrap();
+    assert_eq!(v, "299");
+    let all: Vec<String> = con.lrange(key, 0, -1).await.unwrap();
+    for i in 0..300 {
+        assert_eq!(all[i + 2], i.to_string());
+    }
+
+    let _: () = con.del(key).await.unwrap();
+}
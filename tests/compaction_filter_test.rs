@@ -0,0 +1,38 @@
+use mapuche::rocks::compaction_filter::{expiry_filter, EXPIRY_FILTER_NAME};
+use mapuche::rocks::KEY_ENCODER;
+use mapuche::utils::now_timestamp_in_millis;
+use rocksdb::{Options, DB};
+
+#[test]
+fn expired_strings_removed_in_compaction() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_compaction_filter(EXPIRY_FILTER_NAME, expiry_filter);
+    let db = DB::open(&opts, dir.path()).unwrap();
+
+    let now = now_timestamp_in_millis();
+    let expired = KEY_ENCODER.encode_meta_key("expired");
+    let alive = KEY_ENCODER.encode_meta_key("alive");
+    let persistent = KEY_ENCODER.encode_meta_key("persistent");
+    let expired_hash = KEY_ENCODER.encode_meta_key("expired_hash");
+    db.put(&expired, KEY_ENCODER.encode_string_slice(b"v", now - 1000))
+        .unwrap();
+    db.put(&alive, KEY_ENCODER.encode_string_slice(b"v", now + 600_000))
+        .unwrap();
+    db.put(&persistent, KEY_ENCODER.encode_string_slice(b"v", 0))
+        .unwrap();
+    // data keys of other types are left to the expire paths
+    db.put(
+        &expired_hash,
+        KEY_ENCODER.encode_hash_meta_value(now - 1000, 1, 1),
+    )
+    .unwrap();
+
+    db.compact_range(None::<&[u8]>, None::<&[u8]>);
+
+    assert!(db.get(&expired).unwrap().is_none());
+    assert!(db.get(&alive).unwrap().is_some());
+    assert!(db.get(&persistent).unwrap().is_some());
+    assert!(db.get(&expired_hash).unwrap().is_some());
+}
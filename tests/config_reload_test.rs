@@ -0,0 +1,25 @@
+use mapuche::config::{async_gc_interval_or_default, reload_config, set_config_file};
+
+// the only test of this file, it is the only one changing the global config of
+// its process
+#[test]
+fn gc_interval_follows_reload() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(
+        file.path(),
+        "[server]\n[backend]\nasync_gc_interval = 1234\n",
+    )
+    .unwrap();
+    set_config_file(file.path().to_str().unwrap());
+    reload_config().unwrap();
+    assert_eq!(async_gc_interval_or_default(), 1234);
+
+    // a value of the wrong type keeps the current config
+    std::fs::write(
+        file.path(),
+        "[server]\n[backend]\nasync_gc_interval = \"fast\"\n",
+    )
+    .unwrap();
+    assert!(reload_config().is_err());
+    assert_eq!(async_gc_interval_or_default(), 1234);
+}
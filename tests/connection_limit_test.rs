@@ -0,0 +1,27 @@
+use redis::{AsyncCommands, Client, RedisResult};
+
+// needs the server started with `max_connection = 10` and no other client
+#[tokio::test]
+#[ignore]
+async fn connections_near_limit_only_busy_new_ones() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut first = client.get_async_connection().await.unwrap();
+    let key = "test_connection_limit";
+    let _: () = first.set(key, "v").await.unwrap();
+
+    // the 9th connection reaches 90% of the limit
+    let mut others = vec![];
+    for _ in 0..8 {
+        others.push(client.get_async_connection().await.unwrap());
+    }
+    let last = others.last_mut().unwrap();
+    let res: RedisResult<String> = last.get(key).await;
+    assert!(res.unwrap_err().to_string().contains("BUSY"));
+    let pong: String = redis::cmd("PING").query_async(last).await.unwrap();
+    assert_eq!(pong, "PONG");
+
+    // the connection open before is served as usual
+    let value: String = first.get(key).await.unwrap();
+    assert_eq!(value, "v");
+    let _: () = first.del(key).await.unwrap();
+}
@@ -0,0 +1,117 @@
+use redis::{AsyncCommands, Client, RedisResult};
+use std::time::{Duration, Instant};
+
+// needs the server started with `enable_debug_commands = true`
+#[tokio::test]
+#[ignore]
+async fn debug_sleep_and_object() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_debug_object";
+    let _: () = con.del(key).await.unwrap();
+
+    let start = Instant::now();
+    let _: () = redis::cmd("DEBUG")
+        .arg("SLEEP")
+        .arg(0.1)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(100));
+
+    let _: () = con.set(key, "hello").await.unwrap();
+    let object: String = redis::cmd("DEBUG")
+        .arg("OBJECT")
+        .arg(key)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert!(object.contains("encoding:embstr"));
+    assert!(object.contains("type:string"));
+
+    let _: () = con.del(key).await.unwrap();
+    let res: RedisResult<String> = redis::cmd("DEBUG")
+        .arg("OBJECT")
+        .arg(key)
+        .query_async(&mut con)
+        .await;
+    assert!(res.is_err());
+}
+
+// needs the server started with `enable_debug_commands = true`
+#[tokio::test]
+#[ignore]
+async fn debug_quicklist_packed_threshold() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_debug_packed_threshold";
+    let _: () = con.del(key).await.unwrap();
+
+    let _: () = redis::cmd("DEBUG")
+        .arg("QUICKLIST-PACKED-THRESHOLD")
+        .arg(1)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let _: () = con.rpush(key, "ab").await.unwrap();
+    let encoding: String = redis::cmd("OBJECT")
+        .arg("ENCODING")
+        .arg(key)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(encoding, "quicklist");
+
+    let _: () = con.del(key).await.unwrap();
+    let _: () = redis::cmd("DEBUG")
+        .arg("QUICKLIST-PACKED-THRESHOLD")
+        .arg(1024 * 1024)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let _: () = con.rpush(key, "ab").await.unwrap();
+    let encoding: String = redis::cmd("OBJECT")
+        .arg("ENCODING")
+        .arg(key)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(encoding, "ziplist");
+
+    // back to the default list_max_ziplist_value
+    let _: () = redis::cmd("DEBUG")
+        .arg("QUICKLIST-PACKED-THRESHOLD")
+        .arg(64)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let _: () = con.del(key).await.unwrap();
+}
+
+async fn set_active_expire(con: &mut redis::aio::Connection, enabled: bool) -> RedisResult<()> {
+    redis::cmd("DEBUG")
+        .arg("SET-ACTIVE-EXPIRE")
+        .arg(enabled as i64)
+        .query_async(con)
+        .await
+}
+
+// needs the server started with `enable_debug_commands = true`
+#[tokio::test]
+#[ignore]
+async fn debug_set_active_expire() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_debug_active_expire";
+    let _: () = con.del(key).await.unwrap();
+
+    set_active_expire(&mut con, false).await.unwrap();
+    let _: () = con.pset_ex(key, "v", 10).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let value: Option<String> = con.get(key).await.unwrap();
+    assert_eq!(value.as_deref(), Some("v"));
+
+    set_active_expire(&mut con, true).await.unwrap();
+    let value: Option<String> = con.get(key).await.unwrap();
+    assert_eq!(value, None);
+}
@@ -0,0 +1,146 @@
+use redis::{AsyncCommands, Client, RedisResult};
+
+async fn dump(con: &mut redis::aio::Connection, key: &str) -> Vec<u8> {
+    redis::cmd("DUMP").arg(key).query_async(con).await.unwrap()
+}
+
+async fn restore(
+    con: &mut redis::aio::Connection,
+    key: &str,
+    ttl: i64,
+    payload: &[u8],
+) -> RedisResult<()> {
+    redis::cmd("RESTORE")
+        .arg(key)
+        .arg(ttl)
+        .arg(payload)
+        .query_async(con)
+        .await
+}
+
+#[tokio::test]
+async fn dump_and_restore_round_trip() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let (s, h, l, z) = (
+        "test_dump_str",
+        "test_dump_hash",
+        "test_dump_list",
+        "test_dump_zset",
+    );
+    let restored = "test_dump_restored";
+    let _: () = con.del(&[s, h, l, z, restored]).await.unwrap();
+
+    let _: () = con.set(s, "hello").await.unwrap();
+    let payload = dump(&mut con, s).await;
+    restore(&mut con, restored, 0, &payload).await.unwrap();
+    let value: String = con.get(restored).await.unwrap();
+    assert_eq!(value, "hello");
+
+    let _: () = con
+        .hset_multiple(h, &[("f1", "v1"), ("f2", "v2")])
+        .await
+        .unwrap();
+    let payload = dump(&mut con, h).await;
+    // the key exists, REPLACE is required
+    assert!(restore(&mut con, restored, 0, &payload).await.is_err());
+    let _: () = redis::cmd("RESTORE")
+        .arg(restored)
+        .arg(0)
+        .arg(&payload)
+        .arg("REPLACE")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let mut fields: Vec<(String, String)> = con.hgetall(restored).await.unwrap();
+    fields.sort();
+    assert_eq!(
+        fields,
+        vec![
+            ("f1".to_owned(), "v1".to_owned()),
+            ("f2".to_owned(), "v2".to_owned())
+        ]
+    );
+
+    let _: () = con.del(restored).await.unwrap();
+    let _: () = con.rpush(l, &["a", "b", "c"]).await.unwrap();
+    let payload = dump(&mut con, l).await;
+    restore(&mut con, restored, 10000, &payload).await.unwrap();
+    let values: Vec<String> = con.lrange(restored, 0, -1).await.unwrap();
+    assert_eq!(values, vec!["a", "b", "c"]);
+    let ttl: i64 = con.ttl(restored).await.unwrap();
+    assert!(ttl > 0 && ttl <= 10);
+
+    let _: () = con.del(restored).await.unwrap();
+    let _: () = con
+        .zadd_multiple(z, &[(1.5, "m1"), (2.0, "m2")])
+        .await
+        .unwrap();
+    let payload = dump(&mut con, z).await;
+    restore(&mut con, restored, 0, &payload).await.unwrap();
+    let members: Vec<(String, f64)> = con.zrange_withscores(restored, 0, -1).await.unwrap();
+    assert_eq!(
+        members,
+        vec![("m1".to_owned(), 1.5), ("m2".to_owned(), 2.0)]
+    );
+
+    let _: () = con.del(&[s, h, l, z, restored]).await.unwrap();
+}
+
+#[tokio::test]
+async fn restore_rejects_corrupted_payload() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let (key, restored) = ("test_dump_corrupt", "test_dump_corrupt_restored");
+    let _: () = con.del(&[key, restored]).await.unwrap();
+
+    let _: () = con.set(key, "value").await.unwrap();
+    let mut payload = dump(&mut con, key).await;
+    payload[1] ^= 0xff;
+    let err = restore(&mut con, restored, 0, &payload).await.unwrap_err();
+    assert!(err.to_string().contains("checksum"));
+
+    let missing: Option<Vec<u8>> = redis::cmd("DUMP")
+        .arg(restored)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(missing, None);
+
+    let _: () = con.del(key).await.unwrap();
+}
+
+#[tokio::test]
+async fn restore_replace_overwrites_other_type() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let (set, restored) = ("test_dump_set", "test_dump_replaced");
+    let _: () = con.del(&[set, restored]).await.unwrap();
+
+    let _: () = con.sadd(set, &["a", "b", "c"]).await.unwrap();
+    let payload = dump(&mut con, set).await;
+    let _: () = con.zadd(restored, "m", 1).await.unwrap();
+    let _: () = redis::cmd("RESTORE")
+        .arg(restored)
+        .arg(0)
+        .arg(&payload)
+        .arg("REPLACE")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let mut members: Vec<String> = con.smembers(restored).await.unwrap();
+    members.sort();
+    assert_eq!(members, vec!["a", "b", "c"]);
+    let card: i64 = con.scard(restored).await.unwrap();
+    assert_eq!(card, 3);
+
+    // a busy key is left as it was
+    let _: () = con.set(set, "v").await.unwrap();
+    let payload = dump(&mut con, set).await;
+    let err = restore(&mut con, restored, 0, &payload).await.unwrap_err();
+    assert!(err.to_string().contains("BUSYKEY"));
+    let card: i64 = con.scard(restored).await.unwrap();
+    assert_eq!(card, 3);
+
+    let _: () = con.del(&[set, restored]).await.unwrap();
+}
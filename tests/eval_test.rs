@@ -0,0 +1,181 @@
+use redis::{AsyncCommands, Client, RedisResult};
+
+#[tokio::test]
+async fn eval_scripts() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_eval_key";
+    let _: () = con.set(key, "hello").await.unwrap();
+
+    let n: i64 = redis::cmd("EVAL")
+        .arg("return 1 + 2")
+        .arg(0)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(n, 3);
+
+    let value: String = redis::cmd("EVAL")
+        .arg("return redis.call('get', KEYS[1]) .. ARGV[1]")
+        .arg(1)
+        .arg(key)
+        .arg("!")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(value, "hello!");
+
+    let _: () = redis::cmd("EVAL")
+        .arg("return redis.call('set', KEYS[1], ARGV[1])")
+        .arg(1)
+        .arg(key)
+        .arg("from script")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let value: String = con.get(key).await.unwrap();
+    assert_eq!(value, "from script");
+
+    // errors raised by redis.call and returned by redis.pcall
+    let res: RedisResult<()> = redis::cmd("EVAL")
+        .arg("return redis.call('nosuchcommand')")
+        .arg(0)
+        .query_async(&mut con)
+        .await;
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .contains("Unknown Redis command"));
+    let res: RedisResult<()> = redis::cmd("EVAL")
+        .arg("return redis.pcall('incr', KEYS[1])")
+        .arg(1)
+        .arg(key)
+        .query_async(&mut con)
+        .await;
+    assert!(res.is_err());
+
+    // scripts and transactions can not be nested in a script
+    for script in [
+        "return redis.call('eval', 'return 1', 0)",
+        "return redis.call('script', 'flush')",
+        "return redis.call('multi')",
+    ] {
+        let res: RedisResult<()> = redis::cmd("EVAL")
+            .arg(script)
+            .arg(0)
+            .query_async(&mut con)
+            .await;
+        assert!(res
+            .unwrap_err()
+            .to_string()
+            .contains("not allowed from script"));
+    }
+
+    let res: RedisResult<()> = redis::cmd("EVAL")
+        .arg("return 1")
+        .arg(2)
+        .arg(key)
+        .query_async(&mut con)
+        .await;
+    assert!(res.unwrap_err().to_string().contains("greater than"));
+
+    let _: () = con.del(key).await.unwrap();
+}
+
+#[tokio::test]
+async fn script_load_and_evalsha() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+
+    let script = "return {ARGV[1], tonumber(ARGV[2]) * 2}";
+    let sha: String = redis::cmd("SCRIPT")
+        .arg("LOAD")
+        .arg(script)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(sha, "05160177b79e60a55e832cff5c9ade7865b6450b");
+
+    let exists: Vec<i64> = redis::cmd("SCRIPT")
+        .arg("EXISTS")
+        .arg(&sha)
+        .arg("0000000000000000000000000000000000000000")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(exists, vec![1, 0]);
+
+    let (name, doubled): (String, i64) = redis::cmd("EVALSHA")
+        .arg(&sha)
+        .arg(0)
+        .arg("x")
+        .arg(21)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(name, "x");
+    assert_eq!(doubled, 42);
+
+    let _: () = redis::cmd("SCRIPT")
+        .arg("FLUSH")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let res: RedisResult<()> = redis::cmd("EVALSHA")
+        .arg(&sha)
+        .arg(0)
+        .query_async(&mut con)
+        .await;
+    assert!(res.unwrap_err().to_string().contains("NOSCRIPT"));
+}
+
+#[tokio::test]
+async fn eval_scripts_are_sandboxed() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+
+    // false is replied as nil
+    let reachable: Option<i64> = redis::cmd("EVAL")
+        .arg("return os ~= nil or io ~= nil")
+        .arg(0)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(reachable, None);
+
+    for script in ["return require('os')", "return dofile('/etc/passwd')"] {
+        let res: RedisResult<()> = redis::cmd("EVAL")
+            .arg(script)
+            .arg(0)
+            .query_async(&mut con)
+            .await;
+        assert!(res.is_err());
+    }
+}
+
+#[tokio::test]
+async fn eval_script_time_limit() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+
+    // aborted after lua_time_limit, also when the error is caught by pcall
+    for script in [
+        "while true do end",
+        "while true do pcall(function() while true do end end) end",
+    ] {
+        let res: RedisResult<()> = redis::cmd("EVAL")
+            .arg(script)
+            .arg(0)
+            .query_async(&mut con)
+            .await;
+        assert!(res.unwrap_err().to_string().contains("script killed"));
+    }
+
+    let n: i64 = redis::cmd("EVAL")
+        .arg("return 1")
+        .arg(0)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(n, 1);
+}
@@ -0,0 +1,28 @@
+use futures::StreamExt;
+use redis::{AsyncCommands, Client};
+use std::time::Duration;
+
+// needs the server started with `notify_keyspace_events = "Ex"`
+#[tokio::test]
+#[ignore]
+async fn expired_event_published() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let mut pubsub = client.get_async_connection().await.unwrap().into_pubsub();
+    pubsub.subscribe("__keyevent@0__:expired").await.unwrap();
+
+    let key = "test_expired_event";
+    let _: () = con.pset_ex(key, "v", 50).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    // expired keys are removed when read
+    let value: Option<String> = con.get(key).await.unwrap();
+    assert_eq!(value, None);
+
+    let mut messages = pubsub.on_message();
+    let msg = tokio::time::timeout(Duration::from_secs(5), messages.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(msg.get_channel_name(), "__keyevent@0__:expired");
+    assert_eq!(msg.get_payload::<String>().unwrap(), key);
+}
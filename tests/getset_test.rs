@@ -0,0 +1,42 @@
+use redis::{AsyncCommands, Client, RedisResult};
+
+#[tokio::test]
+async fn getset_string() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_getset";
+    let _: () = con.del(key).await.unwrap();
+
+    // a missing key is created and nil is replied
+    let prev: Option<String> = con.getset(key, "v1").await.unwrap();
+    assert_eq!(prev, None);
+    let _: () = con.expire(key, 100).await.unwrap();
+    let prev: Option<String> = con.getset(key, "v2").await.unwrap();
+    assert_eq!(prev, Some("v1".to_owned()));
+
+    let value: String = con.get(key).await.unwrap();
+    assert_eq!(value, "v2");
+    // the ttl is cleared like SET does
+    let ttl: i64 = con.ttl(key).await.unwrap();
+    assert_eq!(ttl, -1);
+    let _: () = con.del(key).await.unwrap();
+}
+
+#[tokio::test]
+async fn getset_wrong_type() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_getset_hash";
+    let _: () = con.del(key).await.unwrap();
+    let _: () = con.hset(key, "f", "v").await.unwrap();
+
+    let res: RedisResult<Option<String>> = con.getset(key, "v").await;
+    assert!(res.unwrap_err().to_string().contains("WRONGTYPE"));
+
+    // the hash is left untouched
+    let value: String = con.hget(key, "f").await.unwrap();
+    assert_eq!(value, "v");
+    let len: i64 = con.hlen(key).await.unwrap();
+    assert_eq!(len, 1);
+    let _: () = con.del(key).await.unwrap();
+}
@@ -0,0 +1,39 @@
+use redis::{AsyncCommands, Client};
+
+async fn hset_pairs(con: &mut redis::aio::Connection, key: &str, pairs: &[(&str, &str)]) -> i64 {
+    let mut cmd = redis::cmd("HSET");
+    cmd.arg(key);
+    for (field, value) in pairs {
+        cmd.arg(field).arg(value);
+    }
+    cmd.query_async(con).await.unwrap()
+}
+
+#[tokio::test]
+async fn hset_counts_duplicate_fields_once() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_hset_duplicate";
+    let _: () = con.del(key).await.unwrap();
+
+    // compact encoded hash
+    let added = hset_pairs(&mut con, key, &[("f1", "v1"), ("f1", "v2")]).await;
+    assert_eq!(added, 1);
+    let added = hset_pairs(&mut con, key, &[("f1", "v3"), ("f2", "v1")]).await;
+    assert_eq!(added, 1);
+    let v: String = con.hget(key, "f1").await.unwrap();
+    assert_eq!(v, "v3");
+
+    // full layout hash
+    for i in 0..300 {
+        let _: i64 = con.hset(key, format!("big{i}"), i).await.unwrap();
+    }
+    let added = hset_pairs(&mut con, key, &[("f3", "v1"), ("f3", "v2"), ("f1", "v4")]).await;
+    assert_eq!(added, 1);
+    let added = hset_pairs(&mut con, key, &[("f1", "v5"), ("f1", "v6")]).await;
+    assert_eq!(added, 0);
+    let len: i64 = con.hlen(key).await.unwrap();
+    assert_eq!(len, 303);
+
+    let _: () = con.del(key).await.unwrap();
+}
@@ -0,0 +1,11 @@
+#[tokio::test]
+async fn health_and_ready() {
+    let res = reqwest::get("http://127.0.0.1:18080/ready").await.unwrap();
+    assert_eq!(res.status(), 200);
+
+    let res = reqwest::get("http://127.0.0.1:18080/health").await.unwrap();
+    assert_eq!(res.status(), 200);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["status"], "ok");
+    assert!(body["leader"].is_boolean());
+}
@@ -0,0 +1,46 @@
+use redis::{AsyncCommands, Client, RedisResult};
+
+#[tokio::test]
+async fn pfadd_and_pfcount() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let (h1, h2, missing, string) = (
+        "test_hll_h1",
+        "test_hll_h2",
+        "test_hll_missing",
+        "test_hll_string",
+    );
+    let _: () = con.del(&[h1, h2, missing, string]).await.unwrap();
+
+    let added: i64 = con.pfadd(h1, &["a", "b", "c"]).await.unwrap();
+    assert_eq!(added, 1);
+    // no register changed
+    let added: i64 = con.pfadd(h1, "a").await.unwrap();
+    assert_eq!(added, 0);
+    let count: i64 = con.pfcount(h1).await.unwrap();
+    assert_eq!(count, 3);
+    let count: i64 = con.pfcount(missing).await.unwrap();
+    assert_eq!(count, 0);
+    let key_type: String = redis::cmd("TYPE")
+        .arg(h1)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(key_type, "string");
+
+    // enough elements to promote to the dense encoding
+    let elements: Vec<String> = (0..5000).map(|i| format!("element:{i}")).collect();
+    let _: i64 = con.pfadd(h2, &elements).await.unwrap();
+    let count: i64 = con.pfcount(h2).await.unwrap();
+    assert!((count - 5000).abs() < 100, "count {count}");
+
+    // the union of both
+    let union: i64 = con.pfcount(&[h1, h2]).await.unwrap();
+    assert!((union - 5003).abs() < 100, "union {union}");
+
+    let _: () = con.set(string, "value").await.unwrap();
+    let res: RedisResult<i64> = con.pfadd(string, "a").await;
+    assert!(res.unwrap_err().to_string().contains("WRONGTYPE"));
+
+    let _: () = con.del(&[h1, h2, string]).await.unwrap();
+}
@@ -0,0 +1,41 @@
+use redis::{AsyncCommands, Client, RedisResult};
+
+#[tokio::test]
+async fn incr_decr_overflow() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_incr_overflow";
+
+    let _: () = con.set(key, i64::MAX - 1).await.unwrap();
+    let res: RedisResult<i64> = redis::cmd("INCRBY")
+        .arg(key)
+        .arg(2)
+        .query_async(&mut con)
+        .await;
+    assert!(res.unwrap_err().to_string().contains("overflow"));
+    let val: i64 = redis::cmd("INCR")
+        .arg(key)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(val, i64::MAX);
+    let res: RedisResult<i64> = redis::cmd("INCR").arg(key).query_async(&mut con).await;
+    assert!(res.is_err());
+
+    let _: () = con.set(key, i64::MIN).await.unwrap();
+    let res: RedisResult<i64> = redis::cmd("DECR").arg(key).query_async(&mut con).await;
+    assert!(res.unwrap_err().to_string().contains("overflow"));
+    let val: i64 = con.get(key).await.unwrap();
+    assert_eq!(val, i64::MIN);
+
+    let _: () = con.set(key, 10).await.unwrap();
+    let val: i64 = redis::cmd("DECRBY")
+        .arg(key)
+        .arg(3)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(val, 7);
+
+    let _: () = con.del(key).await.unwrap();
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+// send raw bytes and read until `expected` bytes of replies are received
+async fn request(stream: &mut TcpStream, raw: &str, expected: &str) -> String {
+    stream.write_all(raw.as_bytes()).await.unwrap();
+
+    let mut resp = vec![];
+    let mut buf = vec![0u8; 4096];
+    while resp.len() < expected.len() {
+        let n = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(n > 0);
+        resp.extend_from_slice(&buf[..n]);
+    }
+    String::from_utf8_lossy(&resp).to_string()
+}
+
+#[tokio::test]
+async fn inline_commands() {
+    let mut stream = TcpStream::connect("127.0.0.1:6380").await.unwrap();
+
+    assert_eq!(
+        request(&mut stream, "PING\r\n", "+PONG\r\n").await,
+        "+PONG\r\n"
+    );
+    assert_eq!(
+        request(&mut stream, "SET test_inline bar\r\n", "+OK\r\n").await,
+        "+OK\r\n"
+    );
+    assert_eq!(
+        request(&mut stream, "GET test_inline\r\n", "$3\r\nbar\r\n").await,
+        "$3\r\nbar\r\n"
+    );
+
+    // quoted arguments, empty lines are skipped
+    assert_eq!(
+        request(
+            &mut stream,
+            "\r\nSET test_inline \"hello world\\x21\"\r\n",
+            "+OK\r\n"
+        )
+        .await,
+        "+OK\r\n"
+    );
+    let expected = "$12\r\nhello world!\r\n";
+    assert_eq!(
+        request(&mut stream, "GET 'test_inline'\n", expected).await,
+        expected
+    );
+}
@@ -0,0 +1,48 @@
+use redis::{AsyncCommands, Client};
+
+async fn key_counts() -> Vec<i64> {
+    let metrics = reqwest::get("http://127.0.0.1:18080/")
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    ["string", "hash", "list", "set", "zset"]
+        .iter()
+        .map(|data_type| {
+            let prefix = format!("redis_keyspace_keys{{type=\"{data_type}\"}} ");
+            metrics
+                .lines()
+                .find_map(|line| line.strip_prefix(&prefix))
+                .map_or(0, |count| count.parse().unwrap())
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn keyspace_key_count_by_type() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let keys = [
+        "test_keyspace_string",
+        "test_keyspace_hash",
+        "test_keyspace_list",
+        "test_keyspace_set",
+        "test_keyspace_zset",
+    ];
+    let _: () = con.del(&keys).await.unwrap();
+    let before = key_counts().await;
+
+    let _: () = con.set(keys[0], "v").await.unwrap();
+    let _: i64 = con.hset(keys[1], "f", "v").await.unwrap();
+    let _: i64 = con.rpush(keys[2], "v").await.unwrap();
+    let _: i64 = con.sadd(keys[3], "m").await.unwrap();
+    let _: i64 = con.zadd(keys[4], "m", 1).await.unwrap();
+    let created = key_counts().await;
+    for (count, before) in created.iter().zip(&before) {
+        assert_eq!(*count, before + 1);
+    }
+
+    let _: () = con.del(&keys).await.unwrap();
+    assert_eq!(key_counts().await, before);
+}
@@ -0,0 +1,40 @@
+use redis::{Client, Value};
+
+#[tokio::test]
+async fn latency_history_and_reset() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+
+    for _ in 0..3 {
+        let _: () = redis::cmd("PING").query_async(&mut con).await.unwrap();
+    }
+    let history: Vec<(i64, i64)> = redis::cmd("LATENCY")
+        .arg("HISTORY")
+        .arg("command")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert!(!history.is_empty());
+    assert!(history.windows(2).all(|w| w[0].0 < w[1].0));
+
+    let latest: Vec<(String, i64, i64, i64)> = redis::cmd("LATENCY")
+        .arg("LATEST")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert!(latest.iter().any(|(event, _, _, _)| event == "command"));
+
+    let _: i64 = redis::cmd("LATENCY")
+        .arg("RESET")
+        .arg("command")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let history: Value = redis::cmd("LATENCY")
+        .arg("HISTORY")
+        .arg("no-such-event")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(history, Value::Bulk(vec![]));
+}
@@ -0,0 +1,44 @@
+use redis::{AsyncCommands, Client};
+
+async fn linsert(con: &mut redis::aio::Connection, key: &str, dir: &str, pivot: &str) -> i64 {
+    redis::cmd("LINSERT")
+        .arg(key)
+        .arg(dir)
+        .arg(pivot)
+        .arg("new")
+        .query_async(con)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn linsert_before_and_after() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    // the small list is compact encoded, the large one is not
+    let (small, large) = ("test_linsert_small", "test_linsert_large");
+    let _: () = con.del(&[small, large]).await.unwrap();
+
+    assert_eq!(linsert(&mut con, small, "BEFORE", "a").await, 0);
+
+    let filler: Vec<String> = (0..300).map(|i| format!("e{i}")).collect();
+    let _: i64 = con.rpush(large, &filler).await.unwrap();
+    for key in [small, large] {
+        // the pivot is duplicated, only the first one is used
+        let _: i64 = con.lpush(key, &["b", "a", "b"]).await.unwrap();
+        let len: i64 = con.llen(key).await.unwrap();
+
+        assert_eq!(linsert(&mut con, key, "BEFORE", "b").await, len + 1);
+        assert_eq!(linsert(&mut con, key, "AFTER", "a").await, len + 2);
+        assert_eq!(linsert(&mut con, key, "AFTER", "missing").await, -1);
+        let head: Vec<String> = con.lrange(key, 0, 4).await.unwrap();
+        assert_eq!(head, vec!["new", "b", "a", "new", "b"]);
+
+        let last: String = con.lindex(key, -1).await.unwrap();
+        assert_eq!(linsert(&mut con, key, "AFTER", &last).await, len + 3);
+        let tail: String = con.lindex(key, -1).await.unwrap();
+        assert_eq!(tail, "new");
+    }
+
+    let _: () = con.del(&[small, large]).await.unwrap();
+}
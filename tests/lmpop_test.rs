@@ -0,0 +1,57 @@
+use redis::{AsyncCommands, Client, RedisResult};
+
+#[tokio::test]
+async fn lmpop_first_non_empty_list() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let (empty, list, string) = ("test_lmpop_empty", "test_lmpop_list", "test_lmpop_str");
+    let _: () = con.del(&[empty, list, string]).await.unwrap();
+    let _: () = con.rpush(list, &["a", "b", "c"]).await.unwrap();
+
+    let popped: (String, Vec<String>) = redis::cmd("LMPOP")
+        .arg(2)
+        .arg(empty)
+        .arg(list)
+        .arg("LEFT")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(popped, (list.to_owned(), vec!["a".to_owned()]));
+
+    // count larger than the list pops all the elements
+    let popped: (String, Vec<String>) = redis::cmd("LMPOP")
+        .arg(2)
+        .arg(empty)
+        .arg(list)
+        .arg("RIGHT")
+        .arg("COUNT")
+        .arg(10)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(
+        popped,
+        (list.to_owned(), vec!["c".to_owned(), "b".to_owned()])
+    );
+
+    let none: Option<(String, Vec<String>)> = redis::cmd("LMPOP")
+        .arg(2)
+        .arg(empty)
+        .arg(list)
+        .arg("LEFT")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(none, None);
+
+    let _: () = con.set(string, "v").await.unwrap();
+    let res: RedisResult<Option<(String, Vec<String>)>> = redis::cmd("LMPOP")
+        .arg(1)
+        .arg(string)
+        .arg("LEFT")
+        .query_async(&mut con)
+        .await;
+    assert!(res.unwrap_err().to_string().contains("WRONGTYPE"));
+
+    let _: () = con.del(string).await.unwrap();
+}
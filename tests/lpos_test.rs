@@ -0,0 +1,151 @@
+use redis::{AsyncCommands, Client, RedisResult};
+
+#[tokio::test]
+async fn lpos_rank_count_and_maxlen() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_lpos";
+    let _: () = con.del(key).await.unwrap();
+    // positions of "a": 0, 2, 4
+    let _: () = con.rpush(key, &["a", "b", "a", "c", "a"]).await.unwrap();
+
+    let pos: Option<i64> = redis::cmd("LPOS")
+        .arg(key)
+        .arg("a")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(pos, Some(0));
+
+    let pos: Option<i64> = redis::cmd("LPOS")
+        .arg(key)
+        .arg("a")
+        .arg("RANK")
+        .arg(-1)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(pos, Some(4));
+
+    let all: Vec<i64> = redis::cmd("LPOS")
+        .arg(key)
+        .arg("a")
+        .arg("COUNT")
+        .arg(0)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(all, vec![0, 2, 4]);
+
+    // options in any order, negative rank scans from the tail
+    let tail: Vec<i64> = redis::cmd("LPOS")
+        .arg(key)
+        .arg("a")
+        .arg("COUNT")
+        .arg(2)
+        .arg("RANK")
+        .arg(-2)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(tail, vec![2, 0]);
+
+    let cut: Vec<i64> = redis::cmd("LPOS")
+        .arg(key)
+        .arg("a")
+        .arg("MAXLEN")
+        .arg(3)
+        .arg("COUNT")
+        .arg(0)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(cut, vec![0, 2]);
+
+    let none: Option<i64> = redis::cmd("LPOS")
+        .arg(key)
+        .arg("c")
+        .arg("MAXLEN")
+        .arg(2)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(none, None);
+
+    // with a negative rank MAXLEN counts from the tail
+    let tail: Option<i64> = redis::cmd("LPOS")
+        .arg(key)
+        .arg("a")
+        .arg("RANK")
+        .arg(-1)
+        .arg("MAXLEN")
+        .arg(2)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(tail, Some(4));
+    let none: Option<i64> = redis::cmd("LPOS")
+        .arg(key)
+        .arg("b")
+        .arg("RANK")
+        .arg(-1)
+        .arg("MAXLEN")
+        .arg(2)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(none, None);
+
+    let res: RedisResult<Option<i64>> = redis::cmd("LPOS")
+        .arg(key)
+        .arg("a")
+        .arg("RANK")
+        .arg(0)
+        .query_async(&mut con)
+        .await;
+    assert!(res.unwrap_err().to_string().contains("RANK can't be zero"));
+
+    let _: () = con.del(key).await.unwrap();
+}
+
+#[tokio::test]
+async fn lpos_count_limits_matches() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_lpos_count";
+    let _: () = con.del(key).await.unwrap();
+
+    // COUNT on a missing key replies an empty array, not nil
+    let empty: Vec<i64> = redis::cmd("LPOS")
+        .arg(key)
+        .arg("a")
+        .arg("COUNT")
+        .arg(0)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert!(empty.is_empty());
+
+    let _: () = con.rpush(key, &["a", "b", "a", "c", "a"]).await.unwrap();
+    let first: Vec<i64> = redis::cmd("LPOS")
+        .arg(key)
+        .arg("a")
+        .arg("COUNT")
+        .arg(2)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(first, vec![0, 2]);
+
+    let single: Vec<i64> = redis::cmd("LPOS")
+        .arg(key)
+        .arg("c")
+        .arg("COUNT")
+        .arg(0)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(single, vec![3]);
+
+    let _: () = con.del(key).await.unwrap();
+}
@@ -0,0 +1,33 @@
+use redis::{AsyncCommands, Client};
+
+fn observation_count(metrics: &str, cmd: &str, data_type: &str) -> u64 {
+    let prefix = format!(
+        "redis_command_type_handle_time_duration_seconds_count{{cmd=\"{cmd}\",data_type=\"{data_type}\"}} "
+    );
+    metrics
+        .lines()
+        .find_map(|line| line.strip_prefix(&prefix))
+        .map_or(0, |count| count.parse().unwrap())
+}
+
+#[tokio::test]
+async fn command_latency_by_data_type() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let (hash_key, zset_key) = ("test_metrics_hash", "test_metrics_zset");
+
+    let _: i64 = con.hset(hash_key, "f", "v").await.unwrap();
+    let _: i64 = con.zadd(zset_key, "m", 1).await.unwrap();
+    let _: () = con.del(&[hash_key, zset_key]).await.unwrap();
+
+    let metrics = reqwest::get("http://127.0.0.1:18080/")
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(observation_count(&metrics, "hset", "hash") >= 1);
+    assert!(observation_count(&metrics, "zadd", "zset") >= 1);
+    assert!(observation_count(&metrics, "del", "hash") >= 1);
+    assert!(observation_count(&metrics, "del", "zset") >= 1);
+}
@@ -0,0 +1,154 @@
+use redis::{AsyncCommands, Client, ErrorKind, Value};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+#[tokio::test]
+async fn multi_exec() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_multi_exec";
+    let _: () = con.del(key).await.unwrap();
+
+    let (set, incr, get): (String, i64, String) = redis::pipe()
+        .atomic()
+        .set(key, 1)
+        .incr(key, 2)
+        .get(key)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(set, "OK");
+    assert_eq!(incr, 3);
+    assert_eq!(get, "3");
+
+    // nothing is queued once discarded
+    let _: () = redis::cmd("MULTI").query_async(&mut con).await.unwrap();
+    let queued: String = redis::cmd("INCR")
+        .arg(key)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(queued, "QUEUED");
+    let _: () = redis::cmd("DISCARD").query_async(&mut con).await.unwrap();
+    let value: i64 = con.get(key).await.unwrap();
+    assert_eq!(value, 3);
+
+    // EXEC without MULTI
+    assert!(redis::cmd("EXEC")
+        .query_async::<_, Value>(&mut con)
+        .await
+        .is_err());
+    let _: () = con.del(key).await.unwrap();
+}
+
+#[tokio::test]
+async fn multi_execabort_on_queue_error() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_multi_execabort";
+    let _: () = con.del(key).await.unwrap();
+
+    let _: () = redis::cmd("MULTI").query_async(&mut con).await.unwrap();
+    let queued: String = redis::cmd("SET")
+        .arg(key)
+        .arg("v")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(queued, "QUEUED");
+    // missing key
+    assert!(redis::cmd("GET")
+        .query_async::<_, Value>(&mut con)
+        .await
+        .is_err());
+    assert!(redis::cmd("NOSUCHCOMMAND")
+        .query_async::<_, Value>(&mut con)
+        .await
+        .is_err());
+
+    let err = redis::cmd("EXEC")
+        .query_async::<_, Value>(&mut con)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ExtensionError);
+    assert_eq!(err.code(), Some("EXECABORT"));
+    let exists: bool = con.exists(key).await.unwrap();
+    assert!(!exists);
+}
+
+#[tokio::test]
+async fn multi_exec_transactions_do_not_interleave() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_multi_exec_interleave";
+    let _: () = con.del(key).await.unwrap();
+
+    let mut tasks = vec![];
+    for _ in 0..8 {
+        let client = client.clone();
+        tasks.push(tokio::spawn(async move {
+            let mut con = client.get_async_connection().await.unwrap();
+            let mut pipe = redis::pipe();
+            pipe.atomic();
+            for _ in 0..10 {
+                pipe.incr(key, 1);
+            }
+            let values: Vec<i64> = pipe.query_async(&mut con).await.unwrap();
+            values
+        }));
+    }
+    for task in tasks {
+        let values = task.await.unwrap();
+        // the increments of a transaction are consecutive
+        assert!(values.windows(2).all(|w| w[1] == w[0] + 1));
+    }
+    let value: i64 = con.get(key).await.unwrap();
+    assert_eq!(value, 80);
+    let _: () = con.del(key).await.unwrap();
+}
+
+// errors in the reply of EXEC are read from the raw reply, the redis crate
+// fails the whole reply on them
+#[tokio::test]
+async fn multi_exec_command_error() {
+    let mut stream = TcpStream::connect("127.0.0.1:6380").await.unwrap();
+    let key = "test_multi_exec_error";
+    request(&mut stream, &["DEL", key], ":0\r\n").await;
+
+    let resp = request(&mut stream, &["MULTI"], "+OK\r\n").await;
+    assert_eq!(resp, "+OK\r\n");
+    for args in [
+        vec!["SET", key, "v"],
+        vec!["LPUSH", key, "x"],
+        vec!["GET", key],
+    ] {
+        let resp = request(&mut stream, &args, "+QUEUED\r\n").await;
+        assert_eq!(resp, "+QUEUED\r\n");
+    }
+    let expected = "*3\r\n+OK\r\n-WRONGTYPE Operation against a key holding the wrong kind of value\r\n$1\r\nv\r\n";
+    let resp = request(&mut stream, &["EXEC"], expected).await;
+    assert_eq!(resp, expected);
+    request(&mut stream, &["DEL", key], ":1\r\n").await;
+}
+
+// send a request and read until `expected` bytes of replies are received
+async fn request(stream: &mut TcpStream, args: &[&str], expected: &str) -> String {
+    let mut req = format!("*{}\r\n", args.len());
+    for arg in args {
+        req.push_str(&format!("${}\r\n{}\r\n", arg.len(), arg));
+    }
+    stream.write_all(req.as_bytes()).await.unwrap();
+
+    let mut resp = vec![];
+    let mut buf = vec![0u8; 4096];
+    while resp.len() < expected.len() {
+        let n = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(n > 0);
+        resp.extend_from_slice(&buf[..n]);
+    }
+    String::from_utf8_lossy(&resp).to_string()
+}
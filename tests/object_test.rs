@@ -0,0 +1,223 @@
+use redis::{AsyncCommands, Client, RedisResult};
+
+async fn object_encoding(con: &mut redis::aio::Connection, key: &str) -> String {
+    redis::cmd("OBJECT")
+        .arg("ENCODING")
+        .arg(key)
+        .query_async(con)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn object_encoding_of_strings_and_hashes() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let (str_key, hash_key) = ("test_object_str", "test_object_hash");
+    let _: () = con.del(&[str_key, hash_key]).await.unwrap();
+
+    let _: () = con.set(str_key, 12345).await.unwrap();
+    assert_eq!(object_encoding(&mut con, str_key).await, "int");
+    let _: () = con.set(str_key, "42").await.unwrap();
+    assert_eq!(object_encoding(&mut con, str_key).await, "int");
+    for not_canonical in ["042", "+42", "-0"] {
+        let _: () = con.set(str_key, not_canonical).await.unwrap();
+        assert_eq!(object_encoding(&mut con, str_key).await, "embstr");
+    }
+    let _: () = con.set(str_key, "hello").await.unwrap();
+    assert_eq!(object_encoding(&mut con, str_key).await, "embstr");
+    let _: () = con.set(str_key, "x".repeat(100)).await.unwrap();
+    assert_eq!(object_encoding(&mut con, str_key).await, "raw");
+
+    let _: i64 = con.hset(hash_key, "f", "v").await.unwrap();
+    assert_eq!(object_encoding(&mut con, hash_key).await, "ziplist");
+    for i in 0..300 {
+        let _: i64 = con.hset(hash_key, format!("f{i}"), i).await.unwrap();
+    }
+    assert_eq!(object_encoding(&mut con, hash_key).await, "hashtable");
+
+    let none: Option<String> = redis::cmd("OBJECT")
+        .arg("ENCODING")
+        .arg("test_object_missing")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(none, None);
+
+    let _: () = con.del(&[str_key, hash_key]).await.unwrap();
+}
+
+async fn object_freq(con: &mut redis::aio::Connection, key: &str) -> RedisResult<Option<i64>> {
+    redis::cmd("OBJECT")
+        .arg("FREQ")
+        .arg(key)
+        .query_async(con)
+        .await
+}
+
+#[tokio::test]
+async fn object_freq_of_accessed_key() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_object_freq";
+    let _: () = con.del(key).await.unwrap();
+
+    match object_freq(&mut con, key).await {
+        // access frequency is only tracked with an lfu maxmemory policy
+        Err(e) => {
+            assert!(e.to_string().contains("LFU"));
+            return;
+        }
+        Ok(freq) => assert_eq!(freq, None),
+    }
+
+    let _: () = con.set(key, "v").await.unwrap();
+    let first = object_freq(&mut con, key).await.unwrap().unwrap();
+    for _ in 0..200 {
+        let _: String = con.get(key).await.unwrap();
+    }
+    let second = object_freq(&mut con, key).await.unwrap().unwrap();
+    assert!(second > first);
+
+    let _: () = con.del(key).await.unwrap();
+}
+
+#[tokio::test]
+async fn object_idletime_of_expired_key() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_object_idletime_expired";
+    let _: () = redis::cmd("SET")
+        .arg(key)
+        .arg("v")
+        .arg("PX")
+        .arg(50)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let idle: RedisResult<Option<i64>> = redis::cmd("OBJECT")
+        .arg("IDLETIME")
+        .arg(key)
+        .query_async(&mut con)
+        .await;
+    match idle {
+        // idle time is not tracked with an lfu maxmemory policy
+        Err(e) => assert!(e.to_string().contains("LFU")),
+        Ok(idle) => assert_eq!(idle, None),
+    }
+}
+
+#[tokio::test]
+async fn object_refcount_and_help() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_object_refcount";
+    let _: () = con.set(key, "v").await.unwrap();
+
+    let refcount: Option<i64> = redis::cmd("OBJECT")
+        .arg("REFCOUNT")
+        .arg(key)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(refcount, Some(1));
+
+    let refcount: Option<i64> = redis::cmd("OBJECT")
+        .arg("REFCOUNT")
+        .arg("test_object_refcount_missing")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(refcount, None);
+
+    let help: Vec<String> = redis::cmd("OBJECT")
+        .arg("HELP")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert!(help.iter().any(|line| line.starts_with("REFCOUNT")));
+    for subcommand in ["ENCODING", "FREQ", "IDLETIME", "VERSION", "HELP"] {
+        assert!(help.iter().any(|line| line.starts_with(subcommand)));
+    }
+
+    let res: RedisResult<String> = redis::cmd("OBJECT")
+        .arg("BADCMD")
+        .arg(key)
+        .query_async(&mut con)
+        .await;
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .contains("unknown subcommand 'BADCMD'"));
+
+    let _: () = con.del(key).await.unwrap();
+}
+
+async fn object_idletime(con: &mut redis::aio::Connection, key: &str) -> RedisResult<Option<i64>> {
+    redis::cmd("OBJECT")
+        .arg("IDLETIME")
+        .arg(key)
+        .query_async(con)
+        .await
+}
+
+#[tokio::test]
+async fn object_idletime_of_accessed_key() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_object_idletime";
+    let _: () = con.del(key).await.unwrap();
+
+    match object_idletime(&mut con, key).await {
+        // idle time is not tracked with an lfu maxmemory policy
+        Err(e) => {
+            assert!(e.to_string().contains("LFU"));
+            return;
+        }
+        Ok(idle) => assert_eq!(idle, None),
+    }
+
+    let _: () = con.set(key, "v").await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(2100)).await;
+    let idle = object_idletime(&mut con, key).await.unwrap().unwrap();
+    assert!(idle >= 2);
+
+    let _: String = con.get(key).await.unwrap();
+    let idle = object_idletime(&mut con, key).await.unwrap().unwrap();
+    assert_eq!(idle, 0);
+
+    let _: () = con.del(key).await.unwrap();
+}
+
+async fn object_version(con: &mut redis::aio::Connection, key: &str) -> Option<i64> {
+    redis::cmd("OBJECT")
+        .arg("VERSION")
+        .arg(key)
+        .query_async(con)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn object_version_of_keys() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let (str_key, hash_key) = ("test_object_version_str", "test_object_version_hash");
+    let _: () = con.del(&[str_key, hash_key]).await.unwrap();
+
+    assert_eq!(object_version(&mut con, str_key).await, None);
+    let _: () = con.set(str_key, "v").await.unwrap();
+    assert_eq!(object_version(&mut con, str_key).await, Some(0));
+
+    // the version of an expired key is not reported
+    let _: () = con.pset_ex(str_key, "v", 10).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert_eq!(object_version(&mut con, str_key).await, None);
+
+    let _: i64 = con.hset(hash_key, "f", "v").await.unwrap();
+    assert!(object_version(&mut con, hash_key).await.is_some());
+
+    let _: () = con.del(&[str_key, hash_key]).await.unwrap();
+}
@@ -0,0 +1,34 @@
+use mapuche::config::{set_global_config, Config};
+use mapuche::{CommandBuilder, ConnectionPool, Frame};
+use std::time::Duration;
+use tokio::time::timeout;
+
+#[tokio::test]
+async fn pool_waits_for_released_conn() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = format!(
+        "[server]\n[backend]\ndata_store_dir = \"{}\"\n",
+        dir.path().display()
+    );
+    set_global_config(Config::from_toml(&config).unwrap());
+
+    let pool = ConnectionPool::new(2);
+    let mut first = pool.acquire().await;
+    let second = pool.acquire().await;
+    assert_eq!(pool.available(), 0);
+    assert_ne!(first.id(), second.id());
+
+    let set = CommandBuilder::set("pool", b"v", None).unwrap();
+    assert!(matches!(first.execute(set).await.unwrap(), Frame::Simple(s) if s == "OK"));
+
+    let waiting = pool.clone();
+    let third = tokio::spawn(async move { waiting.acquire().await.id() });
+    assert!(timeout(Duration::from_millis(100), pool.acquire())
+        .await
+        .is_err());
+
+    let released = second.id();
+    drop(second);
+    assert_eq!(third.await.unwrap(), released);
+    assert_eq!(pool.available(), 1);
+}
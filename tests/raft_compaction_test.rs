@@ -0,0 +1,29 @@
+use redis::Client;
+use serde_json::Value;
+
+// needs a raft node with `raft_api_port = 26123` and the default
+// `raft_snapshot_interval_commands`
+#[tokio::test]
+#[ignore]
+async fn snapshot_after_interval_commands() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+
+    for i in 0..5001 {
+        let _: () = redis::cmd("SET")
+            .arg("raft_compaction_key")
+            .arg(i)
+            .query_async(&mut con)
+            .await
+            .unwrap();
+    }
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+
+    let metrics: Value = reqwest::get("http://localhost:26123/metrics")
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(!metrics["Ok"]["snapshot"].is_null());
+}
@@ -0,0 +1,23 @@
+use redis::{Client, Value};
+
+#[tokio::test]
+async fn reset_connection_state() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+
+    let _: () = redis::cmd("CLIENT")
+        .arg("SETNAME")
+        .arg("test_reset")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let reply: Value = redis::cmd("RESET").query_async(&mut con).await.unwrap();
+    assert_eq!(reply, Value::Status("RESET".to_owned()));
+
+    let name: Option<String> = redis::cmd("CLIENT")
+        .arg("GETNAME")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(name, None);
+}
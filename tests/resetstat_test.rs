@@ -0,0 +1,33 @@
+use redis::{AsyncCommands, Client};
+
+async fn request_count() -> u64 {
+    let metrics = reqwest::get("http://127.0.0.1:18080/")
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    metrics
+        .lines()
+        .find_map(|line| line.strip_prefix("redis_requests_total "))
+        .map_or(0, |count| count.parse().unwrap())
+}
+
+#[tokio::test]
+async fn resetstat_resets_request_counter() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    for _ in 0..10 {
+        let _: Option<String> = con.get("test_resetstat").await.unwrap();
+    }
+    assert!(request_count().await >= 10);
+
+    let reply: String = redis::cmd("CONFIG")
+        .arg("RESETSTAT")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(reply, "OK");
+    // other tests may run concurrently
+    assert!(request_count().await < 10);
+}
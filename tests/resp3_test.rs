@@ -0,0 +1,60 @@
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+async fn request(stream: &mut TcpStream, args: &[&str]) -> String {
+    let mut req = format!("*{}\r\n", args.len());
+    for arg in args {
+        req.push_str(&format!("${}\r\n{}\r\n", arg.len(), arg));
+    }
+    stream.write_all(req.as_bytes()).await.unwrap();
+
+    let mut buf = vec![0u8; 4096];
+    let n = stream.read(&mut buf).await.unwrap();
+    String::from_utf8_lossy(&buf[..n]).to_string()
+}
+
+#[tokio::test]
+async fn hello_switches_to_resp3() {
+    let mut stream = TcpStream::connect("127.0.0.1:6380").await.unwrap();
+    let key = "test_resp3_hash";
+    request(&mut stream, &["DEL", key]).await;
+    request(&mut stream, &["HSET", key, "f", "v"]).await;
+
+    // RESP2 replies a flat array
+    let resp = request(&mut stream, &["HGETALL", key]).await;
+    assert_eq!(resp, "*2\r\n$1\r\nf\r\n$1\r\nv\r\n");
+
+    let resp = request(&mut stream, &["HELLO", "3"]).await;
+    assert!(resp.starts_with("%7\r\n"));
+    assert!(resp.contains("proto\r\n:3\r\n"));
+
+    let resp = request(&mut stream, &["HGETALL", key]).await;
+    assert_eq!(resp, "%1\r\n$1\r\nf\r\n$1\r\nv\r\n");
+
+    let resp = request(&mut stream, &["HELLO", "4"]).await;
+    assert!(resp.starts_with("-NOPROTO"));
+
+    request(&mut stream, &["DEL", key]).await;
+}
+
+#[tokio::test]
+async fn hello_negotiates_and_authenticates() {
+    let mut stream = TcpStream::connect("127.0.0.1:6380").await.unwrap();
+
+    // RESP2 replies the server info as a flat array
+    let resp = request(&mut stream, &["HELLO", "2"]).await;
+    assert!(resp.starts_with("*14\r\n"));
+    assert!(resp.contains("proto\r\n:2\r\n"));
+
+    let resp = request(&mut stream, &["HELLO", "3", "SETNAME", "hello-test"]).await;
+    assert!(resp.starts_with("%7\r\n"));
+    let resp = request(&mut stream, &["CLIENT", "GETNAME"]).await;
+    assert_eq!(resp, "$10\r\nhello-test\r\n");
+
+    let resp = request(
+        &mut stream,
+        &["HELLO", "3", "AUTH", "no_such_user", "wrong"],
+    )
+    .await;
+    assert!(resp.starts_with("-WRONGPASS"));
+}
@@ -0,0 +1,28 @@
+use mapuche::config::{set_global_config, Config};
+use mapuche::rocks::{get_client, CF_NAME_HASH_DATA, CF_NAME_META};
+
+#[test]
+fn block_cache_capacity_from_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = format!(
+        "[server]\n[backend]\ndata_store_dir = \"{}\"\nrocks_block_cache_mb = 16\n\
+        rocks_compression_type = \"lz4\"\n\
+        [backend.cf_options.hash_data]\nblock_cache_mb = 4\n",
+        dir.path().display()
+    );
+    set_global_config(Config::from_toml(&config).unwrap());
+
+    let capacity = |cf| {
+        get_client()
+            .property_int_value(cf, "rocksdb.block-cache-capacity")
+            .unwrap()
+    };
+    assert_eq!(capacity(CF_NAME_META), Some(16 * 1024 * 1024));
+    assert_eq!(capacity(CF_NAME_HASH_DATA), Some(4 * 1024 * 1024));
+}
+
+#[test]
+fn invalid_compression_type() {
+    let config = "[server]\n[backend]\nrocks_compression_type = \"gzip\"\n";
+    assert!(Config::from_toml(config).is_err());
+}
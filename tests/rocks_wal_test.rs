@@ -0,0 +1,35 @@
+use mapuche::config::{set_global_config, Config};
+use mapuche::rocks::{get_client, CF_NAMES, CF_NAME_META};
+use rocksdb::{Options, DB};
+
+#[test]
+fn flushed_wal_is_recovered() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = format!(
+        "[server]\n[backend]\ndata_store_dir = \"{}\"\n\
+        rocks_manual_wal_flush = true\nrocks_wal_recovery_mode = \"absolute_consistency\"\n",
+        dir.path().display()
+    );
+    set_global_config(Config::from_toml(&config).unwrap());
+
+    let client = get_client();
+    let cf = client.cf_handle(CF_NAME_META).unwrap();
+    client
+        .put(cf, b"wal_key".to_vec().into(), b"wal_value".to_vec())
+        .unwrap();
+    client.flush_wal().unwrap();
+
+    // the memtable is never flushed, a read only instance replays the wal
+    // like the server restarted after a crash
+    let db = DB::open_cf_for_read_only(&Options::default(), dir.path(), CF_NAMES, false).unwrap();
+    let value = db
+        .get_cf(&db.cf_handle(CF_NAME_META).unwrap(), b"wal_key")
+        .unwrap();
+    assert_eq!(value, Some(b"wal_value".to_vec()));
+}
+
+#[test]
+fn invalid_wal_recovery_mode() {
+    let config = "[server]\n[backend]\nrocks_wal_recovery_mode = \"best_effort\"\n";
+    assert!(Config::from_toml(config).is_err());
+}
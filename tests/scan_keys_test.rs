@@ -0,0 +1,37 @@
+use bytes::Bytes;
+use futures::TryStreamExt;
+use mapuche::config::{set_global_config, Config};
+use mapuche::rocks::get_client;
+use mapuche::rocks::string::StringCommand;
+use mapuche::scan_keys;
+use std::collections::HashSet;
+
+#[tokio::test]
+async fn scan_keys_stream() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = format!(
+        "[server]\n[backend]\ndata_store_dir = \"{}\"\n",
+        dir.path().display()
+    );
+    set_global_config(Config::from_toml(&config).unwrap());
+
+    let value = Bytes::from("v");
+    for i in 0..25 {
+        StringCommand::new(&get_client())
+            .put(&format!("user:{i}"), &value, -1)
+            .await
+            .unwrap();
+    }
+    StringCommand::new(&get_client())
+        .put("other", &value, -1)
+        .await
+        .unwrap();
+
+    let keys: Vec<String> = scan_keys(None, 4).try_collect().await.unwrap();
+    assert_eq!(keys.len(), 26);
+    assert_eq!(keys.iter().collect::<HashSet<_>>().len(), 26);
+
+    let users: Vec<String> = scan_keys(Some("user:*"), 4).try_collect().await.unwrap();
+    assert_eq!(users.len(), 25);
+    assert!(users.iter().all(|key| key.starts_with("user:")));
+}
@@ -0,0 +1,72 @@
+use redis::{AsyncCommands, Client};
+
+async fn scan(
+    con: &mut redis::aio::Connection,
+    cursor: &str,
+    pattern: &str,
+    count: usize,
+) -> (String, Vec<String>) {
+    redis::cmd("SCAN")
+        .arg(cursor)
+        .arg("MATCH")
+        .arg(pattern)
+        .arg("COUNT")
+        .arg(count)
+        .query_async(con)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn scan_with_cursor_and_pattern() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let users: Vec<String> = (0..5).map(|i| format!("test_scan_user:{i}")).collect();
+    let other = "test_scan_other";
+    for key in &users {
+        let _: () = con.set(key, "v").await.unwrap();
+    }
+    let _: () = con.set(other, "v").await.unwrap();
+
+    // the cursor advances until the iteration completes with "0"
+    let mut cursor = "0".to_owned();
+    let mut found = vec![];
+    let mut rounds = 0;
+    loop {
+        let (next, keys) = scan(&mut con, &cursor, "test_scan_*", 2).await;
+        assert!(keys.len() <= 2);
+        found.extend(keys);
+        rounds += 1;
+        if next == "0" {
+            break;
+        }
+        assert_ne!(next, cursor);
+        cursor = next;
+    }
+    assert!(rounds > 1);
+    found.sort();
+    let mut expected = users.clone();
+    expected.push(other.to_owned());
+    expected.sort();
+    assert_eq!(found, expected);
+
+    let mut cursor = "0".to_owned();
+    let mut found = vec![];
+    loop {
+        let (next, keys) = scan(&mut con, &cursor, "test_scan_user:*", 100).await;
+        found.extend(keys);
+        if next == "0" {
+            break;
+        }
+        cursor = next;
+    }
+    found.sort();
+    assert_eq!(found, users);
+
+    let mut keys: Vec<String> = con.keys("test_scan_user:*").await.unwrap();
+    keys.sort();
+    assert_eq!(keys, users);
+
+    let _: () = con.del(&users).await.unwrap();
+    let _: () = con.del(other).await.unwrap();
+}
@@ -0,0 +1,51 @@
+use redis::{AsyncCommands, Client};
+use std::collections::HashSet;
+
+async fn srandmember(con: &mut redis::aio::Connection, key: &str, count: i64) -> Vec<String> {
+    redis::cmd("SRANDMEMBER")
+        .arg(key)
+        .arg(count)
+        .query_async(con)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn srandmember_with_count() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_srandmember";
+    let _: () = con.del(key).await.unwrap();
+    let members = ["a", "b", "c"];
+    let _: i64 = con.sadd(key, &members).await.unwrap();
+
+    // negative count allows repeated elements
+    let picked = srandmember(&mut con, key, -10).await;
+    assert_eq!(picked.len(), 10);
+    assert!(picked.iter().all(|m| members.contains(&m.as_str())));
+
+    // positive count returns distinct elements, at most the cardinality
+    let picked = srandmember(&mut con, key, 10).await;
+    assert_eq!(picked.len(), 3);
+    assert_eq!(picked.iter().collect::<HashSet<_>>().len(), 3);
+    let picked = srandmember(&mut con, key, 2).await;
+    assert_eq!(picked.len(), 2);
+    assert_ne!(picked[0], picked[1]);
+
+    let picked = srandmember(&mut con, key, 3).await;
+    assert_eq!(picked.iter().collect::<HashSet<_>>().len(), 3);
+
+    assert!(srandmember(&mut con, key, 0).await.is_empty());
+
+    // the reply of a huge negative count is not built
+    let res: redis::RedisResult<Vec<String>> = redis::cmd("SRANDMEMBER")
+        .arg(key)
+        .arg(-9223372036854775807i64)
+        .query_async(&mut con)
+        .await;
+    assert!(res.unwrap_err().to_string().contains("out of range"));
+
+    let _: () = con.del(key).await.unwrap();
+    assert!(srandmember(&mut con, key, 5).await.is_empty());
+    assert!(srandmember(&mut con, key, -5).await.is_empty());
+}
@@ -0,0 +1,80 @@
+use redis::Client;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+fn encode(args: &[&str]) -> String {
+    let mut req = format!("*{}\r\n", args.len());
+    for arg in args {
+        req.push_str(&format!("${}\r\n{}\r\n", arg.len(), arg));
+    }
+    req
+}
+
+// read until `expected` bytes of replies are received
+async fn read_reply(stream: &mut TcpStream, expected: &str) -> String {
+    let mut resp = vec![];
+    let mut buf = vec![0u8; 4096];
+    while resp.len() < expected.len() {
+        let n = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(n > 0);
+        resp.extend_from_slice(&buf[..n]);
+    }
+    String::from_utf8_lossy(&resp).to_string()
+}
+
+#[tokio::test]
+async fn sharded_channels_are_separate() {
+    let channel = "news:sports";
+    let mut sharded = TcpStream::connect("127.0.0.1:6380").await.unwrap();
+    let mut regular = TcpStream::connect("127.0.0.1:6380").await.unwrap();
+
+    sharded
+        .write_all(encode(&["SSUBSCRIBE", channel]).as_bytes())
+        .await
+        .unwrap();
+    let expected = "*3\r\n$10\r\nssubscribe\r\n$11\r\nnews:sports\r\n:1\r\n";
+    assert_eq!(read_reply(&mut sharded, expected).await, expected);
+
+    regular
+        .write_all(encode(&["SUBSCRIBE", channel]).as_bytes())
+        .await
+        .unwrap();
+    let expected = "*3\r\n$9\r\nsubscribe\r\n$11\r\nnews:sports\r\n:1\r\n";
+    assert_eq!(read_reply(&mut regular, expected).await, expected);
+
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let channels: Vec<String> = redis::cmd("PUBSUB")
+        .arg("SHARDCHANNELS")
+        .arg("news:*")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(channels, vec![channel.to_owned()]);
+    let numsub: (String, i64) = redis::cmd("PUBSUB")
+        .arg("SHARDNUMSUB")
+        .arg(channel)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(numsub, (channel.to_owned(), 1));
+
+    let receivers: i64 = redis::cmd("SPUBLISH")
+        .arg(channel)
+        .arg("goal")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(receivers, 1);
+    let expected = "*3\r\n$8\r\nsmessage\r\n$11\r\nnews:sports\r\n$4\r\ngoal\r\n";
+    assert_eq!(read_reply(&mut sharded, expected).await, expected);
+
+    // the regular subscriber does not receive the sharded message
+    let mut buf = vec![0u8; 64];
+    let res = tokio::time::timeout(Duration::from_millis(200), regular.read(&mut buf)).await;
+    assert!(res.is_err());
+}
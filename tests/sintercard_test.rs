@@ -0,0 +1,56 @@
+use redis::{AsyncCommands, Client};
+
+#[tokio::test]
+async fn sintercard_counts_intersection() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let (s1, s2, empty) = (
+        "test_sintercard_s1",
+        "test_sintercard_s2",
+        "test_sintercard_empty",
+    );
+    let _: () = con.del(&[s1, s2, empty]).await.unwrap();
+    let _: () = con.sadd(s1, &["a", "b", "c", "d"]).await.unwrap();
+    let _: () = con.sadd(s2, &["b", "c", "d", "e", "f"]).await.unwrap();
+
+    let count: i64 = redis::cmd("SINTERCARD")
+        .arg(2)
+        .arg(s1)
+        .arg(s2)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(count, 3);
+
+    // stop counting once the limit is reached
+    let count: i64 = redis::cmd("SINTERCARD")
+        .arg(2)
+        .arg(s1)
+        .arg(s2)
+        .arg("LIMIT")
+        .arg(2)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+
+    // a missing key makes the intersection empty
+    let count: i64 = redis::cmd("SINTERCARD")
+        .arg(2)
+        .arg(s1)
+        .arg(empty)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+
+    let count: i64 = redis::cmd("SINTERCARD")
+        .arg(1)
+        .arg(s2)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(count, 5);
+
+    let _: () = con.del(&[s1, s2]).await.unwrap();
+}
@@ -0,0 +1,58 @@
+use redis::{AsyncCommands, Client, RedisResult};
+use std::collections::HashSet;
+
+async fn sinterstore(
+    con: &mut redis::aio::Connection,
+    dst: &str,
+    keys: &[&str],
+) -> RedisResult<i64> {
+    redis::cmd("SINTERSTORE")
+        .arg(dst)
+        .arg(keys)
+        .query_async(con)
+        .await
+}
+
+#[tokio::test]
+async fn sinterstore_stores_intersection() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let (s1, s2, dst, empty, string) = (
+        "test_sinterstore_s1",
+        "test_sinterstore_s2",
+        "test_sinterstore_dst",
+        "test_sinterstore_empty",
+        "test_sinterstore_string",
+    );
+    let _: () = con.del(&[s1, s2, dst, empty, string]).await.unwrap();
+    let _: () = con.sadd(s1, &["a", "b", "c", "d"]).await.unwrap();
+    let _: () = con.sadd(s2, &["b", "c", "d", "e", "f"]).await.unwrap();
+
+    // an existing destination is overwritten, whatever its type
+    let _: () = con.set(dst, "old").await.unwrap();
+    assert_eq!(sinterstore(&mut con, dst, &[s1, s2]).await.unwrap(), 3);
+    let members: HashSet<String> = con.smembers(dst).await.unwrap();
+    assert_eq!(
+        members,
+        ["b", "c", "d"].iter().map(|m| m.to_string()).collect()
+    );
+
+    // destination is one of the sources
+    assert_eq!(sinterstore(&mut con, s2, &[s1, s2]).await.unwrap(), 3);
+    let members: HashSet<String> = con.smembers(s2).await.unwrap();
+    assert_eq!(
+        members,
+        ["b", "c", "d"].iter().map(|m| m.to_string()).collect()
+    );
+
+    // an empty intersection deletes destination
+    assert_eq!(sinterstore(&mut con, dst, &[s1, empty]).await.unwrap(), 0);
+    let exists: bool = con.exists(dst).await.unwrap();
+    assert!(!exists);
+
+    let _: () = con.set(string, "value").await.unwrap();
+    let res = sinterstore(&mut con, dst, &[s1, string]).await;
+    assert!(res.unwrap_err().to_string().contains("WRONGTYPE"));
+
+    let _: () = con.del(&[s1, s2, string]).await.unwrap();
+}
@@ -0,0 +1,61 @@
+use redis::{AsyncCommands, Client, Value};
+
+#[tokio::test]
+async fn slowlog_len_get_and_reset() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+
+    let len: i64 = redis::cmd("SLOWLOG")
+        .arg("LEN")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert!(len >= 0);
+
+    let entries: Vec<Value> = redis::cmd("SLOWLOG")
+        .arg("GET")
+        .arg(-1)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    for entry in entries {
+        // id, timestamp, duration, arguments, client address, name and trace id
+        assert!(matches!(entry, Value::Bulk(fields) if fields.len() == 7));
+    }
+
+    let _: () = redis::cmd("SLOWLOG")
+        .arg("RESET")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn client_info_shows_last_command() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+
+    let _: Option<String> = con.get("test_client_info").await.unwrap();
+    let info: String = redis::cmd("CLIENT")
+        .arg("INFO")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert!(info.contains(" last_cmd=get "));
+    assert!(info.contains(" last_cmd_duration_us="));
+
+    let _: Option<String> = con.get("test_client_info").await.unwrap();
+    let info_again: String = redis::cmd("CLIENT")
+        .arg("INFO")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    let total = |info: &str| -> u64 {
+        info.split_whitespace()
+            .find_map(|field| field.strip_prefix("total_cmds_processed="))
+            .unwrap()
+            .parse()
+            .unwrap()
+    };
+    assert_eq!(total(&info_again), total(&info) + 2);
+}
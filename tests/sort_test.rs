@@ -0,0 +1,111 @@
+use redis::{AsyncCommands, Client};
+
+#[tokio::test]
+async fn sort_list_set_and_store() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let (list, set, dst) = ("test_sort_list", "test_sort_set", "test_sort_dst");
+    let _: () = con.del(&[list, set, dst]).await.unwrap();
+    let _: () = con.rpush(list, &["3", "10", "1", "2"]).await.unwrap();
+    let _: () = con.sadd(set, &["banana", "apple", "cherry"]).await.unwrap();
+
+    let sorted: Vec<String> = redis::cmd("SORT")
+        .arg(list)
+        .arg("ASC")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(sorted, vec!["1", "2", "3", "10"]);
+
+    let sorted: Vec<String> = redis::cmd("SORT")
+        .arg(set)
+        .arg("ALPHA")
+        .arg("DESC")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(sorted, vec!["cherry", "banana", "apple"]);
+
+    let sorted: Vec<String> = redis::cmd("SORT")
+        .arg(list)
+        .arg("LIMIT")
+        .arg(1)
+        .arg(2)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(sorted, vec!["2", "3"]);
+
+    // elements of the set are not numbers
+    let res: redis::RedisResult<Vec<String>> =
+        redis::cmd("SORT").arg(set).query_async(&mut con).await;
+    assert!(res.is_err());
+
+    let stored: i64 = redis::cmd("SORT")
+        .arg(list)
+        .arg("DESC")
+        .arg("STORE")
+        .arg(dst)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(stored, 4);
+    let values: Vec<String> = con.lrange(dst, 0, -1).await.unwrap();
+    assert_eq!(values, vec!["10", "3", "2", "1"]);
+
+    let _: () = con.del(&[list, set, dst]).await.unwrap();
+}
+
+#[tokio::test]
+async fn sort_by_and_get_patterns() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let list = "test_sort_by_list";
+    let _: () = con.del(list).await.unwrap();
+    let _: () = con.rpush(list, &["a", "b", "c"]).await.unwrap();
+    let _: () = con
+        .set_multiple(&[
+            ("test_sort_weight_a", "3"),
+            ("test_sort_weight_b", "1"),
+            ("test_sort_weight_c", "2"),
+            ("test_sort_name_a", "alice"),
+            ("test_sort_name_b", "bob"),
+        ])
+        .await
+        .unwrap();
+
+    let sorted: Vec<Option<String>> = redis::cmd("SORT")
+        .arg(list)
+        .arg("BY")
+        .arg("test_sort_weight_*")
+        .arg("GET")
+        .arg("#")
+        .arg("GET")
+        .arg("test_sort_name_*")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(
+        sorted,
+        vec![
+            Some("b".to_owned()),
+            Some("bob".to_owned()),
+            Some("c".to_owned()),
+            None,
+            Some("a".to_owned()),
+            Some("alice".to_owned()),
+        ]
+    );
+
+    let _: () = con
+        .del(&[
+            list,
+            "test_sort_weight_a",
+            "test_sort_weight_b",
+            "test_sort_weight_c",
+            "test_sort_name_a",
+            "test_sort_name_b",
+        ])
+        .await
+        .unwrap();
+}
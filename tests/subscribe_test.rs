@@ -0,0 +1,89 @@
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+// send a request and read until `expected` bytes of replies are received
+async fn request(stream: &mut TcpStream, args: &[&str], expected: &str) -> String {
+    let mut req = format!("*{}\r\n", args.len());
+    for arg in args {
+        req.push_str(&format!("${}\r\n{}\r\n", arg.len(), arg));
+    }
+    stream.write_all(req.as_bytes()).await.unwrap();
+
+    let mut resp = vec![];
+    let mut buf = vec![0u8; 4096];
+    while resp.len() < expected.len() {
+        let n = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(n > 0);
+        resp.extend_from_slice(&buf[..n]);
+    }
+    String::from_utf8_lossy(&resp).to_string()
+}
+
+fn reply(kind: &str, channel: &str, count: i64) -> String {
+    format!(
+        "*3\r\n${}\r\n{kind}\r\n${}\r\n{channel}\r\n:{count}\r\n",
+        kind.len(),
+        channel.len()
+    )
+}
+
+#[tokio::test]
+async fn subscribe_multiple_channels() {
+    let mut stream = TcpStream::connect("127.0.0.1:6380").await.unwrap();
+
+    let expected = [("ch1", 1), ("ch2", 2), ("ch3", 3)]
+        .iter()
+        .map(|(ch, n)| reply("subscribe", ch, *n))
+        .collect::<String>();
+    let resp = request(&mut stream, &["SUBSCRIBE", "ch1", "ch2", "ch3"], &expected).await;
+    assert_eq!(resp, expected);
+
+    // subscribing again keeps the count
+    let expected = reply("subscribe", "ch1", 3);
+    let resp = request(&mut stream, &["SUBSCRIBE", "ch1"], &expected).await;
+    assert_eq!(resp, expected);
+
+    let resp = request(
+        &mut stream,
+        &["UNSUBSCRIBE"],
+        &reply("unsubscribe", "chX", 0).repeat(3),
+    )
+    .await;
+    for n in [2, 1, 0] {
+        assert!(resp.contains(&format!(":{n}\r\n")));
+    }
+
+    // the client is no longer subscribed, regular commands are served
+    let resp = request(&mut stream, &["PING"], "+PONG\r\n").await;
+    assert_eq!(resp, "+PONG\r\n");
+
+    let expected = "*3\r\n$11\r\nunsubscribe\r\n$-1\r\n:0\r\n";
+    let resp = request(&mut stream, &["UNSUBSCRIBE"], expected).await;
+    assert_eq!(resp, expected);
+}
+
+#[tokio::test]
+async fn subscribe_repeated_channel_in_one_command() {
+    let mut stream = TcpStream::connect("127.0.0.1:6380").await.unwrap();
+
+    // every channel gets its own reply, the repeated one does not count twice
+    let expected = [("rep1", 1), ("rep2", 2), ("rep1", 2)]
+        .iter()
+        .map(|(ch, n)| reply("subscribe", ch, *n))
+        .collect::<String>();
+    let resp = request(
+        &mut stream,
+        &["SUBSCRIBE", "rep1", "rep2", "rep1"],
+        &expected,
+    )
+    .await;
+    assert_eq!(resp, expected);
+
+    let expected = reply("unsubscribe", "rep2", 1);
+    let resp = request(&mut stream, &["UNSUBSCRIBE", "rep2"], &expected).await;
+    assert_eq!(resp, expected);
+}
@@ -0,0 +1,103 @@
+use mapuche::config::TlsConfig;
+use mapuche::tls::{tls_acceptor, tls_connector, tls_server_name};
+use rcgen::{BasicConstraints, Certificate, CertificateParams, DnType, IsCa};
+use std::path::Path;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+fn write_pem(dir: &Path, name: &str, pem: &str) -> String {
+    let path = dir.join(name);
+    std::fs::write(&path, pem).unwrap();
+    path.to_str().unwrap().to_owned()
+}
+
+// write a test ca and a server cert signed by it, return the server side config
+fn server_config(dir: &Path, require_client_cert: bool) -> TlsConfig {
+    let mut ca_params = CertificateParams::new(vec![]);
+    ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    ca_params
+        .distinguished_name
+        .push(DnType::CommonName, "mapuche test ca");
+    let ca = Certificate::from_params(ca_params).unwrap();
+
+    let cert =
+        Certificate::from_params(CertificateParams::new(vec!["localhost".to_owned()])).unwrap();
+    TlsConfig {
+        cert_file: write_pem(
+            dir,
+            "cert.pem",
+            &cert.serialize_pem_with_signer(&ca).unwrap(),
+        ),
+        key_file: write_pem(dir, "key.pem", &cert.serialize_private_key_pem()),
+        ca_file: Some(write_pem(dir, "ca.pem", &ca.serialize_pem().unwrap())),
+        require_client_cert: Some(require_client_cert),
+        server_name: Some("localhost".to_owned()),
+    }
+}
+
+// accept one connection, reply +OK once the handshake is done
+async fn serve_once(config: &TlsConfig) -> (String, JoinHandle<bool>) {
+    let acceptor = tls_acceptor(config).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let handle = tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        match acceptor.accept(socket).await {
+            Ok(mut stream) => {
+                stream.write_all(b"+OK\r\n").await.unwrap();
+                stream.flush().await.unwrap();
+                true
+            }
+            Err(_) => false,
+        }
+    });
+    (addr, handle)
+}
+
+#[tokio::test]
+async fn tls_connect_with_valid_cert() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = server_config(dir.path(), true);
+    let (addr, handle) = serve_once(&config).await;
+
+    let socket = TcpStream::connect(&addr).await.unwrap();
+    let connector = tls_connector(&config).unwrap();
+    let mut stream = connector
+        .connect(tls_server_name(&config).unwrap(), socket)
+        .await
+        .unwrap();
+    let mut buf = [0u8; 5];
+    stream.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"+OK\r\n");
+    assert!(handle.await.unwrap());
+}
+
+#[tokio::test]
+async fn tls_connect_without_required_client_cert() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = server_config(dir.path(), true);
+    let (addr, handle) = serve_once(&config).await;
+
+    let client_config = TlsConfig {
+        require_client_cert: Some(false),
+        ..config.clone()
+    };
+    let socket = TcpStream::connect(&addr).await.unwrap();
+    let connector = tls_connector(&client_config).unwrap();
+    let _ = connector
+        .connect(tls_server_name(&config).unwrap(), socket)
+        .await;
+    assert!(!handle.await.unwrap());
+}
+
+#[tokio::test]
+async fn plaintext_to_tls_port_fails_handshake() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = server_config(dir.path(), false);
+    let (addr, handle) = serve_once(&config).await;
+
+    let mut socket = TcpStream::connect(&addr).await.unwrap();
+    socket.write_all(b"*1\r\n$4\r\nPING\r\n").await.unwrap();
+    assert!(!handle.await.unwrap());
+}
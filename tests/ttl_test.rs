@@ -0,0 +1,21 @@
+use redis::{AsyncCommands, Client};
+
+#[tokio::test]
+async fn ttl_rounds_up_remaining_millis() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_ttl_precision";
+
+    let _: () = con.set(key, "v").await.unwrap();
+    let _: () = con.pexpire(key, 1500).await.unwrap();
+    let ttl: i64 = con.ttl(key).await.unwrap();
+    assert_eq!(ttl, 2);
+    let pttl: i64 = con.pttl(key).await.unwrap();
+    assert!(pttl > 1000 && pttl <= 1500);
+
+    let _: () = con.expire(key, 1).await.unwrap();
+    let ttl: i64 = con.ttl(key).await.unwrap();
+    assert_eq!(ttl, 1);
+
+    let _: () = con.del(key).await.unwrap();
+}
@@ -0,0 +1,20 @@
+#![cfg(unix)]
+
+use redis::{AsyncCommands, Client};
+
+// requires a server started with `unixsocket` configured, the socket path is
+// passed by MAPUCHE_UNIXSOCKET
+#[tokio::test]
+async fn unixsocket_set_get() {
+    let path = match std::env::var("MAPUCHE_UNIXSOCKET") {
+        Ok(path) => path,
+        Err(_) => return,
+    };
+    let client = Client::open(format!("redis+unix://{path}")).unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+
+    let _: () = con.set("test_unixsocket", "bar").await.unwrap();
+    let v: String = con.get("test_unixsocket").await.unwrap();
+    assert_eq!(v, "bar");
+    let _: () = con.del("test_unixsocket").await.unwrap();
+}
@@ -0,0 +1,40 @@
+use redis::{AsyncCommands, Client};
+
+#[tokio::test]
+async fn unlink_removes_existing_keys() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let (s, l, h, missing) = (
+        "test_unlink_str",
+        "test_unlink_list",
+        "test_unlink_hash",
+        "test_unlink_missing",
+    );
+    let _: () = con.del(&[s, l, h, missing]).await.unwrap();
+
+    let _: () = con.set(s, "v").await.unwrap();
+    let _: () = con.rpush(l, &["a", "b", "c"]).await.unwrap();
+    for i in 0..300 {
+        let _: i64 = con.hset(h, format!("f{i}"), i).await.unwrap();
+    }
+
+    let unlinked: i64 = redis::cmd("UNLINK")
+        .arg(&[s, l, h, missing])
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(unlinked, 3);
+
+    let exists: i64 = con.exists(&[s, l, h]).await.unwrap();
+    assert_eq!(exists, 0);
+
+    // data left to the gc workers is not visible to a new key of the same name
+    let _: () = con.rpush(l, "d").await.unwrap();
+    let values: Vec<String> = con.lrange(l, 0, -1).await.unwrap();
+    assert_eq!(values, vec!["d"]);
+    let _: i64 = con.hset(h, "f", "v").await.unwrap();
+    let len: i64 = con.hlen(h).await.unwrap();
+    assert_eq!(len, 1);
+
+    let _: () = con.del(&[l, h]).await.unwrap();
+}
@@ -0,0 +1,24 @@
+use redis::Client;
+
+#[tokio::test]
+async fn wait_without_replicas() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+
+    let acked: i64 = redis::cmd("WAIT")
+        .arg(0)
+        .arg(0)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(acked, 0);
+
+    // no follower ever catches up on a single node
+    let acked: i64 = redis::cmd("WAIT")
+        .arg(1)
+        .arg(100)
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(acked, 0);
+}
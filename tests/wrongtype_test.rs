@@ -0,0 +1,32 @@
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+async fn request(stream: &mut TcpStream, args: &[&str]) -> Vec<u8> {
+    let mut req = format!("*{}\r\n", args.len());
+    for arg in args {
+        req.push_str(&format!("${}\r\n{}\r\n", arg.len(), arg));
+    }
+    stream.write_all(req.as_bytes()).await.unwrap();
+    let mut buf = vec![0; 1024];
+    let n = stream.read(&mut buf).await.unwrap();
+    buf.truncate(n);
+    buf
+}
+
+#[tokio::test]
+async fn wrongtype_error_bytes() {
+    let mut stream = TcpStream::connect("127.0.0.1:6380").await.unwrap();
+    let key = "test_wrongtype";
+
+    assert_eq!(request(&mut stream, &["SET", key, "v"]).await, b"+OK\r\n");
+    let expected: &[u8] = b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n";
+    assert_eq!(request(&mut stream, &["LLEN", key]).await, expected);
+    assert_eq!(
+        request(&mut stream, &["HSET", key, "f", "v"]).await,
+        expected
+    );
+
+    // the connection is still usable after the error
+    assert_eq!(request(&mut stream, &["PING"]).await, b"+PONG\r\n");
+    assert_eq!(request(&mut stream, &["DEL", key]).await, b":1\r\n");
+}
@@ -0,0 +1,117 @@
+use redis::{AsyncCommands, Client};
+
+#[tokio::test]
+async fn zadd_duplicated_member() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let _: () = con.del("test_zadd_dup").await.unwrap();
+
+    let added: i64 = redis::cmd("ZADD")
+        .arg("test_zadd_dup")
+        .arg(1.0)
+        .arg("member")
+        .arg(2.0)
+        .arg("member")
+        .query_async(&mut con)
+        .await
+        .unwrap();
+    assert_eq!(added, 1);
+
+    let card: i64 = con.zcard("test_zadd_dup").await.unwrap();
+    assert_eq!(card, 1);
+    let score: f64 = con.zscore("test_zadd_dup", "member").await.unwrap();
+    assert_eq!(score, 2.0);
+}
+
+async fn zadd_flags(
+    con: &mut redis::aio::Connection,
+    key: &str,
+    flags: &[&str],
+    pairs: &[(f64, &str)],
+) -> i64 {
+    let mut cmd = redis::cmd("ZADD");
+    cmd.arg(key).arg(flags);
+    for (score, member) in pairs {
+        cmd.arg(*score).arg(*member);
+    }
+    cmd.query_async(con).await.unwrap()
+}
+
+#[tokio::test]
+async fn zadd_changed_count() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    // the small key is compact encoded, the large one is not
+    let (small, large) = ("test_zadd_ch_small", "test_zadd_ch_large");
+    let _: () = con.del(&[small, large]).await.unwrap();
+    let filler: Vec<(f64, String)> = (0..300).map(|i| (i as f64, format!("m{i}"))).collect();
+    let _: i64 = con.zadd_multiple(large, &filler).await.unwrap();
+
+    for key in [small, large] {
+        assert_eq!(zadd_flags(&mut con, key, &[], &[(1.0, "a")]).await, 1);
+        // same score is not a change
+        assert_eq!(zadd_flags(&mut con, key, &["CH"], &[(1.0, "a")]).await, 0);
+        assert_eq!(zadd_flags(&mut con, key, &["CH"], &[(2.0, "a")]).await, 1);
+        // NX only counts the added members
+        assert_eq!(
+            zadd_flags(&mut con, key, &["NX", "CH"], &[(3.0, "a"), (1.0, "b")]).await,
+            1
+        );
+        // XX never adds members
+        assert_eq!(
+            zadd_flags(&mut con, key, &["XX", "CH"], &[(5.0, "a"), (1.0, "c")]).await,
+            1
+        );
+        let score: f64 = con.zscore(key, "a").await.unwrap();
+        assert_eq!(score, 5.0);
+        let c: Option<f64> = con.zscore(key, "c").await.unwrap();
+        assert_eq!(c, None);
+    }
+
+    let _: () = con.del(&[small, large]).await.unwrap();
+}
+
+#[tokio::test]
+async fn zadd_incr() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let key = "test_zadd_incr";
+    let _: () = con.del(key).await.unwrap();
+
+    let incr = |flags: &[&str], score: f64, member: &str| {
+        let mut cmd = redis::cmd("ZADD");
+        cmd.arg(key).arg(flags).arg("INCR").arg(score).arg(member);
+        cmd
+    };
+
+    let score: f64 = incr(&[], 1.5, "a").query_async(&mut con).await.unwrap();
+    assert_eq!(score, 1.5);
+    // CH is ignored, the new score is replied
+    let score: f64 = incr(&["CH"], 2.0, "a").query_async(&mut con).await.unwrap();
+    assert_eq!(score, 3.5);
+
+    // NX adds a new member, but does not update an existing one
+    let score: Option<f64> = incr(&["NX"], 4.0, "b").query_async(&mut con).await.unwrap();
+    assert_eq!(score, Some(4.0));
+    let score: Option<f64> = incr(&["NX"], 1.0, "a").query_async(&mut con).await.unwrap();
+    assert_eq!(score, None);
+
+    // XX does not add a missing member
+    let score: Option<f64> = incr(&["XX"], 1.0, "c").query_async(&mut con).await.unwrap();
+    assert_eq!(score, None);
+    let c: Option<f64> = con.zscore(key, "c").await.unwrap();
+    assert_eq!(c, None);
+
+    let res: redis::RedisResult<f64> = redis::cmd("ZADD")
+        .arg(key)
+        .arg("INCR")
+        .arg(1.0)
+        .arg("a")
+        .arg(1.0)
+        .arg("b")
+        .query_async(&mut con)
+        .await;
+    assert!(res.is_err());
+
+    let _: () = con.del(key).await.unwrap();
+}
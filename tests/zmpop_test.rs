@@ -0,0 +1,50 @@
+use redis::{AsyncCommands, Client};
+
+type Popped = Option<(String, Vec<(String, f64)>)>;
+
+async fn zmpop(
+    con: &mut redis::aio::Connection,
+    keys: &[&str],
+    minmax: &str,
+    count: u64,
+) -> Popped {
+    redis::cmd("ZMPOP")
+        .arg(keys.len())
+        .arg(keys)
+        .arg(minmax)
+        .arg("COUNT")
+        .arg(count)
+        .query_async(con)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn zmpop_first_non_empty_zset() {
+    let client = Client::open("redis://127.0.0.1:6380").unwrap();
+    let mut con = client.get_async_connection().await.unwrap();
+    let (empty, zset) = ("test_zmpop_empty", "test_zmpop_zset");
+    let _: () = con.del(&[empty, zset]).await.unwrap();
+    let _: i64 = con
+        .zadd_multiple(zset, &[(1, "a"), (2, "b"), (3, "c"), (4, "d")])
+        .await
+        .unwrap();
+
+    let popped = zmpop(&mut con, &[empty, zset], "MIN", 1).await;
+    assert_eq!(popped, Some((zset.to_owned(), vec![("a".to_owned(), 1.0)])));
+
+    let popped = zmpop(&mut con, &[empty, zset], "MAX", 1).await;
+    assert_eq!(popped, Some((zset.to_owned(), vec![("d".to_owned(), 4.0)])));
+
+    // count is capped at the cardinality
+    let popped = zmpop(&mut con, &[empty, zset], "MIN", 10).await;
+    assert_eq!(
+        popped,
+        Some((
+            zset.to_owned(),
+            vec![("b".to_owned(), 2.0), ("c".to_owned(), 3.0)]
+        ))
+    );
+
+    assert_eq!(zmpop(&mut con, &[empty, zset], "MIN", 1).await, None);
+}